@@ -0,0 +1,33 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use badges::nft::{MetadataCache, Snapshot};
+use badges::{
+    Badge, CommemorativeToken, FeeRate, MediaAsset, MetadataRevision, MintRule, Phase, Price,
+    RateLimit, RevenueShare, TokenIdFormat, Window,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    // types shared by the Hub and NFT contracts, so that clients can be generated for them once
+    export_schema(&schema_for!(Badge), &out_dir);
+    export_schema(&schema_for!(MetadataRevision), &out_dir);
+    export_schema(&schema_for!(MintRule), &out_dir);
+    export_schema(&schema_for!(Phase), &out_dir);
+    export_schema(&schema_for!(Price), &out_dir);
+    export_schema(&schema_for!(RateLimit), &out_dir);
+    export_schema(&schema_for!(Window), &out_dir);
+    export_schema(&schema_for!(RevenueShare), &out_dir);
+    export_schema(&schema_for!(MediaAsset), &out_dir);
+    export_schema(&schema_for!(CommemorativeToken), &out_dir);
+    export_schema(&schema_for!(FeeRate), &out_dir);
+    export_schema(&schema_for!(TokenIdFormat), &out_dir);
+    export_schema(&schema_for!(MetadataCache), &out_dir);
+    export_schema(&schema_for!(Snapshot), &out_dir);
+}
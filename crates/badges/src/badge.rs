@@ -3,32 +3,114 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sg_metadata::Metadata;
 
-use crate::MintRule;
+use crate::{MintRule, Phase, RateLimit, RevenueShare};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Badge {
     /// Account who has the authority to edit the badge's info.
     pub manager: Addr,
 
-    /// The badge's metadata
+    /// The badge's metadata. Its strings and trait values may contain the `{serial}`,
+    /// `{total_supply}` and `{owner}` placeholders, which the NFT contract fills in for each token
     pub metadata: Metadata,
 
+    /// If set, tokens of this badge are served with a token URI rendered from this template instead
+    /// of the on-chain metadata. The placeholders `{id}` and `{serial}` are replaced with the badge
+    /// id and the token's serial number.
+    pub token_uri: Option<String>,
+
     /// Whether this badge is transferrable
     pub transferrable: bool,
 
+    /// Whether the manager can revoke instances of this badge, burning them from their owners'
+    /// wallets, e.g. for memberships that can be withdrawn
+    pub revocable: bool,
+
     /// The rule by which instances of this badge are to be minted
     pub rule: MintRule,
 
+    /// Whether the mint rule has been frozen. Once frozen, the manager can no longer change the
+    /// rule, including rotating its key, or the mint phases.
+    pub rule_frozen: bool,
+
+    /// Whether the metadata has been frozen. Once frozen, the manager can no longer edit the
+    /// badge's metadata.
+    pub metadata_frozen: bool,
+
+    /// Whether minting of the badge has been paused by the manager, e.g. because a claim key has
+    /// been compromised or the mint price was misconfigured. While paused, the badge cannot be
+    /// minted by any means.
+    pub paused: bool,
+
+    /// Whether the badge has been archived by the manager. Unlike pausing, archiving is permanent:
+    /// an archived badge can never be minted again.
+    pub archived: bool,
+
+    /// Whether the badge is hidden from the default listing of badges. Only archived badges can be
+    /// hidden.
+    pub hidden: bool,
+
+    /// Sequential mint phases. While one of them is active, its rule is used in place of the
+    /// badge's own rule. Outside of all phases, the badge's own rule applies.
+    pub phases: Vec<Phase>,
+
+    /// Shares of the proceeds from paid mints that go to accounts other than the manager. The
+    /// shares apply after the hub treasury's cut; the manager receives whatever remains.
+    pub revenue_split: Vec<RevenueShare>,
+
+    /// Royalty on secondary sales, reported to marketplaces through the CW-2981 queries of the NFT
+    /// contract. Enforcement is up to the marketplaces.
+    pub royalty: Option<RevenueShare>,
+
+    /// The timestamp only after which the badge can be minted
+    pub start_time: Option<u64>,
+
     /// The timestamp only before which the badge can be minted
     pub expiry: Option<u64>,
 
     /// The maximum number of badge instances can be minted
     pub max_supply: Option<u64>,
 
+    /// The maximum number of badge instances that can be minted to a single account, across all
+    /// mint rules
+    pub max_per_owner: Option<u64>,
+
+    /// Throttles how many instances of the badge can be minted within a window
+    pub rate_limit: Option<RateLimit>,
+
     /// The current number of this badge
     ///
-    /// NOTE: We don't consider that users may burn NFTs. `max_supply` refers to the maximum number
-    /// of tokens that can ever be minted. A user burning their tokens does not make room for new
-    /// tokens to be minted.
+    /// NOTE: Burns are tracked separately in `num_burned`, and don't decrease this number.
+    /// `max_supply` refers to the maximum number of tokens that can ever be minted, so burning
+    /// tokens does not make room for new tokens to be minted.
     pub current_supply: u64,
+
+    /// The number of instances of this badge that have been burned, either by their owners or by
+    /// the manager revoking them
+    pub num_burned: u64,
+}
+
+/// A record of an edit made to a badge's metadata
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataRevision {
+    /// The account that made the edit, i.e. the badge's manager at the time
+    pub editor: Addr,
+
+    /// The block height at which the edit was made
+    pub height: u64,
+
+    /// Hex-encoded SHA-256 hash of the JSON-serialized metadata that the edit replaced
+    pub previous_hash: String,
+}
+
+impl Badge {
+    /// The badge's own rule, followed by the rules of each of its mint phases
+    pub fn rules(&self) -> impl Iterator<Item = &MintRule> {
+        std::iter::once(&self.rule).chain(self.phases.iter().map(|phase| &phase.rule))
+    }
+
+    /// The index of the mint phase that is active at the given timestamp, if any
+    pub fn active_phase(&self, time: u64) -> Option<u32> {
+        self.phases.iter().position(|phase| phase.is_active(time)).map(|idx| idx as u32)
+    }
 }
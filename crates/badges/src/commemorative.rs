@@ -0,0 +1,16 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A fungible token minted along with each instance of a badge, e.g. so that badges double as
+/// loyalty points. The token is a token factory denom created and administered by the Hub, i.e.
+/// `factory/{hub}/{subdenom}`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommemorativeToken {
+    /// The denom's subdenom. A subdenom belongs to the manager who first configured it, and can
+    /// be shared among all badges of that manager.
+    pub subdenom: String,
+
+    /// How many tokens are minted to the recipient of each instance
+    pub amount: Uint128,
+}
@@ -0,0 +1,82 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InstantiateMsg {
+    /// Address of the Hub contract whose badges are distributed
+    pub hub: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Start an airdrop campaign. The Merkle tree's leaves are the SHA-256 hashes of
+    /// `{address}:{id},{id},...`, i.e. each account and the ids of the badges it can claim, in
+    /// ascending order; pairs of nodes are hashed in ascending order of their bytes.
+    ///
+    /// A campaign can only distribute badges managed by its creator, and the distributor must be a
+    /// minter of each of them, either under the "by minter" or "by minters" rule or as a delegated
+    /// minter.
+    CreateCampaign {
+        /// The hex-encoded Merkle root
+        merkle_root: String,
+        /// A UNIX timestamp, in seconds, after which badges can no longer be claimed, if any
+        expiry: Option<u64>,
+    },
+
+    /// Stop a campaign early, so that no more badges can be claimed. Only callable by the
+    /// campaign's creator.
+    CloseCampaign {
+        campaign_id: u64,
+    },
+
+    /// Claim the badges the sender is entitled to in a campaign. Each account can only claim once
+    /// per campaign, and must claim all of its badges at once.
+    Claim {
+        campaign_id: u64,
+        /// The ids of the badges to be claimed, in ascending order
+        ids: Vec<u64>,
+        /// The hex-encoded hashes of the leaf's siblings, from the bottom of the tree up
+        proof: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// The contract's configurations. Returns ConfigResponse
+    Config {},
+
+    /// Info of an airdrop campaign. Returns CampaignResponse
+    Campaign {
+        campaign_id: u64,
+    },
+
+    /// Whether a user has claimed their badges in a campaign. Returns ClaimedResponse
+    Claimed {
+        campaign_id: u64,
+        user: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ConfigResponse {
+    pub hub: String,
+    pub campaign_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CampaignResponse {
+    pub id: u64,
+    pub creator: String,
+    pub merkle_root: String,
+    pub expiry: Option<u64>,
+    pub closed: bool,
+    pub num_claimed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClaimedResponse {
+    pub user: String,
+    pub claimed: bool,
+}
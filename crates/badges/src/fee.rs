@@ -1,3 +1,5 @@
+use std::fmt;
+
 use cosmwasm_std::Decimal;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,3 +13,32 @@ pub struct FeeRate {
     /// The fee rate, in ustars per byte, for storing claim keys on-chain
     pub key: Decimal,
 }
+
+/// Where the routed share of the hub's protocol fees is sent
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeDestination {
+    /// The fees are burned
+    Burn,
+    /// The fees are donated to the chain's community pool. Only possible for native coins; fees
+    /// paid in CW20 tokens are sent to their usual recipient instead.
+    CommunityPool,
+}
+
+impl fmt::Display for FeeDestination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FeeDestination::Burn => write!(f, "burn"),
+            FeeDestination::CommunityPool => write!(f, "community_pool"),
+        }
+    }
+}
+
+/// Routes a share of the hub's protocol fees, i.e. creation fees and the treasury's cut of paid
+/// mints, to a destination other than the fee collector or the developer.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeeRouting {
+    pub destination: FeeDestination,
+    /// The share of the fees to be routed, in basis points
+    pub bps: u16,
+}
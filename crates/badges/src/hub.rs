@@ -1,15 +1,268 @@
 use std::collections::BTreeSet;
 
+use cosmwasm_std::{Binary, Coin, Decimal, Uint128};
 use schemars::JsonSchema;
+use cw721::Cw721ReceiveMsg;
+use cw_utils::Expiration;
 use serde::{Deserialize, Serialize};
-use sg_metadata::Metadata;
+use sg_metadata::{Metadata, Trait};
 
-use crate::{Badge, FeeRate, MintRule};
+use crate::{
+    Badge, CommemorativeToken, FeeRate, FeeRouting, MediaAsset, MintRule, Phase, RateLimit,
+    RevenueShare, TokenIdFormat,
+};
+
+/// Proof submitted when minting a badge under a composite rule. Each sub-rule reads the piece of
+/// proof it needs; pieces not needed by any of the sub-rules can be left as None.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+pub struct CompositeProof {
+    /// For "by key" or "by keys" sub-rules, the signature of the claim message
+    pub signature: Option<String>,
+    /// For "by key" or "by keys" sub-rules, the deadline committed to in the signed message, if any
+    pub deadline: Option<Expiration>,
+    /// For "by key" or "by keys" sub-rules, how the message is signed. Defaults to raw.
+    pub sign_mode: Option<SignMode>,
+    /// For "by key" or "by keys" sub-rules signed in ADR-36 mode, the account that signed the
+    /// message. Defaults to the sender.
+    pub signer: Option<String>,
+    /// For "by voucher" sub-rules, the voucher code
+    pub voucher: Option<String>,
+    /// For "by keys" sub-rules, the whitelisted pubkey that produced the signature
+    pub pubkey: Option<String>,
+    /// For "by nft" sub-rules, the token of the designated collection held by the owner
+    pub token_id: Option<String>,
+}
+
+/// How the message for minting a badge under the "by key" or "by keys" rule is signed.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SignMode {
+    /// The SHA-256 hash of the message is signed directly
+    #[default]
+    Raw,
+    /// The message is wrapped in an ADR-36 sign doc. This is what wallets such as Keplr produce
+    /// with `signArbitrary`, allowing users to self-sign their claims.
+    ///
+    /// The signer in the sign doc is the sender of the minting transaction, unless specified
+    /// otherwise, e.g. when a relayer submits the claim on behalf of a user without gas tokens.
+    Adr36,
+    /// The message is signed with Ethereum's `personal_sign`, as wallets such as MetaMask do: the
+    /// Keccak-256 hash of the message, prefixed with `\x19Ethereum Signed Message:\n` and its
+    /// length, is signed. The signature is the 65-byte `r || s || v` recoverable signature, which
+    /// is checked against the badge's key, be it a secp256k1 pubkey or an Ethereum address.
+    EthPersonalSign,
+}
+
+/// Message attached to a token sent to the Hub with a collection's `send_nft` method
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveNftMsg {
+    /// For a badge with the "by deposit" mint rule, mint a badge to the account that sent the
+    /// token. The token must be of the designated collection.
+    MintByDeposit {
+        id: u64,
+    },
+}
+
+/// Set as the `data` of the response of every method that mints badges, so that contracts calling
+/// the Hub in a submessage can read what was minted from the reply, rather than parsing events.
+/// `MintBatch` responds with a list of receipts, one for each entry in the batch.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintReceipt {
+    /// Id of the badge that was minted
+    pub id: u64,
+    /// Serial number of the first instance minted; the rest follow consecutively
+    pub start_serial: u64,
+    /// Number of instances minted, which is zero if e.g. a raffle had no registrants
+    pub amount: u64,
+    /// Token ids of the instances minted, in the order of their serials
+    pub token_ids: Vec<String>,
+    /// Recipients of the instances minted, in the same order as the token ids
+    pub recipients: Vec<String>,
+}
+
+/// Query the Hub makes to its price oracle, for the price in US dollars of one unit of a native
+/// coin, e.g. one `ustars`. Returns OraclePriceResponse
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    Price {
+        denom: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OraclePriceResponse {
+    pub price: Decimal,
+}
+
+/// Query the Hub makes to its name resolver, for the address that a name-service name, e.g.
+/// `alice.stars`, points to. Returns ResolveResponse
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolverQueryMsg {
+    Resolve {
+        name: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ResolveResponse {
+    /// None if the name isn't registered, or doesn't point to an address
+    pub address: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
     /// The fee rate charged for when creating or editing badges, quoted in ustars per byte
     pub fee_rate: FeeRate,
+    /// A flat fee charged for creating a badge, in addition to the fee for storing its metadata.
+    /// None means no creation fee is charged.
+    pub creation_fee: Option<Coin>,
+    /// The account that creation fees are forwarded to. Defaults to the developer if not provided.
+    pub fee_collector: Option<String>,
+    /// How NFT token ids are derived from badge ids and serials. Defaults to `{id}|{serial}` if not
+    /// provided. The NFT contract reads the format from the Hub when it is instantiated.
+    pub token_id_format: Option<TokenIdFormat>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MigrateMsg {}
+
+/// Parameters of a badge to be created in a batch. See `ExecuteMsg::CreateBadge` for the meaning
+/// of each field.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BadgeSpec {
+    pub manager: String,
+    pub metadata: Metadata,
+    pub token_uri: Option<String>,
+    pub transferrable: bool,
+    pub revocable: bool,
+    pub rule: MintRule,
+    pub rule_frozen: bool,
+    pub phases: Vec<Phase>,
+    pub revenue_split: Vec<RevenueShare>,
+    pub royalty: Option<RevenueShare>,
+    pub start_time: Option<u64>,
+    pub expiry: Option<u64>,
+    pub max_supply: Option<u64>,
+    pub max_per_owner: Option<u64>,
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Builder-style setters, so that a badge can be specified without spelling out every optional
+/// field, e.g. `BadgeSpec::new("larry", MintRule::by_key(pubkey)).expiry(ts).max_supply(100)`.
+/// Fields not set default to empty metadata, no limits, and the badge being non-transferrable,
+/// non-revocable, and its rule not frozen.
+impl BadgeSpec {
+    pub fn new(manager: impl Into<String>, rule: MintRule) -> Self {
+        BadgeSpec {
+            manager: manager.into(),
+            metadata: Metadata::default(),
+            token_uri: None,
+            transferrable: false,
+            revocable: false,
+            rule,
+            rule_frozen: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
+            expiry: None,
+            max_supply: None,
+            max_per_owner: None,
+            rate_limit: None,
+        }
+    }
+
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn token_uri(mut self, token_uri: impl Into<String>) -> Self {
+        self.token_uri = Some(token_uri.into());
+        self
+    }
+
+    pub fn transferrable(mut self, transferrable: bool) -> Self {
+        self.transferrable = transferrable;
+        self
+    }
+
+    pub fn revocable(mut self, revocable: bool) -> Self {
+        self.revocable = revocable;
+        self
+    }
+
+    pub fn rule_frozen(mut self, rule_frozen: bool) -> Self {
+        self.rule_frozen = rule_frozen;
+        self
+    }
+
+    /// Append a mint phase; phases must be added in chronological order
+    pub fn phase(mut self, phase: Phase) -> Self {
+        self.phases.push(phase);
+        self
+    }
+
+    /// Append a share of the proceeds from paid mints
+    pub fn revenue_share(mut self, share: RevenueShare) -> Self {
+        self.revenue_split.push(share);
+        self
+    }
+
+    pub fn royalty(mut self, royalty: RevenueShare) -> Self {
+        self.royalty = Some(royalty);
+        self
+    }
+
+    pub fn start_time(mut self, start_time: u64) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    pub fn expiry(mut self, expiry: u64) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    pub fn max_supply(mut self, max_supply: u64) -> Self {
+        self.max_supply = Some(max_supply);
+        self
+    }
+
+    pub fn max_per_owner(mut self, max_per_owner: u64) -> Self {
+        self.max_per_owner = Some(max_per_owner);
+        self
+    }
+
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+}
+
+impl From<BadgeSpec> for ExecuteMsg {
+    fn from(spec: BadgeSpec) -> Self {
+        ExecuteMsg::CreateBadge {
+            manager: spec.manager,
+            metadata: spec.metadata,
+            token_uri: spec.token_uri,
+            transferrable: spec.transferrable,
+            revocable: spec.revocable,
+            rule: spec.rule,
+            rule_frozen: spec.rule_frozen,
+            phases: spec.phases,
+            revenue_split: spec.revenue_split,
+            royalty: spec.royalty,
+            start_time: spec.start_time,
+            expiry: spec.expiry,
+            max_supply: spec.max_supply,
+            max_per_owner: spec.max_per_owner,
+            rate_limit: spec.rate_limit,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -17,7 +270,51 @@ pub enum SudoMsg {
     /// Set the fee rate for creating or editing badges. Callable by L1 governance.
     SetFeeRate {
         fee_rate: FeeRate,
-    }
+    },
+
+    /// Set the share, in basis points, of the proceeds from paid mints that goes to the hub
+    /// treasury, i.e. the developer. Callable by L1 governance.
+    SetTreasuryFee {
+        bps: u16,
+    },
+
+    /// Pause or unpause badge creation and minting hub-wide. Callable by L1 governance, e.g. in an
+    /// emergency where the developer key is unavailable or compromised.
+    SetHubPaused {
+        paused: bool,
+    },
+
+    /// Override the creation fee and the fee collector. Callable by L1 governance.
+    UpdateConfig {
+        creation_fee: Option<Coin>,
+        fee_collector: String,
+    },
+
+    /// Override how protocol fees are routed. Callable by L1 governance.
+    SetFeeRouting {
+        fee_routing: Option<FeeRouting>,
+    },
+
+    /// Override the price oracle. Callable by L1 governance.
+    SetOracle {
+        oracle: Option<String>,
+    },
+
+    /// Override the name resolver. Callable by L1 governance.
+    SetResolver {
+        resolver: Option<String>,
+    },
+
+    /// Override the token factory's denom creation fee. Callable by L1 governance.
+    SetDenomCreationFee {
+        fee: Option<Coin>,
+    },
+
+    /// Replace the developer account without the current developer's consent. Any pending
+    /// ownership transfer is cancelled. Callable by L1 governance.
+    SetDeveloper {
+        developer: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -29,28 +326,73 @@ pub enum ExecuteMsg {
         /// Manager is the account that can 1) change the badge's metadata, and 2) if using the "by
         /// keys" mint rule, whitelist pubkeys.
         ///
+        /// The manager can hand over its role using the `TransferManager` method.
+        ///
         /// TODO: Make mananger an optional parameter; setting it to None meaning no one can change
-        /// the metadata. Also, allow transferring of manager power in the `edit_badge` method.
+        /// the metadata.
         ///
         /// NOTE: If using the "by keys" minting rule, manager cannot be None, because a manager is
         /// is needed to whitelist keys.
         manager: String,
         /// The badge's metadata, defined by the OpenSea standard
         metadata: Metadata,
+        /// If provided, tokens of this badge are served with a token URI rendered from this
+        /// template, e.g. `ipfs://<cid>/{serial}.json`, instead of the on-chain metadata, in which
+        /// case `metadata` can be left empty to save on the metadata fee. The placeholders `{id}`
+        /// and `{serial}` are replaced with the badge id and the token's serial number.
+        token_uri: Option<String>,
         /// Whether this badge is transferrable
         transferrable: bool,
-        /// The rule by which this badge is to be minted. There are three available rules; see the
-        /// docs of `badges::MintRule` for details.
+        /// Whether the manager can revoke instances of this badge using the `Revoke` method. This
+        /// can't be changed after the badge is created.
+        revocable: bool,
+        /// The rule by which this badge is to be minted. See the docs of `badges::MintRule` for the
+        /// available rules.
         rule: MintRule,
+        /// Whether the mint rule is frozen from the start. If not, the manager can change the rule
+        /// later, until it is frozen using the `FreezeMintRule` method.
+        rule_frozen: bool,
+        /// Sequential mint phases, each with its own rule and per-account limit, e.g. an allowlist
+        /// phase followed by a public phase. Outside of all phases, `rule` applies.
+        phases: Vec<Phase>,
+        /// Shares of the proceeds from paid mints that go to accounts other than the manager, e.g.
+        /// artist royalties. They apply after the hub treasury's cut, and must add up to no more
+        /// than 10000 basis points. The manager receives whatever remains.
+        revenue_split: Vec<RevenueShare>,
+        /// Royalty on secondary sales, in basis points of the sale price, paid to the recipient.
+        /// Reported to marketplaces through the CW-2981 queries of the NFT contract.
+        /// Setting this to None means no royalty.
+        royalty: Option<RevenueShare>,
+        /// A timestamp only after which the badge can be minted. This allows a badge to be
+        /// announced ahead of time, with claims opening at a specific time.
+        /// Setting this to None means minting is open immediately.
+        start_time: Option<u64>,
         /// A deadline only before which the badge can be minted.
         /// Setting this to None means there is no deadline.
-        /// Can only be set once when creating the badge; cannot be changed later.
+        /// Can be changed later by the manager using `UpdateMintParams`, as long as the new
+        /// deadline has not passed.
         expiry: Option<u64>,
         /// The maximum amount of badge that can be minted. Note, users burning minted badges does
         /// NOT free up slots for new badges to be minted.
         /// Setting this to None means there is no max supply.
-        /// Can only be set once when creating the badge; cannot be changed later.
+        /// Can be changed later by the manager using `UpdateMintParams`, as long as it is not set
+        /// below the number of instances already minted.
         max_supply: Option<u64>,
+        /// The maximum amount of badge that can be minted to a single account.
+        /// Setting this to None means there is no per-account limit, other than the one implied by
+        /// the mint rule (e.g. "by key" rule only allows one mint per account).
+        max_per_owner: Option<u64>,
+        /// Throttles how many badges can be minted within a window, e.g. at most N per block.
+        /// Setting this to None means there is no throttling.
+        rate_limit: Option<RateLimit>,
+    },
+
+    /// Create multiple badges in one go, e.g. all badges of an event season. The batch is rejected
+    /// as a whole if any of the badges is invalid. The creation fee, if any, is charged for each
+    /// badge. The ids assigned to the new badges are returned in the response data, as a JSON
+    /// array in the same order as the batch.
+    CreateBadges {
+        badges: Vec<BadgeSpec>,
     },
 
     /// Edit the metadata of an existing badge; only the manager can call
@@ -59,6 +401,119 @@ pub enum ExecuteMsg {
         metadata: Metadata,
     },
 
+    /// Set or remove an SVG template, from which the NFT contract renders each token's image
+    /// on-chain into the `image_data` field, so that the badge doesn't depend on off-chain storage.
+    /// The template may contain the same placeholders as the metadata, e.g. `{serial}` and
+    /// `{owner}`. Only the manager can call, and only while the metadata is not frozen
+    SetSvgTemplate {
+        id: u64,
+        template: Option<String>,
+    },
+
+    /// Set or remove the fungible token minted along with each instance of a badge. The first time
+    /// a subdenom is used, the Hub creates it with the token factory; the manager must send along
+    /// the denom creation fee set with `SetDenomCreationFee`, if any. Only the manager can call,
+    /// only with subdenoms not already used by another manager, and only while the badge's
+    /// metadata is not frozen and the badge is not archived
+    SetCommemorativeToken {
+        id: u64,
+        token: Option<CommemorativeToken>,
+    },
+
+    /// Replace the media assets attached to a badge, e.g. a video, its poster image and a 3D model.
+    /// An empty list removes them. Tokens whose metadata has no image or animation url are served
+    /// with the first asset of the matching purpose instead. Only the manager can call, and only
+    /// while the metadata is not frozen
+    SetMedia {
+        id: u64,
+        media: Vec<MediaAsset>,
+    },
+
+    /// Set or remove a translation of a badge's metadata into a locale, e.g. `es` or `pt-BR`.
+    /// Fields present in the translation replace the badge's own when the badge or its tokens are
+    /// queried in that locale; the others are served untranslated. Only the manager can call, and
+    /// only while the metadata is not frozen
+    SetLocalizedMetadata {
+        id: u64,
+        locale: String,
+        metadata: Option<Metadata>,
+    },
+
+    /// Propose to transfer the manager role of a badge to a new account, e.g. a multisig or a
+    /// successor team. The transfer only takes effect once the new account accepts it using the
+    /// `AcceptManager` method; until then, the manager can propose a different account to replace
+    /// this one. Only callable by the manager.
+    TransferManager {
+        id: u64,
+        new_manager: String,
+    },
+
+    /// Accept a proposed transfer of a badge's manager role. Only callable by the proposed account.
+    AcceptManager {
+        id: u64,
+    },
+
+    /// Change the minting deadline and the max supply of an existing badge, e.g. to extend a
+    /// campaign. Setting either to None removes the limit. The new deadline must not have passed,
+    /// and the new max supply must not be below the number of instances already minted. Only
+    /// callable by the manager.
+    UpdateMintParams {
+        id: u64,
+        expiry: Option<u64>,
+        max_supply: Option<u64>,
+    },
+
+    /// Replace the mint rule of an existing badge, e.g. to switch from "by minter" to "by key" once
+    /// distribution moves to self-claim. Records of which users have already claimed the badge are
+    /// kept. Only callable by the manager, and only if the rule has not been frozen.
+    UpdateMintRule {
+        id: u64,
+        rule: MintRule,
+    },
+
+    /// Replace the mint phases of an existing badge. Per-account counts of mints during each phase
+    /// are tracked by the phase's position, so they carry over to the phase at the same position.
+    /// Only callable by the manager, and only if the rule has not been frozen.
+    UpdatePhases {
+        id: u64,
+        phases: Vec<Phase>,
+    },
+
+    /// Pause or unpause the minting of a badge. While paused, the badge cannot be minted by any
+    /// means, including by the hooks and minters it has authorized. Only callable by the manager.
+    SetBadgePaused {
+        id: u64,
+        paused: bool,
+    },
+
+    /// Permanently close the minting of a badge, e.g. to retire a badge that was created for
+    /// testing or was misconfigured. Optionally, also hide the badge from the default listing of
+    /// badges. Only callable by the manager.
+    ArchiveBadge {
+        id: u64,
+        hide: bool,
+    },
+
+    /// Permanently freeze the metadata of a badge, so that it can no longer be edited.
+    /// Only callable by the manager.
+    FreezeMetadata {
+        id: u64,
+    },
+
+    /// Permanently freeze the mint rule of a badge, so that it can no longer be changed.
+    /// Only callable by the manager.
+    FreezeMintRule {
+        id: u64,
+    },
+
+    /// For a badge that uses the "by key" mint rule, replace the pubkey, e.g. if the privkey has
+    /// been compromised. Records of which users have already claimed the badge are kept.
+    /// Only callable by the manager.
+    RotateKey {
+        id: u64,
+        pubkey: String,
+    },
+
     /// For a badge that uses the "by keys" mint rule, invoke this method to whitelist pubkeys.
     /// Only callable by the manager before the minting deadline or max supply has been reached.
     AddKeys {
@@ -68,6 +523,64 @@ pub enum ExecuteMsg {
         keys: BTreeSet<String>,
     },
 
+    /// For a badge that uses the "by voucher" mint rule, invoke this method to upload the
+    /// hex-encoded SHA-256 hashes of voucher codes. Only callable by the manager before the minting
+    /// deadline or max supply has been reached.
+    AddVouchers {
+        id: u64,
+        hashes: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by minter" or "by minters" mint rule, temporarily delegate
+    /// minting authority to an account until the given expiration, e.g. for event staff. Granting
+    /// again to the same account overwrites the previous expiration. Only callable by the manager.
+    GrantMinter {
+        id: u64,
+        minter: String,
+        expiration: Expiration,
+    },
+
+    /// Revoke a delegated minter before its delegation expires. Only callable by the manager.
+    RevokeMinter {
+        id: u64,
+        minter: String,
+    },
+
+    /// For a badge that uses the "by allowlist" mint rule, invoke this method to add accounts to
+    /// the allowlist. Only callable by the manager.
+    AddToAllowlist {
+        id: u64,
+        users: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by allowlist" mint rule, invoke this method to remove accounts
+    /// from the allowlist. Only callable by the manager.
+    RemoveFromAllowlist {
+        id: u64,
+        users: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by minters" mint rule, invoke this method to add minters.
+    /// Only callable by the manager.
+    AddMinters {
+        id: u64,
+        minters: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by minters" mint rule, invoke this method to remove minters.
+    /// Only callable by the manager.
+    RemoveMinters {
+        id: u64,
+        minters: BTreeSet<String>,
+    },
+
+    /// For a badge minted by signatures, increment the nonce that the signed message must commit
+    /// to, invalidating all signatures that have been issued but not yet used.
+    /// Only callable by the manager.
+    BumpNonce {
+        id: u64,
+    },
+
     /// Once a badge has expired or sold out, the whitelisted keys are no longer needed. Invoke this
     /// method to purge these keys from storage in order to reduce the chain's state size.
     PurgeKeys {
@@ -75,6 +588,25 @@ pub enum ExecuteMsg {
         limit: Option<u32>,
     },
 
+    /// For a revocable badge, burn the instances of the specified serial numbers from their owners'
+    /// wallets. Only callable by the manager.
+    Revoke {
+        id: u64,
+        serials: BTreeSet<u64>,
+    },
+
+    /// Attach traits to a single minted instance of a badge, e.g. a "placement" trait for each
+    /// recipient of a competition badge. They are shown on top of the badge's shared metadata,
+    /// replacing shared traits of the same type. An empty list removes the instance's traits.
+    ///
+    /// Only callable by the manager, and not once the badge's metadata is frozen. The manager
+    /// must pay a storage fee for the traits, like for the badge's metadata.
+    SetSerialTraits {
+        id: u64,
+        serial: u64,
+        traits: Vec<Trait>,
+    },
+
     /// Once a badge has expired or sold out, the list of users who have claimed it is no longer
     /// needed. Invoke this method to purge these user addresses in order to reduce the chain's
     /// state size.
@@ -83,19 +615,70 @@ pub enum ExecuteMsg {
         limit: Option<u32>,
     },
 
-    /// For a badge with the "by minter" mint rule, mint new badges to a set of owners.
-    /// Can only be invoked by the designated minter.
+    /// For a badge with the "by minter" or "by minters" mint rule, mint new badges to a set of
+    /// owners. Can only be invoked by the designated minter(s).
+    ///
+    /// Owners can be given either as addresses or as name-service names, i.e. anything containing
+    /// a dot, which are resolved to addresses using the Hub's name resolver.
     MintByMinter {
         id: u64,
         /// NOTE: User BTreeSet instead of HashSet, the same reason as discussed above
         owners: BTreeSet<String>,
     },
 
+    /// Same as `MintByMinter`, but for minting multiple badges in one go. Each entry is a badge id
+    /// and the set of owners to mint it to. The sender must be a minter of every one of the badges;
+    /// if any of the mints fails, the whole batch is rejected.
+    MintBatch {
+        mints: Vec<(u64, BTreeSet<String>)>,
+    },
+
+    /// Same as `MintByMinter`, but for owner lists too long to be minted in one transaction. The
+    /// badge's supply and the owners' serials are reserved right away, subject to the same checks
+    /// as `MintByMinter`, but no instance is minted until `ResumeMint` is invoked. A badge can only
+    /// have one queued mint in progress at a time.
+    QueueMint {
+        id: u64,
+        owners: BTreeSet<String>,
+    },
+
+    /// Mint the next instances of a badge's queued mint, in order of serial, up to the given limit.
+    /// Can be invoked by anyone, repeatedly, until every owner of the queued mint has received
+    /// their instance.
+    ResumeMint {
+        id: u64,
+        limit: Option<u32>,
+    },
+
+    /// Invoked by the NFT contract when an owner burns an instance of a badge, so that the Hub can
+    /// keep count of the badge's burned instances. Only callable by the NFT contract.
+    RecordBurn {
+        id: u64,
+        serial: u64,
+    },
+
+    /// For a badge with the "by contract" mint rule, mint a badge to the specified owner.
+    /// Only callable by the designated contract. Like the "by minter" rule, the same owner may
+    /// receive multiple instances, subject to the badge's per-owner limit.
+    HookMint {
+        id: u64,
+        owner: String,
+    },
+
     /// For a badge with the "by key" mint rule, mint a badge to the specified owner.
     /// The caller must submit a signature to prove they have the minting key.
     MintByKey {
         id: u64,
         owner: String,
+        /// An optional deadline, by block height or time, after which the signature is no longer
+        /// accepted. It is part of the signed message, so it must be the one the key holder signed.
+        deadline: Option<Expiration>,
+        /// How the message is signed. Defaults to raw.
+        sign_mode: Option<SignMode>,
+        /// If signed in ADR-36 mode, the account that signed the message. Defaults to the sender;
+        /// set this when relaying a claim on behalf of the signer. Either way, the badge is minted
+        /// to `owner`, which the signed message commits to.
+        signer: Option<String>,
         signature: String,
     },
 
@@ -104,10 +687,179 @@ pub enum ExecuteMsg {
     MintByKeys {
         id: u64,
         owner: String,
+        /// An optional deadline after which the signature is no longer accepted; see `MintByKey`
+        deadline: Option<Expiration>,
+        /// How the message is signed. Defaults to raw.
+        sign_mode: Option<SignMode>,
+        /// If signed in ADR-36 mode, the account that signed the message; see `MintByKey`
+        signer: Option<String>,
         pubkey: String,
         signature: String,
     },
 
+    /// For a badge with the "by voucher" mint rule, mint a badge to the specified owner.
+    /// The caller must submit a voucher code whose hash has been uploaded by the manager. Each code
+    /// can only be used once.
+    MintByVoucher {
+        id: u64,
+        owner: String,
+        code: String,
+    },
+
+    /// For a badge with the "by payment" mint rule, mint a badge to the specified owner.
+    /// The caller must pay the price, either by attaching native coins, or, if the price is in a
+    /// CW20 token, by granting the Hub an allowance beforehand.
+    MintByPayment {
+        id: u64,
+        owner: String,
+    },
+
+    /// For a badge with the "by nft" mint rule, mint a badge to the specified owner.
+    /// The owner must hold the specified token of the designated collection, and the token must not
+    /// have already been used to mint this badge.
+    MintByNft {
+        id: u64,
+        owner: String,
+        token_id: String,
+    },
+
+    /// Implements the CW721 receiver interface, invoked by a collection when a token is sent to the
+    /// Hub with `send_nft`. The attached message must be a `ReceiveNftMsg`.
+    ReceiveNft(Cw721ReceiveMsg),
+
+    /// For a badge with the "by delegation" mint rule, mint a badge to the sender.
+    /// The sender must have delegated at least the required amount to the designated validator.
+    MintByDelegation {
+        id: u64,
+    },
+
+    /// For a badge with the "by group" mint rule, mint a badge to the sender.
+    /// The sender must be a member of the designated cw4 group, with at least the required weight.
+    /// Each account can only mint once.
+    MintByGroup {
+        id: u64,
+    },
+
+    /// For a badge with the "by allowlist" mint rule, mint a badge to the sender.
+    /// The sender must be on the allowlist, and each account can only claim once.
+    MintByAllowlist {
+        id: u64,
+    },
+
+    /// For a badge with the "by raffle" mint rule, register the sender for the raffle.
+    /// Only possible before registration ends and the raffle is drawn. Each account can only
+    /// register once.
+    RegisterForRaffle {
+        id: u64,
+    },
+
+    /// For a badge with the "by raffle" mint rule, draw the raffle, minting the badge to randomly
+    /// selected registrants. The manager can draw at any time; anyone else can only draw once
+    /// registration has ended. A raffle can only be drawn once.
+    DrawRaffle {
+        id: u64,
+    },
+
+    /// For a badge with the "open" mint rule, mint a badge to the sender.
+    /// Each account can only claim once.
+    Claim {
+        id: u64,
+    },
+
+    /// For a badge with a composite ("all of" or "any of") mint rule, mint a badge to the specified
+    /// owner. The sub-rules are evaluated recursively against the submitted proof.
+    MintByComposite {
+        id: u64,
+        owner: String,
+        proof: CompositeProof,
+    },
+
+    /// Propose to transfer ownership of the hub, i.e. the developer role, to a new account. The
+    /// transfer only takes effect once the new account accepts it using the `AcceptOwnership`
+    /// method; until then, the developer can propose a different account to replace this one.
+    /// Only callable by the developer.
+    TransferOwnership {
+        new_developer: String,
+    },
+
+    /// Accept a proposed transfer of the hub's ownership. Only callable by the proposed account.
+    AcceptOwnership {},
+
+    /// Update the hub's creation fee and the account that creation fees are forwarded to. Setting
+    /// `creation_fee` to None means no creation fee is charged. Only callable by the developer.
+    UpdateConfig {
+        creation_fee: Option<Coin>,
+        fee_collector: String,
+    },
+
+    /// Route a share of the hub's protocol fees, i.e. creation fees and the treasury's cut of paid
+    /// mints, to be burned or donated to the community pool instead of going to the fee collector
+    /// or the developer. None routes no fees. Only callable by the developer.
+    SetFeeRouting {
+        fee_routing: Option<FeeRouting>,
+    },
+
+    /// Set the price oracle used to convert USD-denominated prices of "by payment" badges into
+    /// native coins. None removes the oracle, after which such badges can't be minted. Only
+    /// callable by the developer.
+    SetOracle {
+        oracle: Option<String>,
+    },
+
+    /// Set the contract used to resolve name-service names, e.g. `alice.stars`, given in place of
+    /// addresses as the owners in `MintByMinter` and `MintBatch`. None removes the resolver, after
+    /// which only addresses are accepted. Only callable by the developer.
+    SetResolver {
+        resolver: Option<String>,
+    },
+
+    /// Set the chain's token factory fee for creating a denom, which managers must send along when
+    /// `SetCommemorativeToken` creates a new denom. It should be kept in line with the chain's
+    /// token factory params. None means no fee is required. Only callable by the developer.
+    SetDenomCreationFee {
+        fee: Option<Coin>,
+    },
+
+    /// Restrict, or lift the restriction on, who can create badges. While restricted, only accounts
+    /// on the hub's creator allowlist can create badges. Only callable by the developer.
+    SetCreatorsRestricted {
+        restricted: bool,
+    },
+
+    /// Add an account to the hub's creator allowlist. Only callable by the developer.
+    AddCreator {
+        creator: String,
+    },
+
+    /// Remove an account from the hub's creator allowlist. Only callable by the developer.
+    RemoveCreator {
+        creator: String,
+    },
+
+    /// Pause or unpause the hub as a whole, e.g. in response to an incident. While paused, no
+    /// badge can be created or minted; queries are unaffected. Only callable by the developer.
+    SetHubPaused {
+        paused: bool,
+    },
+
+    /// Migrate the NFT contract to a new code id, passing along the given migrate message. For this
+    /// to succeed, the hub must be set as the NFT contract's admin. Only callable by the developer.
+    MigrateNft {
+        new_code_id: u64,
+        msg: Binary,
+    },
+
+    /// Set a new admin for the NFT contract, e.g. to hand over upgrades of the NFT contract to a
+    /// governance contract. The hub must currently be the NFT contract's admin. Only callable by
+    /// the developer.
+    UpdateNftAdmin {
+        admin: String,
+    },
+
+    /// Clear the admin of the NFT contract, making it permanently immutable. The hub must currently
+    /// be the NFT contract's admin. Only callable by the developer.
+    ClearNftAdmin {},
+
     /// During deployment, once the NFT contract has been deployed, the developer informs Hub of the
     /// NFT contract's address.
     ///
@@ -121,21 +873,183 @@ pub enum ExecuteMsg {
     },
 }
 
+/// Constructors for the minting messages, leaving the optional parameters unset. Set them on the
+/// returned message where needed, e.g. a signature's deadline.
+impl ExecuteMsg {
+    pub fn mint_by_minter<T: Into<String>>(id: u64, owners: impl IntoIterator<Item = T>) -> Self {
+        ExecuteMsg::MintByMinter {
+            id,
+            owners: owners.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// A claim under the "by key" rule, with a raw signature without a deadline
+    pub fn mint_by_key(id: u64, owner: impl Into<String>, signature: impl Into<String>) -> Self {
+        ExecuteMsg::MintByKey {
+            id,
+            owner: owner.into(),
+            deadline: None,
+            sign_mode: None,
+            signer: None,
+            signature: signature.into(),
+        }
+    }
+
+    /// A claim under the "by keys" rule, with a raw signature without a deadline
+    pub fn mint_by_keys(
+        id: u64,
+        owner: impl Into<String>,
+        pubkey: impl Into<String>,
+        signature: impl Into<String>,
+    ) -> Self {
+        ExecuteMsg::MintByKeys {
+            id,
+            owner: owner.into(),
+            deadline: None,
+            sign_mode: None,
+            signer: None,
+            pubkey: pubkey.into(),
+            signature: signature.into(),
+        }
+    }
+
+    pub fn mint_by_voucher(id: u64, owner: impl Into<String>, code: impl Into<String>) -> Self {
+        ExecuteMsg::MintByVoucher {
+            id,
+            owner: owner.into(),
+            code: code.into(),
+        }
+    }
+
+    pub fn mint_by_payment(id: u64, owner: impl Into<String>) -> Self {
+        ExecuteMsg::MintByPayment {
+            id,
+            owner: owner.into(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     /// The contract's configurations. Returns ConfigResponse
     Config {},
 
-    /// Info about a badge. Returns BadgeResponse
+    /// List all accounts on the hub's creator allowlist. Returns CreatorsResponse
+    Creators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// The NFT contract linked to the hub and its current admin. Returns NftContractResponse
+    NftContract {},
+
+    /// Info about a badge. If a locale is given, the metadata is translated into it, falling back
+    /// to the locale's language alone (e.g. `pt` for `pt-BR`) and then to the untranslated
+    /// metadata. Returns BadgeResponse
     Badge {
         id: u64,
+        locale: Option<String>,
+    },
+
+    /// The fungible token minted along with each instance of a badge, if any. Returns
+    /// CommemorativeTokenResponse
+    CommemorativeToken {
+        id: u64,
+    },
+
+    /// The media assets attached to a badge. Returns MediaResponse
+    Media {
+        id: u64,
+    },
+
+    /// The locales a badge's metadata has been translated into, in alphabetical order. Returns
+    /// LocalesResponse
+    Locales {
+        id: u64,
+    },
+
+    /// The SVG template of a badge's on-chain image, if any. Returns SvgTemplateResponse
+    SvgTemplate {
+        id: u64,
     },
 
-    /// Enumerate infos of all badges. Returns BadgesResponse
+    /// Infos of the specified badges, in the order given, e.g. to render a gallery in a single
+    /// round trip. Fails if any of the badges does not exist, or if more than 30 badges are
+    /// requested. Returns BadgesResponse
+    BadgesById {
+        ids: Vec<u64>,
+    },
+
+    /// A badge's supply, and whether it can currently be minted considering its start time,
+    /// expiry, max supply, and whether it or the hub is paused. Returns SupplyResponse
+    Supply {
+        id: u64,
+    },
+
+    /// Hub-wide statistics: the number of badges and of instances minted, both in total and by
+    /// type of mint rule, along with the supplies of badges enumerated by id. Returns
+    /// StatsResponse
+    Stats {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate infos of all badges. Badges that have been hidden upon archiving are skipped,
+    /// unless `include_hidden` is set to true. Returns BadgesResponse
     Badges {
         start_after: Option<u64>,
         limit: Option<u32>,
+        include_hidden: Option<bool>,
+    },
+
+    /// Enumerate infos of the badges that can be minted at the current block, i.e. that have
+    /// started, have not expired, sold out or been archived, and are not paused. The number of
+    /// badges looked at per query is capped, so a page may hold fewer than `limit` badges even if
+    /// more are active; continue from the response's `next_start_after`. Returns
+    /// ActiveBadgesResponse
+    ActiveBadges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate infos of the badges managed by an account, including hidden ones. Returns
+    /// BadgesResponse
+    BadgesByManager {
+        manager: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate infos of the badges using a type of mint rule, e.g. "by_key", regardless of the
+    /// rule's parameters. Hidden badges are skipped unless `include_hidden` is set to true.
+    /// Returns BadgesResponse
+    BadgesByRule {
+        rule: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        include_hidden: Option<bool>,
+    },
+
+    /// Enumerate infos of all badges in order of expiry, soonest first. Badges without an expiry
+    /// come last. Pagination is by badge id. Returns BadgesResponse
+    BadgesByExpiry {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate the edits made to a badge's metadata, oldest first. Returns
+    /// MetadataHistoryResponse
+    MetadataHistory {
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// The account that the manager of a badge has proposed to transfer the manager role to, if
+    /// any. Returns PendingManagerResponse
+    PendingManager {
+        id: u64,
     },
 
     /// Whether a pubkey can be used to mint a badge. Returns KeyResponse
@@ -151,13 +1065,106 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Whether a voucher hash can be used to mint a badge. Returns VoucherResponse
+    Voucher {
+        id: u64,
+        hash: String,
+    },
+
+    /// The expiration of an account's delegated minting authority for a badge, if any. Returns
+    /// DelegatedMinterResponse
+    DelegatedMinter {
+        id: u64,
+        minter: String,
+    },
+
+    /// The progress of a badge's queued mint, if any. Returns PendingMintResponse
+    PendingMint {
+        id: u64,
+    },
+
+    /// The nonce that signatures for minting a badge must commit to. Returns NonceResponse
+    Nonce {
+        id: u64,
+    },
+
+    /// The badge id and serial of one of the Hub's NFTs, e.g. to look up token ids in the counter
+    /// format, which reveal neither. Returns TokenSerialResponse
+    TokenSerial {
+        token_id: String,
+    },
+
+    /// Whether a token of the designated collection has been used to mint a badge under the "by
+    /// nft" rule. Returns NftResponse
+    Nft {
+        id: u64,
+        token_id: String,
+    },
+
+    /// The badge a token was deposited for under the "by deposit" rule, and the account that
+    /// deposited it. Returns DepositResponse
+    Deposit {
+        collection: String,
+        token_id: String,
+    },
+
+    /// List all accounts on the allowlist of a badge using the "by allowlist" rule.
+    /// Returns AllowlistResponse
+    Allowlist {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List all accounts that can mint a badge under the "by minters" rule. Returns MintersResponse
+    Minters {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List all accounts that have registered for the raffle of a badge using the "by raffle" rule.
+    /// Returns RegistrantsResponse
+    Registrants {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List all accounts that have won the raffle of a badge using the "by raffle" rule.
+    /// Returns WinnersResponse
+    Winners {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List the serial numbers of the instances of a badge that have been revoked.
+    /// Returns RevocationsResponse
+    Revocations {
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
     /// Whether a user has claimed the specified badge. Returns OwnerResponse
     Owner {
         id: u64,
         user: String,
     },
 
+    /// Whether each of the users has claimed the specified badge, in the order given. At most 30
+    /// users can be queried at once. Returns OwnerBatchResponse
+    OwnerBatch {
+        id: u64,
+        users: Vec<String>,
+    },
+
     /// List a users that have claimed the specified badge. Returns OwnersResponse
+    ///
+    /// This is the record used to enforce one claim per user, and may have been purged. To
+    /// snapshot the current holders of a badge along with their serials, use the NFT contract's
+    /// `TokensByBadge` query instead.
     Owners {
         id: u64,
         start_after: Option<String>,
@@ -168,9 +1175,34 @@ pub enum QueryMsg {
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ConfigResponse {
     pub developer: String,
+    pub pending_developer: Option<String>,
     pub nft: String,
     pub badge_count: u64,
     pub fee_rate: FeeRate,
+    pub treasury_fee: u16,
+    pub creation_fee: Option<Coin>,
+    pub denom_creation_fee: Option<Coin>,
+    pub fee_collector: String,
+    pub fee_routing: Option<FeeRouting>,
+    pub oracle: Option<String>,
+    pub resolver: Option<String>,
+    pub creators_restricted: bool,
+    pub paused: bool,
+    pub token_id_format: TokenIdFormat,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct NftContractResponse {
+    pub nft: String,
+    pub admin: Option<String>,
+    /// Whether the hub is the NFT contract's admin, i.e. whether the hub's developer can migrate
+    /// the NFT contract or change its admin through the hub
+    pub hub_is_admin: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CreatorsResponse {
+    pub creators: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -178,11 +1210,25 @@ pub struct BadgeResponse {
     pub id: u64,
     pub manager: String,
     pub metadata: Metadata,
+    pub token_uri: Option<String>,
     pub transferrable: bool,
+    pub revocable: bool,
     pub rule: MintRule,
+    pub rule_frozen: bool,
+    pub metadata_frozen: bool,
+    pub paused: bool,
+    pub archived: bool,
+    pub hidden: bool,
+    pub phases: Vec<Phase>,
+    pub revenue_split: Vec<RevenueShare>,
+    pub royalty: Option<RevenueShare>,
+    pub start_time: Option<u64>,
     pub expiry: Option<u64>,
     pub max_supply: Option<u64>,
+    pub max_per_owner: Option<u64>,
+    pub rate_limit: Option<RateLimit>,
     pub current_supply: u64,
+    pub num_burned: u64,
 }
 
 impl From<(u64, Badge)> for BadgeResponse {
@@ -192,11 +1238,25 @@ impl From<(u64, Badge)> for BadgeResponse {
             id,
             manager: badge.manager.into(),
             metadata: badge.metadata,
+            token_uri: badge.token_uri,
             transferrable: badge.transferrable,
+            revocable: badge.revocable,
             rule: badge.rule,
+            rule_frozen: badge.rule_frozen,
+            metadata_frozen: badge.metadata_frozen,
+            paused: badge.paused,
+            archived: badge.archived,
+            hidden: badge.hidden,
+            phases: badge.phases,
+            revenue_split: badge.revenue_split,
+            royalty: badge.royalty,
+            start_time: badge.start_time,
             expiry: badge.expiry,
             max_supply: badge.max_supply,
+            max_per_owner: badge.max_per_owner,
+            rate_limit: badge.rate_limit,
             current_supply: badge.current_supply,
+            num_burned: badge.num_burned,
         }
     }
 }
@@ -206,6 +1266,33 @@ pub struct BadgesResponse {
     pub badges: Vec<BadgeResponse>
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ActiveBadgesResponse {
+    pub badges: Vec<BadgeResponse>,
+    /// The badge id to query from next as `start_after`, or None if all badges have been looked at
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataRevisionResponse {
+    pub revision: u64,
+    pub editor: String,
+    pub height: u64,
+    pub previous_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataHistoryResponse {
+    pub id: u64,
+    pub revisions: Vec<MetadataRevisionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingManagerResponse {
+    pub id: u64,
+    pub pending_manager: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct KeyResponse {
     pub key: String,
@@ -217,13 +1304,147 @@ pub struct KeysResponse {
     pub keys: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllowlistResponse {
+    pub users: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintersResponse {
+    pub minters: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RegistrantsResponse {
+    pub registrants: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct WinnersResponse {
+    pub winners: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoucherResponse {
+    pub hash: String,
+    pub available: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DelegatedMinterResponse {
+    pub minter: String,
+    pub expiration: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingMintResponse {
+    pub id: u64,
+    /// The serial of the next instance to be minted by `ResumeMint`, or None if the badge has no
+    /// queued mint in progress
+    pub next_serial: Option<u64>,
+    /// The number of instances queued but not yet minted
+    pub remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SvgTemplateResponse {
+    pub id: u64,
+    pub template: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommemorativeTokenResponse {
+    pub id: u64,
+    /// The full token factory denom, i.e. `factory/{hub}/{subdenom}`
+    pub denom: Option<String>,
+    pub amount: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MediaResponse {
+    pub id: u64,
+    pub media: Vec<MediaAsset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LocalesResponse {
+    pub id: u64,
+    pub locales: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct NonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct NftResponse {
+    pub token_id: String,
+    pub used: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositResponse {
+    pub collection: String,
+    pub token_id: String,
+    /// The badge the token was deposited for, or None if the token hasn't been deposited
+    pub id: Option<u64>,
+    pub depositor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenSerialResponse {
+    pub token_id: String,
+    pub id: u64,
+    pub serial: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct OwnerResponse {
     pub user: String,
     pub claimed: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SupplyResponse {
+    pub id: u64,
+    pub max_supply: Option<u64>,
+    pub current_supply: u64,
+    /// Number of instances that can still be minted. None if the badge has no max supply
+    pub remaining: Option<u64>,
+    /// Whether the badge can be minted at the current block, assuming the minter is eligible
+    pub mintable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RuleStatsResponse {
+    /// The type of mint rule, e.g. "by_key"
+    pub rule: String,
+    /// Number of badges currently using this type of rule
+    pub badge_count: u64,
+    /// Number of instances minted while their badges were using this type of rule
+    pub minted_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StatsResponse {
+    pub badge_count: u64,
+    pub minted_count: u64,
+    pub rules: Vec<RuleStatsResponse>,
+    pub supplies: Vec<SupplyResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnerBatchResponse {
+    pub owners: Vec<OwnerResponse>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct OwnersResponse {
     pub owners: Vec<String>,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RevocationsResponse {
+    pub serials: Vec<u64>,
+}
@@ -1,11 +1,30 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 
 mod badge;
+mod commemorative;
+pub mod distributor;
 mod fee;
 pub mod hub;
+pub mod locale;
+mod media;
 mod mint_rule;
 pub mod nft;
+mod phase;
+mod price;
+mod rate_limit;
+mod revenue;
+pub mod signing;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod token_id;
 
-pub use badge::Badge;
-pub use fee::FeeRate;
+pub use badge::{Badge, MetadataRevision};
+pub use commemorative::CommemorativeToken;
+pub use fee::{FeeDestination, FeeRate, FeeRouting};
+pub use media::{MediaAsset, MediaPurpose};
 pub use mint_rule::MintRule;
+pub use phase::Phase;
+pub use price::Price;
+pub use rate_limit::{RateLimit, Window};
+pub use revenue::{RevenueShare, BPS_DENOMINATOR};
+pub use token_id::TokenIdFormat;
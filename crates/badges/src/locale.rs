@@ -0,0 +1,47 @@
+use sg_metadata::Metadata;
+
+/// Whether a string is a locale tag the Hub accepts: a language code of 2 or 3 lowercase letters,
+/// optionally followed by a dash and a region or script subtag of 2 to 8 letters or digits, e.g.
+/// `en`, `pt-BR` or `zh-Hant`.
+pub fn is_valid_locale(locale: &str) -> bool {
+    let (language, subtag) = match locale.split_once('-') {
+        Some((language, subtag)) => (language, Some(subtag)),
+        None => (locale, None),
+    };
+
+    let language_ok =
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let subtag_ok = match subtag {
+        Some(subtag) => {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        },
+        None => true,
+    };
+
+    language_ok && subtag_ok
+}
+
+/// The locales to look for, in order of preference, when the given one is requested: the locale
+/// itself and, if it has a subtag, its language alone. E.g. `pt-BR` falls back to `pt`.
+pub fn locale_fallbacks(locale: &str) -> Vec<&str> {
+    match locale.split_once('-') {
+        Some((language, _)) => vec![locale, language],
+        None => vec![locale],
+    }
+}
+
+/// Apply a translation on top of a badge's metadata. Fields present in the translation replace the
+/// badge's own, while those missing from it are kept untranslated.
+pub fn localize(metadata: Metadata, translation: Metadata) -> Metadata {
+    Metadata {
+        image: translation.image.or(metadata.image),
+        image_data: translation.image_data.or(metadata.image_data),
+        external_url: translation.external_url.or(metadata.external_url),
+        description: translation.description.or(metadata.description),
+        name: translation.name.or(metadata.name),
+        attributes: translation.attributes.or(metadata.attributes),
+        background_color: translation.background_color.or(metadata.background_color),
+        animation_url: translation.animation_url.or(metadata.animation_url),
+        youtube_url: translation.youtube_url.or(metadata.youtube_url),
+    }
+}
@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A media file attached to a badge, in addition to the single image and animation url that the
+/// metadata standard allows, e.g. a video along with its poster image and a 3D model.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MediaAsset {
+    /// Location of the file, as an https://, ipfs:// or ar:// url
+    pub uri: String,
+
+    /// MIME type of the file, e.g. `video/mp4` or `model/gltf-binary`. Its top-level type must
+    /// match the asset's purpose.
+    pub mime_type: String,
+
+    /// What the file is to be used for
+    pub purpose: MediaPurpose,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaPurpose {
+    Image,
+    Video,
+    Audio,
+    #[serde(rename = "3d")]
+    Model,
+}
+
+impl MediaPurpose {
+    /// The top-level MIME type that files of this purpose must have
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            MediaPurpose::Image => "image",
+            MediaPurpose::Video => "video",
+            MediaPurpose::Audio => "audio",
+            MediaPurpose::Model => "model",
+        }
+    }
+}
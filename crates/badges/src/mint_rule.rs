@@ -1,8 +1,11 @@
 use std::fmt;
 
+use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::Price;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum MintRule {
@@ -12,8 +15,23 @@ pub enum MintRule {
     /// implements custom minting rules.
     ByMinter(String),
 
+    /// Similar to the `ByMinter` rule, but there can be multiple minters, e.g. a team of operators
+    /// who all push mints for the same badge.
+    ///
+    /// To add or remove minters, the manager uses the `add_minters` and `remove_minters` execute
+    /// methods.
+    ByMinters,
+
+    /// Badges can be minted by a designated external contract, e.g. a quest engine or a game, which
+    /// calls the `hook_mint` execute method whenever one of its users earns the badge. Provide the
+    /// contract's address.
+    ///
+    /// This allows other protocols to use the Hub as their badge backend.
+    ByContract(String),
+
     /// Badges can be minted upon the the signature signed by a designated private key. Provide the
-    /// associated public key in hex encoding.
+    /// associated public key in hex encoding, or for keys held in Ethereum wallets, either the
+    /// public key or the hex-encoded Ethereum address without the `0x` prefix.
     ///
     /// This key can be reused as many time as possible for minting, as long as the badge's deadline
     /// and max supply have not been reached.
@@ -28,25 +46,267 @@ pub enum MintRule {
     /// `clear_keys` method to remove unused keys from the contract storage, thereby reducing the
     /// size of the chain's state.
     ByKeys,
+
+    /// Badges can be minted by presenting a voucher code. The manager uploads the SHA-256 hashes of
+    /// the codes using the `add_vouchers` execute method, and distributes the codes off-chain, e.g.
+    /// printed as QR codes. Claimants submit the code itself; each code can only be used once.
+    ///
+    /// Unlike the "by keys" rule, no signing is involved, so no signing backend is needed.
+    ByVoucher,
+
+    /// Badges can be claimed by accounts on an on-chain allowlist, using the `mint_by_allowlist`
+    /// execute method. Each account can only claim once.
+    ///
+    /// To add or remove accounts, the manager uses the `add_to_allowlist` and
+    /// `remove_from_allowlist` execute methods. For small curated drops, this is simpler and more
+    /// auditable than signatures.
+    ByAllowlist,
+
+    /// Badges can be minted by anyone who pays the specified price. The hub treasury takes its fee
+    /// from the payment first, then the badge's revenue shares are paid out, and the manager
+    /// receives whatever remains.
+    ///
+    /// If the price is in a CW20 token, the user must first grant the Hub contract an allowance,
+    /// which the Hub uses to pull the tokens at the time of minting.
+    ByPayment(Price),
+
+    /// Badges can be minted by holders of tokens of the specified CW721 collection. Provide the
+    /// collection's contract address.
+    ///
+    /// Each token can only be used once to mint the badge.
+    ByNft(String),
+
+    /// Badges can be minted by depositing a token of the specified CW721 collection, i.e. sending
+    /// it to the Hub with the collection's `send_nft` method. Depending on `burn`, the deposited
+    /// token is either burned, or held by the Hub in escrow for good.
+    ///
+    /// With the Hub's own NFT contract as the collection, this allows users to upgrade instances
+    /// of one badge to another.
+    ByDeposit {
+        collection: String,
+        burn: bool,
+    },
+
+    /// Badges can be minted by accounts that have delegated at least `min_amount` of the staking
+    /// token to the specified validator. Each account can only mint once.
+    ByDelegation {
+        validator: String,
+        min_amount: Uint128,
+    },
+
+    /// Badges can be minted by members of the specified cw4 group, e.g. a DAO's membership, with at
+    /// least `min_weight` if specified. Membership is queried from the group contract at the time
+    /// of minting. Each account can only mint once.
+    ByGroup {
+        group: String,
+        min_weight: Option<u64>,
+    },
+
+    /// Badges are distributed by raffle. Users register using the `register_for_raffle` execute
+    /// method until `registration_end` (a UNIX timestamp, in seconds); the raffle is then drawn
+    /// using the `draw_raffle` method, which mints the badge to `winners` randomly selected
+    /// registrants.
+    ///
+    /// The manager may draw at any time, closing registration early; anyone else may only draw
+    /// once registration has ended. The raffle can only be drawn once.
+    ///
+    /// Since all registrants are loaded and all winners minted by the single `draw_raffle` call,
+    /// the hub caps both the number of winners and the number of accounts that can register.
+    ///
+    /// NOTE: The draw is seeded by the block info, which is pseudorandom at best and could be
+    /// influenced by the block proposer. Don't use this rule for high-value badges.
+    ByRaffle {
+        registration_end: u64,
+        winners: u64,
+    },
+
+    /// Badges can be claimed by anyone using the `claim` execute method. Each account can only
+    /// claim once.
+    ///
+    /// Useful for low-stakes participation badges where distributing signatures is overkill.
+    Open,
+
+    /// Badges can be minted if all of the sub-rules are satisfied, e.g. the user must both present
+    /// a signature AND make a payment.
+    ///
+    /// Minting under a composite rule is done with the `mint_by_composite` execute method. Each
+    /// account can only mint once.
+    AllOf(Vec<MintRule>),
+
+    /// Badges can be minted if any one of the sub-rules is satisfied, e.g. the user can present a
+    /// signature signed by either one of two keys.
+    AnyOf(Vec<MintRule>),
 }
 
 impl fmt::Display for MintRule {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let s = match self {
             MintRule::ByMinter(minter) => format!("by_minter:{}", minter),
+            MintRule::ByMinters => "by_minters".to_string(),
+            MintRule::ByContract(contract) => format!("by_contract:{}", contract),
             MintRule::ByKey(pubkey) => format!("by_key:{}", pubkey),
             MintRule::ByKeys => "by_keys".to_string(),
+            MintRule::ByVoucher => "by_voucher".to_string(),
+            MintRule::ByAllowlist => "by_allowlist".to_string(),
+            MintRule::ByPayment(price) => format!("by_payment:{}", price),
+            MintRule::ByNft(collection) => format!("by_nft:{}", collection),
+            MintRule::ByDeposit {
+                collection,
+                burn,
+            } => format!("by_deposit:{}:{}", collection, if *burn { "burn" } else { "escrow" }),
+            MintRule::ByDelegation {
+                validator,
+                min_amount,
+            } => format!("by_delegation:{}:{}", validator, min_amount),
+            MintRule::ByGroup {
+                group,
+                min_weight,
+            } => format!("by_group:{}:{}", group, min_weight.unwrap_or(0)),
+            MintRule::ByRaffle {
+                registration_end,
+                winners,
+            } => format!("by_raffle:{}:{}", registration_end, winners),
+            MintRule::Open => "open".to_string(),
+            MintRule::AllOf(rules) => format!("all_of:[{}]", join(rules)),
+            MintRule::AnyOf(rules) => format!("any_of:[{}]", join(rules)),
         };
         write!(f, "{}", s)
     }
 }
 
+fn join(rules: &[MintRule]) -> String {
+    rules.iter().map(|rule| rule.to_string()).collect::<Vec<_>>().join(",")
+}
+
 impl MintRule {
     pub fn by_minter(minter: impl Into<String>) -> Self {
         MintRule::ByMinter(minter.into())
     }
 
+    pub fn by_contract(contract: impl Into<String>) -> Self {
+        MintRule::ByContract(contract.into())
+    }
+
     pub fn by_key(key: impl Into<String>) -> Self {
         MintRule::ByKey(key.into())
     }
+
+    pub fn by_payment(price: Price) -> Self {
+        MintRule::ByPayment(price)
+    }
+
+    pub fn by_nft(collection: impl Into<String>) -> Self {
+        MintRule::ByNft(collection.into())
+    }
+
+    pub fn by_deposit(collection: impl Into<String>, burn: bool) -> Self {
+        MintRule::ByDeposit {
+            collection: collection.into(),
+            burn,
+        }
+    }
+
+    pub fn by_delegation(validator: impl Into<String>, min_amount: u128) -> Self {
+        MintRule::ByDelegation {
+            validator: validator.into(),
+            min_amount: Uint128::new(min_amount),
+        }
+    }
+
+    pub fn by_group(group: impl Into<String>, min_weight: Option<u64>) -> Self {
+        MintRule::ByGroup {
+            group: group.into(),
+            min_weight,
+        }
+    }
+
+    pub fn by_raffle(registration_end: u64, winners: u64) -> Self {
+        MintRule::ByRaffle {
+            registration_end,
+            winners,
+        }
+    }
+
+    pub fn all_of(rules: impl Into<Vec<MintRule>>) -> Self {
+        MintRule::AllOf(rules.into())
+    }
+
+    pub fn any_of(rules: impl Into<Vec<MintRule>>) -> Self {
+        MintRule::AnyOf(rules.into())
+    }
+
+    /// Whether this rule, or any of its sub-rules, is the "by keys" rule, meaning that pubkeys
+    /// need to be whitelisted for minting.
+    pub fn uses_keys(&self) -> bool {
+        match self {
+            MintRule::ByKeys => true,
+            MintRule::AllOf(rules) | MintRule::AnyOf(rules) => rules.iter().any(Self::uses_keys),
+            _ => false,
+        }
+    }
+
+    /// Whether this rule, or any of its sub-rules, is the "by allowlist" rule, meaning that
+    /// accounts need to be added to the allowlist for minting.
+    pub fn uses_allowlist(&self) -> bool {
+        match self {
+            MintRule::ByAllowlist => true,
+            MintRule::AllOf(rules) | MintRule::AnyOf(rules) => {
+                rules.iter().any(Self::uses_allowlist)
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether this rule, or any of its sub-rules, is the "by voucher" rule, meaning that voucher
+    /// hashes need to be uploaded for minting.
+    pub fn uses_vouchers(&self) -> bool {
+        match self {
+            MintRule::ByVoucher => true,
+            MintRule::AllOf(rules) | MintRule::AnyOf(rules) => {
+                rules.iter().any(Self::uses_vouchers)
+            },
+            _ => false,
+        }
+    }
+
+    /// The number of "by payment" rules among this rule and its sub-rules
+    pub fn num_payments(&self) -> usize {
+        match self {
+            MintRule::ByPayment(_) => 1,
+            MintRule::AllOf(rules) | MintRule::AnyOf(rules) => {
+                rules.iter().map(Self::num_payments).sum()
+            },
+            _ => 0,
+        }
+    }
+
+    /// The rule's type, without its parameters, e.g. "by_key" for `ByKey(pubkey)`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MintRule::ByMinter(_) => "by_minter",
+            MintRule::ByMinters => "by_minters",
+            MintRule::ByContract(_) => "by_contract",
+            MintRule::ByKey(_) => "by_key",
+            MintRule::ByKeys => "by_keys",
+            MintRule::ByVoucher => "by_voucher",
+            MintRule::ByAllowlist => "by_allowlist",
+            MintRule::ByPayment(_) => "by_payment",
+            MintRule::ByNft(_) => "by_nft",
+            MintRule::ByDeposit {
+                ..
+            } => "by_deposit",
+            MintRule::ByDelegation {
+                ..
+            } => "by_delegation",
+            MintRule::ByGroup {
+                ..
+            } => "by_group",
+            MintRule::ByRaffle {
+                ..
+            } => "by_raffle",
+            MintRule::Open => "open",
+            MintRule::AllOf(_) => "all_of",
+            MintRule::AnyOf(_) => "any_of",
+        }
+    }
 }
@@ -1,7 +1,9 @@
-use cosmwasm_std::Empty;
+use cosmwasm_std::{Binary, Empty, StdError, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sg_metadata::Metadata;
+use sg_metadata::{Metadata, Trait};
+
+use crate::MediaAsset;
 
 pub type Extension = Option<Empty>;
 
@@ -17,8 +19,337 @@ pub struct InstantiateMsg {
 }
 
 // message types
-pub type ExecuteMsg = sg721::ExecuteMsg<Extension, Empty>;
-pub type QueryMsg = sg721_base::msg::QueryMsg;
+pub type ExecuteMsg = sg721::ExecuteMsg<Extension, ExtensionMsg>;
+
+/// Execute messages specific to badges, wrapped in SG-721's `Extension` variant
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ExtensionMsg {
+    /// Point the collection to a new Hub contract, which becomes the minter. Can only be invoked
+    /// by the contract's admin
+    UpdateMinter {
+        minter: String,
+    },
+
+    /// Cache a badge's metadata, so that token info can be served without querying the Hub. Can
+    /// only be invoked by the Hub, whenever a badge is created or its metadata edited
+    CacheMetadata {
+        id: u64,
+        cache: MetadataCache,
+    },
+
+    /// Set or remove the SVG template from which the images of a badge's tokens are rendered
+    /// on-chain. Can only be invoked by the Hub
+    SetSvgTemplate {
+        id: u64,
+        template: Option<String>,
+    },
+
+    /// Replace the media assets attached to a badge. An empty list removes them. Can only be
+    /// invoked by the Hub
+    SetMedia {
+        id: u64,
+        media: Vec<MediaAsset>,
+    },
+
+    /// Set or remove a translation of a badge's metadata into a locale. Can only be invoked by the
+    /// Hub
+    SetLocalizedMetadata {
+        id: u64,
+        locale: String,
+        metadata: Option<Metadata>,
+    },
+
+    /// Attach traits to a single instance of a badge, on top of the badge's shared metadata. An
+    /// empty list removes the instance's traits. Can only be invoked by the Hub
+    SetSerialTraits {
+        id: u64,
+        serial: u64,
+        traits: Vec<Trait>,
+    },
+
+    /// Send a token to another chain over an ICS-721 channel. The token is held in escrow by this
+    /// contract until it is sent back, or returned to the sender if the transfer fails or times
+    /// out. Can only be invoked by the token's owner or an approved spender, and only for
+    /// transferrable badges
+    IbcTransfer {
+        token_id: String,
+        channel_id: String,
+        /// Address of the recipient on the other chain
+        receiver: String,
+        /// How long relayers have to deliver the transfer. Defaults to 10 minutes if not provided
+        timeout_seconds: Option<u64>,
+    },
+
+    /// Record the current holders of a badge, along with the serials each of them holds, so that
+    /// they can be queried later. Can only be invoked by the badge's manager or the Hub's
+    /// developer.
+    ///
+    /// Only the first chunk of serials is walked; if the badge has more, the snapshot is finished
+    /// with `ResumeSnapshot`. A badge can only have one snapshot in progress at a time.
+    TakeSnapshot {
+        id: u64,
+    },
+
+    /// Walk the next chunk of serials of the badge's snapshot in progress. Can be invoked by
+    /// anyone
+    ResumeSnapshot {
+        id: u64,
+        limit: Option<u32>,
+    },
+
+    /// After migrating from a version without the holdings index, index the holdings of the next
+    /// chunk of existing tokens. The `Portfolio` query is unavailable until all tokens have been
+    /// indexed. Can be invoked by anyone
+    BackfillHoldings {
+        limit: Option<u32>,
+    },
+}
+
+/// The parts of a badge needed to render its tokens' info
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataCache {
+    pub metadata: Metadata,
+    pub token_uri: Option<String>,
+    pub max_supply: Option<u64>,
+}
+
+/// The packet sent over ICS-721 channels, as defined by the spec:
+/// https://github.com/cosmos/ibc/tree/main/spec/app/ics-721-nft-transfer
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NonFungibleTokenPacketData {
+    /// The collection the tokens belong to, prefixed by the port and channel of each hop it has
+    /// been sent through, e.g. `wasm.stars1.../channel-0/stars1...`
+    pub class_id: String,
+    pub class_uri: Option<String>,
+    pub class_data: Option<Binary>,
+    pub token_ids: Vec<String>,
+    pub token_uris: Option<Vec<String>>,
+    /// For badges, the on-chain metadata of each token, as returned by the `NftInfo` query
+    pub token_data: Option<Vec<Binary>>,
+    pub sender: String,
+    pub receiver: String,
+    pub memo: Option<String>,
+}
+
+/// The acknowledgement written for ICS-721 packets
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Ics721Ack {
+    Result(Binary),
+    Error(String),
+}
+
+/// A point-in-time record of a badge's holders. The holders themselves are stored separately,
+/// each with the serials they held
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Snapshot {
+    /// The block height at which the snapshot was taken
+    pub height: u64,
+    /// Number of accounts holding at least one token of the badge
+    pub num_holders: u64,
+    /// Number of tokens of the badge in existence, i.e. not burned
+    pub num_tokens: u64,
+}
+
+/// The standard SG-721 query messages, plus the CW-2981 royalty extension and queries specific to
+/// badges
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
+    /// If a locale is given, the metadata is translated into it where a translation exists
+    NftInfo {
+        token_id: String,
+        locale: Option<String>,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+        locale: Option<String>,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Minter {},
+    CollectionInfo {},
+    Extension {
+        msg: Cw2981QueryMsg,
+    },
+
+    /// Enumerate the existing tokens of a badge, along with their owners, in ascending order of
+    /// serial numbers. Burned tokens are skipped. The number of serials looked at per query is
+    /// capped, so a page may hold fewer than `limit` tokens even if more exist; continue from the
+    /// response's `next_start_after`. Returns TokensByBadgeResponse
+    TokensByBadge {
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// The tokens held by an account, grouped by badge, in ascending order of badge ids. Fails
+    /// while the holdings of existing tokens are still being backfilled. Returns
+    /// PortfolioResponse
+    Portfolio {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate the snapshots taken of a badge's holders, oldest first. Returns SnapshotsResponse
+    Snapshots {
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Enumerate the holders recorded in a snapshot, along with the serials each of them held.
+    /// Fails if the snapshot is still in progress. Returns SnapshotHoldersResponse
+    SnapshotHolders {
+        id: u64,
+        snapshot: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw2981QueryMsg {
+    /// The royalty owed to the badge's royalty recipient when the token is sold at the given price.
+    /// Returns RoyaltiesInfoResponse
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+
+    /// Whether the contract implements CW-2981 royalties. Returns CheckRoyaltiesResponse
+    CheckRoyalties {},
+}
+
+impl TryFrom<QueryMsg> for sg721_base::msg::QueryMsg {
+    type Error = StdError;
+
+    fn try_from(msg: QueryMsg) -> Result<Self, Self::Error> {
+        use sg721_base::msg::QueryMsg as Sg721QueryMsg;
+
+        Ok(match msg {
+            QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            } => Sg721QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            },
+            QueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+            } => Sg721QueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+            },
+            QueryMsg::Approvals {
+                token_id,
+                include_expired,
+            } => Sg721QueryMsg::Approvals {
+                token_id,
+                include_expired,
+            },
+            QueryMsg::AllOperators {
+                owner,
+                include_expired,
+                start_after,
+                limit,
+            } => Sg721QueryMsg::AllOperators {
+                owner,
+                include_expired,
+                start_after,
+                limit,
+            },
+            QueryMsg::NumTokens {} => Sg721QueryMsg::NumTokens {},
+            QueryMsg::ContractInfo {} => Sg721QueryMsg::ContractInfo {},
+            QueryMsg::NftInfo {
+                token_id,
+                ..
+            } => Sg721QueryMsg::NftInfo {
+                token_id,
+            },
+            QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+                ..
+            } => Sg721QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+            },
+            QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            } => Sg721QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+            } => Sg721QueryMsg::AllTokens {
+                start_after,
+                limit,
+            },
+            QueryMsg::Minter {} => Sg721QueryMsg::Minter {},
+            QueryMsg::CollectionInfo {} => Sg721QueryMsg::CollectionInfo {},
+            QueryMsg::Extension {
+                ..
+            }
+            | QueryMsg::TokensByBadge {
+                ..
+            }
+            | QueryMsg::Portfolio {
+                ..
+            }
+            | QueryMsg::Snapshots {
+                ..
+            }
+            | QueryMsg::SnapshotHolders {
+                ..
+            } => {
+                return Err(StdError::generic_err("query is not supported by sg721"));
+            },
+        })
+    }
+}
 
 // response types
 pub type ContractInfoResponse = cw721::ContractInfoResponse;
@@ -32,3 +363,74 @@ pub type NftInfoResponse = cw721::NftInfoResponse<Metadata>;
 pub type AllNftInfoResponse = cw721::AllNftInfoResponse<Metadata>;
 pub type MinterResponse = cw721_base::MinterResponse;
 pub type CollectionInfoResponse = sg721_base::msg::CollectionInfoResponse;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BadgeTokenResponse {
+    pub token_id: String,
+    pub serial: u64,
+    pub owner: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokensByBadgeResponse {
+    pub tokens: Vec<BadgeTokenResponse>,
+    /// The serial to query from next as `start_after`, or None if all serials have been looked at
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HoldingResponse {
+    pub id: u64,
+    pub serials: Vec<u64>,
+    /// Name of the badge, from its metadata
+    pub name: Option<String>,
+    /// Image of the badge, from its metadata
+    pub image: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioResponse {
+    pub owner: String,
+    pub holdings: Vec<HoldingResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SnapshotResponse {
+    pub snapshot: u64,
+    pub height: u64,
+    pub num_holders: u64,
+    pub num_tokens: u64,
+    /// Whether all of the badge's serials have been walked
+    pub complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SnapshotsResponse {
+    pub id: u64,
+    pub snapshots: Vec<SnapshotResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SnapshotHolderResponse {
+    pub owner: String,
+    pub serials: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SnapshotHoldersResponse {
+    pub id: u64,
+    pub snapshot: u64,
+    pub holders: Vec<SnapshotHolderResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoyaltiesInfoResponse {
+    /// The account to pay the royalty to; empty if the badge has no royalty
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
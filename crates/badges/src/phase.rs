@@ -0,0 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::MintRule;
+
+/// A period during which a badge is minted by a rule different from its own, e.g. an allowlist
+/// phase followed by a public phase. Phases of a badge are sequential and must not overlap.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Phase {
+    /// The rule by which instances of the badge are to be minted during this phase, which also
+    /// determines the price if any
+    pub rule: MintRule,
+
+    /// The timestamp at which this phase starts
+    pub start_time: u64,
+
+    /// The timestamp after which this phase ends. Setting this to None means the phase lasts until
+    /// the badge expires, in which case it must be the last phase.
+    pub end_time: Option<u64>,
+
+    /// The maximum number of badge instances that can be minted to a single account during this
+    /// phase. This is in addition to the badge's own per-owner limit.
+    pub max_per_owner: Option<u64>,
+}
+
+impl Phase {
+    /// Whether the phase is active at the given timestamp
+    pub fn is_active(&self, time: u64) -> bool {
+        time >= self.start_time && !matches!(self.end_time, Some(end_time) if time > end_time)
+    }
+}
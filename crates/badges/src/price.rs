@@ -0,0 +1,65 @@
+use std::fmt;
+
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The price a user needs to pay in order to mint a badge under the "by payment" rule.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Price {
+    /// The price is paid in a native coin, which must be attached to the minting message.
+    Native(Coin),
+
+    /// The price is paid in a CW20 token. Before minting, the user must give the Hub contract an
+    /// allowance of at least the specified amount; the Hub pulls the tokens using `TransferFrom`.
+    Cw20 {
+        token: String,
+        amount: Uint128,
+    },
+
+    /// The price is quoted in US dollars and paid in a native coin, which must be attached to the
+    /// minting message. The amount due is converted at the time of minting using the Hub's price
+    /// oracle, so the price doesn't need to be updated as the coin's value moves. Any excess coins
+    /// attached, e.g. as slippage buffer, are refunded.
+    Usd {
+        amount: Decimal,
+        denom: String,
+    },
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Price::Native(coin) => write!(f, "{}", coin),
+            Price::Cw20 {
+                token,
+                amount,
+            } => write!(f, "{}{}", amount, token),
+            Price::Usd {
+                amount,
+                denom,
+            } => write!(f, "{}usd:{}", amount, denom),
+        }
+    }
+}
+
+impl Price {
+    pub fn native(amount: u128, denom: impl Into<String>) -> Self {
+        Price::Native(Coin::new(amount, denom))
+    }
+
+    pub fn usd(amount: Decimal, denom: impl Into<String>) -> Self {
+        Price::Usd {
+            amount,
+            denom: denom.into(),
+        }
+    }
+
+    pub fn cw20(token: impl Into<String>, amount: u128) -> Self {
+        Price::Cw20 {
+            token: token.into(),
+            amount: Uint128::new(amount),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Throttles how fast a badge can be minted, e.g. to prevent paid or open mints from being drained
+/// by bots in a single block.
+///
+/// Raffle draws are exempt, since all of a raffle's winners are minted at once.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RateLimit {
+    /// The maximum number of badge instances that can be minted within a single window
+    pub max_mints: u64,
+
+    /// The length of each window
+    pub window: Window,
+}
+
+/// Time is divided into fixed windows of the specified length. The mint counter resets at the start
+/// of each window.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Window {
+    /// Windows measured in number of blocks
+    Blocks(u64),
+
+    /// Windows measured in number of seconds
+    Seconds(u64),
+}
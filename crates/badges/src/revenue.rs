@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The number of basis points that make up the whole of a payment
+pub const BPS_DENOMINATOR: u16 = 10000;
+
+/// A share of the proceeds from minting a badge under the "by payment" rule, paid to an account
+/// other than the badge's manager, e.g. an artist or a collaborator.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RevenueShare {
+    /// The account that receives this share
+    pub recipient: String,
+
+    /// The size of this share, in basis points (1/100 of a percent) of the proceeds
+    pub bps: u16,
+}
+
+impl RevenueShare {
+    pub fn new(recipient: impl Into<String>, bps: u16) -> Self {
+        RevenueShare {
+            recipient: recipient.into(),
+            bps,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! The messages that must be signed to mint badges under the "by key" or "by keys" rule.
+//!
+//! The Hub verifies claim signatures against exactly these messages, so backend services issuing
+//! signatures should produce them here rather than re-implement the format. With the `signing`
+//! feature enabled, the messages can also be signed with a secp256k1 key. The feature pulls in
+//! `k256`, so it's meant for native code only and shouldn't be enabled in contracts.
+
+use cosmwasm_std::Binary;
+use cw_utils::Expiration;
+
+use crate::hub::{ExecuteMsg, SignMode};
+
+/// The message the user needs to sign to claim the badge under "by key" or "by keys" rule.
+///
+/// Besides the badge id and the user address, the message includes the Hub contract's address and
+/// the chain id, so that a signature issued for one badge or one Hub deployment can't be replayed
+/// against another.
+///
+/// The message also commits to the badge's current nonce. The manager can bump the nonce to
+/// invalidate all signatures that have been issued but not yet used, e.g. if they have leaked.
+///
+/// If the signature comes with a deadline, the deadline is appended to the message as well, so that
+/// it can't be extended or removed by whoever submits the signature.
+pub fn message(
+    hub: &str,
+    chain_id: &str,
+    id: u64,
+    user: &str,
+    nonce: u64,
+    deadline: Option<&Expiration>,
+) -> String {
+    let mut message = format!(
+        "claim badge {} for user {} at hub {} on chain {} with nonce {}",
+        id, user, hub, chain_id, nonce,
+    );
+    match deadline {
+        Some(Expiration::AtHeight(height)) => {
+            message += &format!(" before height {}", height);
+        },
+        Some(Expiration::AtTime(time)) => {
+            message += &format!(" before time {}", time.seconds());
+        },
+        _ => (),
+    }
+    message
+}
+
+/// The bytes that are actually signed, depending on the sign mode: either the message itself, an
+/// ADR-36 sign doc wrapping the message, with the sender as the signer, or the message with the
+/// prefix Ethereum's `personal_sign` adds.
+/// https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md
+/// https://eips.ethereum.org/EIPS/eip-191
+pub fn sign_doc(message: String, sign_mode: &SignMode, signer: &str) -> String {
+    match sign_mode {
+        SignMode::Raw => message,
+        SignMode::EthPersonalSign => {
+            format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message)
+        },
+        SignMode::Adr36 => format!(
+            concat!(
+                r#"{{"account_number":"0","chain_id":"","#,
+                r#""fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+                r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"#,
+                r#""sequence":"0"}}"#,
+            ),
+            Binary::from(message.as_bytes()).to_base64(),
+            signer,
+        ),
+    }
+}
+
+/// A claim of a badge under the "by key" or "by keys" rule, i.e. everything the signed message
+/// commits to
+#[derive(Clone, Debug, PartialEq)]
+pub struct Claim {
+    /// Address of the Hub contract
+    pub hub: String,
+    pub chain_id: String,
+    pub id: u64,
+    /// The account the badge is to be minted to
+    pub owner: String,
+    /// The badge's current nonce, as returned by the Hub's `nonce` query
+    pub nonce: u64,
+    pub deadline: Option<Expiration>,
+}
+
+impl Claim {
+    pub fn message(&self) -> String {
+        message(&self.hub, &self.chain_id, self.id, &self.owner, self.nonce, self.deadline.as_ref())
+    }
+
+    /// The bytes to be signed in the given sign mode. For ADR-36, the signer is the account that
+    /// will submit the claim, unless it's relayed on behalf of the signer.
+    pub fn sign_doc(&self, sign_mode: &SignMode, signer: &str) -> String {
+        sign_doc(self.message(), sign_mode, signer)
+    }
+
+    /// The message to submit the claim under the "by key" rule with the given signature. The
+    /// signer only needs to be specified if the claim is signed in ADR-36 mode and relayed.
+    pub fn mint_by_key_msg(
+        &self,
+        signature: impl Into<String>,
+        sign_mode: SignMode,
+        signer: Option<String>,
+    ) -> ExecuteMsg {
+        ExecuteMsg::MintByKey {
+            id: self.id,
+            owner: self.owner.clone(),
+            deadline: self.deadline,
+            sign_mode: Some(sign_mode),
+            signer,
+            signature: signature.into(),
+        }
+    }
+
+    /// The message to submit the claim under the "by keys" rule with the given whitelisted pubkey
+    /// and signature; see `mint_by_key_msg`
+    pub fn mint_by_keys_msg(
+        &self,
+        pubkey: impl Into<String>,
+        signature: impl Into<String>,
+        sign_mode: SignMode,
+        signer: Option<String>,
+    ) -> ExecuteMsg {
+        ExecuteMsg::MintByKeys {
+            id: self.id,
+            owner: self.owner.clone(),
+            deadline: self.deadline,
+            sign_mode: Some(sign_mode),
+            signer,
+            pubkey: pubkey.into(),
+            signature: signature.into(),
+        }
+    }
+
+    /// Sign the claim with a secp256k1 key in the given sign mode, and encode the signature in hex,
+    /// as the Hub expects it. For Ethereum's `personal_sign`, this is the 65-byte recoverable
+    /// signature over the Keccak-256 hash; otherwise the 64-byte signature over the SHA-256 hash.
+    #[cfg(feature = "signing")]
+    pub fn sign(
+        &self,
+        privkey: &k256::ecdsa::SigningKey,
+        sign_mode: &SignMode,
+        signer: &str,
+    ) -> String {
+        use k256::ecdsa::signature::{DigestSigner, Signer};
+        use k256::ecdsa::{recoverable, Signature};
+        use sha3::{Digest, Keccak256};
+
+        let sign_doc = self.sign_doc(sign_mode, signer);
+        match sign_mode {
+            SignMode::EthPersonalSign => {
+                let digest = Keccak256::new_with_prefix(sign_doc.as_bytes());
+                let signature: recoverable::Signature = privkey.sign_digest(digest);
+                hex::encode(signature.as_ref())
+            },
+            SignMode::Raw | SignMode::Adr36 => {
+                let signature: Signature = privkey.sign(sign_doc.as_bytes());
+                hex::encode(signature.as_ref())
+            },
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Mocks for unit testing contracts that query the Hub, e.g. the NFT contract querying a badge's
+//! metadata, without having to run the Hub contract itself. Enabled by the `testing` feature.
+
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::{to_binary, Addr, Decimal, QuerierResult};
+
+use crate::hub::{BadgeResponse, ConfigResponse, QueryMsg, TokenSerialResponse};
+use crate::{Badge, FeeRate, TokenIdFormat};
+
+/// Answers the Hub's `badge`, `config` and `token_serial` queries from badges and token serials set
+/// by the test. The NFT contract is assumed to be the mock contract.
+pub struct HubQuerier {
+    pub contract_addr: Addr,
+    pub token_id_format: TokenIdFormat,
+    badges: HashMap<u64, Badge>,
+    token_serials: HashMap<String, (u64, u64)>,
+}
+
+impl Default for HubQuerier {
+    fn default() -> Self {
+        HubQuerier::new("hub")
+    }
+}
+
+impl HubQuerier {
+    pub fn new(contract_addr: impl Into<String>) -> Self {
+        HubQuerier {
+            contract_addr: Addr::unchecked(contract_addr),
+            token_id_format: TokenIdFormat::default(),
+            badges: HashMap::default(),
+            token_serials: HashMap::default(),
+        }
+    }
+
+    pub fn set_badge(&mut self, id: u64, badge: Badge) {
+        self.badges.insert(id, badge);
+    }
+
+    pub fn set_token_serial(&mut self, token_id: impl Into<String>, id: u64, serial: u64) {
+        self.token_serials.insert(token_id.into(), (id, serial));
+    }
+
+    pub fn handle_query(&self, contract_addr: &Addr, msg: QueryMsg) -> QuerierResult {
+        if contract_addr != &self.contract_addr {
+            panic!(
+                "[mock]: made a badge hub query but addresses is incorrect: expected {}, found {}",
+                self.contract_addr, contract_addr
+            );
+        }
+
+        match msg {
+            QueryMsg::Badge {
+                id,
+                ..
+            } => {
+                let badge = self
+                    .badges
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("[mock]: cannot find badge with id {}", id));
+                let res = BadgeResponse::from((id, badge));
+                Ok(to_binary(&res).into()).into()
+            },
+
+            QueryMsg::Config {} => {
+                let res = ConfigResponse {
+                    developer: "developer".to_string(),
+                    pending_developer: None,
+                    nft: MOCK_CONTRACT_ADDR.to_string(),
+                    badge_count: self.badges.len() as u64,
+                    fee_rate: FeeRate {
+                        metadata: Decimal::zero(),
+                        key: Decimal::zero(),
+                    },
+                    treasury_fee: 0,
+                    creation_fee: None,
+                    denom_creation_fee: None,
+                    fee_collector: "larry".to_string(),
+                    fee_routing: None,
+                    oracle: None,
+                    resolver: None,
+                    creators_restricted: false,
+                    paused: false,
+                    token_id_format: self.token_id_format,
+                };
+                Ok(to_binary(&res).into()).into()
+            },
+
+            QueryMsg::TokenSerial {
+                token_id,
+            } => {
+                let (id, serial) =
+                    self.token_serials.get(&token_id).cloned().unwrap_or_else(|| {
+                        panic!("[mock]: cannot find token with id {}", token_id)
+                    });
+                let res = TokenSerialResponse {
+                    token_id,
+                    id,
+                    serial,
+                };
+                Ok(to_binary(&res).into()).into()
+            },
+
+            _ => panic!("[mock]: unsupported hub query: {:?}", msg),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::any::type_name;
+use std::str::FromStr;
+
+use cosmwasm_std::{StdError, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How the token ids of badge NFTs are derived from the badge id and the serial number.
+///
+/// The format is chosen when the Hub is instantiated; the NFT contract copies it from the Hub when
+/// it is instantiated, so that it can parse the ids back.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenIdFormat {
+    /// `{id}|{serial}`, e.g. `12|34`
+    #[default]
+    Pipe,
+
+    /// `{id}-{serial}`, e.g. `12-34`
+    Dash,
+
+    /// `{id}-{serial}` with both numbers zero-padded to the given number of digits, e.g.
+    /// `0012-0034` for a width of 4. As long as the numbers fit in the width, token ids sort in the
+    /// same order as badge ids and serials.
+    ZeroPadded {
+        width: u8,
+    },
+
+    /// An opaque number counting the instances minted across all badges, starting from 1, e.g.
+    /// `1234`. The id reveals neither the badge id nor the serial, so it can't be built or parsed
+    /// on its own: the Hub assigns the numbers as it mints and records the badge id and serial of
+    /// each one, which can be looked up with its `Token` query.
+    Counter,
+}
+
+impl TokenIdFormat {
+    /// Build the token id of the given badge id and serial number. Fails for the counter format,
+    /// whose ids are assigned by the Hub.
+    pub fn build(&self, id: u64, serial: u64) -> StdResult<String> {
+        match self {
+            TokenIdFormat::ZeroPadded {
+                width,
+            } => Ok(format!("{:0w$}-{:0w$}", id, serial, w = *width as usize)),
+            TokenIdFormat::Counter => Err(Self::counter_err()),
+            _ => Ok(format!("{}{}{}", id, self.separator(), serial)),
+        }
+    }
+
+    /// Split a token id into badge id and serial number. Fails for the counter format, whose ids
+    /// must be looked up from the Hub.
+    pub fn parse(&self, token_id: &str) -> StdResult<(u64, u64)> {
+        if *self == TokenIdFormat::Counter {
+            return Err(Self::counter_err());
+        }
+
+        let split = token_id.split(self.separator()).collect::<Vec<&str>>();
+        if split.len() != 2 {
+            return Err(self.invalid_err(token_id));
+        }
+
+        let id =
+            u64::from_str(split[0]).map_err(|err| StdError::parse_err(type_name::<u64>(), err))?;
+        let serial =
+            u64::from_str(split[1]).map_err(|err| StdError::parse_err(type_name::<u64>(), err))?;
+
+        // each token has only one valid id, so e.g. `+12|34`, `12|034`, or a zero-padded id
+        // without the full padding, are rejected
+        if self.build(id, serial)? != token_id {
+            return Err(self.invalid_err(token_id));
+        }
+
+        Ok((id, serial))
+    }
+
+    /// The token id of the instance assigned the given number, in the counter format
+    pub fn build_counter(count: u64) -> String {
+        count.to_string()
+    }
+
+    /// The number of a token id in the counter format
+    pub fn parse_counter(token_id: &str) -> StdResult<u64> {
+        let count =
+            u64::from_str(token_id).map_err(|err| StdError::parse_err(type_name::<u64>(), err))?;
+        if Self::build_counter(count) != token_id {
+            return Err(TokenIdFormat::Counter.invalid_err(token_id));
+        }
+        Ok(count)
+    }
+
+    fn counter_err() -> StdError {
+        StdError::generic_err(
+            "token ids in the counter format are assigned by the Hub and must be looked up",
+        )
+    }
+
+    fn invalid_err(&self, token_id: &str) -> StdError {
+        StdError::generic_err(format!(
+            "invalid token id `{}`: must be in the format {}",
+            token_id,
+            self.pattern(),
+        ))
+    }
+
+    fn separator(&self) -> char {
+        match self {
+            TokenIdFormat::Pipe => '|',
+            _ => '-',
+        }
+    }
+
+    fn pattern(&self) -> String {
+        match self {
+            TokenIdFormat::ZeroPadded {
+                width,
+            } => format!("{{id}}-{{serial}} zero-padded to {} digits", width),
+            TokenIdFormat::Counter => "{count}".to_string(),
+            _ => format!("{{id}}{}{{serial}}", self.separator()),
+        }
+    }
+}
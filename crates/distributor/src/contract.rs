@@ -0,0 +1,59 @@
+use cosmwasm_std::{
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+
+use badges::distributor::{ExecuteMsg, InstantiateMsg, QueryMsg};
+
+use crate::{error::ContractError, execute, query};
+
+pub const CONTRACT_NAME: &str = "crates.io:badge-distributor";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    execute::init(deps, &msg.hub)
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateCampaign {
+            merkle_root,
+            expiry,
+        } => execute::create_campaign(deps, info.sender, &merkle_root, expiry),
+        ExecuteMsg::CloseCampaign {
+            campaign_id,
+        } => execute::close_campaign(deps, info.sender, campaign_id),
+        ExecuteMsg::Claim {
+            campaign_id,
+            ids,
+            proof,
+        } => execute::claim(deps, env, info.sender, campaign_id, ids, proof),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query::config(deps)?),
+        QueryMsg::Campaign {
+            campaign_id,
+        } => to_binary(&query::campaign(deps, campaign_id)?),
+        QueryMsg::Claimed {
+            campaign_id,
+            user,
+        } => to_binary(&query::claimed(deps, campaign_id, user)),
+    }
+}
@@ -0,0 +1,43 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] cosmwasm_std::StdError),
+
+    #[error(transparent)]
+    FromHex(#[from] hex::FromHexError),
+
+    #[error("merkle root and proof hashes must be 32 bytes")]
+    InvalidHashLength,
+
+    #[error("merkle proof verification failed")]
+    InvalidProof,
+
+    #[error("sender is not the creator of the campaign")]
+    NotCreator,
+
+    #[error("campaign {id} has been closed")]
+    CampaignClosed {
+        id: u64,
+    },
+
+    #[error("campaign {id} has expired")]
+    CampaignExpired {
+        id: u64,
+    },
+
+    #[error("user {user} has already claimed in campaign {id}")]
+    AlreadyClaimed {
+        id: u64,
+        user: String,
+    },
+
+    #[error("must claim at least one badge, with ids in ascending order")]
+    InvalidIds,
+
+    #[error("badge {id} is not managed by the campaign's creator")]
+    NotManagedByCreator {
+        id: u64,
+    },
+}
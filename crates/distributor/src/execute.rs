@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{to_binary, Addr, DepsMut, Env, Response, StdResult, WasmMsg};
+
+use badges::hub::ExecuteMsg as HubExecuteMsg;
+
+use crate::{
+    error::ContractError,
+    helpers::{assert_managed_by, assert_valid_proof, decode_hash, leaf},
+    state::{Campaign, CAMPAIGNS, CAMPAIGN_COUNT, CLAIMED, HUB},
+};
+
+pub fn init(deps: DepsMut, hub: &str) -> StdResult<Response> {
+    let hub_addr = deps.api.addr_validate(hub)?;
+    HUB.save(deps.storage, &hub_addr)?;
+    CAMPAIGN_COUNT.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/distributor/init")
+        .add_attribute("hub", hub_addr))
+}
+
+pub fn create_campaign(
+    deps: DepsMut,
+    creator: Addr,
+    merkle_root: &str,
+    expiry: Option<u64>,
+) -> Result<Response, ContractError> {
+    let merkle_root = decode_hash(merkle_root)?;
+
+    let id = CAMPAIGN_COUNT.update(deps.storage, |count| StdResult::Ok(count + 1))?;
+    CAMPAIGNS.save(
+        deps.storage,
+        id,
+        &Campaign {
+            creator: creator.clone(),
+            merkle_root: merkle_root.to_vec(),
+            expiry,
+            closed: false,
+            num_claimed: 0,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/distributor/create_campaign")
+        .add_attribute("campaign_id", id.to_string())
+        .add_attribute("creator", creator))
+}
+
+pub fn close_campaign(
+    deps: DepsMut,
+    sender: Addr,
+    campaign_id: u64,
+) -> Result<Response, ContractError> {
+    let mut campaign = CAMPAIGNS.load(deps.storage, campaign_id)?;
+
+    if sender != campaign.creator {
+        return Err(ContractError::NotCreator);
+    }
+
+    campaign.closed = true;
+    CAMPAIGNS.save(deps.storage, campaign_id, &campaign)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/distributor/close_campaign")
+        .add_attribute("campaign_id", campaign_id.to_string()))
+}
+
+pub fn claim(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    campaign_id: u64,
+    ids: Vec<u64>,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let hub_addr = HUB.load(deps.storage)?;
+    let mut campaign = CAMPAIGNS.load(deps.storage, campaign_id)?;
+
+    if campaign.closed {
+        return Err(ContractError::CampaignClosed {
+            id: campaign_id,
+        });
+    }
+
+    if matches!(campaign.expiry, Some(expiry) if env.block.time.seconds() > expiry) {
+        return Err(ContractError::CampaignExpired {
+            id: campaign_id,
+        });
+    }
+
+    if CLAIMED.contains(deps.storage, (campaign_id, sender.as_str())) {
+        return Err(ContractError::AlreadyClaimed {
+            id: campaign_id,
+            user: sender.into(),
+        });
+    }
+
+    // ids must be strictly ascending, so that each account has exactly one valid leaf
+    if ids.is_empty() || ids.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(ContractError::InvalidIds);
+    }
+
+    assert_valid_proof(&campaign.merkle_root, leaf(sender.as_str(), &ids), &proof)?;
+    assert_managed_by(deps.as_ref(), &hub_addr, &ids, &campaign.creator)?;
+
+    CLAIMED.insert(deps.storage, (campaign_id, sender.as_str()))?;
+    campaign.num_claimed += 1;
+    CAMPAIGNS.save(deps.storage, campaign_id, &campaign)?;
+
+    // the Hub checks that the distributor is a minter of each of the badges
+    let owners: BTreeSet<String> = [sender.to_string()].into();
+    let mints = ids.iter().map(|id| (*id, owners.clone())).collect();
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: hub_addr.into(),
+            msg: to_binary(&HubExecuteMsg::MintBatch {
+                mints,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "badges/distributor/claim")
+        .add_attribute("campaign_id", campaign_id.to_string())
+        .add_attribute("user", sender)
+        .add_attribute("ids", ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",")))
+}
@@ -0,0 +1,69 @@
+use cosmwasm_std::{Addr, Deps};
+use sha2::{Digest, Sha256};
+
+use badges::hub::{BadgeResponse, QueryMsg as HubQueryMsg};
+
+use crate::error::ContractError;
+
+/// Decode a hex-encoded SHA-256 hash, e.g. a Merkle root or a node of a Merkle proof
+pub fn decode_hash(hash: &str) -> Result<[u8; 32], ContractError> {
+    hex::decode(hash)?.try_into().map_err(|_| ContractError::InvalidHashLength)
+}
+
+/// The leaf of the Merkle tree for an account and the badges it can claim, i.e. the SHA-256 hash
+/// of `{address}:{id},{id},...`
+pub fn leaf(user: &str, ids: &[u64]) -> [u8; 32] {
+    let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    Sha256::digest(format!("{}:{}", user, ids).as_bytes()).into()
+}
+
+/// Assert that the leaf is in the Merkle tree with the given root. Pairs of nodes are hashed in
+/// ascending order of their bytes, so the proof doesn't need to say which side each sibling is on.
+pub fn assert_valid_proof(
+    root: &[u8],
+    leaf: [u8; 32],
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let mut hash = leaf;
+    for sibling in proof {
+        let sibling = decode_hash(sibling)?;
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+        hash = Sha256::new().chain_update(first).chain_update(second).finalize().into();
+    }
+
+    if hash.as_slice() != root {
+        return Err(ContractError::InvalidProof);
+    }
+
+    Ok(())
+}
+
+/// Assert that each of the badges is managed by the campaign's creator, so that a campaign can't
+/// be used to mint the badges of others who made the distributor a minter.
+pub fn assert_managed_by(
+    deps: Deps,
+    hub_addr: &Addr,
+    ids: &[u64],
+    creator: &Addr,
+) -> Result<(), ContractError> {
+    for id in ids {
+        let badge: BadgeResponse = deps.querier.query_wasm_smart(
+            hub_addr,
+            &HubQueryMsg::Badge {
+                id: *id,
+                locale: None,
+            },
+        )?;
+        if badge.manager != *creator {
+            return Err(ContractError::NotManagedByCreator {
+                id: *id,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+#[cfg(not(feature = "library"))]
+pub mod contract;
+pub mod error;
+pub mod execute;
+pub mod helpers;
+pub mod query;
+pub mod state;
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use badges::distributor::{CampaignResponse, ClaimedResponse, ConfigResponse};
+
+use crate::state::{CAMPAIGNS, CAMPAIGN_COUNT, CLAIMED, HUB};
+
+pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
+    Ok(ConfigResponse {
+        hub: HUB.load(deps.storage)?.into(),
+        campaign_count: CAMPAIGN_COUNT.load(deps.storage)?,
+    })
+}
+
+pub fn campaign(deps: Deps, campaign_id: u64) -> StdResult<CampaignResponse> {
+    let campaign = CAMPAIGNS.load(deps.storage, campaign_id)?;
+    Ok(CampaignResponse {
+        id: campaign_id,
+        creator: campaign.creator.into(),
+        merkle_root: hex::encode(campaign.merkle_root),
+        expiry: campaign.expiry,
+        closed: campaign.closed,
+        num_claimed: campaign.num_claimed,
+    })
+}
+
+pub fn claimed(deps: Deps, campaign_id: u64, user: impl Into<String>) -> ClaimedResponse {
+    let user = user.into();
+    let claimed = CLAIMED.contains(deps.storage, (campaign_id, &user));
+    ClaimedResponse {
+        user,
+        claimed,
+    }
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::Addr;
+use cw_item_set::Set;
+use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
+
+/// An airdrop campaign
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Campaign {
+    /// The account that created the campaign. Only badges managed by this account can be claimed.
+    pub creator: Addr,
+    /// Root of the Merkle tree of accounts and the badges they can claim
+    pub merkle_root: Vec<u8>,
+    /// A UNIX timestamp, in seconds, after which badges can no longer be claimed
+    pub expiry: Option<u64>,
+    /// Whether the creator has stopped the campaign early
+    pub closed: bool,
+    /// The number of accounts that have claimed their badges
+    pub num_claimed: u64,
+}
+
+/// Address of the Hub contract
+pub const HUB: Item<Addr> = Item::new("hub");
+
+/// Total number of campaigns created. Also the id of the latest campaign.
+pub const CAMPAIGN_COUNT: Item<u64> = Item::new("campaign_count");
+
+/// Airdrop campaigns, indexed by campaign ids
+pub const CAMPAIGNS: Map<u64, Campaign> = Map::new("campaigns");
+
+/// Accounts that have claimed their badges, indexed by {campaign_id, user_addr}
+pub const CLAIMED: Set<(u64, &str)> = Set::new("claimed");
@@ -0,0 +1,294 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Empty, Env, OwnedDeps, SubMsg, Timestamp, WasmMsg,
+    WasmQuery,
+};
+use sg_metadata::Metadata;
+use sha2::{Digest, Sha256};
+
+use badge_distributor::error::ContractError;
+use badge_distributor::helpers::leaf;
+use badge_distributor::{execute, query};
+use badges::hub::{BadgeResponse, ExecuteMsg as HubExecuteMsg, QueryMsg as HubQueryMsg};
+use badges::{Badge, MintRule};
+
+/// Return an `env` object at the specified UNIX timestamp
+fn mock_env_at_timestamp(timestamp: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(timestamp);
+    env
+}
+
+/// Hash a pair of nodes of the Merkle tree, in ascending order of their bytes
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    Sha256::new().chain_update(first).chain_update(second).finalize().into()
+}
+
+fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
+    let mut deps = mock_dependencies();
+
+    execute::init(deps.as_mut(), "hub").unwrap();
+
+    // in the mock hub, badges 1 and 2 are managed by larry, and all others by doge
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg,
+        } if contract_addr == "hub" => {
+            let id = match from_binary(msg).unwrap() {
+                HubQueryMsg::Badge {
+                    id,
+                    ..
+                } => id,
+                _ => panic!("[mock]: unsupported hub query"),
+            };
+            let manager = if id <= 2 {
+                "larry"
+            } else {
+                "doge"
+            };
+            let badge = Badge {
+                manager: Addr::unchecked(manager),
+                metadata: Metadata::default(),
+                token_uri: None,
+                transferrable: true,
+                revocable: false,
+                rule: MintRule::by_minter(MOCK_CONTRACT_ADDR),
+                rule_frozen: false,
+                metadata_frozen: false,
+                paused: false,
+                archived: false,
+                hidden: false,
+                phases: vec![],
+                revenue_split: vec![],
+                royalty: None,
+                start_time: None,
+                expiry: None,
+                max_supply: None,
+                max_per_owner: None,
+                rate_limit: None,
+                current_supply: 0,
+                num_burned: 0,
+            };
+            Ok(to_binary(&BadgeResponse::from((id, badge))).into()).into()
+        },
+        _ => panic!("[mock]: unsupported wasm query: {:?}", query),
+    });
+
+    deps
+}
+
+#[test]
+fn distributing() {
+    let mut deps = setup_test();
+
+    // jake can claim badges 1 and 2, pumpkin can claim badge 3
+    let jake_leaf = leaf("jake", &[1, 2]);
+    let pumpkin_leaf = leaf("pumpkin", &[3]);
+    let root = hex::encode(hash_pair(jake_leaf, pumpkin_leaf));
+    let jake_proof = vec![hex::encode(pumpkin_leaf)];
+    let pumpkin_proof = vec![hex::encode(jake_leaf)];
+
+    // the merkle root must be a valid hash
+    {
+        let err = execute::create_campaign(deps.as_mut(), Addr::unchecked("larry"), "1234", None)
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidHashLength);
+    }
+
+    // create a campaign
+    {
+        let res =
+            execute::create_campaign(deps.as_mut(), Addr::unchecked("larry"), &root, Some(20000))
+                .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/distributor/create_campaign"),
+                attr("campaign_id", "1"),
+                attr("creator", "larry"),
+            ],
+        );
+
+        let res = query::campaign(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.merkle_root, root);
+        assert_eq!(res.expiry, Some(20000));
+    }
+
+    // ids must match the leaf
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            Addr::unchecked("jake"),
+            1,
+            vec![1],
+            jake_proof.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidProof);
+    }
+
+    // ids must be in ascending order
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            Addr::unchecked("jake"),
+            1,
+            vec![2, 1],
+            jake_proof.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidIds);
+    }
+
+    // badges not managed by the campaign's creator can't be claimed
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            Addr::unchecked("pumpkin"),
+            1,
+            vec![3],
+            pumpkin_proof,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotManagedByCreator {
+                id: 3,
+            },
+        );
+    }
+
+    // can't claim after the campaign expires
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(30000),
+            Addr::unchecked("jake"),
+            1,
+            vec![1, 2],
+            jake_proof.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CampaignExpired {
+                id: 1,
+            },
+        );
+    }
+
+    // properly claim
+    {
+        let res = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            Addr::unchecked("jake"),
+            1,
+            vec![1, 2],
+            jake_proof.clone(),
+        )
+        .unwrap();
+
+        let owners: BTreeSet<String> = ["jake".to_string()].into();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "hub".to_string(),
+                msg: to_binary(&HubExecuteMsg::MintBatch {
+                    mints: vec![(1, owners.clone()), (2, owners)],
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/distributor/claim"),
+                attr("campaign_id", "1"),
+                attr("user", "jake"),
+                attr("ids", "1,2"),
+            ],
+        );
+
+        let res = query::claimed(deps.as_ref(), 1, "jake");
+        assert!(res.claimed);
+
+        let res = query::campaign(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.num_claimed, 1);
+    }
+
+    // can't claim twice
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            Addr::unchecked("jake"),
+            1,
+            vec![1, 2],
+            jake_proof.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AlreadyClaimed {
+                id: 1,
+                user: "jake".to_string(),
+            },
+        );
+    }
+}
+
+#[test]
+fn closing_campaigns() {
+    let mut deps = setup_test();
+
+    let root = hex::encode(leaf("jake", &[1]));
+    execute::create_campaign(deps.as_mut(), Addr::unchecked("larry"), &root, None).unwrap();
+
+    // only the creator can close the campaign
+    {
+        let err = execute::close_campaign(deps.as_mut(), Addr::unchecked("jake"), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotCreator);
+    }
+
+    // close the campaign
+    {
+        execute::close_campaign(deps.as_mut(), Addr::unchecked("larry"), 1).unwrap();
+
+        let res = query::campaign(deps.as_ref(), 1).unwrap();
+        assert!(res.closed);
+    }
+
+    // badges can no longer be claimed
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("jake"),
+            1,
+            vec![1],
+            vec![],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CampaignClosed {
+                id: 1,
+            },
+        );
+    }
+}
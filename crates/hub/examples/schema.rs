@@ -4,8 +4,15 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use badges::hub::{
-    BadgeResponse, BadgesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, KeyResponse,
-    KeysResponse, OwnerResponse, OwnersResponse, QueryMsg,
+    ActiveBadgesResponse, AllowlistResponse, BadgeResponse, BadgesResponse,
+    CommemorativeTokenResponse, ConfigResponse, CreatorsResponse, DelegatedMinterResponse,
+    DepositResponse, ExecuteMsg, InstantiateMsg, KeyResponse, KeysResponse, LocalesResponse,
+    MediaResponse, MetadataHistoryResponse, MigrateMsg, MintReceipt, MintersResponse,
+    NftContractResponse, NftResponse, NonceResponse, OraclePriceResponse, OracleQueryMsg,
+    OwnerBatchResponse, OwnerResponse, OwnersResponse, PendingManagerResponse, PendingMintResponse,
+    QueryMsg, ReceiveNftMsg, RegistrantsResponse, ResolveResponse, ResolverQueryMsg,
+    RevocationsResponse, StatsResponse, SupplyResponse, SvgTemplateResponse, TokenSerialResponse,
+    VoucherResponse, WinnersResponse,
 };
 
 fn main() {
@@ -15,14 +22,45 @@ fn main() {
     remove_schemas(&out_dir).unwrap();
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveNftMsg), &out_dir);
+    export_schema(&schema_for!(MintReceipt), &out_dir);
+    export_schema(&schema_for!(OracleQueryMsg), &out_dir);
+    export_schema(&schema_for!(ResolverQueryMsg), &out_dir);
 
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(CreatorsResponse), &out_dir);
+    export_schema(&schema_for!(NftContractResponse), &out_dir);
     export_schema(&schema_for!(BadgeResponse), &out_dir);
     export_schema(&schema_for!(BadgesResponse), &out_dir);
+    export_schema(&schema_for!(ActiveBadgesResponse), &out_dir);
+    export_schema(&schema_for!(SvgTemplateResponse), &out_dir);
+    export_schema(&schema_for!(LocalesResponse), &out_dir);
+    export_schema(&schema_for!(CommemorativeTokenResponse), &out_dir);
+    export_schema(&schema_for!(MediaResponse), &out_dir);
+    export_schema(&schema_for!(SupplyResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(MetadataHistoryResponse), &out_dir);
+    export_schema(&schema_for!(PendingManagerResponse), &out_dir);
     export_schema(&schema_for!(KeyResponse), &out_dir);
     export_schema(&schema_for!(KeysResponse), &out_dir);
+    export_schema(&schema_for!(AllowlistResponse), &out_dir);
+    export_schema(&schema_for!(MintersResponse), &out_dir);
+    export_schema(&schema_for!(RegistrantsResponse), &out_dir);
+    export_schema(&schema_for!(WinnersResponse), &out_dir);
+    export_schema(&schema_for!(VoucherResponse), &out_dir);
+    export_schema(&schema_for!(DelegatedMinterResponse), &out_dir);
+    export_schema(&schema_for!(PendingMintResponse), &out_dir);
+    export_schema(&schema_for!(NonceResponse), &out_dir);
+    export_schema(&schema_for!(TokenSerialResponse), &out_dir);
+    export_schema(&schema_for!(NftResponse), &out_dir);
+    export_schema(&schema_for!(OraclePriceResponse), &out_dir);
+    export_schema(&schema_for!(ResolveResponse), &out_dir);
+    export_schema(&schema_for!(DepositResponse), &out_dir);
     export_schema(&schema_for!(OwnerResponse), &out_dir);
+    export_schema(&schema_for!(OwnerBatchResponse), &out_dir);
     export_schema(&schema_for!(OwnersResponse), &out_dir);
+    export_schema(&schema_for!(RevocationsResponse), &out_dir);
 }
@@ -0,0 +1,99 @@
+/// Characters of the base58btc alphabet, used by CIDv0 and by CIDv1 with the `z` multibase prefix
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Characters of the lowercase base32 alphabet, used by CIDv1 with the `b` multibase prefix
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Multihash code of SHA2-256, the only hash function CIDv0 supports
+const SHA2_256: u64 = 0x12;
+
+/// Whether a string is a well-formed IPFS content identifier, either a CIDv0 (a base58btc-encoded
+/// SHA2-256 multihash, always starting with `Qm`) or a CIDv1 encoded in lowercase base32 or in
+/// base58btc. The hash itself can't be checked without the content; only the structure is.
+pub fn is_valid_cid(cid: &str) -> bool {
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        return match decode_base58(cid) {
+            Some(bytes) => bytes.len() == 34 && bytes[0] as u64 == SHA2_256 && bytes[1] == 32,
+            None => false,
+        };
+    }
+
+    // the first character is the multibase prefix, indicating how the rest is encoded
+    let bytes = if let Some(data) = cid.strip_prefix('b') {
+        decode_base32(data)
+    } else if let Some(data) = cid.strip_prefix('z') {
+        decode_base58(data)
+    } else {
+        None
+    };
+    match bytes {
+        Some(bytes) => is_valid_cid_v1(&bytes),
+        None => false,
+    }
+}
+
+/// A binary CIDv1 is the version, the codec of the content, and a multihash: the hash function's
+/// code, the digest's length, and the digest itself. All but the digest are unsigned varints.
+fn is_valid_cid_v1(mut bytes: &[u8]) -> bool {
+    let version = read_varint(&mut bytes);
+    let codec = read_varint(&mut bytes);
+    let hash_code = read_varint(&mut bytes);
+    let digest_len = read_varint(&mut bytes);
+
+    match (version, codec, hash_code, digest_len) {
+        (Some(1), Some(_), Some(_), Some(len)) => len > 0 && bytes.len() as u64 == len,
+        _ => false,
+    }
+}
+
+/// Read an unsigned LEB128 varint from the front of the bytes, advancing past it. Multiformats
+/// limit varints to 9 bytes.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn decode_base58(data: &str) -> Option<Vec<u8>> {
+    // big-endian bytes of the number encoded so far, without leading zeros
+    let mut bytes: Vec<u8> = vec![];
+    for c in data.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // each leading `1` encodes a leading zero byte
+    let mut decoded = vec![0; data.bytes().take_while(|&c| c == b'1').count()];
+    decoded.extend(bytes);
+    Some(decoded)
+}
+
+fn decode_base32(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
@@ -1,14 +1,15 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, StdResult,
+    entry_point, from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, StdResult,
 };
+use cw721::Cw721ReceiveMsg;
 use sg_std::Response;
 
 use badges::{
-    hub::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg},
+    hub::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveNftMsg, SudoMsg},
     Badge,
 };
 
-use crate::{error::ContractError, execute, query, upgrades};
+use crate::{error::ContractError, execute, helpers::ClaimSignature, query, upgrades};
 
 pub const CONTRACT_NAME: &str = "crates.io:badge-hub";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -21,15 +22,53 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    execute::init(deps, info.sender, msg.fee_rate)
+
+    let fee_collector_addr = match msg.fee_collector {
+        Some(fee_collector) => deps.api.addr_validate(&fee_collector)?,
+        None => info.sender.clone(),
+    };
+
+    execute::init(
+        deps,
+        info.sender,
+        msg.fee_rate,
+        msg.creation_fee,
+        fee_collector_addr,
+        msg.token_id_format.unwrap_or_default(),
+    )
 }
 
 #[entry_point]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> StdResult<Response> {
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
         SudoMsg::SetFeeRate {
             fee_rate,
-        } => execute::set_fee_rate(deps, fee_rate),
+        } => execute::set_fee_rate(deps, fee_rate).map_err(ContractError::from),
+        SudoMsg::SetTreasuryFee {
+            bps,
+        } => execute::set_treasury_fee(deps, bps),
+        SudoMsg::SetHubPaused {
+            paused,
+        } => execute::override_hub_paused(deps, paused),
+        SudoMsg::UpdateConfig {
+            creation_fee,
+            fee_collector,
+        } => execute::override_config(deps, creation_fee, &fee_collector),
+        SudoMsg::SetFeeRouting {
+            fee_routing,
+        } => execute::override_fee_routing(deps, fee_routing),
+        SudoMsg::SetOracle {
+            oracle,
+        } => execute::override_oracle(deps, oracle),
+        SudoMsg::SetResolver {
+            resolver,
+        } => execute::override_resolver(deps, resolver),
+        SudoMsg::SetDenomCreationFee {
+            fee,
+        } => execute::override_denom_creation_fee(deps, fee),
+        SudoMsg::SetDeveloper {
+            developer,
+        } => execute::override_developer(deps, &developer),
     }
 }
 
@@ -44,34 +83,186 @@ pub fn execute(
         ExecuteMsg::CreateBadge {
             manager,
             metadata,
+            token_uri,
             transferrable,
+            revocable,
             rule,
+            rule_frozen,
+            phases,
+            revenue_split,
+            royalty,
+            start_time,
             expiry,
             max_supply,
+            max_per_owner,
+            rate_limit,
         } => {
             let badge = Badge {
                 manager: deps.api.addr_validate(&manager)?,
                 metadata,
+                token_uri,
                 transferrable,
+                revocable,
                 rule,
+                rule_frozen,
+                metadata_frozen: false,
+                paused: false,
+                archived: false,
+                hidden: false,
+                phases,
+                revenue_split,
+                royalty,
+                start_time,
                 expiry,
                 max_supply,
+                max_per_owner,
+                rate_limit,
                 current_supply: 0,
+                num_burned: 0,
             };
             execute::create_badge(deps, env, info, badge)
         },
+        ExecuteMsg::CreateBadges {
+            badges,
+        } => {
+            let badges = badges
+                .into_iter()
+                .map(|spec| {
+                    Ok(Badge {
+                        manager: deps.api.addr_validate(&spec.manager)?,
+                        metadata: spec.metadata,
+                        token_uri: spec.token_uri,
+                        transferrable: spec.transferrable,
+                        revocable: spec.revocable,
+                        rule: spec.rule,
+                        rule_frozen: spec.rule_frozen,
+                        metadata_frozen: false,
+                        paused: false,
+                        archived: false,
+                        hidden: false,
+                        phases: spec.phases,
+                        revenue_split: spec.revenue_split,
+                        royalty: spec.royalty,
+                        start_time: spec.start_time,
+                        expiry: spec.expiry,
+                        max_supply: spec.max_supply,
+                        max_per_owner: spec.max_per_owner,
+                        rate_limit: spec.rate_limit,
+                        current_supply: 0,
+                        num_burned: 0,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            execute::create_badges(deps, env, info, badges)
+        },
         ExecuteMsg::EditBadge {
             id,
             metadata,
-        } => execute::edit_badge(deps, info, id, metadata),
+        } => execute::edit_badge(deps, env, info, id, metadata),
+        ExecuteMsg::SetSvgTemplate {
+            id,
+            template,
+        } => execute::set_svg_template(deps, info, id, template),
+        ExecuteMsg::SetCommemorativeToken {
+            id,
+            token,
+        } => execute::set_commemorative_token(deps, env, info, id, token),
+        ExecuteMsg::SetMedia {
+            id,
+            media,
+        } => execute::set_media(deps, info, id, media),
+        ExecuteMsg::SetLocalizedMetadata {
+            id,
+            locale,
+            metadata,
+        } => execute::set_localized_metadata(deps, info, id, locale, metadata),
+        ExecuteMsg::TransferManager {
+            id,
+            new_manager,
+        } => execute::transfer_manager(deps, info, id, new_manager),
+        ExecuteMsg::AcceptManager {
+            id,
+        } => execute::accept_manager(deps, info, id),
+        ExecuteMsg::UpdateMintParams {
+            id,
+            expiry,
+            max_supply,
+        } => execute::update_mint_params(deps, env, info, id, expiry, max_supply),
+        ExecuteMsg::UpdateMintRule {
+            id,
+            rule,
+        } => execute::update_mint_rule(deps, info, id, rule),
+        ExecuteMsg::UpdatePhases {
+            id,
+            phases,
+        } => execute::update_phases(deps, info, id, phases),
+        ExecuteMsg::SetBadgePaused {
+            id,
+            paused,
+        } => execute::set_badge_paused(deps, info, id, paused),
+        ExecuteMsg::ArchiveBadge {
+            id,
+            hide,
+        } => execute::archive_badge(deps, info, id, hide),
+        ExecuteMsg::FreezeMetadata {
+            id,
+        } => execute::freeze_metadata(deps, info, id),
+        ExecuteMsg::FreezeMintRule {
+            id,
+        } => execute::freeze_mint_rule(deps, info, id),
+        ExecuteMsg::RotateKey {
+            id,
+            pubkey,
+        } => execute::rotate_key(deps, info, id, pubkey),
         ExecuteMsg::AddKeys {
             id,
             keys,
         } => execute::add_keys(deps, env, info, id, keys),
+        ExecuteMsg::AddVouchers {
+            id,
+            hashes,
+        } => execute::add_vouchers(deps, env, info, id, hashes),
+        ExecuteMsg::GrantMinter {
+            id,
+            minter,
+            expiration,
+        } => execute::grant_minter(deps, env, info, id, minter, expiration),
+        ExecuteMsg::RevokeMinter {
+            id,
+            minter,
+        } => execute::revoke_minter(deps, info, id, minter),
+        ExecuteMsg::AddToAllowlist {
+            id,
+            users,
+        } => execute::add_to_allowlist(deps, info, id, users),
+        ExecuteMsg::RemoveFromAllowlist {
+            id,
+            users,
+        } => execute::remove_from_allowlist(deps, info, id, users),
+        ExecuteMsg::AddMinters {
+            id,
+            minters,
+        } => execute::add_minters(deps, info, id, minters),
+        ExecuteMsg::RemoveMinters {
+            id,
+            minters,
+        } => execute::remove_minters(deps, info, id, minters),
+        ExecuteMsg::BumpNonce {
+            id,
+        } => execute::bump_nonce(deps, info, id),
         ExecuteMsg::PurgeKeys {
             id,
             limit,
         } => execute::purge_keys(deps, env, id, limit),
+        ExecuteMsg::Revoke {
+            id,
+            serials,
+        } => execute::revoke(deps, info, id, serials),
+        ExecuteMsg::SetSerialTraits {
+            id,
+            serial,
+            traits,
+        } => execute::set_serial_traits(deps, info, id, serial, traits),
         ExecuteMsg::PurgeOwners {
             id,
             limit,
@@ -80,17 +271,147 @@ pub fn execute(
             id,
             owners,
         } => execute::mint_by_minter(deps, env, id, owners, info.sender),
+        ExecuteMsg::MintBatch {
+            mints,
+        } => execute::mint_batch(deps, env, mints, info.sender),
+        ExecuteMsg::QueueMint {
+            id,
+            owners,
+        } => execute::queue_mint(deps, env, id, owners, info.sender),
+        ExecuteMsg::ResumeMint {
+            id,
+            limit,
+        } => execute::resume_mint(deps, env, id, limit),
+        ExecuteMsg::RecordBurn {
+            id,
+            serial,
+        } => execute::record_burn(deps, info, id, serial),
+        ExecuteMsg::HookMint {
+            id,
+            owner,
+        } => execute::hook_mint(deps, env, info, id, owner),
         ExecuteMsg::MintByKey {
             id,
             owner,
+            deadline,
+            sign_mode,
+            signer,
             signature,
-        } => execute::mint_by_key(deps, env, id, owner, signature),
+        } => {
+            let signature = ClaimSignature {
+                signature,
+                deadline,
+                sign_mode: sign_mode.unwrap_or_default(),
+                signer: signer.map(|signer| deps.api.addr_validate(&signer)).transpose()?,
+            };
+            execute::mint_by_key(deps, env, info, id, owner, signature)
+        },
         ExecuteMsg::MintByKeys {
             id,
             owner,
+            deadline,
+            sign_mode,
+            signer,
             pubkey,
             signature,
-        } => execute::mint_by_keys(deps, env, id, owner, pubkey, signature),
+        } => {
+            let signature = ClaimSignature {
+                signature,
+                deadline,
+                sign_mode: sign_mode.unwrap_or_default(),
+                signer: signer.map(|signer| deps.api.addr_validate(&signer)).transpose()?,
+            };
+            execute::mint_by_keys(deps, env, info, id, owner, pubkey, signature)
+        },
+        ExecuteMsg::MintByVoucher {
+            id,
+            owner,
+            code,
+        } => execute::mint_by_voucher(deps, env, id, owner, code),
+        ExecuteMsg::MintByPayment {
+            id,
+            owner,
+        } => execute::mint_by_payment(deps, env, info, id, owner),
+        ExecuteMsg::MintByNft {
+            id,
+            owner,
+            token_id,
+        } => execute::mint_by_nft(deps, env, id, owner, token_id),
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender,
+            token_id,
+            msg,
+        }) => match from_binary(&msg)? {
+            ReceiveNftMsg::MintByDeposit {
+                id,
+            } => {
+                let owner = deps.api.addr_validate(&sender)?;
+                execute::mint_by_deposit(deps, env, id, info.sender, owner, token_id)
+            },
+        },
+        ExecuteMsg::MintByDelegation {
+            id,
+        } => execute::mint_by_delegation(deps, env, id, info.sender),
+        ExecuteMsg::MintByGroup {
+            id,
+        } => execute::mint_by_group(deps, env, id, info.sender),
+        ExecuteMsg::MintByAllowlist {
+            id,
+        } => execute::mint_by_allowlist(deps, env, id, info.sender),
+        ExecuteMsg::RegisterForRaffle {
+            id,
+        } => execute::register_for_raffle(deps, env, id, info.sender),
+        ExecuteMsg::DrawRaffle {
+            id,
+        } => execute::draw_raffle(deps, env, info, id),
+        ExecuteMsg::Claim {
+            id,
+        } => execute::claim(deps, env, id, info.sender),
+        ExecuteMsg::MintByComposite {
+            id,
+            owner,
+            proof,
+        } => execute::mint_by_composite(deps, env, info, id, owner, proof),
+        ExecuteMsg::TransferOwnership {
+            new_developer,
+        } => execute::transfer_ownership(deps, info.sender, &new_developer),
+        ExecuteMsg::AcceptOwnership {} => execute::accept_ownership(deps, info.sender),
+        ExecuteMsg::UpdateConfig {
+            creation_fee,
+            fee_collector,
+        } => execute::update_config(deps, info.sender, creation_fee, &fee_collector),
+        ExecuteMsg::SetFeeRouting {
+            fee_routing,
+        } => execute::set_fee_routing(deps, info.sender, fee_routing),
+        ExecuteMsg::SetOracle {
+            oracle,
+        } => execute::set_oracle(deps, info.sender, oracle),
+        ExecuteMsg::SetResolver {
+            resolver,
+        } => execute::set_resolver(deps, info.sender, resolver),
+        ExecuteMsg::SetDenomCreationFee {
+            fee,
+        } => execute::set_denom_creation_fee(deps, info.sender, fee),
+        ExecuteMsg::SetCreatorsRestricted {
+            restricted,
+        } => execute::set_creators_restricted(deps, info.sender, restricted),
+        ExecuteMsg::AddCreator {
+            creator,
+        } => execute::add_creator(deps, info.sender, &creator),
+        ExecuteMsg::RemoveCreator {
+            creator,
+        } => execute::remove_creator(deps, info.sender, &creator),
+        ExecuteMsg::SetHubPaused {
+            paused,
+        } => execute::set_hub_paused(deps, info.sender, paused),
+        ExecuteMsg::MigrateNft {
+            new_code_id,
+            msg,
+        } => execute::migrate_nft(deps, info.sender, new_code_id, msg),
+        ExecuteMsg::UpdateNftAdmin {
+            admin,
+        } => execute::update_nft_admin(deps, info.sender, &admin),
+        ExecuteMsg::ClearNftAdmin {} => execute::clear_nft_admin(deps, info.sender),
         ExecuteMsg::SetNft {
             nft,
         } => execute::set_nft(deps, info.sender, &nft),
@@ -98,16 +419,72 @@ pub fn execute(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query::config(deps)?),
+        QueryMsg::NftContract {} => to_binary(&query::nft_contract(deps, env)?),
+        QueryMsg::Creators {
+            start_after,
+            limit,
+        } => to_binary(&query::creators(deps, start_after, limit)?),
         QueryMsg::Badge {
             id,
-        } => to_binary(&query::badge(deps, id)?),
+            locale,
+        } => to_binary(&query::localized_badge(deps, id, locale)?),
+        QueryMsg::CommemorativeToken {
+            id,
+        } => to_binary(&query::commemorative_token(deps, env, id)?),
+        QueryMsg::Media {
+            id,
+        } => to_binary(&query::media(deps, id)?),
+        QueryMsg::Locales {
+            id,
+        } => to_binary(&query::locales(deps, id)?),
+        QueryMsg::SvgTemplate {
+            id,
+        } => to_binary(&query::svg_template(deps, id)?),
+        QueryMsg::BadgesById {
+            ids,
+        } => to_binary(&query::badges_by_id(deps, ids)?),
+        QueryMsg::Supply {
+            id,
+        } => to_binary(&query::supply(deps, env, id)?),
+        QueryMsg::Stats {
+            start_after,
+            limit,
+        } => to_binary(&query::stats(deps, env, start_after, limit)?),
         QueryMsg::Badges {
             start_after,
             limit,
-        } => to_binary(&query::badges(deps, start_after, limit)?),
+            include_hidden,
+        } => to_binary(&query::badges(deps, start_after, limit, include_hidden)?),
+        QueryMsg::ActiveBadges {
+            start_after,
+            limit,
+        } => to_binary(&query::active_badges(deps, env, start_after, limit)?),
+        QueryMsg::BadgesByManager {
+            manager,
+            start_after,
+            limit,
+        } => to_binary(&query::badges_by_manager(deps, manager, start_after, limit)?),
+        QueryMsg::BadgesByRule {
+            rule,
+            start_after,
+            limit,
+            include_hidden,
+        } => to_binary(&query::badges_by_rule(deps, rule, start_after, limit, include_hidden)?),
+        QueryMsg::BadgesByExpiry {
+            start_after,
+            limit,
+        } => to_binary(&query::badges_by_expiry(deps, start_after, limit)?),
+        QueryMsg::MetadataHistory {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::metadata_history(deps, id, start_after, limit)?),
+        QueryMsg::PendingManager {
+            id,
+        } => to_binary(&query::pending_manager(deps, id)?),
         QueryMsg::Key {
             id,
             pubkey,
@@ -117,10 +494,64 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
         } => to_binary(&query::keys(deps, id, start_after, limit)?),
+        QueryMsg::Allowlist {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::allowlist(deps, id, start_after, limit)?),
+        QueryMsg::Minters {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::minters(deps, id, start_after, limit)?),
+        QueryMsg::Registrants {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::registrants(deps, id, start_after, limit)?),
+        QueryMsg::Winners {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::winners(deps, id, start_after, limit)?),
+        QueryMsg::Voucher {
+            id,
+            hash,
+        } => to_binary(&query::voucher(deps, id, hash)),
+        QueryMsg::DelegatedMinter {
+            id,
+            minter,
+        } => to_binary(&query::delegated_minter(deps, id, minter)?),
+        QueryMsg::PendingMint {
+            id,
+        } => to_binary(&query::pending_mint(deps, id)?),
+        QueryMsg::Nonce {
+            id,
+        } => to_binary(&query::nonce(deps, id)?),
+        QueryMsg::TokenSerial {
+            token_id,
+        } => to_binary(&query::token_serial(deps, token_id)?),
+        QueryMsg::Nft {
+            id,
+            token_id,
+        } => to_binary(&query::nft(deps, id, token_id)),
+        QueryMsg::Deposit {
+            collection,
+            token_id,
+        } => to_binary(&query::deposit(deps, collection, token_id)?),
         QueryMsg::Owner {
             id,
             user,
         } => to_binary(&query::owner(deps, id, user)),
+        QueryMsg::OwnerBatch {
+            id,
+            users,
+        } => to_binary(&query::owner_batch(deps, id, users)?),
+        QueryMsg::Revocations {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::revocations(deps, id, start_after, limit)?),
         QueryMsg::Owners {
             id,
             start_after,
@@ -130,7 +561,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let cw2::ContractVersion {
         contract,
         version,
@@ -140,11 +571,27 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
         return Err(ContractError::incorrect_contract_name(CONTRACT_NAME, contract));
     }
 
-    // in the previous v1.1 update, we forgot to set the contract version to `1.1.0`
-    // so for now it's still `1.0.0`
-    if version != "1.0.0" {
-        return Err(ContractError::incorrect_contract_version("1.0.0", version));
+    // apply, in order, the state transformations of each version newer than the deployed one
+    //
+    // NOTE: in the v1.1 update, we forgot to set the contract version to `1.1.0`, so a contract
+    // that has been upgraded to v1.1 still reports `1.0.0`
+    match version.as_str() {
+        "1.0.0" => {
+            upgrades::v1_2::migrate(deps.storage)?;
+            upgrades::v1_3::migrate(deps.storage)?;
+        },
+        "1.2.0" => {
+            upgrades::v1_3::migrate(deps.storage)?;
+        },
+        _ => {
+            return Err(ContractError::incorrect_contract_version("1.0.0 or 1.2.0", version));
+        },
     }
 
-    upgrades::v1_2::migrate(deps).map_err(ContractError::from)
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/migrate")
+        .add_attribute("from_version", version)
+        .add_attribute("to_version", CONTRACT_VERSION))
 }
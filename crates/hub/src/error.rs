@@ -1,3 +1,4 @@
+use cosmwasm_std::Uint128;
 use thiserror::Error;
 
 use badges::MintRule;
@@ -11,18 +12,15 @@ pub enum ContractError {
     Verification(#[from] cosmwasm_std::VerificationError),
 
     #[error(transparent)]
-    ParseReply(#[from] cw_utils::ParseReplyError),
+    Fee(#[from] sg1::FeeError),
 
     #[error(transparent)]
-    Fee(#[from] sg1::FeeError),
+    Payment(#[from] cw_utils::PaymentError),
 
     #[error(transparent)]
     FromHex(#[from] hex::FromHexError),
 
-    #[error("invalid reply id {0}; must be 1")]
-    InvalidReplyId(u64),
-
-    #[error("not a valid secp256k1 public key")]
+    #[error("not a valid secp256k1 or ed25519 public key, or ethereum address")]
     InvalidPubkey,
 
     #[error("signature verification failed")]
@@ -34,21 +32,157 @@ pub enum ContractError {
     #[error("unauthorized: sender is not project developer")]
     NotDeveloper,
 
+    #[error("unauthorized: sender is not on the creator allowlist")]
+    NotCreator,
+
+    #[error("batch must contain at least one badge")]
+    EmptyBatch,
+
+    #[error("unauthorized: sender is not the proposed new developer")]
+    NotPendingDeveloper,
+
     #[error("unauthorized: sender is not badge manager")]
     NotManager,
 
+    #[error("unauthorized: sender is not the proposed new badge manager")]
+    NotPendingManager,
+
+    #[error("the hub is paused")]
+    HubPaused,
+
+    #[error("badge has been archived")]
+    Archived,
+
+    #[error("minting of this badge is paused")]
+    Paused,
+
+    #[error("the badge's metadata has been frozen")]
+    MetadataFrozen,
+
+    #[error("the badge's mint rule has been frozen")]
+    RuleFrozen,
+
     #[error("unauthorized: sender is not badge minter")]
     NotMinter,
 
+    #[error("unauthorized: sender is not the owner the badge is to be minted to")]
+    SenderNotOwner,
+
+    #[error("unauthorized: sender is not the badge's hook contract")]
+    NotHookContract,
+
+    #[error("minter delegation must expire in the future")]
+    DelegationExpired,
+
+    #[error("user {user} is not on the allowlist of badge {id}")]
+    NotAllowlisted {
+        id: u64,
+        user: String,
+    },
+
+    #[error("user {user} has already registered for the raffle of badge {id}")]
+    AlreadyRegistered {
+        id: u64,
+        user: String,
+    },
+
+    #[error("raffle registration has closed")]
+    RaffleClosed,
+
+    #[error("unauthorized: only the badge manager can draw the raffle before registration ends")]
+    RaffleNotClosed,
+
+    #[error("the raffle of badge {id} has already been drawn")]
+    RaffleDrawn {
+        id: u64,
+    },
+
+    #[error("the raffle of badge {id} has registrants but has not been drawn")]
+    RaffleUndrawn {
+        id: u64,
+    },
+
+    #[error("badge {id} has unused keys")]
+    KeysOutstanding {
+        id: u64,
+    },
+
+    #[error("badge {id} has unused vouchers")]
+    VouchersOutstanding {
+        id: u64,
+    },
+
+    #[error("badge {id} already has a queued mint in progress")]
+    MintPending {
+        id: u64,
+    },
+
+    #[error("badge {id} has no queued mint in progress")]
+    NoPendingMint {
+        id: u64,
+    },
+
+    #[error("raffle must have at least one winner")]
+    ZeroRaffleWinners,
+
+    #[error("raffle can have no more than {max} winners")]
+    TooManyRaffleWinners {
+        max: u64,
+    },
+
+    #[error("raffle registration is full: no more than {max} accounts can register")]
+    RaffleFull {
+        max: u64,
+    },
+
+    #[error("raffle mint rule cannot be nested in a composite rule")]
+    NestedRaffle,
+
+    #[error("deposit mint rule cannot be nested in a composite rule")]
+    NestedDeposit,
+
+    #[error("composite mint rule can contain at most one payment sub-rule")]
+    MultiplePayments,
+
+    #[error("token was not sent by the designated collection")]
+    NotDepositCollection,
+
+    #[error("owner does not hold the token {token_id} of the designated collection")]
+    NotNftOwner {
+        token_id: String,
+    },
+
     #[error("expecting the badge to be unavailable but it is available")]
     Available,
 
+    #[error("badge minting has not started")]
+    NotStarted,
+
     #[error("badge minting deadline has been been exceeded")]
     Expired,
 
+    #[error("claim signature deadline has been exceeded")]
+    SignatureExpired,
+
     #[error("badge max supply has been been exceeded")]
     SoldOut,
 
+    #[error("badge rate limit has been exceeded; try again later")]
+    RateLimited,
+
+    #[error("mint phases must be in chronological order and must not overlap")]
+    InvalidPhases,
+
+    #[error("user {user} has reached the max number of badge {id} per account in phase {phase}")]
+    PhaseMaxPerOwnerReached {
+        id: u64,
+        phase: u32,
+        user: String,
+    },
+
+    #[error("rate limit must have non-zero max mints and window length")]
+    InvalidRateLimit,
+
     #[error("key {key} already exists for badge {id}")]
     KeyExists {
         id: u64,
@@ -60,12 +194,190 @@ pub enum ContractError {
         id: u64,
     },
 
+    #[error("voucher hash must be a hex-encoded SHA-256 hash")]
+    InvalidVoucherHash,
+
+    #[error("voucher hash {hash} already exists for badge {id}")]
+    VoucherExists {
+        id: u64,
+        hash: String,
+    },
+
+    #[error("the provided voucher does not exist or has already been used for badge {id}")]
+    VoucherDoesNotExist {
+        id: u64,
+    },
+
+    #[error("composite mint rule must contain at least one sub-rule")]
+    EmptyCompositeRule,
+
+    #[error("missing proof: {name}")]
+    MissingProof {
+        name: String,
+    },
+
+    #[error("insufficient delegation: expecting at least {expected}, found {found}")]
+    InsufficientDelegation {
+        expected: Uint128,
+        found: Uint128,
+    },
+
+    #[error("insufficient group weight: expecting at least {expected}, found {found}")]
+    InsufficientGroupWeight {
+        expected: u64,
+        found: u64,
+    },
+
+    #[error("account is not a member of the designated group")]
+    NotGroupMember,
+
+    #[error("token {token_id} has already been used to mint badge {id}")]
+    NftUsed {
+        id: u64,
+        token_id: String,
+    },
+
+    #[error("user {user} has reached the max number of badge {id} per account")]
+    MaxPerOwnerReached {
+        id: u64,
+        user: String,
+    },
+
+    #[error("unauthorized: sender is not the nft contract")]
+    NotNft,
+
+    #[error("badge is not revocable")]
+    NotRevocable,
+
+    #[error("instance {serial} of badge {id} has not been minted")]
+    NotMinted {
+        id: u64,
+        serial: u64,
+    },
+
+    #[error("instance {serial} of badge {id} has already been revoked")]
+    AlreadyRevoked {
+        id: u64,
+        serial: u64,
+    },
+
     #[error("user {user} has already claimed badge {id}")]
     AlreadyClaimed {
         id: u64,
         user: String,
     },
 
+    #[error("price must be greater than zero")]
+    ZeroPrice,
+
+    #[error("revenue shares must add up to no more than 10000 bps")]
+    InvalidRevenueSplit,
+
+    #[error("royalty must be no more than 10000 bps")]
+    InvalidRoyalty,
+
+    #[error("treasury fee must not exceed 10000 bps")]
+    InvalidTreasuryFee,
+
+    #[error("routed share of fees must not exceed 10000 bps")]
+    InvalidFeeRouting,
+
+    #[error("no price oracle is set for converting usd prices")]
+    NoOracle,
+
+    #[error("price oracle returned a zero price for `{denom}`")]
+    InvalidOraclePrice {
+        denom: String,
+    },
+
+    #[error("no name resolver is set for resolving `{name}`")]
+    NoResolver {
+        name: String,
+    },
+
+    #[error("name `{name}` does not resolve to an address")]
+    NameNotResolved {
+        name: String,
+    },
+
+    #[error("invalid locale `{locale}`: expected e.g. `en` or `pt-BR`")]
+    InvalidLocale {
+        locale: String,
+    },
+
+    #[error("invalid mime type `{mime_type}` for {purpose} media: expected `{purpose}/*`")]
+    InvalidMimeType {
+        mime_type: String,
+        purpose: String,
+    },
+
+    #[error("a badge can have no more than {max} media assets")]
+    TooManyMediaAssets {
+        max: usize,
+    },
+
+    #[error("invalid subdenom `{subdenom}`: must be 1-44 letters, digits, `.` or `-`")]
+    InvalidSubdenom {
+        subdenom: String,
+    },
+
+    #[error("commemorative token amount must be greater than zero")]
+    ZeroCommemorativeAmount,
+
+    #[error("subdenom `{subdenom}` is already used by another manager")]
+    SubdenomTaken {
+        subdenom: String,
+    },
+
+    #[error("svg template must start with `<svg` and be no longer than {max} bytes")]
+    InvalidSvgTemplate {
+        max: usize,
+    },
+
+    #[error("metadata must include at least a name or an image")]
+    EmptyMetadata,
+
+    #[error("invalid {field} `{url}`: must be an https://, ipfs:// or ar:// url")]
+    InvalidUrl {
+        field: String,
+        url: String,
+    },
+
+    #[error("invalid background color `{color}`: must be a six-digit hex color without #")]
+    InvalidBackgroundColor {
+        color: String,
+    },
+
+    #[error("unknown display type `{display_type}`")]
+    UnknownDisplayType {
+        display_type: String,
+    },
+
+    #[error("trait `{trait_type}` has display type {display_type} but non-numeric value `{value}`")]
+    NonNumericTrait {
+        trait_type: String,
+        display_type: String,
+        value: String,
+    },
+
+    #[error("invalid {field} `{url}`: does not contain a well-formed ipfs cid")]
+    InvalidCid {
+        field: String,
+        url: String,
+    },
+
+    #[error("{field} must be no longer than {max} bytes")]
+    MetadataTooLong {
+        field: String,
+        max: usize,
+    },
+
+    #[error("incorrect payment: expecting {expected}, found {found}")]
+    IncorrectPayment {
+        expected: String,
+        found: String,
+    },
+
     #[error("unknown mint rule {found}, expecting by_minter|key|keys")]
     UnknownMintRule {
         found: String,
@@ -104,6 +416,76 @@ impl ContractError {
         }
     }
 
+    pub fn voucher_exists(id: u64, hash: impl Into<String>) -> Self {
+        ContractError::VoucherExists {
+            id,
+            hash: hash.into(),
+        }
+    }
+
+    pub fn voucher_does_not_exist(id: u64) -> Self {
+        ContractError::VoucherDoesNotExist {
+            id,
+        }
+    }
+
+    pub fn not_allowlisted(id: u64, user: impl Into<String>) -> Self {
+        ContractError::NotAllowlisted {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn already_registered(id: u64, user: impl Into<String>) -> Self {
+        ContractError::AlreadyRegistered {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn raffle_drawn(id: u64) -> Self {
+        ContractError::RaffleDrawn {
+            id,
+        }
+    }
+
+    pub fn raffle_undrawn(id: u64) -> Self {
+        ContractError::RaffleUndrawn {
+            id,
+        }
+    }
+
+    pub fn keys_outstanding(id: u64) -> Self {
+        ContractError::KeysOutstanding {
+            id,
+        }
+    }
+
+    pub fn vouchers_outstanding(id: u64) -> Self {
+        ContractError::VouchersOutstanding {
+            id,
+        }
+    }
+
+    pub fn not_nft_owner(token_id: impl Into<String>) -> Self {
+        ContractError::NotNftOwner {
+            token_id: token_id.into(),
+        }
+    }
+
+    pub fn nft_used(id: u64, token_id: impl Into<String>) -> Self {
+        ContractError::NftUsed {
+            id,
+            token_id: token_id.into(),
+        }
+    }
+
+    pub fn missing_proof(name: impl Into<String>) -> Self {
+        ContractError::MissingProof {
+            name: name.into(),
+        }
+    }
+
     pub fn already_claimed(id: u64, user: impl Into<String>) -> Self {
         ContractError::AlreadyClaimed {
             id,
@@ -111,6 +493,42 @@ impl ContractError {
         }
     }
 
+    pub fn not_minted(id: u64, serial: u64) -> Self {
+        ContractError::NotMinted {
+            id,
+            serial,
+        }
+    }
+
+    pub fn already_revoked(id: u64, serial: u64) -> Self {
+        ContractError::AlreadyRevoked {
+            id,
+            serial,
+        }
+    }
+
+    pub fn incorrect_payment(expected: impl ToString, found: impl ToString) -> Self {
+        ContractError::IncorrectPayment {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    pub fn max_per_owner_reached(id: u64, user: impl Into<String>) -> Self {
+        ContractError::MaxPerOwnerReached {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn phase_max_per_owner_reached(id: u64, phase: u32, user: impl Into<String>) -> Self {
+        ContractError::PhaseMaxPerOwnerReached {
+            id,
+            phase,
+            user: user.into(),
+        }
+    }
+
     pub fn unknown_mint_rule(found: impl Into<String>) -> Self {
         ContractError::UnknownMintRule {
             found: found.into(),
@@ -124,6 +542,20 @@ impl ContractError {
         }
     }
 
+    pub fn invalid_url(field: impl Into<String>, url: impl Into<String>) -> Self {
+        ContractError::InvalidUrl {
+            field: field.into(),
+            url: url.into(),
+        }
+    }
+
+    pub fn metadata_too_long(field: impl Into<String>, max: usize) -> Self {
+        ContractError::MetadataTooLong {
+            field: field.into(),
+            max,
+        }
+    }
+
     pub fn incorrect_contract_name(expect: impl Into<String>, found: impl Into<String>) -> Self {
         ContractError::IncorrectContractName {
             expect: expect.into(),
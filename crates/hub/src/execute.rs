@@ -1,346 +1,2175 @@
 use std::collections::BTreeSet;
+use std::iter;
+use std::slice;
 
-use cosmwasm_std::{to_binary, Addr, DepsMut, Empty, Env, MessageInfo, StdResult, WasmMsg};
-use cw721_base::msg::MintMsg;
-use sg_metadata::Metadata;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Coin, DepsMut, Env, MessageInfo, Order, StdResult,
+    WasmMsg,
+};
+use cw_utils::{must_pay, nonpayable, Expiration};
+use sg_metadata::{Metadata, Trait};
 use sg_std::Response;
 
-use badges::{Badge, FeeRate, MintRule};
+use badges::{
+    hub::{CompositeProof, MintReceipt},
+    Badge, CommemorativeToken, FeeRate, FeeRouting, MediaAsset, MetadataRevision, MintRule, Phase,
+    TokenIdFormat, BPS_DENOMINATOR,
+};
+
+use crate::{
+    error::ContractError,
+    fee::{charge_creation_fee, handle_batch_fee, handle_fee},
+    helpers::*,
+    query,
+    state::*,
+    token_factory,
+};
+
+pub fn init(
+    deps: DepsMut,
+    developer: Addr,
+    fee_rate: FeeRate,
+    creation_fee: Option<Coin>,
+    fee_collector: Addr,
+    token_id_format: TokenIdFormat,
+) -> StdResult<Response> {
+    DEVELOPER.save(deps.storage, &developer)?;
+    BADGE_COUNT.save(deps.storage, &0)?;
+    FEE_RATE.save(deps.storage, &fee_rate)?;
+    FEE_COLLECTOR.save(deps.storage, &fee_collector)?;
+    TOKEN_ID_FORMAT.save(deps.storage, &token_id_format)?;
+
+    if let Some(creation_fee) = &creation_fee {
+        CREATION_FEE.save(deps.storage, creation_fee)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/init"))
+}
+
+pub fn set_nft(deps: DepsMut, sender_addr: Addr, nft: &str) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    if NFT.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::DoubleInit);
+    }
+
+    let nft_addr = deps.api.addr_validate(nft)?;
+
+    NFT.save(deps.storage, &nft_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_nft")
+        .add_attribute("nft", nft))
+}
+
+pub fn migrate_nft(
+    deps: DepsMut,
+    sender_addr: Addr,
+    new_code_id: u64,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    let nft_addr = NFT.load(deps.storage)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Migrate {
+            contract_addr: nft_addr.to_string(),
+            new_code_id,
+            msg,
+        })
+        .add_attribute("action", "badges/hub/migrate_nft")
+        .add_attribute("nft", nft_addr)
+        .add_attribute("new_code_id", new_code_id.to_string()))
+}
+
+pub fn update_nft_admin(
+    deps: DepsMut,
+    sender_addr: Addr,
+    admin: &str,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    let nft_addr = NFT.load(deps.storage)?;
+    let admin_addr = deps.api.addr_validate(admin)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::UpdateAdmin {
+            contract_addr: nft_addr.to_string(),
+            admin: admin_addr.to_string(),
+        })
+        .add_attribute("action", "badges/hub/update_nft_admin")
+        .add_attribute("nft", nft_addr)
+        .add_attribute("admin", admin_addr))
+}
+
+pub fn clear_nft_admin(deps: DepsMut, sender_addr: Addr) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    let nft_addr = NFT.load(deps.storage)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::ClearAdmin {
+            contract_addr: nft_addr.to_string(),
+        })
+        .add_attribute("action", "badges/hub/clear_nft_admin")
+        .add_attribute("nft", nft_addr))
+}
+
+pub fn transfer_ownership(
+    deps: DepsMut,
+    sender_addr: Addr,
+    new_developer: &str,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    let new_developer_addr = deps.api.addr_validate(new_developer)?;
+
+    PENDING_DEVELOPER.save(deps.storage, &new_developer_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/transfer_ownership")
+        .add_attribute("new_developer", new_developer_addr))
+}
+
+pub fn accept_ownership(deps: DepsMut, sender_addr: Addr) -> Result<Response, ContractError> {
+    let pending_developer_addr = PENDING_DEVELOPER.may_load(deps.storage)?;
+
+    if pending_developer_addr.as_ref() != Some(&sender_addr) {
+        return Err(ContractError::NotPendingDeveloper);
+    }
+
+    DEVELOPER.save(deps.storage, &sender_addr)?;
+    PENDING_DEVELOPER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/accept_ownership")
+        .add_attribute("developer", sender_addr))
+}
+
+pub fn override_developer(deps: DepsMut, developer: &str) -> Result<Response, ContractError> {
+    let developer_addr = deps.api.addr_validate(developer)?;
+
+    DEVELOPER.save(deps.storage, &developer_addr)?;
+    PENDING_DEVELOPER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/override_developer")
+        .add_attribute("developer", developer_addr))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    sender_addr: Addr,
+    creation_fee: Option<Coin>,
+    fee_collector: &str,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_config(deps, creation_fee, fee_collector)
+}
+
+pub fn override_config(
+    deps: DepsMut,
+    creation_fee: Option<Coin>,
+    fee_collector: &str,
+) -> Result<Response, ContractError> {
+    let fee_collector_addr = deps.api.addr_validate(fee_collector)?;
+    FEE_COLLECTOR.save(deps.storage, &fee_collector_addr)?;
+
+    match &creation_fee {
+        Some(creation_fee) => CREATION_FEE.save(deps.storage, creation_fee)?,
+        None => CREATION_FEE.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/update_config")
+        .add_attribute("creation_fee", stringify_option(creation_fee))
+        .add_attribute("fee_collector", fee_collector_addr))
+}
+
+pub fn set_fee_routing(
+    deps: DepsMut,
+    sender_addr: Addr,
+    fee_routing: Option<FeeRouting>,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_fee_routing(deps, fee_routing)
+}
+
+pub fn override_fee_routing(
+    deps: DepsMut,
+    fee_routing: Option<FeeRouting>,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new().add_attribute("action", "badges/hub/set_fee_routing");
+
+    match fee_routing {
+        Some(fee_routing) => {
+            if fee_routing.bps > BPS_DENOMINATOR {
+                return Err(ContractError::InvalidFeeRouting);
+            }
+            FEE_ROUTING.save(deps.storage, &fee_routing)?;
+            res = res
+                .add_attribute("destination", fee_routing.destination.to_string())
+                .add_attribute("bps", fee_routing.bps.to_string());
+        },
+        None => FEE_ROUTING.remove(deps.storage),
+    }
+
+    Ok(res)
+}
+
+pub fn set_oracle(
+    deps: DepsMut,
+    sender_addr: Addr,
+    oracle: Option<String>,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_oracle(deps, oracle)
+}
+
+pub fn override_oracle(deps: DepsMut, oracle: Option<String>) -> Result<Response, ContractError> {
+    match &oracle {
+        Some(oracle) => {
+            let oracle_addr = deps.api.addr_validate(oracle)?;
+            ORACLE.save(deps.storage, &oracle_addr)?;
+        },
+        None => ORACLE.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_oracle")
+        .add_attribute("oracle", stringify_option(oracle)))
+}
+
+pub fn set_resolver(
+    deps: DepsMut,
+    sender_addr: Addr,
+    resolver: Option<String>,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_resolver(deps, resolver)
+}
+
+pub fn override_resolver(
+    deps: DepsMut,
+    resolver: Option<String>,
+) -> Result<Response, ContractError> {
+    match &resolver {
+        Some(resolver) => {
+            let resolver_addr = deps.api.addr_validate(resolver)?;
+            RESOLVER.save(deps.storage, &resolver_addr)?;
+        },
+        None => RESOLVER.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_resolver")
+        .add_attribute("resolver", stringify_option(resolver)))
+}
+
+pub fn set_denom_creation_fee(
+    deps: DepsMut,
+    sender_addr: Addr,
+    fee: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_denom_creation_fee(deps, fee)
+}
+
+pub fn override_denom_creation_fee(
+    deps: DepsMut,
+    fee: Option<Coin>,
+) -> Result<Response, ContractError> {
+    match &fee {
+        Some(fee) => DENOM_CREATION_FEE.save(deps.storage, fee)?,
+        None => DENOM_CREATION_FEE.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_denom_creation_fee")
+        .add_attribute("fee", stringify_option(fee)))
+}
+
+pub fn set_creators_restricted(
+    deps: DepsMut,
+    sender_addr: Addr,
+    restricted: bool,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    CREATORS_RESTRICTED.save(deps.storage, &restricted)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_creators_restricted")
+        .add_attribute("restricted", restricted.to_string()))
+}
+
+pub fn add_creator(
+    deps: DepsMut,
+    sender_addr: Addr,
+    creator: &str,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    let creator_addr = deps.api.addr_validate(creator)?;
+    CREATORS.insert(deps.storage, creator_addr.as_str())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/add_creator")
+        .add_attribute("creator", creator_addr))
+}
+
+pub fn remove_creator(
+    deps: DepsMut,
+    sender_addr: Addr,
+    creator: &str,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    CREATORS.remove(deps.storage, creator)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/remove_creator")
+        .add_attribute("creator", creator))
+}
+
+pub fn set_hub_paused(
+    deps: DepsMut,
+    sender_addr: Addr,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let developer_addr = DEVELOPER.load(deps.storage)?;
+
+    if sender_addr != developer_addr {
+        return Err(ContractError::NotDeveloper);
+    }
+
+    override_hub_paused(deps, paused)
+}
+
+pub fn override_hub_paused(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    HUB_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_hub_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn set_fee_rate(deps: DepsMut, fee_rate: FeeRate) -> StdResult<Response> {
+    FEE_RATE.save(deps.storage, &fee_rate)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_fee_rate")
+        .add_attribute("metadata_fee_rate", fee_rate.metadata.to_string())
+        .add_attribute("key_fee_rate", fee_rate.key.to_string()))
+}
+
+pub fn set_treasury_fee(deps: DepsMut, bps: u16) -> Result<Response, ContractError> {
+    if bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidTreasuryFee);
+    }
+
+    TREASURY_FEE.save(deps.storage, &bps)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_treasury_fee")
+        .add_attribute("treasury_fee", bps.to_string()))
+}
+
+pub fn create_badge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    badge: Badge,
+) -> Result<Response, ContractError> {
+    // badges cannot be created while the hub is paused
+    assert_hub_not_paused(deps.storage)?;
+
+    // if badge creation is restricted, the sender must be on the creator allowlist
+    assert_can_create(deps.storage, &info.sender)?;
+
+    // the badge must not have already expired or have a max supply of zero
+    assert_available(&badge, &env.block, 1)?;
+
+    // ensure the creator has paid the creation fee, if any, which is forwarded to the fee
+    // collector, less any share routed elsewhere; the remaining funds go towards the metadata fee
+    let (fee_info, creation_fee_msgs) = charge_creation_fee(deps.storage, &info, 1)?;
+
+    // ensure the creator has paid a sufficient fee
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(
+        deps.as_ref().storage,
+        &fee_info,
+        None,
+        Some(&badge),
+        fee_rate.metadata,
+    )?
+    .add_messages(creation_fee_msgs);
+
+    // the mint rule's parameters must be valid, e.g. if the badge uses "by key" mint rule, the key
+    // must be a valid secp256k1 public key
+    assert_valid_mint_rule(deps.api, &badge.rule)?;
+
+    // the mint phases must be in chronological order, each with a valid rule
+    assert_valid_phases(deps.api, &badge.phases)?;
+
+    // the revenue shares must add up to no more than the whole
+    assert_valid_revenue_split(deps.api, &badge.revenue_split)?;
+
+    // the royalty, if any, must be no more than the whole
+    assert_valid_royalty(deps.api, badge.royalty.as_ref())?;
+
+    // if the badge is rate limited, the max mints and window length must be non-zero
+    if let Some(rate_limit) = &badge.rate_limit {
+        assert_valid_rate_limit(rate_limit)?;
+    }
+
+    // the metadata must be renderable by downstream apps
+    assert_valid_metadata(&badge.metadata)?;
+
+    let id = BADGE_COUNT.update(deps.storage, |id| StdResult::Ok(id + 1))?;
+    badge_map().save(deps.storage, id, &badge)?;
+    increment_rule_count(deps.storage, &badge.rule)?;
+
+    Ok(res
+        .add_messages(cache_metadata_msgs(deps.storage, id, &badge)?)
+        .add_attribute("action", "badges/hub/create_badge")
+        .add_attribute("id", id.to_string())
+        .add_attribute("manager", badge.manager)
+        .add_attribute("rule", badge.rule.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn create_badges(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    badges: Vec<Badge>,
+) -> Result<Response, ContractError> {
+    assert_hub_not_paused(deps.storage)?;
+    assert_can_create(deps.storage, &info.sender)?;
+
+    if badges.is_empty() {
+        return Err(ContractError::EmptyBatch);
+    }
+
+    // the creation fee is charged once for each badge, while the metadata fee is charged on the
+    // total size of all badges
+    let (fee_info, creation_fee_msgs) =
+        charge_creation_fee(deps.storage, &info, badges.len() as u64)?;
+
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let mut res = handle_batch_fee(deps.as_ref().storage, &fee_info, &badges, fee_rate.metadata)?
+        .add_messages(creation_fee_msgs);
+
+    // each badge is validated the same way as in `create_badge`; if any of them is invalid, the
+    // whole batch is rejected before anything is saved
+    for badge in &badges {
+        assert_available(badge, &env.block, 1)?;
+        assert_valid_mint_rule(deps.api, &badge.rule)?;
+        assert_valid_phases(deps.api, &badge.phases)?;
+        assert_valid_revenue_split(deps.api, &badge.revenue_split)?;
+        assert_valid_royalty(deps.api, badge.royalty.as_ref())?;
+
+        if let Some(rate_limit) = &badge.rate_limit {
+            assert_valid_rate_limit(rate_limit)?;
+        }
+
+        assert_valid_metadata(&badge.metadata)?;
+    }
+
+    let mut ids = vec![];
+    for badge in &badges {
+        let id = BADGE_COUNT.update(deps.storage, |id| StdResult::Ok(id + 1))?;
+        badge_map().save(deps.storage, id, badge)?;
+        increment_rule_count(deps.storage, &badge.rule)?;
+
+        res = res.add_messages(cache_metadata_msgs(deps.storage, id, badge)?);
+        ids.push(id);
+    }
+
+    Ok(res
+        .set_data(to_binary(&ids)?)
+        .add_attribute("action", "badges/hub/create_badges")
+        .add_attribute("ids", ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(","))
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn edit_badge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    metadata: Metadata,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    // the new metadata must be renderable by downstream apps
+    assert_valid_metadata(&metadata)?;
+
+    // ensure the manager pays a sufficient fee
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(
+        deps.as_ref().storage,
+        &info,
+        Some(&badge.metadata),
+        &metadata,
+        fee_rate.metadata,
+    )?;
+
+    // record the edit in the badge's metadata history
+    let revision = METADATA_HISTORY
+        .prefix(id)
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last| last + 1);
+    METADATA_HISTORY.save(
+        deps.storage,
+        (id, revision),
+        &MetadataRevision {
+            editor: info.sender.clone(),
+            height: env.block.height,
+            previous_hash: hash_metadata(&badge.metadata)?,
+        },
+    )?;
+
+    badge.metadata = metadata;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(res
+        .add_messages(cache_metadata_msgs(deps.storage, id, &badge)?)
+        .add_attribute("action", "badges/hub/edit_badge")
+        .add_attribute("id", id.to_string())
+        .add_attribute("revision", revision.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn set_svg_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    template: Option<String>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the template is part of the badge's appearance, so it's frozen along with the metadata
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    let old_template = SVG_TEMPLATES.may_load(deps.storage, id)?;
+    let res = match &template {
+        Some(template) => {
+            assert_valid_svg_template(template)?;
+
+            // ensure the manager pays a sufficient fee for storing the template
+            let fee_rate = FEE_RATE.load(deps.storage)?;
+            let res = handle_fee(
+                deps.as_ref().storage,
+                &info,
+                old_template.as_ref(),
+                template,
+                fee_rate.metadata,
+            )?;
+
+            SVG_TEMPLATES.save(deps.storage, id, template)?;
+            res
+        },
+        None => {
+            nonpayable(&info)?;
+            SVG_TEMPLATES.remove(deps.storage, id);
+            Response::new()
+        },
+    };
+
+    Ok(res
+        .add_message(svg_template_msg(&nft_addr, id, template)?)
+        .add_attribute("action", "badges/hub/set_svg_template")
+        .add_attribute("id", id.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn set_commemorative_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    token: Option<CommemorativeToken>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the token is part of what holders receive, so it's frozen along with the metadata
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    if badge.archived {
+        return Err(ContractError::Archived);
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "badges/hub/set_commemorative_token")
+        .add_attribute("id", id.to_string());
+
+    match token {
+        Some(token) => {
+            assert_valid_commemorative_token(&token)?;
+
+            // the first manager to use a subdenom creates it and owns it from then on
+            match SUBDENOM_OWNERS.may_load(deps.storage, &token.subdenom)? {
+                Some(owner) if owner != info.sender => {
+                    return Err(ContractError::SubdenomTaken {
+                        subdenom: token.subdenom,
+                    });
+                },
+                Some(_) => nonpayable(&info)?,
+                None => {
+                    // the token factory charges the denom creation fee to the Hub, which pays it
+                    // out of the funds sent along by the manager
+                    match DENOM_CREATION_FEE.may_load(deps.storage)? {
+                        Some(fee) if !fee.amount.is_zero() => {
+                            let paid = must_pay(&info, &fee.denom)?;
+                            if paid != fee.amount {
+                                let found = Coin::new(paid.u128(), &fee.denom);
+                                return Err(ContractError::incorrect_payment(&fee, found));
+                            }
+                        },
+                        _ => nonpayable(&info)?,
+                    }
+
+                    SUBDENOM_OWNERS.save(deps.storage, &token.subdenom, &info.sender)?;
+                    res = res.add_message(token_factory::create_denom_msg(
+                        &env.contract.address,
+                        &token.subdenom,
+                    ));
+                },
+            }
+
+            res = res.add_attribute(
+                "denom",
+                token_factory::denom(&env.contract.address, &token.subdenom),
+            );
+            COMMEMORATIVE_TOKENS.save(deps.storage, id, &token)?;
+        },
+        None => {
+            nonpayable(&info)?;
+            COMMEMORATIVE_TOKENS.remove(deps.storage, id);
+        },
+    }
+
+    Ok(res)
+}
+
+pub fn set_media(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    media: Vec<MediaAsset>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // media assets are part of the badge's appearance, so they're frozen along with the metadata
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    assert_valid_media(&media)?;
+
+    let old_media = MEDIA.may_load(deps.storage, id)?;
+    let res = if media.is_empty() {
+        nonpayable(&info)?;
+        MEDIA.remove(deps.storage, id);
+        Response::new()
+    } else {
+        // ensure the manager pays a sufficient fee for storing the assets
+        let fee_rate = FEE_RATE.load(deps.storage)?;
+        let res = handle_fee(
+            deps.as_ref().storage,
+            &info,
+            old_media.as_ref(),
+            &media,
+            fee_rate.metadata,
+        )?;
+
+        MEDIA.save(deps.storage, id, &media)?;
+        res
+    };
+
+    Ok(res
+        .add_message(media_msg(&nft_addr, id, media)?)
+        .add_attribute("action", "badges/hub/set_media")
+        .add_attribute("id", id.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn set_localized_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    locale: String,
+    metadata: Option<Metadata>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // translations are part of the badge's metadata, so they're frozen along with it
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    assert_valid_locale(&locale)?;
+
+    let old_metadata = LOCALIZED_METADATA.may_load(deps.storage, (id, &locale))?;
+    let res = match &metadata {
+        Some(metadata) => {
+            assert_valid_metadata(metadata)?;
+
+            // ensure the manager pays a sufficient fee for storing the translation
+            let fee_rate = FEE_RATE.load(deps.storage)?;
+            let res = handle_fee(
+                deps.as_ref().storage,
+                &info,
+                old_metadata.as_ref(),
+                metadata,
+                fee_rate.metadata,
+            )?;
+
+            LOCALIZED_METADATA.save(deps.storage, (id, &locale), metadata)?;
+            res
+        },
+        None => {
+            nonpayable(&info)?;
+            LOCALIZED_METADATA.remove(deps.storage, (id, &locale));
+            Response::new()
+        },
+    };
+
+    Ok(res
+        .add_message(localized_metadata_msg(&nft_addr, id, locale.clone(), metadata)?)
+        .add_attribute("action", "badges/hub/set_localized_metadata")
+        .add_attribute("id", id.to_string())
+        .add_attribute("locale", locale)
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn transfer_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    new_manager: String,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can transfer the manager role
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    let new_manager_addr = deps.api.addr_validate(&new_manager)?;
+    PENDING_MANAGERS.save(deps.storage, id, &new_manager_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/transfer_manager")
+        .add_attribute("id", id.to_string())
+        .add_attribute("new_manager", new_manager_addr))
+}
+
+pub fn accept_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the proposed account can accept the manager role
+    let pending_manager_addr = PENDING_MANAGERS.may_load(deps.storage, id)?;
+    if pending_manager_addr.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotPendingManager);
+    }
+
+    badge.manager = info.sender;
+    badge_map().save(deps.storage, id, &badge)?;
+    PENDING_MANAGERS.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/accept_manager")
+        .add_attribute("id", id.to_string())
+        .add_attribute("manager", badge.manager))
+}
+
+pub fn update_mint_params(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    expiry: Option<u64>,
+    max_supply: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can update the mint params
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    badge.expiry = expiry;
+    badge.max_supply = max_supply;
+
+    // the new deadline must not have been reached, and the new max supply must not be below the
+    // current supply
+    assert_available(&badge, &env.block, 0)?;
+
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_messages(cache_metadata_msgs(deps.storage, id, &badge)?)
+        .add_attribute("action", "badges/hub/update_mint_params")
+        .add_attribute("id", id.to_string())
+        .add_attribute("expiry", stringify_option(badge.expiry))
+        .add_attribute("max_supply", stringify_option(badge.max_supply)))
+}
+
+pub fn update_mint_rule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    rule: MintRule,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can update the mint rule
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the rule must not have been frozen
+    if badge.rule_frozen {
+        return Err(ContractError::RuleFrozen);
+    }
+
+    // the new rule's parameters must be valid
+    assert_valid_mint_rule(deps.api, &rule)?;
+
+    // the old rule must not have a pending raffle, or keys or vouchers that would be left unused
+    let new_rules = iter::once(&rule)
+        .chain(badge.phases.iter().map(|phase| &phase.rule))
+        .collect::<Vec<_>>();
+    assert_can_replace_rule(deps.storage, id, &badge.rule, &new_rules)?;
+
+    // the list of users who have already claimed the badge is kept, so they can't claim again
+    // under the new rule
+    decrement_rule_count(deps.storage, &badge.rule)?;
+    increment_rule_count(deps.storage, &rule)?;
+    badge.rule = rule;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/update_mint_rule")
+        .add_attribute("id", id.to_string())
+        .add_attribute("rule", badge.rule.to_string()))
+}
+
+pub fn update_phases(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    phases: Vec<Phase>,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can update the mint phases
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the phases are frozen along with the rule
+    if badge.rule_frozen {
+        return Err(ContractError::RuleFrozen);
+    }
+
+    // the new phases must be in chronological order, each with a valid rule
+    assert_valid_phases(deps.api, &phases)?;
+
+    // likewise, the rules of the old phases must not have a pending raffle, or keys or vouchers
+    // that would be left unused
+    let new_rules = iter::once(&badge.rule)
+        .chain(phases.iter().map(|phase| &phase.rule))
+        .collect::<Vec<_>>();
+    for phase in &badge.phases {
+        assert_can_replace_rule(deps.storage, id, &phase.rule, &new_rules)?;
+    }
+
+    badge.phases = phases;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/update_phases")
+        .add_attribute("id", id.to_string())
+        .add_attribute("phases", badge.phases.len().to_string()))
+}
+
+pub fn set_badge_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can pause or unpause minting
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    badge.paused = paused;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_badge_paused")
+        .add_attribute("id", id.to_string())
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn archive_badge(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    hide: bool,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can archive the badge
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    if badge.archived {
+        return Err(ContractError::Archived);
+    }
+
+    badge.archived = true;
+    badge.hidden = hide;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/archive_badge")
+        .add_attribute("id", id.to_string())
+        .add_attribute("hidden", hide.to_string()))
+}
+
+pub fn freeze_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can freeze the metadata
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    badge.metadata_frozen = true;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/freeze_metadata")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn freeze_mint_rule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can freeze the mint rule
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    badge.rule_frozen = true;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/freeze_mint_rule")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn rotate_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    pubkey: String,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can rotate the key
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge must be of "by key" minting rule
+    match &badge.rule {
+        MintRule::ByKey(_) => (),
+        rule => return Err(ContractError::wrong_mint_rule("by_key", rule)),
+    }
+
+    // the rule must not have been frozen
+    if badge.rule_frozen {
+        return Err(ContractError::RuleFrozen);
+    }
+
+    // the new key must be a valid secp256k1 or ed25519 public key
+    let bytes = hex::decode(&pubkey)?;
+    assert_valid_pubkey(&bytes)?;
+
+    // the list of users who have already claimed the badge is kept, so they can't claim again with
+    // a signature produced by the new key
+    badge.rule = MintRule::ByKey(pubkey);
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/rotate_key")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn add_keys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    keys: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can add keys
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge, or one of its mint phases, must be of "by keys" minting rule, or a composite rule
+    // that includes it
+    if !badge.rules().any(MintRule::uses_keys) {
+        return Err(ContractError::wrong_mint_rule("by_keys", &badge.rule));
+    }
+
+    // ensure the manager pays a sufficient fee
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(
+        deps.as_ref().storage,
+        &info,
+        None,
+        &keys,
+        fee_rate.key,
+    )?;
+
+    // the minting deadline must not have been reached
+    // the max supply must not have been reached
+    assert_available(&badge, &env.block, 1)?;
+
+    // save the keys
+    keys.iter().try_for_each(|key| -> Result<_, ContractError> {
+        // key must be a of valid hex encoding
+        let bytes = hex::decode(key)?;
+
+        // key must be a valid secp256k1 or ed25519 public key
+        assert_valid_pubkey(&bytes)?;
+
+        // the key must not already exist
+        if KEYS.insert(deps.storage, (id, key))? {
+            Ok(())
+        } else {
+            Err(ContractError::key_exists(id, key))
+        }
+    })?;
+
+    Ok(res
+        .add_attribute("action", "badges/hub/add_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds))
+        .add_attribute("keys_added", keys.len().to_string()))
+}
+
+pub fn add_vouchers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    hashes: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can add vouchers
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge, or one of its mint phases, must be of "by voucher" minting rule, or a composite
+    // rule that includes it
+    if !badge.rules().any(MintRule::uses_vouchers) {
+        return Err(ContractError::wrong_mint_rule("by_voucher", &badge.rule));
+    }
+
+    // ensure the manager pays a sufficient fee, charged at the same rate as keys
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(
+        deps.as_ref().storage,
+        &info,
+        None,
+        &hashes,
+        fee_rate.key,
+    )?;
+
+    // the minting deadline must not have been reached
+    // the max supply must not have been reached
+    assert_available(&badge, &env.block, 1)?;
+
+    // save the voucher hashes
+    hashes.iter().try_for_each(|voucher_hash| -> Result<_, ContractError> {
+        assert_valid_voucher_hash(voucher_hash)?;
+
+        if VOUCHERS.insert(deps.storage, (id, voucher_hash))? {
+            Ok(())
+        } else {
+            Err(ContractError::voucher_exists(id, voucher_hash))
+        }
+    })?;
+
+    Ok(res
+        .add_attribute("action", "badges/hub/add_vouchers")
+        .add_attribute("id", id.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds))
+        .add_attribute("vouchers_added", hashes.len().to_string()))
+}
+
+pub fn add_minters(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    minters: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can add minters
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge, or one of its mint phases, must be of "by minters" minting rule
+    if !badge.rules().any(|rule| matches!(rule, MintRule::ByMinters)) {
+        return Err(ContractError::wrong_mint_rule("by_minters", &badge.rule));
+    }
+
+    for minter in &minters {
+        let minter_addr = deps.api.addr_validate(minter)?;
+        MINTERS.insert(deps.storage, (id, minter_addr.as_str()))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/add_minters")
+        .add_attribute("id", id.to_string())
+        .add_attribute("minters_added", minters.len().to_string()))
+}
+
+pub fn remove_minters(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    minters: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can remove minters
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    for minter in &minters {
+        MINTERS.remove(deps.storage, (id, minter))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/remove_minters")
+        .add_attribute("id", id.to_string())
+        .add_attribute("minters_removed", minters.len().to_string()))
+}
+
+pub fn grant_minter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    minter: String,
+    expiration: Expiration,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can delegate minting authority
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge, or one of its mint phases, must be of "by minter" or "by minters" minting rule
+    if !badge.rules().any(|rule| matches!(rule, MintRule::ByMinter(_) | MintRule::ByMinters)) {
+        return Err(ContractError::wrong_mint_rule("by_minter", &badge.rule));
+    }
+
+    // a delegation that has already expired would be useless
+    if expiration.is_expired(&env.block) {
+        return Err(ContractError::DelegationExpired);
+    }
+
+    let minter_addr = deps.api.addr_validate(&minter)?;
+    DELEGATED_MINTERS.save(deps.storage, (id, minter_addr.as_str()), &expiration)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/grant_minter")
+        .add_attribute("id", id.to_string())
+        .add_attribute("minter", minter_addr)
+        .add_attribute("expiration", expiration.to_string()))
+}
+
+pub fn revoke_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    minter: String,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can revoke delegated minting authority
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    DELEGATED_MINTERS.remove(deps.storage, (id, &minter));
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/revoke_minter")
+        .add_attribute("id", id.to_string())
+        .add_attribute("minter", minter))
+}
+
+pub fn add_to_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    users: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can add to the allowlist
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge, or one of its mint phases, must be of "by allowlist" minting rule, or a composite
+    // rule that includes it
+    if !badge.rules().any(MintRule::uses_allowlist) {
+        return Err(ContractError::wrong_mint_rule("by_allowlist", &badge.rule));
+    }
+
+    for user in &users {
+        let user_addr = deps.api.addr_validate(user)?;
+        ALLOWLIST.insert(deps.storage, (id, user_addr.as_str()))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/add_to_allowlist")
+        .add_attribute("id", id.to_string())
+        .add_attribute("users_added", users.len().to_string()))
+}
+
+pub fn remove_from_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    users: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can remove from the allowlist
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    for user in &users {
+        ALLOWLIST.remove(deps.storage, (id, user))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/remove_from_allowlist")
+        .add_attribute("id", id.to_string())
+        .add_attribute("users_removed", users.len().to_string()))
+}
+
+pub fn bump_nonce(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can bump the nonce
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    let nonce = NONCES.may_load(deps.storage, id)?.unwrap_or_default() + 1;
+    NONCES.save(deps.storage, id, &nonce)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/bump_nonce")
+        .add_attribute("id", id.to_string())
+        .add_attribute("nonce", nonce.to_string()))
+}
+
+pub fn record_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    serial: u64,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+
+    // only the nft contract can report burns
+    if info.sender != nft_addr {
+        return Err(ContractError::NotNft);
+    }
+
+    let mut badge = badge_map().load(deps.storage, id)?;
+    badge.num_burned += 1;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/record_burn")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", serial.to_string()))
+}
+
+pub fn revoke(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    serials: BTreeSet<u64>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+
+    // only the badge's manager can revoke instances of the badge
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // the badge must have been created as revocable
+    if !badge.revocable {
+        return Err(ContractError::NotRevocable);
+    }
+
+    for serial in &serials {
+        if *serial == 0 || *serial > badge.current_supply {
+            return Err(ContractError::not_minted(id, *serial));
+        }
+
+        if REVOKED.contains(deps.storage, (id, *serial)) {
+            return Err(ContractError::already_revoked(id, *serial));
+        }
+
+        REVOKED.insert(deps.storage, (id, *serial))?;
+    }
+
+    badge.num_burned += serials.len() as u64;
+    badge_map().save(deps.storage, id, &badge)?;
+
+    let msgs = serials
+        .iter()
+        .map(|serial| burn_msg(deps.storage, &nft_addr, id, *serial))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "badges/hub/revoke")
+        .add_attribute("id", id.to_string())
+        .add_attribute("amount", serials.len().to_string()))
+}
+
+pub fn set_serial_traits(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    serial: u64,
+    traits: Vec<Trait>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let badge = badge_map().load(deps.storage, id)?;
+
+    if info.sender != badge.manager {
+        return Err(ContractError::NotManager);
+    }
+
+    // an instance's traits are part of its metadata, so they're frozen along with the badge's
+    if badge.metadata_frozen {
+        return Err(ContractError::MetadataFrozen);
+    }
+
+    if serial == 0 || serial > badge.current_supply {
+        return Err(ContractError::not_minted(id, serial));
+    }
+
+    let res = if traits.is_empty() {
+        nonpayable(&info)?;
+        Response::new()
+    } else {
+        assert_valid_traits(&traits)?;
+
+        // ensure the manager pays a sufficient fee for storing the traits. the traits are only
+        // stored by the NFT contract, so the hub charges for their full size on every update
+        let fee_rate = FEE_RATE.load(deps.storage)?;
+        handle_fee(deps.as_ref().storage, &info, None, &traits, fee_rate.metadata)?
+    };
+
+    Ok(res
+        .add_message(serial_traits_msg(&nft_addr, id, serial, traits)?)
+        .add_attribute("action", "badges/hub/set_serial_traits")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", serial.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn purge_keys(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // can only purge keys once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
+
+    // need to collect the keys into a Vec first before creating a new iterator to delete them
+    // because of how Rust works
+    let res = query::keys(deps.as_ref(), id, None, limit)?;
+    for key in &res.keys {
+        KEYS.remove(deps.storage, (id, key))?;
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("keys_purged", res.keys.len().to_string()))
+}
+
+pub fn purge_owners(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+
+    // can only purge user data once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
+
+    // need to collect the user addresses into a Vec first before creating a new iterator to delete
+    // them because of how Rust works
+    let res = query::owners(deps.as_ref(), id, None, limit)?;
+    for owner in &res.owners {
+        OWNERS.remove(deps.storage, (id, owner))?;
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_owners")
+        .add_attribute("id", id.to_string())
+        .add_attribute("owners_purged", res.owners.len().to_string()))
+}
+
+pub fn mint_by_minter(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owners: BTreeSet<String>,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
+
+    // names resolving to the same address as another owner are only minted to once
+    let owners = resolve_owners(deps.as_ref(), owners)?.into_iter().collect::<Vec<_>>();
+    let amount = owners.len() as u64;
+    let start_serial = badge.current_supply + 1;
+
+    assert_mintable(deps.storage, &env.block, &badge, amount)?;
+    assert_can_mint_by_minter(deps.storage, &env.block, id, &phased, &sender)?;
+
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &owners)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_minter")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serials", stringify_serials(start_serial, amount))
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn mint_batch(
+    mut deps: DepsMut,
+    env: Env,
+    mints: Vec<(u64, BTreeSet<String>)>,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    if mints.is_empty() {
+        return Err(ContractError::EmptyBatch);
+    }
+
+    let mut res = Response::new().add_attribute("action", "badges/hub/mint_batch");
+    let mut receipts: Vec<MintReceipt> = vec![];
+
+    // each entry goes through the same checks as a standalone `mint_by_minter` call. the
+    // attributes of each entry (id, serials, amount) are kept, minus the action, and the receipts
+    // of the entries are collected into a list
+    for (id, owners) in mints {
+        let entry_res = mint_by_minter(deps.branch(), env.clone(), id, owners, sender.clone())?;
+        if let Some(data) = &entry_res.data {
+            receipts.push(from_binary(data)?);
+        }
+        res = res
+            .add_submessages(entry_res.messages)
+            .add_attributes(entry_res.attributes.into_iter().filter(|attr| attr.key != "action"));
+    }
+
+    Ok(res.set_data(to_binary(&receipts)?))
+}
+
+/// Default number of instances minted by each `ResumeMint` call, if no limit is given
+pub const DEFAULT_MINT_CHUNK: u32 = 50;
+
+/// Max number of instances minted by each `ResumeMint` call, so that the call, including the
+/// NFT contract's mint submessages, stays within the block gas limit
+pub const MAX_MINT_CHUNK: u32 = 200;
+
+pub fn queue_mint(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owners: BTreeSet<String>,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
+
+    if PENDING_MINTS.has(deps.storage, id) {
+        return Err(ContractError::MintPending {
+            id,
+        });
+    }
+
+    let owners = resolve_owners(deps.as_ref(), owners)?.into_iter().collect::<Vec<_>>();
+    let amount = owners.len() as u64;
+
+    assert_mintable(deps.storage, &env.block, &badge, amount)?;
+    assert_can_mint_by_minter(deps.storage, &env.block, id, &phased, &sender)?;
+
+    // unlike with `MintByMinter`, an invalid address would only be rejected by the NFT contract
+    // once its turn comes, leaving the rest of the queue stuck behind it, so validate upfront
+    for owner in &owners {
+        deps.api.addr_validate(owner)?;
+    }
+
+    // the supply is reserved upfront, so that other mints of the badge that take place before the
+    // queue is cleared don't take the queued owners' serials, or their share of the max supply
+    let start_serial = record_mint(deps.storage, &env.block, id, &mut badge, &owners)?;
+
+    for (idx, owner) in owners.iter().enumerate() {
+        PENDING_OWNERS.save(deps.storage, (id, start_serial + idx as u64), owner)?;
+    }
+
+    if amount > 0 {
+        PENDING_MINTS.save(deps.storage, id, &(start_serial, start_serial + amount - 1))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/queue_mint")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serials", stringify_serials(start_serial, amount))
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn resume_mint(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = badge_map().load(deps.storage, id)?;
+    let (next_serial, last_serial) =
+        PENDING_MINTS.may_load(deps.storage, id)?.ok_or(ContractError::NoPendingMint {
+            id,
+        })?;
+
+    assert_hub_not_paused(deps.storage)?;
+    assert_not_paused(&badge)?;
+
+    let limit = limit.unwrap_or(DEFAULT_MINT_CHUNK).clamp(1, MAX_MINT_CHUNK) as u64;
+    let end_serial = last_serial.min(next_serial + limit - 1);
+
+    let owners = (next_serial..=end_serial)
+        .map(|serial| {
+            let owner = PENDING_OWNERS.load(deps.storage, (id, serial))?;
+            PENDING_OWNERS.remove(deps.storage, (id, serial));
+            Ok(owner)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let amount = owners.len() as u64;
+
+    if end_serial == last_serial {
+        PENDING_MINTS.remove(deps.storage, id);
+    } else {
+        PENDING_MINTS.save(deps.storage, id, &(end_serial + 1, last_serial))?;
+    }
+
+    let (msgs, receipt) = mint_msgs(deps.storage, &env, id, next_serial, &owners)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/resume_mint")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serials", stringify_serials(next_serial, amount))
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("remaining", (last_serial - end_serial).to_string()))
+}
+
+pub fn hook_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
+
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_can_hook_mint(&phased, &info.sender)?;
+
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/hook_mint")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner)
+        .add_attribute("hook", info.sender))
+}
+
+pub fn mint_by_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+    signature: ClaimSignature,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
+
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, &owner)?;
+    assert_can_mint_by_key(deps.as_ref(), &env, &info.sender, id, &phased, &owner, &signature)?;
+
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
+
+    OWNERS.insert(deps.storage, (id, &owner))?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_key")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_keys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+    pubkey: String,
+    signature: ClaimSignature,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-use crate::{
-    error::ContractError,
-    fee::handle_fee,
-    helpers::*,
-    query,
-    state::*,
-};
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, &owner)?;
+    assert_can_mint_by_keys(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        id,
+        &phased,
+        &owner,
+        &pubkey,
+        &signature,
+    )?;
 
-pub fn init(deps: DepsMut, developer: Addr, fee_rate: FeeRate) -> StdResult<Response> {
-    DEVELOPER.save(deps.storage, &developer)?;
-    BADGE_COUNT.save(deps.storage, &0)?;
-    FEE_RATE.save(deps.storage, &fee_rate)?;
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
+
+    KEYS.remove(deps.storage, (id, &pubkey))?;
+    OWNERS.insert(deps.storage, (id, &owner))?;
 
     Ok(Response::new()
-        .add_attribute("action", "badges/hub/init"))
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
 }
 
-pub fn set_nft(deps: DepsMut, sender_addr: Addr, nft: &str) -> Result<Response, ContractError> {
-    let developer_addr = DEVELOPER.load(deps.storage)?;
-
-    if sender_addr != developer_addr {
-        return Err(ContractError::NotDeveloper);
-    }
+pub fn mint_by_voucher(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    code: String,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    if NFT.may_load(deps.storage)?.is_some() {
-        return Err(ContractError::DoubleInit);
-    }
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, &owner)?;
+    let voucher_hash = assert_can_mint_by_voucher(deps.storage, id, &phased, &code)?;
 
-    let nft_addr = deps.api.addr_validate(nft)?;
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
 
-    NFT.save(deps.storage, &nft_addr)?;
+    VOUCHERS.remove(deps.storage, (id, &voucher_hash))?;
+    OWNERS.insert(deps.storage, (id, &owner))?;
 
     Ok(Response::new()
-        .add_attribute("action", "badges/hub/set_nft")
-        .add_attribute("nft", nft))
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_voucher")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
 }
 
-pub fn set_fee_rate(deps: DepsMut, fee_rate: FeeRate) -> StdResult<Response> {
-    FEE_RATE.save(deps.storage, &fee_rate)?;
+pub fn mint_by_payment(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
+
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    let payment_msgs = assert_can_mint_by_payment(deps.as_ref(), &phased, &info)?;
+
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
 
     Ok(Response::new()
-        .add_attribute("action", "badges/hub/set_fee_rate")
-        .add_attribute("metadata_fee_rate", fee_rate.metadata.to_string())
-        .add_attribute("key_fee_rate", fee_rate.key.to_string()))
+        .add_messages(payment_msgs)
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_payment")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
 }
 
-pub fn create_badge(
+pub fn mint_by_nft(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
-    badge: Badge,
+    id: u64,
+    owner: String,
+    token_id: String,
 ) -> Result<Response, ContractError> {
-    // the badge must not have already expired or have a max supply of zero
-    assert_available(&badge, &env.block, 1)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    // ensure the creator has paid a sufficient fee
-    let fee_rate = FEE_RATE.load(deps.storage)?;
-    let res = handle_fee(
-        deps.as_ref().storage,
-        &info,
-        None,
-        Some(&badge),
-        fee_rate.metadata,
-    )?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_can_mint_by_nft(deps.as_ref(), id, &phased, &owner, &token_id)?;
 
-    // if the badge uses "by key" mint rule, the key must be a valid secp256k1
-    // public key
-    if let MintRule::ByKey(key) = &badge.rule {
-        let bytes = hex::decode(key)?;
-        assert_valid_secp256k1_pubkey(&bytes)?;
-    }
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
 
-    let id = BADGE_COUNT.update(deps.storage, |id| StdResult::Ok(id + 1))?;
-    BADGES.save(deps.storage, id, &badge)?;
+    NFTS.insert(deps.storage, (id, &token_id))?;
 
-    Ok(res
-        .add_attribute("action", "badges/hub/create_badge")
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_nft")
         .add_attribute("id", id.to_string())
-        .add_attribute("fee", stringify_funds(&info.funds)))
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner)
+        .add_attribute("token_id", token_id))
 }
 
-pub fn edit_badge(
+pub fn mint_by_deposit(
     deps: DepsMut,
-    info: MessageInfo,
+    env: Env,
     id: u64,
-    metadata: Metadata,
+    collection: Addr,
+    owner: Addr,
+    token_id: String,
 ) -> Result<Response, ContractError> {
-    let mut badge = BADGES.load(deps.storage, id)?;
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    if info.sender != badge.manager {
-        return Err(ContractError::NotManager);
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    let burn = assert_can_mint_by_deposit(&phased, &collection)?;
+
+    // the NFT contract doesn't report burns made by the Hub, so if the deposited token is one of
+    // the Hub's own badges, its burn is recorded here
+    if burn && collection == nft_addr {
+        let (burned_id, _) = parse_token_id(deps.storage, &token_id)?;
+        if burned_id == id {
+            badge.num_burned += 1;
+        } else {
+            let mut burned_badge = badge_map().load(deps.storage, burned_id)?;
+            burned_badge.num_burned += 1;
+            badge_map().save(deps.storage, burned_id, &burned_badge)?;
+        }
     }
 
-    // ensure the manager pays a sufficient fee
-    let fee_rate = FEE_RATE.load(deps.storage)?;
-    let res = handle_fee(
-        deps.as_ref().storage,
-        &info,
-        Some(&badge.metadata),
-        &metadata,
-        fee_rate.metadata,
-    )?;
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &[owner.to_string()])?;
 
-    badge.metadata = metadata;
-    BADGES.save(deps.storage, id, &badge)?;
+    // the Hub now owns the deposited token, so it can burn it
+    let mut res = Response::new();
+    if burn {
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: collection.to_string(),
+            msg: to_binary(&cw721::Cw721ExecuteMsg::Burn {
+                token_id: token_id.clone(),
+            })?,
+            funds: vec![],
+        });
+    } else {
+        DEPOSITS.save(deps.storage, (collection.as_str(), &token_id), &(id, owner.clone()))?;
+    }
 
     Ok(res
-        .add_attribute("action", "badges/hub/edit_badge")
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_deposit")
         .add_attribute("id", id.to_string())
-        .add_attribute("fee", stringify_funds(&info.funds)))
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner)
+        .add_attribute("token_id", token_id)
+        .add_attribute("burned", burn.to_string()))
 }
 
-pub fn add_keys(
+pub fn mint_by_delegation(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
     id: u64,
-    keys: BTreeSet<String>,
+    sender: Addr,
 ) -> Result<Response, ContractError> {
-    let badge = BADGES.load(deps.storage, id)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    // only the badge's manager can add keys
-    if info.sender != badge.manager {
-        return Err(ContractError::NotManager);
-    }
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, sender.as_str())?;
+    assert_can_mint_by_delegation(deps.as_ref(), &phased, &sender)?;
 
-    // the badge must be of "by keys" minting rule
-    match &badge.rule {
-        MintRule::ByKeys => (),
-        rule => return Err(ContractError::wrong_mint_rule("by_keys", rule)),
-    }
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &[sender.to_string()])?;
 
-    // ensure the manager pays a sufficient fee
-    let fee_rate = FEE_RATE.load(deps.storage)?;
-    let res = handle_fee(
-        deps.as_ref().storage,
-        &info,
-        None,
-        &keys,
-        fee_rate.key,
-    )?;
+    OWNERS.insert(deps.storage, (id, sender.as_str()))?;
 
-    // the minting deadline must not have been reached
-    // the max supply must not have been reached
-    assert_available(&badge, &env.block, 1)?;
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_delegation")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", sender))
+}
 
-    // save the keys
-    keys.iter().try_for_each(|key| -> Result<_, ContractError> {
-        // key must be a of valid hex encoding
-        let bytes = hex::decode(key)?;
+pub fn mint_by_group(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-        // key must be a valid secp256k1 public key
-        assert_valid_secp256k1_pubkey(&bytes)?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, sender.as_str())?;
+    assert_can_mint_by_group(deps.as_ref(), &phased, &sender)?;
 
-        // the key must not already exist
-        if KEYS.insert(deps.storage, (id, key))? {
-            Ok(())
-        } else {
-            Err(ContractError::key_exists(id, key))
-        }
-    })?;
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &[sender.to_string()])?;
 
-    Ok(res
-        .add_attribute("action", "badges/hub/add_keys")
+    OWNERS.insert(deps.storage, (id, sender.as_str()))?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_group")
         .add_attribute("id", id.to_string())
-        .add_attribute("fee", stringify_funds(&info.funds))
-        .add_attribute("keys_added", keys.len().to_string()))
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", sender))
 }
 
-pub fn purge_keys(
+pub fn claim(
     deps: DepsMut,
     env: Env,
     id: u64,
-    limit: Option<u32>,
+    sender: Addr,
 ) -> Result<Response, ContractError> {
-    let badge = BADGES.load(deps.storage, id)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    // can only purge keys once the badge becomes unavailable to be minted
-    assert_unavailable(&badge, &env.block)?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, sender.as_str())?;
+    assert_can_claim(&phased)?;
 
-    // need to collect the keys into a Vec first before creating a new iterator to delete them
-    // because of how Rust works
-    let res = query::keys(deps.as_ref(), id, None, limit)?;
-    for key in &res.keys {
-        KEYS.remove(deps.storage, (id, key))?;
-    };
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &[sender.to_string()])?;
+
+    OWNERS.insert(deps.storage, (id, sender.as_str()))?;
 
     Ok(Response::new()
-        .add_attribute("action", "badges/hub/purge_keys")
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/claim")
         .add_attribute("id", id.to_string())
-        .add_attribute("keys_purged", res.keys.len().to_string()))
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", sender))
 }
 
-pub fn purge_owners(
+pub fn mint_by_allowlist(
     deps: DepsMut,
     env: Env,
     id: u64,
-    limit: Option<u32>,
+    sender: Addr,
 ) -> Result<Response, ContractError> {
-    let badge = BADGES.load(deps.storage, id)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    // can only purge user data once the badge becomes unavailable to be minted
-    assert_unavailable(&badge, &env.block)?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, sender.as_str())?;
+    assert_can_mint_by_allowlist(deps.storage, id, &phased, sender.as_str())?;
 
-    // need to collect the user addresses into a Vec first before creating a new iterator to delete
-    // them because of how Rust works
-    let res = query::owners(deps.as_ref(), id, None, limit)?;
-    for owner in &res.owners {
-        OWNERS.remove(deps.storage, (id, owner))?;
-    };
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &[sender.to_string()])?;
+
+    OWNERS.insert(deps.storage, (id, sender.as_str()))?;
 
     Ok(Response::new()
-        .add_attribute("action", "badges/hub/purge_owners")
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_allowlist")
         .add_attribute("id", id.to_string())
-        .add_attribute("owners_purged", res.owners.len().to_string()))
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", sender))
 }
 
-pub fn mint_by_minter(
+pub fn register_for_raffle(
     deps: DepsMut,
     env: Env,
     id: u64,
-    owners: BTreeSet<String>,
     sender: Addr,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
+    let badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    let amount = owners.len() as u64;
-    let start_serial = badge.current_supply + 1;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
+    assert_eligible(deps.storage, id, sender.as_str())?;
+    assert_can_register_for_raffle(deps.storage, &env.block, id, &phased, sender.as_str())?;
 
-    assert_available(&badge, &env.block, amount)?;
-    assert_can_mint_by_minter(&badge, &sender)?;
-
-    badge.current_supply += amount;
-    BADGES.save(deps.storage, id, &badge)?;
-
-    let msgs = owners
-        .into_iter()
-        .enumerate()
-        .map(|(idx, owner)| -> StdResult<_> {
-            let serial = start_serial + (idx as u64);
-            Ok(WasmMsg::Execute {
-                contract_addr: nft_addr.to_string(),
-                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                    token_id: token_id(id, serial),
-                    owner,
-                    token_uri: None,
-                    extension: None,
-                }))?,
-                funds: vec![],
-            })
-        })
-        .collect::<StdResult<Vec<_>>>()?;
+    REGISTRANTS.insert(deps.storage, (id, sender.as_str()))?;
+    REGISTRANT_COUNTS.update(deps.storage, id, |count| -> StdResult<_> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
 
     Ok(Response::new()
-        .add_messages(msgs)
-        .add_attribute("action", "badges/hub/mint_by_minter")
+        .add_attribute("action", "badges/hub/register_for_raffle")
         .add_attribute("id", id.to_string())
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("user", sender))
 }
 
-pub fn mint_by_key(
+pub fn draw_raffle(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     id: u64,
-    owner: String,
-    signature: String,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    assert_available(&badge, &env.block, 1)?;
-    assert_eligible(deps.storage, id, &owner)?;
-    assert_can_mint_by_key(deps.api, id, &badge, &owner, &signature)?;
+    // a drawn raffle usually leaves the badge sold out, so check that first for a clearer error
+    let winners = assert_can_draw_raffle(deps.storage, &env.block, id, &phased, &info.sender)?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
 
-    badge.current_supply += 1;
-    BADGES.save(deps.storage, id, &badge)?;
+    // the number of winners is capped by the remaining supply
+    let amount = match badge.max_supply {
+        Some(max_supply) => winners.min(max_supply - badge.current_supply),
+        None => winners,
+    };
 
-    OWNERS.insert(deps.storage, (id, &owner))?;
+    let registrants = REGISTRANTS
+        .prefix(id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let winners = draw_winners(&raffle_seed(&env, id), registrants, amount as usize);
+
+    let amount = winners.len() as u64;
+    let (msgs, receipt) = prepare_mint(deps.storage, &env, id, &mut badge, &winners)?;
+
+    for winner in &winners {
+        WINNERS.insert(deps.storage, (id, winner))?;
+        OWNERS.insert(deps.storage, (id, winner))?;
+    }
+
+    RAFFLES_DRAWN.insert(deps.storage, id)?;
 
     Ok(Response::new()
-        .add_message(WasmMsg::Execute {
-            contract_addr: nft_addr.to_string(),
-            msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                token_id: token_id(id, badge.current_supply),
-                // NOTE: it's possible to avoid cloning and save a liiiittle bit of gas here, simply
-                // by moving this `add_message` after the one `add_attribute` that uses `owner`.
-                // however this makes the code uglier so i don't want to do it.
-                // Stargaze has free gas price anyways (for now at least)
-                owner: owner.clone(),
-                token_uri: None,
-                extension: None,
-            }))?,
-            funds: vec![],
-        })
-        .add_attribute("action", "badges/hub/mint_by_key")
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/draw_raffle")
         .add_attribute("id", id.to_string())
-        .add_attribute("serial", badge.current_supply.to_string())
-        .add_attribute("recipient", owner))
+        .add_attribute("serials", stringify_serials(receipt.start_serial, amount))
+        .add_attribute("amount", amount.to_string()))
 }
 
-pub fn mint_by_keys(
+pub fn mint_by_composite(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     id: u64,
     owner: String,
-    pubkey: String,
-    signature: String,
+    proof: CompositeProof,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
+    let mut badge = badge_map().load(deps.storage, id)?;
+    let (phased, _) = enter_phase(&badge, &env.block);
 
-    assert_available(&badge, &env.block, 1)?;
+    assert_mintable(deps.storage, &env.block, &badge, 1)?;
     assert_eligible(deps.storage, id, &owner)?;
-    assert_can_mint_by_keys(deps.as_ref(), id, &badge, &owner, &pubkey, &signature)?;
+    let effects =
+        assert_can_mint_by_composite(deps.as_ref(), &env, &info, id, &phased, &owner, &proof)?;
 
-    badge.current_supply += 1;
-    BADGES.save(deps.storage, id, &badge)?;
+    let (msgs, receipt) =
+        prepare_mint(deps.storage, &env, id, &mut badge, slice::from_ref(&owner))?;
 
-    KEYS.remove(deps.storage, (id, &pubkey))?;
     OWNERS.insert(deps.storage, (id, &owner))?;
 
-    Ok(Response::new()
-        .add_message(WasmMsg::Execute {
-            contract_addr: nft_addr.to_string(),
-            msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                token_id: token_id(id, badge.current_supply),
-                owner: owner.clone(),
-                token_uri: None,
-                extension: None,
-            }))?,
-            funds: vec![],
-        })
-        .add_attribute("action", "badges/hub/mint_by_keys")
+    let mut res = Response::new();
+    for effect in effects {
+        match effect {
+            MintEffect::Payment(msgs) => res = res.add_messages(msgs),
+            MintEffect::ConsumeKey(pubkey) => {
+                KEYS.remove(deps.storage, (id, &pubkey))?;
+            },
+            MintEffect::ConsumeVoucher(voucher_hash) => {
+                VOUCHERS.remove(deps.storage, (id, &voucher_hash))?;
+            },
+            MintEffect::UseNft(token_id) => {
+                NFTS.insert(deps.storage, (id, &token_id))?;
+            },
+        }
+    }
+
+    Ok(res
+        .add_messages(msgs)
+        .set_data(to_binary(&receipt)?)
+        .add_attribute("action", "badges/hub/mint_by_composite")
         .add_attribute("id", id.to_string())
         .add_attribute("serial", badge.current_supply.to_string())
         .add_attribute("recipient", owner))
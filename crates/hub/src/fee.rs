@@ -1,7 +1,12 @@
-use cosmwasm_std::{to_binary, MessageInfo, Storage, Uint128, Decimal};
-use sg_std::Response;
+use cosmwasm_std::{to_binary, BankMsg, Coin, MessageInfo, StdResult, Storage, Uint128, Decimal};
+use sg_std::{create_fund_community_pool_msg, CosmosMsg, Response};
 
-use crate::{error::ContractError, state::DEVELOPER};
+use badges::{FeeDestination, BPS_DENOMINATOR};
+
+use crate::{
+    error::ContractError,
+    state::{CREATION_FEE, DEVELOPER, FEE_COLLECTOR, FEE_ROUTING},
+};
 
 // TODO: add docs
 pub fn handle_fee<T: serde::Serialize>(
@@ -20,8 +25,33 @@ pub fn handle_fee<T: serde::Serialize>(
     let new_bytes = to_binary(&new_data)?.len();
     let bytes_diff = new_bytes.saturating_sub(old_bytes);
 
+    charge_storage_fee(store, info, bytes_diff, fee_per_byte)
+}
+
+/// Same as `handle_fee`, but for storing multiple new pieces of data at once, e.g. when creating
+/// badges in a batch. The fee is charged on their total size.
+pub fn handle_batch_fee<T: serde::Serialize>(
+    store: &dyn Storage,
+    info: &MessageInfo,
+    new_data: &[T],
+    fee_per_byte: Decimal,
+) -> Result<Response, ContractError> {
+    let bytes = new_data
+        .iter()
+        .map(|data| to_binary(data).map(|bytes| bytes.len()))
+        .sum::<StdResult<usize>>()?;
+
+    charge_storage_fee(store, info, bytes, fee_per_byte)
+}
+
+fn charge_storage_fee(
+    store: &dyn Storage,
+    info: &MessageInfo,
+    bytes: usize,
+    fee_per_byte: Decimal,
+) -> Result<Response, ContractError> {
     // fee amount is bytes * fee rate (ustars per bytes)
-    let fee = Uint128::new(bytes_diff as u128) * fee_per_byte;
+    let fee = Uint128::new(bytes as u128) * fee_per_byte;
 
     let mut res = Response::new();
 
@@ -32,3 +62,89 @@ pub fn handle_fee<T: serde::Serialize>(
 
     Ok(res)
 }
+
+/// The share of a protocol fee to be routed away from its usual recipient, and where to, if the hub
+/// routes fees and the share doesn't round down to zero.
+pub fn route_fee(
+    store: &dyn Storage,
+    amount: Uint128,
+) -> StdResult<Option<(FeeDestination, Uint128)>> {
+    let fee_routing = match FEE_ROUTING.may_load(store)? {
+        Some(fee_routing) => fee_routing,
+        None => return Ok(None),
+    };
+
+    let routed = amount.multiply_ratio(fee_routing.bps, BPS_DENOMINATOR);
+    if routed.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some((fee_routing.destination, routed)))
+}
+
+/// The message that sends routed fees in native coins to their destination
+pub fn routed_fee_msg(destination: &FeeDestination, coin: Coin) -> CosmosMsg {
+    match destination {
+        FeeDestination::Burn => BankMsg::Burn {
+            amount: vec![coin],
+        }
+        .into(),
+        FeeDestination::CommunityPool => create_fund_community_pool_msg(vec![coin]),
+    }
+}
+
+/// Deduct the hub's creation fee, if any, for each of the badges being created from the funds sent
+/// along, and return the remaining funds together with the messages that forward the fees to the
+/// fee collector and, if the hub routes fees, to their destination.
+pub fn charge_creation_fee(
+    store: &dyn Storage,
+    info: &MessageInfo,
+    num_badges: u64,
+) -> Result<(MessageInfo, Vec<CosmosMsg>), ContractError> {
+    let creation_fee = match CREATION_FEE.may_load(store)? {
+        Some(creation_fee) if !creation_fee.amount.is_zero() => Coin {
+            denom: creation_fee.denom,
+            amount: creation_fee.amount * Uint128::from(num_badges),
+        },
+        _ => return Ok((info.clone(), vec![])),
+    };
+
+    let mut funds = info.funds.clone();
+    match funds.iter_mut().find(|coin| coin.denom == creation_fee.denom) {
+        Some(coin) if coin.amount >= creation_fee.amount => coin.amount -= creation_fee.amount,
+        Some(coin) => return Err(ContractError::incorrect_payment(&creation_fee, coin)),
+        None => {
+            let found = Coin::new(0, &creation_fee.denom);
+            return Err(ContractError::incorrect_payment(&creation_fee, found));
+        },
+    }
+    funds.retain(|coin| !coin.amount.is_zero());
+
+    let fee_collector_addr = match FEE_COLLECTOR.may_load(store)? {
+        Some(fee_collector_addr) => fee_collector_addr,
+        None => DEVELOPER.load(store)?,
+    };
+
+    let fee_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds,
+    };
+
+    let mut msgs = vec![];
+    let mut collected = creation_fee.amount;
+    if let Some((destination, routed)) = route_fee(store, creation_fee.amount)? {
+        msgs.push(routed_fee_msg(&destination, Coin::new(routed.u128(), &creation_fee.denom)));
+        collected -= routed;
+    }
+    if !collected.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: fee_collector_addr.into(),
+                amount: vec![Coin::new(collected.u128(), creation_fee.denom)],
+            }
+            .into(),
+        );
+    }
+
+    Ok((fee_info, msgs))
+}
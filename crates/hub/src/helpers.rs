@@ -1,28 +1,444 @@
+use std::collections::BTreeSet;
 use std::fmt;
 
-use cosmwasm_std::{Addr, Api, BlockInfo, Deps, Storage, Coin};
+use cosmwasm_std::{
+    to_binary, to_vec, Addr, Api, BankMsg, BlockInfo, Coin, Decimal, Deps, Empty, Env,
+    MessageInfo, Order, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw4::{Cw4QueryMsg, MemberResponse};
+use cw721::{Cw721QueryMsg, OwnerOfResponse};
+use cw721_base::msg::MintMsg;
+use cw_item_set::Set;
+use cw_utils::{must_pay, nonpayable, Expiration};
+use sg_metadata::{Metadata, Trait};
+use sg_std::CosmosMsg;
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
-use badges::{Badge, MintRule};
+use badges::hub::{
+    CompositeProof, MintReceipt, OraclePriceResponse, OracleQueryMsg, ResolveResponse,
+    ResolverQueryMsg, SignMode,
+};
+use badges::locale::is_valid_locale;
+use badges::signing;
+use badges::nft::{ExtensionMsg, MetadataCache};
+use badges::{
+    Badge, CommemorativeToken, FeeDestination, MediaAsset, MintRule, Phase, Price, RateLimit,
+    RevenueShare, TokenIdFormat, Window, BPS_DENOMINATOR,
+};
 
 use crate::{
+    cid::is_valid_cid,
     error::ContractError,
-    state::{KEYS, OWNERS},
+    fee::{route_fee, routed_fee_msg},
+    state::{
+        badge_map, ALLOWLIST, COMMEMORATIVE_TOKENS, CREATORS, CREATORS_RESTRICTED,
+        DELEGATED_MINTERS, DEVELOPER, HUB_PAUSED, KEYS, MINTED_COUNT, MINTERS, MINT_COUNTS,
+        MINT_WINDOWS, NFT, NFTS, NONCES, ORACLE, OWNERS, PHASE_MINT_COUNTS, RAFFLES_DRAWN,
+        REGISTRANTS, REGISTRANT_COUNTS, RESOLVER, RULE_STATS, TOKEN_COUNT, TOKEN_ID_FORMAT,
+        TOKEN_NUMBERS, TOKEN_SERIALS, TREASURY_FEE, VOUCHERS,
+    },
+    token_factory,
 };
 
+/// URL schemes accepted in the metadata of badges
+const URL_SCHEMES: [&str; 3] = ["https://", "ipfs://", "ar://"];
+/// Maximum length, in bytes, of a badge's name
+const MAX_NAME_LEN: usize = 256;
+/// Maximum length, in bytes, of a badge's description or raw image data
+const MAX_DESCRIPTION_LEN: usize = 8192;
+/// Maximum length, in bytes, of each of the urls in a badge's metadata
+const MAX_URL_LEN: usize = 512;
+/// Maximum length, in bytes, of the type and value of each of a badge's traits
+const MAX_TRAIT_LEN: usize = 256;
+/// Maximum length, in bytes, of a token factory subdenom
+const MAX_SUBDENOM_LEN: usize = 44;
+/// Maximum number of media assets attached to a badge
+const MAX_MEDIA_ASSETS: usize = 8;
+/// Maximum length, in bytes, of the MIME type of a media asset
+const MAX_MIME_TYPE_LEN: usize = 128;
+/// Maximum length, in bytes, of the SVG template of a badge's on-chain image
+const MAX_SVG_TEMPLATE_LEN: usize = 16384;
+/// Maximum number of winners of a raffle, all of which are minted by a single `DrawRaffle` call
+pub const MAX_RAFFLE_WINNERS: u64 = 100;
+/// Maximum number of accounts that can register for a raffle, all of which are loaded when drawing
+pub const MAX_RAFFLE_REGISTRANTS: u64 = 2000;
+
 /// Length of a serialized compressed public key
 const ECDSA_COMPRESSED_PUBKEY_LEN: usize = 33;
 /// Length of a serialized uncompressed public key
 const ECDSA_UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+/// Length of a serialized ed25519 public key
+const EDDSA_PUBKEY_LEN: usize = 32;
+/// Length of an Ethereum address
+const ETH_ADDRESS_LEN: usize = 20;
+
+/// The signature schemes supported for minting keys
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyType {
+    Secp256k1,
+    Ed25519,
+    /// Not a pubkey, but the address of an Ethereum account, which can only be checked against
+    /// signatures the pubkey can be recovered from, i.e. those produced with `personal_sign`
+    EthAddress,
+}
+
+impl KeyType {
+    /// Determine the key type from the serialized pubkey. A secp256k1 key is 33 bytes compressed,
+    /// or 65 bytes uncompressed, with the corresponding prefix; an ed25519 key is 32 bytes; an
+    /// Ethereum address is 20 bytes.
+    ///
+    /// The secp256k1 check is copied from cosmwasm-crypto:
+    /// https://github.com/CosmWasm/cosmwasm/blob/v1.1.9/packages/crypto/src/secp256k1.rs#L140-L151
+    ///
+    /// Previously I attempted to use the `k256` library for pubkey validation.
+    /// But it did not work because `rand` is a non-optional dependency for `k256`.
+    pub fn from_pubkey(bytes: &[u8]) -> Result<Self, ContractError> {
+        match (bytes.first(), bytes.len()) {
+            (Some(0x02) | Some(0x03), ECDSA_COMPRESSED_PUBKEY_LEN) => Ok(KeyType::Secp256k1),
+            (Some(0x04), ECDSA_UNCOMPRESSED_PUBKEY_LEN) => Ok(KeyType::Secp256k1),
+            (Some(_), EDDSA_PUBKEY_LEN) => Ok(KeyType::Ed25519),
+            (Some(_), ETH_ADDRESS_LEN) => Ok(KeyType::EthAddress),
+            _ => Err(ContractError::InvalidPubkey),
+        }
+    }
+}
+
+/// Each NFT's token id is derived from the badge id and the serial, in the format chosen when the
+/// hub was instantiated. In the counter format, it's the number assigned to the instance when its
+/// mint was recorded.
+pub fn token_id(store: &dyn Storage, id: u64, serial: u64) -> StdResult<String> {
+    match TOKEN_ID_FORMAT.may_load(store)?.unwrap_or_default() {
+        TokenIdFormat::Counter => {
+            let count = TOKEN_NUMBERS.load(store, (id, serial))?;
+            Ok(TokenIdFormat::build_counter(count))
+        },
+        format => format.build(id, serial),
+    }
+}
+
+/// Split a token id of the Hub's NFT contract into the badge id and the serial.
+pub fn parse_token_id(store: &dyn Storage, token_id: &str) -> StdResult<(u64, u64)> {
+    match TOKEN_ID_FORMAT.may_load(store)?.unwrap_or_default() {
+        TokenIdFormat::Counter => {
+            let count = TokenIdFormat::parse_counter(token_id)?;
+            TOKEN_SERIALS
+                .may_load(store, count)?
+                .ok_or_else(|| StdError::generic_err(format!("token {} not found", token_id)))
+        },
+        format => format.parse(token_id),
+    }
+}
+
+/// In the counter token id format, assign the next numbers to the instances of a badge with the
+/// given serials. Does nothing in the other formats.
+pub fn assign_token_numbers(
+    store: &mut dyn Storage,
+    id: u64,
+    serials: impl Iterator<Item = u64>,
+) -> StdResult<()> {
+    if TOKEN_ID_FORMAT.may_load(store)?.unwrap_or_default() != TokenIdFormat::Counter {
+        return Ok(());
+    }
+
+    let mut count = TOKEN_COUNT.may_load(store)?.unwrap_or(0);
+    for serial in serials {
+        count += 1;
+        TOKEN_NUMBERS.save(store, (id, serial), &count)?;
+        TOKEN_SERIALS.save(store, count, &(id, serial))?;
+    }
+    TOKEN_COUNT.save(store, &count)
+}
+
+/// The receipt of minting instances of a badge to the recipients, with consecutive serials starting
+/// from `start_serial`.
+pub fn mint_receipt(
+    store: &dyn Storage,
+    id: u64,
+    start_serial: u64,
+    recipients: Vec<String>,
+) -> StdResult<MintReceipt> {
+    let token_ids = (start_serial..)
+        .take(recipients.len())
+        .map(|serial| token_id(store, id, serial))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(MintReceipt {
+        id,
+        start_serial,
+        amount: recipients.len() as u64,
+        token_ids,
+        recipients,
+    })
+}
+
+/// The message to be sent to the NFT contract to mint an instance of the badge to the owner.
+pub fn mint_msg(
+    store: &dyn Storage,
+    nft_addr: &Addr,
+    id: u64,
+    serial: u64,
+    owner: impl Into<String>,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+            token_id: token_id(store, id, serial)?,
+            owner: owner.into(),
+            token_uri: None,
+            extension: None,
+        }))?,
+        funds: vec![],
+    })
+}
+
+/// The message to mint a badge's commemorative tokens to the recipient of an instance, if the badge
+/// has any configured
+pub fn commemorative_msg(
+    store: &dyn Storage,
+    hub_addr: &Addr,
+    id: u64,
+    recipient: &str,
+) -> StdResult<Option<CosmosMsg>> {
+    let token = COMMEMORATIVE_TOKENS.may_load(store, id)?;
+    Ok(token.map(|token| {
+        let denom = token_factory::denom(hub_addr, &token.subdenom);
+        token_factory::mint_msg(hub_addr, &denom, token.amount, recipient)
+    }))
+}
+
+/// Mint a badge to the owners, who are given consecutive serials following the badge's current
+/// supply. The mint is recorded with `record_mint`; returns the messages minting the owners' NFTs
+/// and commemorative tokens, along with the receipt of the mint.
+pub fn prepare_mint(
+    store: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    badge: &mut Badge,
+    owners: &[String],
+) -> Result<(Vec<CosmosMsg>, MintReceipt), ContractError> {
+    let start_serial = record_mint(store, &env.block, id, badge, owners)?;
+    Ok(mint_msgs(store, env, id, start_serial, owners)?)
+}
+
+/// Increase a badge's supply by the number of owners and save it, and increment the mint counters,
+/// throwing an error if this exceeds any of the badge's limits. Returns the serial of the first
+/// owner.
+pub fn record_mint(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    id: u64,
+    badge: &mut Badge,
+    owners: &[String],
+) -> Result<u64, ContractError> {
+    let amount = owners.len() as u64;
+    let start_serial = badge.current_supply + 1;
+
+    badge.current_supply += amount;
+    badge_map().save(store, id, badge)?;
+    assign_token_numbers(store, id, start_serial..start_serial + amount)?;
+
+    let phase = badge.active_phase(block.time.seconds());
+    let rule = match phase {
+        Some(phase) => &badge.phases[phase as usize].rule,
+        None => &badge.rule,
+    };
+
+    // raffle draws are exempt from the rate limit: all winners are minted at once, and the number
+    // of winners is already fixed by the mint rule
+    if !matches!(rule, MintRule::ByRaffle { .. }) {
+        increment_window_count(store, block, id, badge, amount)?;
+    }
+    increment_minted_count(store, rule, amount)?;
+
+    for owner in owners {
+        increment_mint_count(store, id, badge, owner)?;
+        increment_phase_mint_count(store, id, badge, phase, owner)?;
+    }
+
+    Ok(start_serial)
+}
+
+/// The messages minting the NFTs of a badge to the owners, with consecutive serials starting from
+/// `start_serial`, followed by the ones minting their commemorative tokens, if any. Also returns
+/// the receipt of the mint.
+pub fn mint_msgs(
+    store: &dyn Storage,
+    env: &Env,
+    id: u64,
+    start_serial: u64,
+    owners: &[String],
+) -> StdResult<(Vec<CosmosMsg>, MintReceipt)> {
+    let nft_addr = NFT.load(store)?;
+
+    let mut msgs = owners
+        .iter()
+        .enumerate()
+        .map(|(idx, owner)| {
+            let serial = start_serial + (idx as u64);
+            Ok(mint_msg(store, &nft_addr, id, serial, owner)?.into())
+        })
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    for owner in owners {
+        msgs.extend(commemorative_msg(store, &env.contract.address, id, owner)?);
+    }
+
+    let receipt = mint_receipt(store, id, start_serial, owners.to_vec())?;
+
+    Ok((msgs, receipt))
+}
+
+/// The message to be sent to the NFT contract to burn an instance of the badge, e.g. when the badge
+/// is revoked by its manager.
+pub fn burn_msg(store: &dyn Storage, nft_addr: &Addr, id: u64, serial: u64) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&sg721::ExecuteMsg::<Option<Empty>, Empty>::Burn {
+            token_id: token_id(store, id, serial)?,
+        })?,
+        funds: vec![],
+    })
+}
+
+/// The messages to be sent to the NFT contract to cache the badge's metadata, so that it can serve
+/// token info without querying the hub. Empty if the NFT contract has not been set yet; in that
+/// case the NFT contract falls back to querying the hub.
+pub fn cache_metadata_msgs(store: &dyn Storage, id: u64, badge: &Badge) -> StdResult<Vec<WasmMsg>> {
+    let nft_addr = match NFT.may_load(store)? {
+        Some(nft_addr) => nft_addr,
+        None => return Ok(vec![]),
+    };
+
+    Ok(vec![WasmMsg::Execute {
+        contract_addr: nft_addr.into(),
+        msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+            msg: ExtensionMsg::CacheMetadata {
+                id,
+                cache: MetadataCache {
+                    metadata: badge.metadata.clone(),
+                    token_uri: badge.token_uri.clone(),
+                    max_supply: badge.max_supply,
+                },
+            },
+        })?,
+        funds: vec![],
+    }])
+}
+
+/// The message to be sent to the NFT contract to replace a badge's media assets
+pub fn media_msg(nft_addr: &Addr, id: u64, media: Vec<MediaAsset>) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetMedia {
+                id,
+                media,
+            },
+        })?,
+        funds: vec![],
+    })
+}
+
+/// The message to be sent to the NFT contract to set or remove a translation of a badge's metadata
+pub fn localized_metadata_msg(
+    nft_addr: &Addr,
+    id: u64,
+    locale: String,
+    metadata: Option<Metadata>,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetLocalizedMetadata {
+                id,
+                locale,
+                metadata,
+            },
+        })?,
+        funds: vec![],
+    })
+}
+
+/// The message to be sent to the NFT contract to set or remove a badge's SVG template
+pub fn svg_template_msg(nft_addr: &Addr, id: u64, template: Option<String>) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetSvgTemplate {
+                id,
+                template,
+            },
+        })?,
+        funds: vec![],
+    })
+}
+
+/// The message to be sent to the NFT contract to attach traits to a single instance of a badge
+pub fn serial_traits_msg(
+    nft_addr: &Addr,
+    id: u64,
+    serial: u64,
+    traits: Vec<Trait>,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetSerialTraits {
+                id,
+                serial,
+                traits,
+            },
+        })?,
+        funds: vec![],
+    })
+}
+
+/// The message the user needs to sign to claim the badge under "by key" or "by keys" rule, at this
+/// Hub on this chain; see `badges::signing::message` for the format.
+pub fn message(
+    env: &Env,
+    id: u64,
+    user: impl fmt::Display,
+    nonce: u64,
+    deadline: Option<&Expiration>,
+) -> String {
+    signing::message(
+        env.contract.address.as_str(),
+        &env.block.chain_id,
+        id,
+        &user.to_string(),
+        nonce,
+        deadline,
+    )
+}
 
-/// Each NFT's token id is simply the badge id and the serial separated by a pipe.
-pub fn token_id(id: u64, serial: u64) -> String {
-    format!("{}|{}", id, serial)
+/// The bytes that are actually signed in the given sign mode; see `badges::signing::sign_doc`.
+pub fn sign_doc(message: String, sign_mode: &SignMode, signer: &Addr) -> String {
+    signing::sign_doc(message, sign_mode, signer.as_str())
 }
 
-/// The message the user needs to sign to claim the badge under "by key" or "by keys" rule
-pub fn message(id: u64, user: impl fmt::Display) -> String {
-    format!("claim badge {} for user {}", id, user)
+/// A signature submitted to mint a badge under the "by key" or "by keys" rule, along with the
+/// parameters that determine which message it was produced over.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimSignature {
+    pub signature: String,
+    pub deadline: Option<Expiration>,
+    pub sign_mode: SignMode,
+    /// The signer in the ADR-36 sign doc, if not the sender, e.g. when the claim is relayed
+    pub signer: Option<Addr>,
+}
+
+impl ClaimSignature {
+    /// A signature over the raw message, without a deadline
+    pub fn new(signature: impl Into<String>) -> Self {
+        ClaimSignature {
+            signature: signature.into(),
+            deadline: None,
+            sign_mode: SignMode::Raw,
+            signer: None,
+        }
+    }
 }
 
 /// The hash function to be used to sign a message before signing it. Here we use SHA256.
@@ -33,6 +449,18 @@ pub fn hash(msg: &str) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+/// The hash function Ethereum uses, both for signing messages and for deriving addresses
+pub fn keccak256(bytes: &[u8]) -> Vec<u8> {
+    Keccak256::digest(bytes).to_vec()
+}
+
+/// Hex-encoded SHA-256 hash of a badge's metadata, serialized as JSON
+pub fn hash_metadata(metadata: &Metadata) -> StdResult<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(to_vec(metadata)?);
+    Ok(hex::encode(hasher.finalize()))
+}
+
 /// A helper function to help casting Option to String
 pub fn stringify_option(opt: Option<impl fmt::Display>) -> String {
     opt.map_or_else(|| "undefined".to_string(), |value| value.to_string())
@@ -53,33 +481,105 @@ pub fn stringify_funds(funds: &[Coin]) -> String {
         .join(",")
 }
 
-/// This is basically a wrapper of `api.secp256k1_verify`, but instead of taking raw bytes in the
-/// form of `&[u8]`, it takes the pubkey and signature as hex-encoded strings, and the original
-/// message before hashing.
+/// Casting a consecutive range of serial numbers to a string, e.g. `99-100`.
+///
+/// If the range is empty, return the string `[]`, for the same reason as in `stringify_funds`.
+pub fn stringify_serials(start_serial: u64, amount: u64) -> String {
+    if amount == 0 {
+        return "[]".to_string();
+    }
+    format!("{}-{}", start_serial, start_serial + amount - 1)
+}
+
+/// This is basically a wrapper of `api.secp256k1_verify` and `api.ed25519_verify`, but instead of
+/// taking raw bytes in the form of `&[u8]`, it takes the pubkey and signature as hex-encoded
+/// strings, and the original message before hashing.
+///
+/// The verification method is chosen based on the type of the pubkey. For secp256k1, the message is
+/// hashed before signing; for ed25519, the message is signed as is, as the scheme does its own
+/// hashing.
+///
+/// Signatures produced with Ethereum's `personal_sign` are over the message's Keccak-256 hash
+/// instead, and carry a recovery id, so that they can be checked against an Ethereum address by
+/// recovering the signer's pubkey.
 pub fn assert_valid_signature(
     api: &dyn Api,
     pubkey: &str,
     message: &str,
     signature: &str,
+    sign_mode: &SignMode,
 ) -> Result<(), ContractError> {
-    let msg_hash_bytes = hash(message);
     let key_bytes = hex::decode(pubkey)?;
     let sig_bytes = hex::decode(signature)?;
 
-    if api.secp256k1_verify(&msg_hash_bytes, &sig_bytes, &key_bytes)? {
+    let valid = match (KeyType::from_pubkey(&key_bytes)?, sign_mode) {
+        (KeyType::Secp256k1, SignMode::EthPersonalSign) => {
+            // the recovery id, if any, isn't needed when the pubkey is known
+            let sig_bytes = &sig_bytes[..sig_bytes.len().min(64)];
+            api.secp256k1_verify(&keccak256(message.as_bytes()), sig_bytes, &key_bytes)?
+        },
+        (KeyType::EthAddress, SignMode::EthPersonalSign) => {
+            recover_eth_address(api, message, &sig_bytes).as_deref() == Some(&key_bytes[..])
+        },
+        (KeyType::Secp256k1, _) => api.secp256k1_verify(&hash(message), &sig_bytes, &key_bytes)?,
+        (KeyType::Ed25519, SignMode::EthPersonalSign) => false,
+        (KeyType::Ed25519, _) => api.ed25519_verify(message.as_bytes(), &sig_bytes, &key_bytes)?,
+        (KeyType::EthAddress, _) => false,
+    };
+
+    if valid {
         Ok(())
     } else {
         Err(ContractError::InvalidSignature)
     }
 }
 
+/// Recover the address of the Ethereum account that produced a 65-byte `r || s || v` signature over
+/// the message. The recovery id `v` may be given either as 0 or 1, or as 27 or 28, as Ethereum
+/// wallets do. Returns None if the signature is malformed.
+fn recover_eth_address(api: &dyn Api, message: &str, sig_bytes: &[u8]) -> Option<Vec<u8>> {
+    let (signature, v) = match sig_bytes {
+        [signature @ .., v] if signature.len() == 64 => (signature, *v),
+        _ => return None,
+    };
+    let recovery_param = match v {
+        0 | 1 => v,
+        27 | 28 => v - 27,
+        _ => return None,
+    };
+
+    // the recovered pubkey is uncompressed; the address is the last 20 bytes of the hash of its
+    // coordinates, i.e. without the 0x04 prefix
+    let pubkey = api
+        .secp256k1_recover_pubkey(&keccak256(message.as_bytes()), signature, recovery_param)
+        .ok()?;
+    let hash = keccak256(pubkey.get(1..)?);
+    Some(hash[12..].to_vec())
+}
+
+/// Assert that a claim signature has not passed its deadline, if it comes with one.
+pub fn assert_signature_not_expired(
+    block: &BlockInfo,
+    deadline: Option<&Expiration>,
+) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if deadline.is_expired(block) => Err(ContractError::SignatureExpired),
+        _ => Ok(()),
+    }
+}
+
 // Assert the badge is available to be minted.
-// Throw an error if the mint deadline or the max supply has been reached.
+// Throw an error if the badge has been archived, or the mint deadline or the max supply has been
+// reached.
 pub fn assert_available(
     badge: &Badge,
     block: &BlockInfo,
     amount: u64,
 ) -> Result<(), ContractError> {
+    if badge.archived {
+        return Err(ContractError::Archived);
+    }
+
     if let Some(expiry) = badge.expiry {
         if block.time.seconds() > expiry {
             return Err(ContractError::Expired);
@@ -95,6 +595,60 @@ pub fn assert_available(
     Ok(())
 }
 
+/// Assert that the badge's minting has started.
+///
+/// This is checked separately from `assert_available`, because a badge that has not yet started
+/// is still considered available, e.g. the manager may add keys to it in preparation.
+pub fn assert_started(badge: &Badge, block: &BlockInfo) -> Result<(), ContractError> {
+    if let Some(start_time) = badge.start_time {
+        if block.time.seconds() < start_time {
+            return Err(ContractError::NotStarted);
+        }
+    }
+    Ok(())
+}
+
+/// Assert that `amount` instances of a badge can be minted at this time, whatever its mint rule:
+/// neither the hub nor the badge is paused, and the badge has started and is still available.
+pub fn assert_mintable(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    badge: &Badge,
+    amount: u64,
+) -> Result<(), ContractError> {
+    assert_hub_not_paused(store)?;
+    assert_not_paused(badge)?;
+    assert_started(badge, block)?;
+    assert_available(badge, block, amount)
+}
+
+/// Assert that the hub as a whole has not been paused by the developer.
+pub fn assert_hub_not_paused(store: &dyn Storage) -> Result<(), ContractError> {
+    if HUB_PAUSED.may_load(store)?.unwrap_or_default() {
+        return Err(ContractError::HubPaused);
+    }
+    Ok(())
+}
+
+/// Assert that the account is allowed to create badges, i.e. either badge creation is not
+/// restricted, or the account is on the creator allowlist.
+pub fn assert_can_create(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if CREATORS_RESTRICTED.may_load(store)?.unwrap_or_default()
+        && !CREATORS.contains(store, sender.as_str())
+    {
+        return Err(ContractError::NotCreator);
+    }
+    Ok(())
+}
+
+/// Assert that minting of the badge has not been paused by its manager.
+pub fn assert_not_paused(badge: &Badge) -> Result<(), ContractError> {
+    if badge.paused {
+        return Err(ContractError::Paused);
+    }
+    Ok(())
+}
+
 // Assert the badge it NOT available to be minted. Throw an error if it is available.
 pub fn assert_unavailable(badge: &Badge, block: &BlockInfo) -> Result<(), ContractError> {
     match assert_available(badge, block, 1) {
@@ -112,86 +666,1429 @@ pub fn assert_eligible(store: &dyn Storage, id: u64, user: &str) -> Result<(), C
     }
 }
 
-/// Assert that a badge indeed uses the "by minter" rule, and that the sender is the minter.
-pub fn assert_can_mint_by_minter(badge: &Badge, sender: &Addr) -> Result<(), ContractError> {
-    match &badge.rule {
-        MintRule::ByMinter(minter) => {
-            if minter != sender {
-                Err(ContractError::NotMinter)
-            } else {
-                Ok(())
-            }
+/// Increment the number of instances of the badge minted to the user. Throw an error if this
+/// exceeds the badge's per-owner limit.
+///
+/// If the badge doesn't have a per-owner limit, the count is not tracked, to save storage.
+pub fn increment_mint_count(
+    store: &mut dyn Storage,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    let max_per_owner = match badge.max_per_owner {
+        Some(max_per_owner) => max_per_owner,
+        None => return Ok(()),
+    };
+
+    let count = MINT_COUNTS.may_load(store, (id, user))?.unwrap_or(0) + 1;
+    if count > max_per_owner {
+        return Err(ContractError::max_per_owner_reached(id, user));
+    }
+
+    MINT_COUNTS.save(store, (id, user), &count)?;
+
+    Ok(())
+}
+
+/// The badge as seen by the mint handlers at the current block, along with the index of the active
+/// mint phase, if any. While a phase is active, its rule takes the place of the badge's own rule.
+///
+/// The returned badge is only to be used for checking the mint rule. Changes such as incrementing
+/// the supply should be made to the original badge, which is the one to be saved.
+pub fn enter_phase(badge: &Badge, block: &BlockInfo) -> (Badge, Option<u32>) {
+    match badge.active_phase(block.time.seconds()) {
+        Some(phase) => {
+            let phased = Badge {
+                rule: badge.phases[phase as usize].rule.clone(),
+                ..badge.clone()
+            };
+            (phased, Some(phase))
         },
-        rule => Err(ContractError::wrong_mint_rule("by_minter", rule)),
+        None => (badge.clone(), None),
     }
 }
 
-/// Assert that a badge indeed uses the "by key" rule, and the signature was produced by signing the
-/// correct message with the correct privkey.
-pub fn assert_can_mint_by_key(
-    api: &dyn Api,
+/// Increment the number of instances of the badge minted to the user during the active phase.
+/// Throw an error if this exceeds the phase's per-owner limit.
+///
+/// If no phase is active, or the phase doesn't have a per-owner limit, the count is not tracked.
+pub fn increment_phase_mint_count(
+    store: &mut dyn Storage,
     id: u64,
     badge: &Badge,
-    owner: &str,
-    signature: &str,
+    phase: Option<u32>,
+    user: &str,
 ) -> Result<(), ContractError> {
-    // the badge must use the "by key" minting rule
-    let pubkey = match &badge.rule {
-        MintRule::ByKey(key) => key,
-        rule => return Err(ContractError::wrong_mint_rule("by_key", rule)),
+    let (phase, max_per_owner) = match phase {
+        Some(phase) => match badge.phases[phase as usize].max_per_owner {
+            Some(max_per_owner) => (phase, max_per_owner),
+            None => return Ok(()),
+        },
+        None => return Ok(()),
     };
 
-    // the signature must be valid
-    let message = message(id, owner);
-    assert_valid_signature(api, pubkey, &message, signature)?;
+    let count = PHASE_MINT_COUNTS.may_load(store, (id, phase, user))?.unwrap_or(0) + 1;
+    if count > max_per_owner {
+        return Err(ContractError::phase_max_per_owner_reached(id, phase, user));
+    }
+
+    PHASE_MINT_COUNTS.save(store, (id, phase, user), &count)?;
 
     Ok(())
 }
 
-/// Assert that a badge indeed uses the "by keys" rule, and that the signature was produced by
-/// signing the correct message using a whitelisted privkey.
-pub fn assert_can_mint_by_keys(
-    deps: Deps,
+/// Increment the number of instances of the badge minted within the current window. Throw an error
+/// if this exceeds the badge's rate limit.
+pub fn increment_window_count(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
     id: u64,
     badge: &Badge,
-    owner: &str,
-    pubkey: &str,
-    signature: &str,
+    amount: u64,
 ) -> Result<(), ContractError> {
-    // the badge must use the "by keys" minting rule
-    match &badge.rule {
-        MintRule::ByKeys => (),
-        rule => return Err(ContractError::wrong_mint_rule("by_keys", rule)),
-    }
+    let rate_limit = match &badge.rate_limit {
+        Some(rate_limit) => rate_limit,
+        None => return Ok(()),
+    };
 
-    // the key must be whitelisted
-    if !KEYS.contains(deps.storage, (id, pubkey)) {
-        return Err(ContractError::key_does_not_exist(id));
+    let window = match rate_limit.window {
+        Window::Blocks(blocks) => block.height / blocks,
+        Window::Seconds(seconds) => block.time.seconds() / seconds,
+    };
+
+    // the counter resets once a new window starts
+    let count = match MINT_WINDOWS.may_load(store, id)? {
+        Some((last_window, count)) if last_window == window => count + amount,
+        _ => amount,
+    };
+    if count > rate_limit.max_mints {
+        return Err(ContractError::RateLimited);
     }
 
-    // the signature must be valid
-    let message = message(id, owner);
-    assert_valid_signature(deps.api, pubkey, &message, signature)?;
+    MINT_WINDOWS.save(store, id, &(window, count))?;
 
     Ok(())
 }
 
-/// Assert that a byte array is a valid secp256k1 public key.
-///
-/// Copied from cosmwasm-crypto:
-/// https://github.com/CosmWasm/cosmwasm/blob/v1.1.9/packages/crypto/src/secp256k1.rs#L140-L151
-///
-/// Previously I attempted to use the `k256` library for pubkey validation.
-/// But it did not work because `rand` is a non-optional dependency for `k256`.
-pub fn assert_valid_secp256k1_pubkey(bytes: &[u8]) -> Result<(), ContractError> {
-    let ok = match bytes.first() {
-        Some(0x02) | Some(0x03) => bytes.len() == ECDSA_COMPRESSED_PUBKEY_LEN,
-        Some(0x04) => bytes.len() == ECDSA_UNCOMPRESSED_PUBKEY_LEN,
-        _ => false,
-    };
-    if ok {
+/// Record that a badge has started using a mint rule, either upon creation or when its rule is
+/// updated.
+pub fn increment_rule_count(store: &mut dyn Storage, rule: &MintRule) -> StdResult<()> {
+    let (badge_count, minted_count) = RULE_STATS.may_load(store, rule.kind())?.unwrap_or_default();
+    RULE_STATS.save(store, rule.kind(), &(badge_count + 1, minted_count))
+}
+
+/// Record that a badge has stopped using a mint rule, i.e. its rule has been updated.
+pub fn decrement_rule_count(store: &mut dyn Storage, rule: &MintRule) -> StdResult<()> {
+    let (badge_count, minted_count) = RULE_STATS.may_load(store, rule.kind())?.unwrap_or_default();
+    RULE_STATS.save(store, rule.kind(), &(badge_count.saturating_sub(1), minted_count))
+}
+
+/// Add newly minted instances of a badge to the hub-wide total, and to the total of the mint rule
+/// they were minted under, i.e. the rule of the active phase if there is one.
+pub fn increment_minted_count(
+    store: &mut dyn Storage,
+    rule: &MintRule,
+    amount: u64,
+) -> StdResult<()> {
+    let total = MINTED_COUNT.may_load(store)?.unwrap_or_default();
+    MINTED_COUNT.save(store, &(total + amount))?;
+
+    let kind = rule.kind();
+    let (badge_count, minted_count) = RULE_STATS.may_load(store, kind)?.unwrap_or_default();
+    RULE_STATS.save(store, kind, &(badge_count, minted_count + amount))
+}
+
+/// Assert that the parameters of a rate limit are valid.
+pub fn assert_valid_rate_limit(rate_limit: &RateLimit) -> Result<(), ContractError> {
+    let window = match rate_limit.window {
+        Window::Blocks(blocks) => blocks,
+        Window::Seconds(seconds) => seconds,
+    };
+    if rate_limit.max_mints == 0 || window == 0 {
+        return Err(ContractError::InvalidRateLimit);
+    }
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by minter" or "by minters" rule, and that the sender is one
+/// of the minters.
+pub fn assert_can_mint_by_minter(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    id: u64,
+    badge: &Badge,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let is_minter = match &badge.rule {
+        MintRule::ByMinter(minter) => minter == sender,
+        MintRule::ByMinters => MINTERS.contains(store, (id, sender.as_str())),
+        rule => return Err(ContractError::wrong_mint_rule("by_minter", rule)),
+    };
+    if is_minter || is_delegated_minter(store, block, id, sender)? {
         Ok(())
     } else {
-        Err(ContractError::InvalidPubkey)
+        Err(ContractError::NotMinter)
+    }
+}
+
+/// Resolve the owners given to `MintByMinter`, replacing name-service names with the addresses they
+/// point to, as returned by the hub's name resolver. Anything containing a dot is taken to be a
+/// name, as bech32 addresses never do; everything else is kept as is.
+pub fn resolve_owners(
+    deps: Deps,
+    owners: BTreeSet<String>,
+) -> Result<BTreeSet<String>, ContractError> {
+    if !owners.iter().any(|owner| owner.contains('.')) {
+        return Ok(owners);
+    }
+
+    let resolver_addr = RESOLVER.may_load(deps.storage)?;
+
+    owners
+        .into_iter()
+        .map(|owner| {
+            if !owner.contains('.') {
+                return Ok(owner);
+            }
+
+            let resolver_addr = resolver_addr.as_ref().ok_or_else(|| ContractError::NoResolver {
+                name: owner.clone(),
+            })?;
+            let res: ResolveResponse = deps.querier.query_wasm_smart(
+                resolver_addr,
+                &ResolverQueryMsg::Resolve {
+                    name: owner.clone(),
+                },
+            )?;
+            let address = res.address.ok_or(ContractError::NameNotResolved {
+                name: owner,
+            })?;
+            Ok(deps.api.addr_validate(&address)?.into())
+        })
+        .collect()
+}
+
+/// Assert that a badge indeed uses the "by contract" rule, and the sender is the designated
+/// contract.
+pub fn assert_can_hook_mint(badge: &Badge, sender: &Addr) -> Result<(), ContractError> {
+    match &badge.rule {
+        MintRule::ByContract(contract) if contract == sender => Ok(()),
+        MintRule::ByContract(_) => Err(ContractError::NotHookContract),
+        rule => Err(ContractError::wrong_mint_rule("by_contract", rule)),
+    }
+}
+
+/// Whether the manager has delegated minting authority to the account, and the delegation has not
+/// yet expired.
+pub fn is_delegated_minter(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    id: u64,
+    user: &Addr,
+) -> StdResult<bool> {
+    let expiration = DELEGATED_MINTERS.may_load(store, (id, user.as_str()))?;
+    Ok(matches!(expiration, Some(expiration) if !expiration.is_expired(block)))
+}
+
+/// Assert that a badge indeed uses the "open" rule.
+pub fn assert_can_claim(badge: &Badge) -> Result<(), ContractError> {
+    match &badge.rule {
+        MintRule::Open => Ok(()),
+        rule => Err(ContractError::wrong_mint_rule("open", rule)),
     }
 }
+
+/// Assert that a badge indeed uses the "by allowlist" rule, and that the user is on the allowlist.
+pub fn assert_can_mint_by_allowlist(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by allowlist" minting rule
+    match &badge.rule {
+        MintRule::ByAllowlist => (),
+        rule => return Err(ContractError::wrong_mint_rule("by_allowlist", rule)),
+    }
+
+    assert_allowlisted(store, id, user)
+}
+
+/// Assert that a user is on a badge's allowlist.
+pub fn assert_allowlisted(store: &dyn Storage, id: u64, user: &str) -> Result<(), ContractError> {
+    if !ALLOWLIST.contains(store, (id, user)) {
+        return Err(ContractError::not_allowlisted(id, user));
+    }
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by raffle" rule, that registration is still open, and that
+/// the user has not already registered.
+pub fn assert_can_register_for_raffle(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by raffle" minting rule
+    let registration_end = match &badge.rule {
+        MintRule::ByRaffle {
+            registration_end,
+            ..
+        } => *registration_end,
+        rule => return Err(ContractError::wrong_mint_rule("by_raffle", rule)),
+    };
+
+    // registration closes either when it ends, or when the manager draws the raffle early
+    if block.time.seconds() > registration_end || RAFFLES_DRAWN.contains(store, id) {
+        return Err(ContractError::RaffleClosed);
+    }
+
+    if REGISTRANTS.contains(store, (id, user)) {
+        return Err(ContractError::already_registered(id, user));
+    }
+
+    if REGISTRANT_COUNTS.may_load(store, id)?.unwrap_or_default() >= MAX_RAFFLE_REGISTRANTS {
+        return Err(ContractError::RaffleFull {
+            max: MAX_RAFFLE_REGISTRANTS,
+        });
+    }
+
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by raffle" rule, that its raffle has not been drawn, and
+/// that the sender is allowed to draw it, i.e. either the sender is the manager, or registration
+/// has ended. Returns the number of winners to be drawn.
+pub fn assert_can_draw_raffle(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    id: u64,
+    badge: &Badge,
+    sender: &Addr,
+) -> Result<u64, ContractError> {
+    // the badge must use the "by raffle" minting rule
+    let (registration_end, winners) = match &badge.rule {
+        MintRule::ByRaffle {
+            registration_end,
+            winners,
+        } => (*registration_end, *winners),
+        rule => return Err(ContractError::wrong_mint_rule("by_raffle", rule)),
+    };
+
+    if RAFFLES_DRAWN.contains(store, id) {
+        return Err(ContractError::raffle_drawn(id));
+    }
+
+    if *sender != badge.manager && block.time.seconds() <= registration_end {
+        return Err(ContractError::RaffleNotClosed);
+    }
+
+    Ok(winners)
+}
+
+/// Assert that a mint rule of a badge, either its own rule or that of one of its phases, can be
+/// replaced without stranding state of the old rule. `new_rules` are all the rules the badge will
+/// have afterwards: the raffle, if any, must have been drawn, and no keys or vouchers can be left
+/// unused if none of the new rules uses them.
+pub fn assert_can_replace_rule(
+    store: &dyn Storage,
+    id: u64,
+    old_rule: &MintRule,
+    new_rules: &[&MintRule],
+) -> Result<(), ContractError> {
+    let is_raffle = |rule: &MintRule| matches!(rule, MintRule::ByRaffle { .. });
+
+    if is_raffle(old_rule)
+        && !new_rules.iter().any(|rule| is_raffle(rule))
+        && !RAFFLES_DRAWN.contains(store, id)
+        && has_any(store, &REGISTRANTS, id)?
+    {
+        return Err(ContractError::raffle_undrawn(id));
+    }
+
+    if old_rule.uses_keys()
+        && !new_rules.iter().any(|rule| rule.uses_keys())
+        && has_any(store, &KEYS, id)?
+    {
+        return Err(ContractError::keys_outstanding(id));
+    }
+
+    if old_rule.uses_vouchers()
+        && !new_rules.iter().any(|rule| rule.uses_vouchers())
+        && has_any(store, &VOUCHERS, id)?
+    {
+        return Err(ContractError::vouchers_outstanding(id));
+    }
+
+    Ok(())
+}
+
+/// Whether the set has any entry under the badge id
+fn has_any(store: &dyn Storage, set: &Set<(u64, &str)>, id: u64) -> StdResult<bool> {
+    Ok(set.prefix(id).keys(store, None, None, Order::Ascending).next().transpose()?.is_some())
+}
+
+/// The seed for drawing a raffle, derived from the block info, the Hub contract's address, and the
+/// badge id.
+///
+/// NOTE: There is no source of true randomness on-chain. The block proposer can predict, and to
+/// some extent influence, the outcome, so this is only suitable for low-stakes raffles.
+pub fn raffle_seed(env: &Env, id: u64) -> Vec<u8> {
+    hash(&format!(
+        "draw raffle of badge {} at hub {} on chain {} at height {} time {}",
+        id,
+        env.contract.address,
+        env.block.chain_id,
+        env.block.height,
+        env.block.time.nanos(),
+    ))
+}
+
+/// Select `amount` winners out of the registrants, using a partial Fisher-Yates shuffle driven by
+/// the seed. If there are fewer registrants than `amount`, all of them win.
+pub fn draw_winners(seed: &[u8], mut registrants: Vec<String>, amount: usize) -> Vec<String> {
+    let amount = amount.min(registrants.len());
+    for i in 0..amount {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update((i as u64).to_be_bytes());
+        let digest = hasher.finalize();
+
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        let j = i + (u64::from_be_bytes(bytes) % (registrants.len() - i) as u64) as usize;
+        registrants.swap(i, j);
+    }
+    registrants.truncate(amount);
+    registrants
+}
+
+/// Assert that a claim signature has not passed its deadline, and was produced by signing the
+/// correct message, in the specified sign mode, with the privkey corresponding to `pubkey`.
+fn assert_valid_claim_signature(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    id: u64,
+    owner: &str,
+    pubkey: &str,
+    signature: &ClaimSignature,
+) -> Result<(), ContractError> {
+    let deadline = signature.deadline.as_ref();
+    assert_signature_not_expired(&env.block, deadline)?;
+
+    let nonce = NONCES.may_load(deps.storage, id)?.unwrap_or_default();
+    let message = message(env, id, owner, nonce, deadline);
+    let signer = signature.signer.as_ref().unwrap_or(sender);
+    let sign_doc = sign_doc(message, &signature.sign_mode, signer);
+    assert_valid_signature(deps.api, pubkey, &sign_doc, &signature.signature, &signature.sign_mode)
+}
+
+/// Assert that a badge indeed uses the "by key" rule, and the signature was produced by signing the
+/// correct message with the correct privkey.
+pub fn assert_can_mint_by_key(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    signature: &ClaimSignature,
+) -> Result<(), ContractError> {
+    // the badge must use the "by key" minting rule
+    let pubkey = match &badge.rule {
+        MintRule::ByKey(key) => key,
+        rule => return Err(ContractError::wrong_mint_rule("by_key", rule)),
+    };
+
+    // the signature must be valid
+    assert_valid_claim_signature(deps, env, sender, id, owner, pubkey, signature)
+}
+
+/// Assert that a badge indeed uses the "by keys" rule, and that the signature was produced by
+/// signing the correct message using a whitelisted privkey.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_can_mint_by_keys(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    pubkey: &str,
+    signature: &ClaimSignature,
+) -> Result<(), ContractError> {
+    // the badge must use the "by keys" minting rule
+    match &badge.rule {
+        MintRule::ByKeys => (),
+        rule => return Err(ContractError::wrong_mint_rule("by_keys", rule)),
+    }
+
+    // the key must be whitelisted
+    if !KEYS.contains(deps.storage, (id, pubkey)) {
+        return Err(ContractError::key_does_not_exist(id));
+    }
+
+    // the signature must be valid
+    assert_valid_claim_signature(deps, env, sender, id, owner, pubkey, signature)
+}
+
+/// Assert that a badge indeed uses the "by voucher" rule, and that the code's hash has been
+/// uploaded and not yet used. Returns the hash, which the caller should remove from storage.
+pub fn assert_can_mint_by_voucher(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    code: &str,
+) -> Result<String, ContractError> {
+    // the badge must use the "by voucher" minting rule
+    match &badge.rule {
+        MintRule::ByVoucher => (),
+        rule => return Err(ContractError::wrong_mint_rule("by_voucher", rule)),
+    }
+
+    assert_voucher_exists(store, id, code)
+}
+
+/// Assert that the hash of a voucher code has been uploaded and not yet used; return the hash.
+pub fn assert_voucher_exists(
+    store: &dyn Storage,
+    id: u64,
+    code: &str,
+) -> Result<String, ContractError> {
+    let voucher_hash = hex::encode(hash(code));
+    if !VOUCHERS.contains(store, (id, &voucher_hash)) {
+        return Err(ContractError::voucher_does_not_exist(id));
+    }
+    Ok(voucher_hash)
+}
+
+/// Assert that a voucher hash is a hex-encoded SHA-256 hash.
+pub fn assert_valid_voucher_hash(voucher_hash: &str) -> Result<(), ContractError> {
+    match hex::decode(voucher_hash) {
+        Ok(bytes) if bytes.len() == 32 => Ok(()),
+        _ => Err(ContractError::InvalidVoucherHash),
+    }
+}
+
+/// Assert that a badge indeed uses the "by payment" rule, and that the sender has paid the price.
+///
+/// Returns the messages that distribute the payment among the hub treasury, the badge's revenue
+/// shares, and the badge's manager.
+pub fn assert_can_mint_by_payment(
+    deps: Deps,
+    badge: &Badge,
+    info: &MessageInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    // the badge must use the "by payment" minting rule
+    let price = match &badge.rule {
+        MintRule::ByPayment(price) => price,
+        rule => return Err(ContractError::wrong_mint_rule("by_payment", rule)),
+    };
+
+    payment_msgs(deps, price, badge, info)
+}
+
+/// Assert that the sender has paid the price, and return the messages that distribute the payment
+/// as determined by `split_payment`.
+///
+/// For native coins, these are bank transfers of the attached funds; for CW20 tokens, these are
+/// `TransferFrom` calls that pull the tokens using the allowance the sender has granted to the Hub.
+/// For prices in USD, the amount due is converted using the oracle, and any excess coins attached
+/// are refunded to the sender.
+pub fn payment_msgs(
+    deps: Deps,
+    price: &Price,
+    badge: &Badge,
+    info: &MessageInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    match price {
+        Price::Native(coin) => {
+            let amount = must_pay(info, &coin.denom)?;
+            if amount != coin.amount {
+                return Err(ContractError::incorrect_payment(
+                    coin,
+                    Coin::new(amount.u128(), &coin.denom),
+                ));
+            }
+            Ok(native_payment_msgs(deps.storage, badge, coin)?)
+        },
+        Price::Usd {
+            amount,
+            denom,
+        } => {
+            let coin = Coin {
+                denom: denom.clone(),
+                amount: usd_to_native(deps, *amount, denom)?,
+            };
+            let paid = must_pay(info, denom)?;
+            if paid < coin.amount {
+                return Err(ContractError::incorrect_payment(
+                    &coin,
+                    Coin::new(paid.u128(), denom),
+                ));
+            }
+            let mut msgs = native_payment_msgs(deps.storage, badge, &coin)?;
+            if paid > coin.amount {
+                msgs.push(
+                    BankMsg::Send {
+                        to_address: info.sender.to_string(),
+                        amount: vec![Coin::new((paid - coin.amount).u128(), denom)],
+                    }
+                    .into(),
+                );
+            }
+            Ok(msgs)
+        },
+        Price::Cw20 {
+            token,
+            amount,
+        } => {
+            nonpayable(info)?;
+            let msgs = split_payment(deps.storage, badge, *amount, false)?
+                .into_iter()
+                .map(|(payee, amount)| -> StdResult<CosmosMsg> {
+                    let owner = info.sender.to_string();
+                    let msg = match payee {
+                        Payee::Account(recipient) => Cw20ExecuteMsg::TransferFrom {
+                            owner,
+                            recipient,
+                            amount,
+                        },
+                        Payee::Routed(_) => Cw20ExecuteMsg::BurnFrom {
+                            owner,
+                            amount,
+                        },
+                    };
+                    Ok(WasmMsg::Execute {
+                        contract_addr: token.clone(),
+                        msg: to_binary(&msg)?,
+                        funds: vec![],
+                    }
+                    .into())
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(msgs)
+        },
+    }
+}
+
+/// Bank transfers, or burns and community pool donations for the routed share of fees, that
+/// distribute a payment in native coins as determined by `split_payment`.
+fn native_payment_msgs(
+    store: &dyn Storage,
+    badge: &Badge,
+    coin: &Coin,
+) -> StdResult<Vec<CosmosMsg>> {
+    let msgs = split_payment(store, badge, coin.amount, true)?
+        .into_iter()
+        .map(|(payee, amount)| {
+            let amount = Coin::new(amount.u128(), &coin.denom);
+            match payee {
+                Payee::Account(recipient) => BankMsg::Send {
+                    to_address: recipient,
+                    amount: vec![amount],
+                }
+                .into(),
+                Payee::Routed(destination) => routed_fee_msg(&destination, amount),
+            }
+        })
+        .collect();
+    Ok(msgs)
+}
+
+/// Convert a price in US dollars into the amount of a native coin it's worth, according to the
+/// hub's price oracle. The amount is rounded up, so that the payee never receives less than the
+/// price.
+pub fn usd_to_native(deps: Deps, amount: Decimal, denom: &str) -> Result<Uint128, ContractError> {
+    let oracle_addr = ORACLE.may_load(deps.storage)?.ok_or(ContractError::NoOracle)?;
+
+    let res: OraclePriceResponse = deps.querier.query_wasm_smart(
+        oracle_addr,
+        &OracleQueryMsg::Price {
+            denom: denom.into(),
+        },
+    )?;
+    if res.price.is_zero() {
+        return Err(ContractError::InvalidOraclePrice {
+            denom: denom.into(),
+        });
+    }
+
+    // both are fixed-point decimals with the same number of decimal places, so the ratio of their
+    // atomics is the ratio of their values
+    let numerator = amount.atomics();
+    let denominator = res.price.atomics();
+    let mut native_amount = numerator / denominator;
+    if !(numerator % denominator).is_zero() {
+        native_amount += Uint128::one();
+    }
+
+    Ok(native_amount)
+}
+
+/// The recipient of a cut of a payment
+#[derive(Debug, PartialEq)]
+pub enum Payee {
+    Account(String),
+    /// The share of the treasury's cut that the hub routes away from the developer
+    Routed(FeeDestination),
+}
+
+/// Split a payment among the hub treasury, the badge's revenue shares, and the badge's manager.
+///
+/// The treasury's cut is taken first, of which the hub's routed share, if any, goes to its
+/// destination rather than the developer. Payments in CW20 tokens can't be donated to the
+/// community pool, so unless `native` is set, only routing to be burned applies. The revenue shares
+/// then apply to what remains, and the manager receives the rest, including any dust from rounding
+/// down. Recipients whose cut rounds down to zero are left out.
+pub fn split_payment(
+    store: &dyn Storage,
+    badge: &Badge,
+    amount: Uint128,
+    native: bool,
+) -> StdResult<Vec<(Payee, Uint128)>> {
+    let mut payouts = vec![];
+    let mut remaining = amount;
+
+    let treasury_fee = TREASURY_FEE.may_load(store)?.unwrap_or_default();
+    if treasury_fee > 0 {
+        let developer_addr = DEVELOPER.load(store)?;
+        let mut cut = amount.multiply_ratio(treasury_fee, BPS_DENOMINATOR);
+        remaining -= cut;
+
+        match route_fee(store, cut)? {
+            Some((FeeDestination::CommunityPool, _)) if !native => (),
+            Some((destination, routed)) => {
+                payouts.push((Payee::Routed(destination), routed));
+                cut -= routed;
+            },
+            None => (),
+        }
+        payouts.push((Payee::Account(developer_addr.into()), cut));
+    }
+
+    let net = remaining;
+    for share in &badge.revenue_split {
+        let cut = net.multiply_ratio(share.bps, BPS_DENOMINATOR);
+        payouts.push((Payee::Account(share.recipient.clone()), cut));
+        remaining -= cut;
+    }
+
+    payouts.push((Payee::Account(badge.manager.to_string()), remaining));
+    payouts.retain(|(_, amount)| !amount.is_zero());
+
+    Ok(payouts)
+}
+
+/// Assert that a badge's metadata can be rendered by downstream apps: it must include a name or an
+/// image, its urls must use a supported scheme, its background color must be a hex color, and none
+/// of its fields may be excessively long.
+pub fn assert_valid_metadata(metadata: &Metadata) -> Result<(), ContractError> {
+    if metadata.name.is_none() && metadata.image.is_none() {
+        return Err(ContractError::EmptyMetadata);
+    }
+
+    assert_max_len("name", metadata.name.as_deref(), MAX_NAME_LEN)?;
+    assert_max_len("description", metadata.description.as_deref(), MAX_DESCRIPTION_LEN)?;
+    assert_max_len("image_data", metadata.image_data.as_deref(), MAX_DESCRIPTION_LEN)?;
+
+    for (field, url) in [
+        ("image", &metadata.image),
+        ("animation_url", &metadata.animation_url),
+        ("external_url", &metadata.external_url),
+        ("youtube_url", &metadata.youtube_url),
+    ] {
+        if let Some(url) = url {
+            assert_valid_url(field, url)?;
+        }
+    }
+
+    if let Some(color) = &metadata.background_color {
+        if color.len() != 6 || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ContractError::InvalidBackgroundColor {
+                color: color.clone(),
+            });
+        }
+    }
+
+    assert_valid_traits(metadata.attributes.as_deref().unwrap_or_default())
+}
+
+/// Assert that each of the traits is of reasonable length and, if it has a display type, that the
+/// value can be interpreted accordingly.
+pub fn assert_valid_traits(traits: &[Trait]) -> Result<(), ContractError> {
+    for attr in traits {
+        assert_max_len("trait_type", Some(attr.trait_type.as_str()), MAX_TRAIT_LEN)?;
+        assert_max_len("trait value", Some(attr.value.as_str()), MAX_TRAIT_LEN)?;
+        assert_valid_trait(attr)?;
+    }
+    Ok(())
+}
+
+/// Whether a string is a decimal number, e.g. `5`, `-3` or `12.5`. This is checked by hand rather
+/// than by parsing the string as a float, since contracts can't use floating point operations.
+fn is_decimal_number(value: &str) -> bool {
+    let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let value = value.strip_prefix('-').unwrap_or(value);
+    match value.split_once('.') {
+        Some((int, frac)) => all_digits(int) && all_digits(frac),
+        None => all_digits(value),
+    }
+}
+
+/// Assert that a trait's display type, if any, is one recognized by marketplaces, following the
+/// OpenSea metadata standard, and that the value can be interpreted accordingly: a number for the
+/// numeric display types, or a UNIX timestamp (in seconds) for dates.
+fn assert_valid_trait(attr: &Trait) -> Result<(), ContractError> {
+    let display_type = match &attr.display_type {
+        Some(display_type) => display_type,
+        None => return Ok(()),
+    };
+
+    let numeric = match display_type.as_str() {
+        "number" | "boost_number" | "boost_percentage" => {
+            is_decimal_number(&attr.value)
+        },
+        "date" => attr.value.parse::<u64>().is_ok(),
+        _ => {
+            return Err(ContractError::UnknownDisplayType {
+                display_type: display_type.clone(),
+            });
+        },
+    };
+
+    if !numeric {
+        return Err(ContractError::NonNumericTrait {
+            trait_type: attr.trait_type.clone(),
+            display_type: display_type.clone(),
+            value: attr.value.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Assert that a commemorative token mints a non-zero amount, and that its subdenom is accepted by
+/// the token factory.
+pub fn assert_valid_commemorative_token(token: &CommemorativeToken) -> Result<(), ContractError> {
+    if token.amount.is_zero() {
+        return Err(ContractError::ZeroCommemorativeAmount);
+    }
+
+    let subdenom = &token.subdenom;
+    if subdenom.is_empty()
+        || subdenom.len() > MAX_SUBDENOM_LEN
+        || !subdenom.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    {
+        return Err(ContractError::InvalidSubdenom {
+            subdenom: subdenom.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Assert that a badge has a reasonable number of media assets, each with a url of a supported
+/// scheme and a MIME type matching its purpose.
+pub fn assert_valid_media(media: &[MediaAsset]) -> Result<(), ContractError> {
+    if media.len() > MAX_MEDIA_ASSETS {
+        return Err(ContractError::TooManyMediaAssets {
+            max: MAX_MEDIA_ASSETS,
+        });
+    }
+
+    for asset in media {
+        assert_valid_url("media uri", &asset.uri)?;
+
+        assert_max_len("mime type", Some(asset.mime_type.as_str()), MAX_MIME_TYPE_LEN)?;
+        let valid = match asset.mime_type.split_once('/') {
+            Some((kind, subtype)) => kind == asset.purpose.mime_type() && !subtype.is_empty(),
+            None => false,
+        };
+        if !valid {
+            return Err(ContractError::InvalidMimeType {
+                mime_type: asset.mime_type.clone(),
+                purpose: asset.purpose.mime_type().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Assert that a locale is a language code, optionally followed by a region or script subtag.
+pub fn assert_valid_locale(locale: &str) -> Result<(), ContractError> {
+    if !is_valid_locale(locale) {
+        return Err(ContractError::InvalidLocale {
+            locale: locale.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Assert that an SVG template looks like an SVG document and is of reasonable length.
+pub fn assert_valid_svg_template(template: &str) -> Result<(), ContractError> {
+    if !template.trim_start().starts_with("<svg") || template.len() > MAX_SVG_TEMPLATE_LEN {
+        return Err(ContractError::InvalidSvgTemplate {
+            max: MAX_SVG_TEMPLATE_LEN,
+        });
+    }
+    Ok(())
+}
+
+/// Assert that a url is of reasonable length and uses a supported scheme. For ipfs urls, the CID
+/// must also be well-formed, as broken CIDs otherwise only surface once wallets fail to render the
+/// badge.
+fn assert_valid_url(field: &str, url: &str) -> Result<(), ContractError> {
+    assert_max_len(field, Some(url), MAX_URL_LEN)?;
+
+    if !URL_SCHEMES.iter().any(|scheme| url.starts_with(scheme)) {
+        return Err(ContractError::invalid_url(field, url));
+    }
+
+    if let Some(path) = url.strip_prefix("ipfs://") {
+        // the CID may be followed by a path within the content, a query or a fragment
+        let cid = path.split(['/', '?', '#']).next().unwrap_or_default();
+        if !is_valid_cid(cid) {
+            return Err(ContractError::InvalidCid {
+                field: field.to_string(),
+                url: url.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn assert_max_len(field: &str, value: Option<&str>, max: usize) -> Result<(), ContractError> {
+    match value {
+        Some(value) if value.len() > max => Err(ContractError::metadata_too_long(field, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Assert that the recipients of revenue shares are valid addresses, and that the shares add up to
+/// no more than the whole.
+pub fn assert_valid_revenue_split(
+    api: &dyn Api,
+    revenue_split: &[RevenueShare],
+) -> Result<(), ContractError> {
+    let mut total_bps = 0u64;
+    for share in revenue_split {
+        api.addr_validate(&share.recipient)?;
+        total_bps += share.bps as u64;
+    }
+    if total_bps > BPS_DENOMINATOR as u64 {
+        return Err(ContractError::InvalidRevenueSplit);
+    }
+    Ok(())
+}
+
+/// Assert that the royalty, if any, is paid to a valid address and does not exceed the whole of the
+/// sale price.
+pub fn assert_valid_royalty(
+    api: &dyn Api,
+    royalty: Option<&RevenueShare>,
+) -> Result<(), ContractError> {
+    if let Some(royalty) = royalty {
+        api.addr_validate(&royalty.recipient)?;
+        if royalty.bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidRoyalty);
+        }
+    }
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by nft" rule, that the owner holds the specified token of
+/// the designated collection, and that the token has not already been used to mint this badge.
+pub fn assert_can_mint_by_nft(
+    deps: Deps,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by nft" minting rule
+    let collection = match &badge.rule {
+        MintRule::ByNft(collection) => collection,
+        rule => return Err(ContractError::wrong_mint_rule("by_nft", rule)),
+    };
+
+    assert_holds_nft(deps, id, collection, owner, token_id)
+}
+
+/// Assert that a badge indeed uses the "by deposit" rule, and that the token was sent by the
+/// designated collection. Returns whether the deposited token is to be burned.
+pub fn assert_can_mint_by_deposit(badge: &Badge, collection: &Addr) -> Result<bool, ContractError> {
+    match &badge.rule {
+        MintRule::ByDeposit {
+            collection: designated,
+            burn,
+        } => {
+            if designated != collection {
+                return Err(ContractError::NotDepositCollection);
+            }
+            Ok(*burn)
+        },
+        rule => Err(ContractError::wrong_mint_rule("by_deposit", rule)),
+    }
+}
+
+/// Assert that the owner holds the specified token of the collection, and that the token has not
+/// already been used to mint the badge.
+pub fn assert_holds_nft(
+    deps: Deps,
+    id: u64,
+    collection: &str,
+    owner: &str,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    // the token must not have already been used
+    if NFTS.contains(deps.storage, (id, token_id)) {
+        return Err(ContractError::nft_used(id, token_id));
+    }
+
+    // the owner must currently hold the token
+    let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+        collection,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+    if res.owner != owner {
+        return Err(ContractError::not_nft_owner(token_id));
+    }
+
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by delegation" rule, and that the user has delegated at
+/// least the required amount to the designated validator.
+pub fn assert_can_mint_by_delegation(
+    deps: Deps,
+    badge: &Badge,
+    user: &Addr,
+) -> Result<(), ContractError> {
+    // the badge must use the "by delegation" minting rule
+    let (validator, min_amount) = match &badge.rule {
+        MintRule::ByDelegation {
+            validator,
+            min_amount,
+        } => (validator, *min_amount),
+        rule => return Err(ContractError::wrong_mint_rule("by_delegation", rule)),
+    };
+
+    assert_delegated(deps, validator, min_amount, user.as_str())
+}
+
+/// Assert that the user's delegation to the validator is no less than the minimum amount.
+pub fn assert_delegated(
+    deps: Deps,
+    validator: &str,
+    min_amount: Uint128,
+    user: &str,
+) -> Result<(), ContractError> {
+    let amount = deps
+        .querier
+        .query_delegation(user, validator)?
+        .map(|delegation| delegation.amount.amount)
+        .unwrap_or_default();
+    if amount < min_amount {
+        return Err(ContractError::InsufficientDelegation {
+            expected: min_amount,
+            found: amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by group" rule, and that the user is a member of the
+/// designated group with at least the required weight.
+pub fn assert_can_mint_by_group(
+    deps: Deps,
+    badge: &Badge,
+    user: &Addr,
+) -> Result<(), ContractError> {
+    // the badge must use the "by group" minting rule
+    let (group, min_weight) = match &badge.rule {
+        MintRule::ByGroup {
+            group,
+            min_weight,
+        } => (group, *min_weight),
+        rule => return Err(ContractError::wrong_mint_rule("by_group", rule)),
+    };
+
+    assert_group_member(deps, group, min_weight, user.as_str())
+}
+
+/// Assert that the user is currently a member of the cw4 group, with a weight no less than the
+/// minimum, if any.
+pub fn assert_group_member(
+    deps: Deps,
+    group: &str,
+    min_weight: Option<u64>,
+    user: &str,
+) -> Result<(), ContractError> {
+    let res: MemberResponse = deps.querier.query_wasm_smart(
+        group,
+        &Cw4QueryMsg::Member {
+            addr: user.to_string(),
+            at_height: None,
+        },
+    )?;
+
+    let weight = res.weight.ok_or(ContractError::NotGroupMember)?;
+    if let Some(min_weight) = min_weight {
+        if weight < min_weight {
+            return Err(ContractError::InsufficientGroupWeight {
+                expected: min_weight,
+                found: weight,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// A state change to be applied once a composite rule has been found to be satisfied.
+///
+/// Sub-rules are evaluated without touching the storage, so that a failing branch of an "any of"
+/// rule doesn't leave side effects behind. The effects of the branches that do pass are collected
+/// and applied by the caller.
+#[derive(Debug, PartialEq)]
+pub enum MintEffect {
+    /// The messages forwarding the payment made for a "by payment" sub-rule
+    Payment(Vec<CosmosMsg>),
+    /// A whitelisted pubkey that has been used and needs to be removed
+    ConsumeKey(String),
+    /// The hash of a voucher code that has been used and needs to be removed
+    ConsumeVoucher(String),
+    /// A token of the designated collection that has been used and needs to be recorded
+    UseNft(String),
+}
+
+/// Assert that a badge indeed uses a composite ("all of" or "any of") rule, and that the rule is
+/// satisfied by the submitted proof. Returns the effects to be applied.
+pub fn assert_can_mint_by_composite(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    proof: &CompositeProof,
+) -> Result<Vec<MintEffect>, ContractError> {
+    match &badge.rule {
+        MintRule::AllOf(_) | MintRule::AnyOf(_) => (),
+        rule => return Err(ContractError::wrong_mint_rule("all_of|any_of", rule)),
+    }
+
+    let nonce = NONCES.may_load(deps.storage, id)?.unwrap_or_default();
+    let message = message(env, id, owner, nonce, proof.deadline.as_ref());
+    let sign_mode = proof.sign_mode.clone().unwrap_or_default();
+    let signer = match &proof.signer {
+        Some(signer) => deps.api.addr_validate(signer)?,
+        None => info.sender.clone(),
+    };
+    let ctx = CompositeContext {
+        deps,
+        block: &env.block,
+        info,
+        id,
+        badge,
+        owner,
+        proof,
+        sign_doc: sign_doc(message, &sign_mode, &signer),
+        sign_mode,
+    };
+
+    let effects = ctx.assert_satisfied(&badge.rule)?;
+
+    // funds are only checked by the "by payment" sub-rules. if the rule was satisfied without any
+    // of them, e.g. by an "any of" branch that doesn't require payment, funds sent along would be
+    // kept by the contract without counting towards anything, so they are rejected instead
+    if !effects.iter().any(|effect| matches!(effect, MintEffect::Payment(_))) {
+        nonpayable(info)?;
+    }
+
+    Ok(effects)
+}
+
+/// Everything needed to evaluate a composite rule, bundled together so that it can be passed down
+/// to the sub-rules recursively.
+struct CompositeContext<'a> {
+    deps: Deps<'a>,
+    block: &'a BlockInfo,
+    info: &'a MessageInfo,
+    id: u64,
+    badge: &'a Badge,
+    owner: &'a str,
+    proof: &'a CompositeProof,
+    /// The bytes to be signed for the "by key" and "by keys" sub-rules
+    sign_doc: String,
+    sign_mode: SignMode,
+}
+
+impl<'a> CompositeContext<'a> {
+    /// Unwrap a piece of proof needed by a sub-rule; throw an error if it's not provided.
+    fn required(piece: &'a Option<String>, name: &str) -> Result<&'a str, ContractError> {
+        piece.as_deref().ok_or_else(|| ContractError::missing_proof(name))
+    }
+
+    /// The "by delegation", "by group" and "by allowlist" sub-rules check the owner's eligibility,
+    /// which is then used up by the mint. Like the standalone handlers, which mint to the sender,
+    /// they can only be satisfied by the owner themselves, so that no one can spend another
+    /// account's eligibility.
+    fn assert_sender_is_owner(&self) -> Result<(), ContractError> {
+        if self.info.sender != self.owner {
+            return Err(ContractError::SenderNotOwner);
+        }
+        Ok(())
+    }
+
+    /// Recursively evaluate a rule against the submitted proof.
+    fn assert_satisfied(&self, rule: &MintRule) -> Result<Vec<MintEffect>, ContractError> {
+        let deps = self.deps;
+        let id = self.id;
+
+        match rule {
+            MintRule::ByMinter(minter) => {
+                if minter != &self.info.sender
+                    && !is_delegated_minter(deps.storage, self.block, id, &self.info.sender)?
+                {
+                    return Err(ContractError::NotMinter);
+                }
+                Ok(vec![])
+            },
+            MintRule::ByMinters => {
+                if !MINTERS.contains(deps.storage, (id, self.info.sender.as_str()))
+                    && !is_delegated_minter(deps.storage, self.block, id, &self.info.sender)?
+                {
+                    return Err(ContractError::NotMinter);
+                }
+                Ok(vec![])
+            },
+            MintRule::ByContract(contract) => {
+                if contract != &self.info.sender {
+                    return Err(ContractError::NotHookContract);
+                }
+                Ok(vec![])
+            },
+            MintRule::ByKey(pubkey) => {
+                let signature = Self::required(&self.proof.signature, "signature")?;
+                assert_signature_not_expired(self.block, self.proof.deadline.as_ref())?;
+                assert_valid_signature(
+                    deps.api,
+                    pubkey,
+                    &self.sign_doc,
+                    signature,
+                    &self.sign_mode,
+                )?;
+                Ok(vec![])
+            },
+            MintRule::ByKeys => {
+                let pubkey = Self::required(&self.proof.pubkey, "pubkey")?;
+                let signature = Self::required(&self.proof.signature, "signature")?;
+                if !KEYS.contains(deps.storage, (id, pubkey)) {
+                    return Err(ContractError::key_does_not_exist(id));
+                }
+                assert_signature_not_expired(self.block, self.proof.deadline.as_ref())?;
+                assert_valid_signature(
+                    deps.api,
+                    pubkey,
+                    &self.sign_doc,
+                    signature,
+                    &self.sign_mode,
+                )?;
+                Ok(vec![MintEffect::ConsumeKey(pubkey.to_string())])
+            },
+            MintRule::ByPayment(price) => {
+                let msgs = payment_msgs(deps, price, self.badge, self.info)?;
+                Ok(vec![MintEffect::Payment(msgs)])
+            },
+            MintRule::ByNft(collection) => {
+                let token_id = Self::required(&self.proof.token_id, "token_id")?;
+                assert_holds_nft(deps, id, collection, self.owner, token_id)?;
+                Ok(vec![MintEffect::UseNft(token_id.to_string())])
+            },
+            MintRule::ByDelegation {
+                validator,
+                min_amount,
+            } => {
+                self.assert_sender_is_owner()?;
+                assert_delegated(deps, validator, *min_amount, self.owner)?;
+                Ok(vec![])
+            },
+            MintRule::ByGroup {
+                group,
+                min_weight,
+            } => {
+                self.assert_sender_is_owner()?;
+                assert_group_member(deps, group, *min_weight, self.owner)?;
+                Ok(vec![])
+            },
+            MintRule::ByVoucher => {
+                let code = Self::required(&self.proof.voucher, "voucher")?;
+                let voucher_hash = assert_voucher_exists(deps.storage, id, code)?;
+                Ok(vec![MintEffect::ConsumeVoucher(voucher_hash)])
+            },
+            MintRule::ByAllowlist => {
+                self.assert_sender_is_owner()?;
+                assert_allowlisted(deps.storage, id, self.owner)?;
+                Ok(vec![])
+            },
+            MintRule::ByRaffle {
+                ..
+            } => Err(ContractError::NestedRaffle),
+            MintRule::ByDeposit {
+                ..
+            } => Err(ContractError::NestedDeposit),
+            MintRule::Open => Ok(vec![]),
+            MintRule::AllOf(rules) => {
+                let mut effects = vec![];
+                for rule in rules {
+                    effects.extend(self.assert_satisfied(rule)?);
+                }
+                Ok(effects)
+            },
+            MintRule::AnyOf(rules) => {
+                let mut last_err = ContractError::EmptyCompositeRule;
+                for rule in rules {
+                    match self.assert_satisfied(rule) {
+                        Ok(effects) => return Ok(effects),
+                        Err(err) => last_err = err,
+                    }
+                }
+                Err(last_err)
+            },
+        }
+    }
+}
+
+/// Assert that the parameters of a mint rule are valid.
+pub fn assert_valid_mint_rule(api: &dyn Api, rule: &MintRule) -> Result<(), ContractError> {
+    match rule {
+        // if the badge uses "by key" mint rule, the key must be a valid secp256k1 or ed25519
+        // public key, or a 20-byte Ethereum address
+        MintRule::ByKey(key) => {
+            let bytes = hex::decode(key)?;
+            assert_valid_pubkey(&bytes)
+        },
+        // if the badge uses "by payment" mint rule, the price must be non-zero, and if paid in a
+        // CW20 token, the token address must be valid
+        MintRule::ByPayment(price) => assert_valid_price(api, price),
+        // if the badge uses "by nft" or "by contract" mint rule, the contract address must be
+        // valid
+        MintRule::ByNft(contract) | MintRule::ByContract(contract) => {
+            api.addr_validate(contract)?;
+            Ok(())
+        },
+        // if the badge uses "by group" mint rule, the group address must be valid
+        MintRule::ByGroup {
+            group,
+            ..
+        } => {
+            api.addr_validate(group)?;
+            Ok(())
+        },
+        // if the badge uses "by deposit" mint rule, the collection address must be valid
+        MintRule::ByDeposit {
+            collection,
+            ..
+        } => {
+            api.addr_validate(collection)?;
+            Ok(())
+        },
+        // if the badge uses "by raffle" mint rule, there must be at least one winner, and no more
+        // than can be minted at once when the raffle is drawn
+        MintRule::ByRaffle {
+            winners,
+            ..
+        } => {
+            if *winners == 0 {
+                return Err(ContractError::ZeroRaffleWinners);
+            }
+            if *winners > MAX_RAFFLE_WINNERS {
+                return Err(ContractError::TooManyRaffleWinners {
+                    max: MAX_RAFFLE_WINNERS,
+                });
+            }
+            Ok(())
+        },
+        // composite rules must not be empty, and each sub-rule must itself be valid. raffles are
+        // drawn and deposits are received rather than minted by proof, so they can't be part of a
+        // composite rule. every payment sub-rule would be checked against the same funds, so at
+        // most one of them is allowed
+        MintRule::AllOf(rules) | MintRule::AnyOf(rules) => {
+            if rules.is_empty() {
+                return Err(ContractError::EmptyCompositeRule);
+            }
+            if rule.num_payments() > 1 {
+                return Err(ContractError::MultiplePayments);
+            }
+            if rules.iter().any(|rule| matches!(rule, MintRule::ByRaffle { .. })) {
+                return Err(ContractError::NestedRaffle);
+            }
+            if rules.iter().any(|rule| matches!(rule, MintRule::ByDeposit { .. })) {
+                return Err(ContractError::NestedDeposit);
+            }
+            rules.iter().try_for_each(|rule| assert_valid_mint_rule(api, rule))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Assert that mint phases are in chronological order without overlapping, and that the rule of
+/// each phase is valid.
+pub fn assert_valid_phases(api: &dyn Api, phases: &[Phase]) -> Result<(), ContractError> {
+    for phase in phases {
+        if matches!(phase.end_time, Some(end_time) if end_time < phase.start_time) {
+            return Err(ContractError::InvalidPhases);
+        }
+        assert_valid_mint_rule(api, &phase.rule)?;
+    }
+
+    // each phase must end before the next one starts, so a phase without an end must be the last
+    for pair in phases.windows(2) {
+        match pair[0].end_time {
+            Some(end_time) if end_time < pair[1].start_time => (),
+            _ => return Err(ContractError::InvalidPhases),
+        }
+    }
+
+    Ok(())
+}
+
+/// Assert that a price is non-zero, and if it's in a CW20 token, that the token address is valid.
+pub fn assert_valid_price(api: &dyn Api, price: &Price) -> Result<(), ContractError> {
+    let is_zero = match price {
+        Price::Native(coin) => coin.amount.is_zero(),
+        Price::Cw20 {
+            token,
+            amount,
+        } => {
+            api.addr_validate(token)?;
+            amount.is_zero()
+        },
+        Price::Usd {
+            amount,
+            ..
+        } => amount.is_zero(),
+    };
+    if is_zero {
+        return Err(ContractError::ZeroPrice);
+    }
+    Ok(())
+}
+
+/// Assert that a byte array is a valid secp256k1 or ed25519 public key, or an Ethereum address.
+/// Any 20-byte value is accepted as an Ethereum address.
+pub fn assert_valid_pubkey(bytes: &[u8]) -> Result<(), ContractError> {
+    KeyType::from_pubkey(bytes).map(|_| ())
+}
@@ -1,9 +1,11 @@
 #[cfg(not(feature = "library"))]
 pub mod contract;
+pub mod cid;
 pub mod error;
 pub mod execute;
 pub mod fee;
 pub mod helpers;
 pub mod query;
 pub mod state;
+pub mod token_factory;
 pub mod upgrades;
@@ -1,43 +1,374 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{
+    BlockInfo, ContractInfoResponse, Deps, Env, Order, QueryRequest, StdError, StdResult, Storage,
+    WasmQuery,
+};
 use cw_storage_plus::Bound;
 
 use badges::hub::{
-    BadgeResponse, BadgesResponse, ConfigResponse, KeyResponse, KeysResponse, OwnerResponse,
-    OwnersResponse,
+    ActiveBadgesResponse, AllowlistResponse, BadgeResponse, BadgesResponse,
+    CommemorativeTokenResponse, ConfigResponse, CreatorsResponse, DelegatedMinterResponse,
+    DepositResponse, KeyResponse, KeysResponse, LocalesResponse, MediaResponse,
+    MetadataHistoryResponse, MetadataRevisionResponse, MintersResponse, NftContractResponse,
+    NftResponse, NonceResponse, OwnerBatchResponse, OwnerResponse, OwnersResponse,
+    PendingManagerResponse, PendingMintResponse, RegistrantsResponse, RevocationsResponse,
+    RuleStatsResponse, StatsResponse, SupplyResponse, SvgTemplateResponse, TokenSerialResponse,
+    VoucherResponse, WinnersResponse,
 };
+use badges::locale::{locale_fallbacks, localize};
+use badges::Badge;
 
+use crate::helpers::{
+    assert_available, assert_hub_not_paused, assert_not_paused, assert_started, parse_token_id,
+};
 use crate::state::*;
+use crate::token_factory::denom;
 
 pub const DEFAULT_LIMIT: u32 = 10;
 pub const MAX_LIMIT: u32 = 30;
 
+/// The maximum number of badges `active_badges` looks at in a single query, whether or not they
+/// are active
+pub const MAX_BADGES_SCANNED: usize = 300;
+
 pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
     let developer_addr = DEVELOPER.load(deps.storage)?;
+    let pending_developer_addr = PENDING_DEVELOPER.may_load(deps.storage)?;
     let nft_addr = NFT.load(deps.storage)?;
     let badge_count = BADGE_COUNT.load(deps.storage)?;
     let fee_rate = FEE_RATE.load(deps.storage)?;
+    let treasury_fee = TREASURY_FEE.may_load(deps.storage)?.unwrap_or_default();
+    let creation_fee = CREATION_FEE.may_load(deps.storage)?;
+    let denom_creation_fee = DENOM_CREATION_FEE.may_load(deps.storage)?;
+    let fee_collector_addr =
+        FEE_COLLECTOR.may_load(deps.storage)?.unwrap_or_else(|| developer_addr.clone());
+    let fee_routing = FEE_ROUTING.may_load(deps.storage)?;
+    let oracle_addr = ORACLE.may_load(deps.storage)?;
+    let resolver_addr = RESOLVER.may_load(deps.storage)?;
+    let creators_restricted = CREATORS_RESTRICTED.may_load(deps.storage)?.unwrap_or_default();
+    let paused = HUB_PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    let token_id_format = TOKEN_ID_FORMAT.may_load(deps.storage)?.unwrap_or_default();
     Ok(ConfigResponse {
         developer: developer_addr.into(),
+        pending_developer: pending_developer_addr.map(String::from),
         nft: nft_addr.into(),
         badge_count,
         fee_rate,
+        treasury_fee,
+        creation_fee,
+        denom_creation_fee,
+        fee_collector: fee_collector_addr.into(),
+        fee_routing,
+        oracle: oracle_addr.map(String::from),
+        resolver: resolver_addr.map(String::from),
+        creators_restricted,
+        paused,
+        token_id_format,
+    })
+}
+
+pub fn nft_contract(deps: Deps, env: Env) -> StdResult<NftContractResponse> {
+    let nft_addr = NFT.load(deps.storage)?;
+
+    let info: ContractInfoResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+            contract_addr: nft_addr.to_string(),
+        }))?;
+    let hub_is_admin = info.admin.as_deref() == Some(env.contract.address.as_str());
+
+    Ok(NftContractResponse {
+        nft: nft_addr.into(),
+        admin: info.admin,
+        hub_is_admin,
+    })
+}
+
+pub fn creators(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CreatorsResponse> {
+    let start = start_after.map(|creator| Bound::ExclusiveRaw(creator.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let creators = CREATORS
+        .items(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CreatorsResponse {
+        creators,
     })
 }
 
 pub fn badge(deps: Deps, id: u64) -> StdResult<BadgeResponse> {
-    let badge = BADGES.load(deps.storage, id)?;
+    let badge = badge_map().load(deps.storage, id)?;
     Ok((id, badge).into())
 }
 
+/// Info about a badge, with its metadata translated into the given locale if a translation exists
+pub fn localized_badge(deps: Deps, id: u64, locale: Option<String>) -> StdResult<BadgeResponse> {
+    let mut res = badge(deps, id)?;
+
+    if let Some(locale) = locale {
+        for locale in locale_fallbacks(&locale) {
+            if let Some(translation) = LOCALIZED_METADATA.may_load(deps.storage, (id, locale))? {
+                res.metadata = localize(res.metadata, translation);
+                break;
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn commemorative_token(deps: Deps, env: Env, id: u64) -> StdResult<CommemorativeTokenResponse> {
+    let token = COMMEMORATIVE_TOKENS.may_load(deps.storage, id)?;
+
+    Ok(CommemorativeTokenResponse {
+        id,
+        denom: token.as_ref().map(|token| denom(&env.contract.address, &token.subdenom)),
+        amount: token.map(|token| token.amount),
+    })
+}
+
+pub fn media(deps: Deps, id: u64) -> StdResult<MediaResponse> {
+    Ok(MediaResponse {
+        id,
+        media: MEDIA.may_load(deps.storage, id)?.unwrap_or_default(),
+    })
+}
+
+pub fn locales(deps: Deps, id: u64) -> StdResult<LocalesResponse> {
+    let locales = LOCALIZED_METADATA
+        .prefix(id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(LocalesResponse {
+        id,
+        locales,
+    })
+}
+
+pub fn svg_template(deps: Deps, id: u64) -> StdResult<SvgTemplateResponse> {
+    Ok(SvgTemplateResponse {
+        id,
+        template: SVG_TEMPLATES.may_load(deps.storage, id)?,
+    })
+}
+
+pub fn badges_by_id(deps: Deps, ids: Vec<u64>) -> StdResult<BadgesResponse> {
+    assert_batch_size(ids.len())?;
+
+    let badges = ids.into_iter().map(|id| badge(deps, id)).collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BadgesResponse {
+        badges,
+    })
+}
+
+pub fn supply(deps: Deps, env: Env, id: u64) -> StdResult<SupplyResponse> {
+    let badge = badge_map().load(deps.storage, id)?;
+    let mintable = is_mintable(deps.storage, &badge, &env.block);
+
+    let remaining =
+        badge.max_supply.map(|max_supply| max_supply.saturating_sub(badge.current_supply));
+
+    Ok(SupplyResponse {
+        id,
+        max_supply: badge.max_supply,
+        current_supply: badge.current_supply,
+        remaining,
+        mintable,
+    })
+}
+
+pub fn stats(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<StatsResponse> {
+    let badge_count = BADGE_COUNT.load(deps.storage)?;
+    let minted_count = MINTED_COUNT.may_load(deps.storage)?.unwrap_or_default();
+
+    let rules = RULE_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (rule, (badge_count, minted_count)) = item?;
+            Ok(RuleStatsResponse {
+                rule,
+                badge_count,
+                minted_count,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let supplies = badge_map()
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| supply(deps, env.clone(), id?))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(StatsResponse {
+        badge_count,
+        minted_count,
+        rules,
+        supplies,
+    })
+}
+
+/// Whether a badge can be minted at the given block, regardless of who is minting it
+fn is_mintable(store: &dyn Storage, badge: &Badge, block: &BlockInfo) -> bool {
+    assert_hub_not_paused(store).is_ok()
+        && assert_not_paused(badge).is_ok()
+        && assert_started(badge, block).is_ok()
+        && assert_available(badge, block, 1).is_ok()
+}
+
+/// At most `MAX_BADGES_SCANNED` badges are looked at per query, so that a long run of expired or
+/// sold out badges can't make it run out of gas; the response tells where to resume from.
+pub fn active_badges(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ActiveBadgesResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let badge_count = BADGE_COUNT.load(deps.storage)?;
+
+    let mut badges = vec![];
+    let mut last_scanned = None;
+    let range = badge_map().range(deps.storage, start, None, Order::Ascending);
+    for item in range.take(MAX_BADGES_SCANNED) {
+        let (id, badge) = item?;
+        last_scanned = Some(id);
+        if is_mintable(deps.storage, &badge, &env.block) {
+            badges.push((id, badge).into());
+            if badges.len() == limit {
+                break;
+            }
+        }
+    }
+
+    Ok(ActiveBadgesResponse {
+        badges,
+        // badge ids are assigned sequentially, so there are more to look at unless the last one
+        // looked at is the latest badge
+        next_start_after: last_scanned.filter(|id| *id < badge_count),
+    })
+}
+
 pub fn badges(
     deps: Deps,
     start_after: Option<u64>,
     limit: Option<u32>,
+    include_hidden: Option<bool>,
+) -> StdResult<BadgesResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let include_hidden = include_hidden.unwrap_or(false);
+
+    let badges = badge_map()
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, badge)) => include_hidden || !badge.hidden,
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (id, badge) = item?;
+            Ok((id, badge).into())
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BadgesResponse {
+        badges,
+    })
+}
+
+pub fn badges_by_manager(
+    deps: Deps,
+    manager: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<BadgesResponse> {
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let badges = badge_map()
+        .idx
+        .manager
+        .prefix(manager_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, badge) = item?;
+            Ok((id, badge).into())
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BadgesResponse {
+        badges,
+    })
+}
+
+pub fn badges_by_rule(
+    deps: Deps,
+    rule: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    include_hidden: Option<bool>,
 ) -> StdResult<BadgesResponse> {
     let start = start_after.map(Bound::exclusive);
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let include_hidden = include_hidden.unwrap_or(false);
+
+    let badges = badge_map()
+        .idx
+        .rule
+        .prefix(rule)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, badge)) => include_hidden || !badge.hidden,
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (id, badge) = item?;
+            Ok((id, badge).into())
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BadgesResponse {
+        badges,
+    })
+}
+
+pub fn badges_by_expiry(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<BadgesResponse> {
+    // the index is keyed by {expiry, id}, so the bound is made of the expiry of the badge to
+    // start after, as well as its id
+    let start = match start_after {
+        Some(id) => {
+            let badge = badge_map().load(deps.storage, id)?;
+            Some(Bound::exclusive((badge.expiry.unwrap_or(u64::MAX), id)))
+        },
+        None => None,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-    let badges = BADGES
+    let badges = badge_map()
+        .idx
+        .expiry
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
@@ -51,6 +382,44 @@ pub fn badges(
     })
 }
 
+pub fn metadata_history(
+    deps: Deps,
+    id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MetadataHistoryResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let revisions = METADATA_HISTORY
+        .prefix(id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (revision, r) = item?;
+            Ok(MetadataRevisionResponse {
+                revision,
+                editor: r.editor.into(),
+                height: r.height,
+                previous_hash: r.previous_hash,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MetadataHistoryResponse {
+        id,
+        revisions,
+    })
+}
+
+pub fn pending_manager(deps: Deps, id: u64) -> StdResult<PendingManagerResponse> {
+    let pending_manager_addr = PENDING_MANAGERS.may_load(deps.storage, id)?;
+    Ok(PendingManagerResponse {
+        id,
+        pending_manager: pending_manager_addr.map(String::from),
+    })
+}
+
 pub fn key(deps: Deps, id: u64, pubkey: impl Into<String>) -> KeyResponse {
     let key = pubkey.into();
     let whitelisted = KEYS.contains(deps.storage, (id, &key));
@@ -80,6 +449,172 @@ pub fn keys(
     })
 }
 
+pub fn allowlist(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowlistResponse> {
+    let start = start_after.map(|user| Bound::ExclusiveRaw(user.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let users = ALLOWLIST
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllowlistResponse {
+        users,
+    })
+}
+
+pub fn minters(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MintersResponse> {
+    let start = start_after.map(|minter| Bound::ExclusiveRaw(minter.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let minters = MINTERS
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MintersResponse {
+        minters,
+    })
+}
+
+pub fn registrants(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RegistrantsResponse> {
+    let start = start_after.map(|user| Bound::ExclusiveRaw(user.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let registrants = REGISTRANTS
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RegistrantsResponse {
+        registrants,
+    })
+}
+
+pub fn winners(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<WinnersResponse> {
+    let start = start_after.map(|user| Bound::ExclusiveRaw(user.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let winners = WINNERS
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(WinnersResponse {
+        winners,
+    })
+}
+
+pub fn voucher(deps: Deps, id: u64, voucher_hash: impl Into<String>) -> VoucherResponse {
+    let hash = voucher_hash.into();
+    let available = VOUCHERS.contains(deps.storage, (id, &hash));
+    VoucherResponse {
+        hash,
+        available,
+    }
+}
+
+pub fn delegated_minter(
+    deps: Deps,
+    id: u64,
+    minter: impl Into<String>,
+) -> StdResult<DelegatedMinterResponse> {
+    let minter = minter.into();
+    let expiration = DELEGATED_MINTERS.may_load(deps.storage, (id, &minter))?;
+    Ok(DelegatedMinterResponse {
+        minter,
+        expiration,
+    })
+}
+
+pub fn pending_mint(deps: Deps, id: u64) -> StdResult<PendingMintResponse> {
+    let pending = PENDING_MINTS.may_load(deps.storage, id)?;
+    Ok(PendingMintResponse {
+        id,
+        next_serial: pending.map(|(next_serial, _)| next_serial),
+        remaining: pending.map_or(0, |(next_serial, last_serial)| last_serial - next_serial + 1),
+    })
+}
+
+pub fn nonce(deps: Deps, id: u64) -> StdResult<NonceResponse> {
+    let nonce = NONCES.may_load(deps.storage, id)?.unwrap_or_default();
+    Ok(NonceResponse {
+        nonce,
+    })
+}
+
+pub fn token_serial(deps: Deps, token_id: String) -> StdResult<TokenSerialResponse> {
+    let (id, serial) = parse_token_id(deps.storage, &token_id)?;
+    Ok(TokenSerialResponse {
+        token_id,
+        id,
+        serial,
+    })
+}
+
+pub fn nft(deps: Deps, id: u64, token_id: impl Into<String>) -> NftResponse {
+    let token_id = token_id.into();
+    let used = NFTS.contains(deps.storage, (id, &token_id));
+    NftResponse {
+        token_id,
+        used,
+    }
+}
+
+pub fn deposit(deps: Deps, collection: String, token_id: String) -> StdResult<DepositResponse> {
+    let deposit = DEPOSITS.may_load(deps.storage, (&collection, &token_id))?;
+    Ok(DepositResponse {
+        collection,
+        token_id,
+        id: deposit.as_ref().map(|(id, _)| *id),
+        depositor: deposit.map(|(_, depositor)| depositor.to_string()),
+    })
+}
+
+pub fn revocations(
+    deps: Deps,
+    id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RevocationsResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let serials = REVOKED
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RevocationsResponse {
+        serials,
+    })
+}
+
 /// This function takes `impl Into<String>` instead of `String` so that i can type a few characters
 /// less in the unit tests =)
 pub fn owner(deps: Deps, id: u64, user: impl Into<String>) -> OwnerResponse {
@@ -91,6 +626,14 @@ pub fn owner(deps: Deps, id: u64, user: impl Into<String>) -> OwnerResponse {
     }
 }
 
+pub fn owner_batch(deps: Deps, id: u64, users: Vec<String>) -> StdResult<OwnerBatchResponse> {
+    assert_batch_size(users.len())?;
+
+    Ok(OwnerBatchResponse {
+        owners: users.into_iter().map(|user| owner(deps, id, user)).collect(),
+    })
+}
+
 pub fn owners(
     deps: Deps,
     id: u64,
@@ -110,3 +653,15 @@ pub fn owners(
         owners,
     })
 }
+
+/// Batch queries are capped at `MAX_LIMIT` entries, same as paginated ones, so that a single query
+/// can't be made arbitrarily expensive.
+fn assert_batch_size(size: usize) -> StdResult<()> {
+    if size > MAX_LIMIT as usize {
+        return Err(StdError::generic_err(format!(
+            "batch of {} entries exceeds the maximum of {}",
+            size, MAX_LIMIT,
+        )));
+    }
+    Ok(())
+}
@@ -1,27 +1,215 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin};
 use cw_item_set::Set;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
+use sg_metadata::Metadata;
 
-use badges::{Badge, FeeRate};
+use badges::{
+    Badge, CommemorativeToken, FeeRate, FeeRouting, MediaAsset, MetadataRevision, TokenIdFormat,
+};
 
 /// Address of the developer
 pub const DEVELOPER: Item<Addr> = Item::new("owner");
 
+/// Whether the hub has been paused by the developer. While paused, badges can be neither created
+/// nor minted. Defaults to false if not set.
+pub const HUB_PAUSED: Item<bool> = Item::new("hub_paused");
+
+/// Address of the account the developer has proposed to transfer ownership of the hub to, pending
+/// its acceptance
+pub const PENDING_DEVELOPER: Item<Addr> = Item::new("pending_owner");
+
 /// Address of badge nft contract
 pub const NFT: Item<Addr> = Item::new("nft");
 
 /// The fee rate, in ustars per byte, charged for storing data on-chain
 pub const FEE_RATE: Item<FeeRate> = Item::new("fee_rate");
 
+/// The share, in basis points, of the proceeds from paid mints that goes to the hub treasury, i.e.
+/// the developer. Defaults to zero if not set.
+pub const TREASURY_FEE: Item<u16> = Item::new("treasury_fee");
+
+/// How a share of the hub's protocol fees is routed away from the fee collector and the developer.
+/// No fees are routed if not set.
+pub const FEE_ROUTING: Item<FeeRouting> = Item::new("fee_routing");
+
+/// Address of the oracle used to convert USD-denominated prices into native coins. Badges priced in
+/// USD can't be minted if not set.
+pub const ORACLE: Item<Addr> = Item::new("oracle");
+
+/// Address of the contract used to resolve name-service names given in place of addresses. Only
+/// addresses are accepted if not set.
+pub const RESOLVER: Item<Addr> = Item::new("resolver");
+
+/// The flat fee charged for creating a badge. No creation fee is charged if not set.
+pub const CREATION_FEE: Item<Coin> = Item::new("creation_fee");
+
+/// The chain's token factory fee for creating a denom, which managers pay when a commemorative
+/// token's subdenom is first used. No fee is required if not set.
+pub const DENOM_CREATION_FEE: Item<Coin> = Item::new("denom_creation_fee");
+
+/// Address of the account that creation fees are forwarded to. Defaults to the developer if not
+/// set.
+pub const FEE_COLLECTOR: Item<Addr> = Item::new("fee_collector");
+
+/// How NFT token ids are derived from badge ids and serials. Defaults to `{id}|{serial}` if not
+/// set.
+pub const TOKEN_ID_FORMAT: Item<TokenIdFormat> = Item::new("token_id_format");
+
+/// In the counter token id format, the number of instances that have been assigned token ids,
+/// across all badges. Not set until the first instance is minted.
+pub const TOKEN_COUNT: Item<u64> = Item::new("token_count");
+
+/// In the counter token id format, the number assigned to each instance, indexed by {badge_id,
+/// serial}
+pub const TOKEN_NUMBERS: Map<(u64, u64), u64> = Map::new("token_numbers");
+
+/// In the counter token id format, the badge id and serial of each instance, indexed by the number
+/// assigned to it
+pub const TOKEN_SERIALS: Map<u64, (u64, u64)> = Map::new("token_serials");
+
+/// Whether only accounts on the creator allowlist can create badges. Defaults to false if not set.
+pub const CREATORS_RESTRICTED: Item<bool> = Item::new("creators_restricted");
+
+/// Accounts that are allowed to create badges while badge creation is restricted
+pub const CREATORS: Set<&str> = Set::new("creators");
+
 /// Total number of badges
 pub const BADGE_COUNT: Item<u64> = Item::new("badge_count");
 
-/// Badges, indexed by ids
-pub const BADGES: Map<u64, Badge> = Map::new("badges");
+/// Total number of badge instances minted across all badges. Defaults to zero if not set.
+pub const MINTED_COUNT: Item<u64> = Item::new("minted_count");
+
+/// The number of badges currently using each type of mint rule, and the number of instances
+/// minted under each type, indexed by rule types, e.g. "by_key"
+pub const RULE_STATS: Map<&str, (u64, u64)> = Map::new("rule_stats");
+
+/// Secondary indexes of badges: by manager, by the type of their mint rule (e.g. "by_key"), and by
+/// expiry. Badges without an expiry are indexed as expiring at `u64::MAX`, i.e. after all others.
+pub struct BadgeIndexes<'a> {
+    pub manager: MultiIndex<'a, Addr, Badge, u64>,
+    pub rule: MultiIndex<'a, String, Badge, u64>,
+    pub expiry: MultiIndex<'a, u64, Badge, u64>,
+}
+
+impl<'a> IndexList<Badge> for BadgeIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Badge>> + '_> {
+        let v: Vec<&dyn Index<Badge>> = vec![&self.manager, &self.rule, &self.expiry];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Badges, indexed by ids. The primary namespace is the same as when badges were stored in a
+/// plain map, so raw queries by id are unaffected by the secondary indexes.
+pub fn badge_map<'a>() -> IndexedMap<'a, u64, Badge, BadgeIndexes<'a>> {
+    IndexedMap::new(
+        "badges",
+        BadgeIndexes {
+            manager: MultiIndex::new(|_, badge| badge.manager.clone(), "badges", "badges__manager"),
+            rule: MultiIndex::new(
+                |_, badge| badge.rule.kind().to_string(),
+                "badges",
+                "badges__rule",
+            ),
+            expiry: MultiIndex::new(
+                |_, badge| badge.expiry.unwrap_or(u64::MAX),
+                "badges",
+                "badges__expiry",
+            ),
+        },
+    )
+}
+
+/// SVG templates of badges whose images are rendered on-chain by the NFT contract, indexed by
+/// badge ids
+pub const SVG_TEMPLATES: Map<u64, String> = Map::new("svg_templates");
+
+/// Fungible tokens minted along with instances of badges, indexed by badge ids
+pub const COMMEMORATIVE_TOKENS: Map<u64, CommemorativeToken> = Map::new("commemorative_tokens");
+
+/// The manager who created each token factory subdenom. Only they can use it for their badges.
+pub const SUBDENOM_OWNERS: Map<&str, Addr> = Map::new("subdenom_owners");
+
+/// Media assets attached to badges, indexed by badge ids. Only badges with assets have an entry.
+pub const MEDIA: Map<u64, Vec<MediaAsset>> = Map::new("media");
+
+/// Translations of badges' metadata, indexed by badge ids and locales
+pub const LOCALIZED_METADATA: Map<(u64, &str), Metadata> = Map::new("localized_metadata");
+
+/// Edits made to the metadata of badges, indexed by {badge_id, revision}. Revisions are numbered
+/// from 1, and are never modified or removed once recorded.
+pub const METADATA_HISTORY: Map<(u64, u64), MetadataRevision> = Map::new("metadata_history");
+
+/// Accounts that the managers of badges have proposed to transfer the manager role to, pending
+/// their acceptance, indexed by badge ids
+pub const PENDING_MANAGERS: Map<u64, Addr> = Map::new("pending_managers");
 
 /// Pubkeys that are whitelisted to mint a badge
 pub const KEYS: Set<(u64, &str)> = Set::new("keys");
 
+/// Hex-encoded SHA-256 hashes of unused voucher codes for minting a badge under the "by voucher"
+/// rule, indexed by {badge_id, hash}
+pub const VOUCHERS: Set<(u64, &str)> = Set::new("vouchers");
+
+/// Accounts that are allowed to claim a badge under the "by allowlist" rule
+pub const ALLOWLIST: Set<(u64, &str)> = Set::new("allowlist");
+
+/// Accounts that are allowed to mint a badge under the "by minters" rule
+pub const MINTERS: Set<(u64, &str)> = Set::new("minters");
+
+/// Temporary minters that the manager has delegated minting authority to, along with the time at
+/// which each delegation lapses, indexed by {badge_id, minter_addr}. Honored by `mint_by_minter`
+/// under both the "by minter" and "by minters" rules.
+pub const DELEGATED_MINTERS: Map<(u64, &str), Expiration> = Map::new("delegated_minters");
+
+/// Accounts that have registered for the raffle of a badge under the "by raffle" rule
+pub const REGISTRANTS: Set<(u64, &str)> = Set::new("registrants");
+
+/// The number of accounts that have registered for the raffle of each badge
+pub const REGISTRANT_COUNTS: Map<u64, u64> = Map::new("registrant_counts");
+
+/// Accounts that have won the raffle of a badge under the "by raffle" rule
+pub const WINNERS: Set<(u64, &str)> = Set::new("winners");
+
+/// Ids of badges whose raffles have already been drawn
+pub const RAFFLES_DRAWN: Set<u64> = Set::new("raffles_drawn");
+
+/// For badges minted by signatures, a nonce that the signed message must commit to. Bumping it
+/// invalidates all outstanding signatures at once. Defaults to zero if not set.
+pub const NONCES: Map<u64, u64> = Map::new("nonces");
+
+/// Tokens of the designated collection that have already been used to mint a badge under the
+/// "by nft" rule, indexed by {badge_id, token_id}
+pub const NFTS: Set<(u64, &str)> = Set::new("nfts");
+
+/// Tokens deposited to mint a badge under the "by deposit" rule and held in escrow, indexed by
+/// {collection_addr, token_id}, along with the badge id and the depositor
+pub const DEPOSITS: Map<(&str, &str), (u64, Addr)> = Map::new("deposits");
+
+/// The number of instances of a badge that have been minted to each account, indexed by
+/// {badge_id, user_addr}. Only tracked for badges that have a per-owner limit.
+pub const MINT_COUNTS: Map<(u64, &str), u64> = Map::new("mint_counts");
+
+/// The number of instances of a badge that have been minted to each account during each mint
+/// phase, indexed by {badge_id, phase_idx, user_addr}. Only tracked for phases that have a
+/// per-owner limit.
+pub const PHASE_MINT_COUNTS: Map<(u64, u32, &str), u64> = Map::new("phase_mint_counts");
+
+/// For badges that are rate limited, the index of the current window, and the number of instances
+/// minted within it
+pub const MINT_WINDOWS: Map<u64, (u64, u64)> = Map::new("mint_windows");
+
+/// For badges with a queued mint in progress, the serials of the next instance to be minted and of
+/// the last instance queued
+pub const PENDING_MINTS: Map<u64, (u64, u64)> = Map::new("pending_mints");
+
+/// The owners of the instances of queued mints that are yet to be minted, indexed by
+/// {badge_id, serial}
+pub const PENDING_OWNERS: Map<(u64, u64), String> = Map::new("pending_owners");
+
+/// Serial numbers of the badge instances that have been revoked by their badge's manager
+pub const REVOKED: Set<(u64, u64)> = Set::new("revoked");
+
 /// User addresses that have already claimed a badge. If a composite key {badge_id, user_addr}
 /// exists in the map, then this user has already claimed.
 ///
@@ -0,0 +1,59 @@
+use cosmwasm_std::{Addr, Binary, Uint128};
+use sg_std::CosmosMsg;
+
+/// The full denom of a token created by the Hub with the token factory
+pub fn denom(hub_addr: &Addr, subdenom: &str) -> String {
+    format!("factory/{}/{}", hub_addr, subdenom)
+}
+
+/// `MsgCreateDenom`, creating a denom administered by the Hub
+pub fn create_denom_msg(hub_addr: &Addr, subdenom: &str) -> CosmosMsg {
+    let mut value = vec![];
+    encode_string(&mut value, 1, hub_addr.as_str());
+    encode_string(&mut value, 2, subdenom);
+
+    CosmosMsg::Stargate {
+        type_url: "/osmosis.tokenfactory.v1beta1.MsgCreateDenom".to_string(),
+        value: Binary::from(value),
+    }
+}
+
+/// `MsgMint`, minting tokens of a denom administered by the Hub to the recipient
+pub fn mint_msg(hub_addr: &Addr, denom: &str, amount: Uint128, recipient: &str) -> CosmosMsg {
+    let mut coin = vec![];
+    encode_string(&mut coin, 1, denom);
+    encode_string(&mut coin, 2, &amount.to_string());
+
+    let mut value = vec![];
+    encode_string(&mut value, 1, hub_addr.as_str());
+    encode_bytes(&mut value, 2, &coin);
+    encode_string(&mut value, 3, recipient);
+
+    CosmosMsg::Stargate {
+        type_url: "/osmosis.tokenfactory.v1beta1.MsgMint".to_string(),
+        value: Binary::from(value),
+    }
+}
+
+// The messages above only consist of strings and embedded messages, both of which are encoded in
+// protobuf as length-delimited fields: the field's tag, the length as a varint, then the bytes.
+// This saves us from depending on a protobuf library for two messages.
+
+fn encode_string(buf: &mut Vec<u8>, field: u32, value: &str) {
+    encode_bytes(buf, field, value.as_bytes());
+}
+
+fn encode_bytes(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    // wire type 2 = length-delimited
+    encode_varint(buf, ((field << 3) | 2) as u64);
+    encode_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
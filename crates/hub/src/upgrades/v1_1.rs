@@ -6,9 +6,11 @@ use badges::FeeRate;
 
 use crate::{
     contract::{CONTRACT_NAME, CONTRACT_VERSION},
-    state::{BADGES, FEE_RATE},
+    state::FEE_RATE,
 };
 
+use super::v1_3::LEGACY_BADGES;
+
 const LEGACY_FEE_PER_BYTE: Item<Decimal> = Item::new("fee_per_byte");
 
 /// Date and time (GMT): Wednesday, December 31, 2022 11:59:59 PM
@@ -47,8 +49,10 @@ fn update_fee_rate(store: &mut dyn Storage, fee_rate: &FeeRate) -> StdResult<()>
     FEE_RATE.save(store, fee_rate)
 }
 
+// at this version badges are still stored in the legacy layout, which is only converted by the
+// v1.3 migration
 fn update_badge_3_expiry(store: &mut dyn Storage) -> StdResult<()> {
-    BADGES.update(
+    LEGACY_BADGES.update(
         store,
         3,
         |opt| {
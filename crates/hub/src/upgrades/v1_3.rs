@@ -0,0 +1,80 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::Map;
+use serde::{Deserialize, Serialize};
+use sg_metadata::Metadata;
+
+use badges::{Badge, MintRule};
+
+use crate::helpers::{increment_minted_count, increment_rule_count};
+use crate::state::{badge_map, DEVELOPER, FEE_COLLECTOR};
+
+/// A badge as stored by v1.2 of the contract, prior to the introduction of mint phases, revenue
+/// splits, per-owner and rate limits, and pausing and archiving
+#[derive(Serialize, Deserialize)]
+pub(super) struct LegacyBadge {
+    manager: Addr,
+    metadata: Metadata,
+    transferrable: bool,
+    pub(super) rule: MintRule,
+    pub(super) expiry: Option<u64>,
+    max_supply: Option<u64>,
+    current_supply: u64,
+}
+
+impl From<LegacyBadge> for Badge {
+    fn from(legacy: LegacyBadge) -> Self {
+        Badge {
+            manager: legacy.manager,
+            metadata: legacy.metadata,
+            token_uri: None,
+            transferrable: legacy.transferrable,
+            revocable: false,
+            rule: legacy.rule,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
+            expiry: legacy.expiry,
+            max_supply: legacy.max_supply,
+            max_per_owner: None,
+            rate_limit: None,
+            current_supply: legacy.current_supply,
+            num_burned: 0,
+        }
+    }
+}
+
+pub(super) const LEGACY_BADGES: Map<u64, LegacyBadge> = Map::new("badges");
+
+pub fn migrate(store: &mut dyn Storage) -> StdResult<()> {
+    // convert badges to the new storage layout
+    update_badges(store)?;
+
+    // creation fees, if enabled later, go to the developer unless configured otherwise
+    let developer_addr = DEVELOPER.load(store)?;
+    FEE_COLLECTOR.save(store, &developer_addr)
+}
+
+fn update_badges(store: &mut dyn Storage) -> StdResult<()> {
+    let legacy_badges = LEGACY_BADGES
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (id, legacy_badge) in legacy_badges {
+        let badge: Badge = legacy_badge.into();
+        increment_rule_count(store, &badge.rule)?;
+        increment_minted_count(store, &badge.rule, badge.current_supply)?;
+
+        // the legacy entry must be removed first: when saving to the indexed map, the existing
+        // entry is loaded in the new layout in order to update the secondary indexes
+        LEGACY_BADGES.remove(store, id);
+        badge_map().save(store, id, &badge)?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,26 @@
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{attr, Addr, Decimal, DepsMut, Empty, OwnedDeps};
-use sg_metadata::Metadata;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coin, to_binary, Addr, Decimal, Deps, DepsMut, Empty, OwnedDeps, StdResult, SubMsg,
+    Timestamp, Uint128, WasmMsg,
+};
+use cw_utils::{Expiration, PaymentError};
+use sg_metadata::{Metadata, Trait};
 use sg_std::Response;
 
 use badge_hub::error::ContractError;
 use badge_hub::state::*;
-use badge_hub::{execute, query};
-use badges::{Badge, MintRule, FeeRate};
+use badge_hub::{execute, query, token_factory};
+use badges::hub::{
+    CommemorativeTokenResponse, MetadataRevisionResponse, RuleStatsResponse, StatsResponse,
+    SupplyResponse,
+};
+use badges::nft::ExtensionMsg;
+use badges::{
+    Badge, CommemorativeToken, MediaAsset, MediaPurpose, MintRule, FeeRate, Phase, Price,
+    RevenueShare,
+};
 
 mod utils;
 
@@ -14,7 +28,7 @@ mod utils;
 const KEY_1: &str = "026f476708bd8fcc8a58bae717ee6922cdefd7917492dbc1a4c2f96d22ba30e470";
 const KEY_2: &str = "03858cd06aadf3e26b05bc3d5ceacae2fb1ea4027b2c63730e3de39abea255ee8c";
 
-// a valid ed25519 pubkey, but not a valid secp256k1 pubkey
+// an ed25519 pubkey in SSH wire format; neither a valid raw secp256k1 nor a raw ed25519 pubkey
 const INVALID_KEY: &str = "0000000b7373682d6564323535313900000020060892d88619ba6f56bc2ec5f1daec09529fbfc4f7a6723006f19e724c3deea5";
 
 fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
@@ -46,11 +60,25 @@ fn mock_badge() -> Badge {
             name: Some("first-badge".to_string()),
             ..Default::default()
         },
+        token_uri: None,
         transferrable: true,
+        revocable: false,
         rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry: Some(12345),
         max_supply: Some(100),
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 0,
+        num_burned: 0,
     }
 }
 
@@ -108,20 +136,36 @@ fn creating_badge() {
                 name: Some("first-badge".to_string()),
                 ..Default::default()
             },
+            token_uri: None,
             transferrable: true,
+            revocable: false,
             rule: MintRule::ByMinter("larry".to_string()),
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
             expiry: Some(12345),
             max_supply: Some(100),
+            max_per_owner: None,
+            rate_limit: None,
             current_supply: 0,
+            num_burned: 0,
         };
 
         let res = create_badge(deps.as_mut(), &badge);
-        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.messages, vec![SubMsg::new(utils::cache_metadata_msg(1, &badge))]);
         assert_eq!(
             res.attributes,
             vec![
                 attr("action", "badges/hub/create_badge"),
                 attr("id", "1"),
+                attr("manager", "larry"),
+                attr("rule", "by_minter:larry"),
                 attr("fee", "[]"),
             ]
         );
@@ -141,72 +185,2222 @@ fn creating_badge() {
                 name: Some("second-badge".to_string()),
                 ..Default::default()
             },
+            token_uri: None,
             transferrable: false,
+            revocable: false,
             rule: MintRule::ByKeys,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
             expiry: None,
             max_supply: None,
+            max_per_owner: None,
+            rate_limit: None,
             current_supply: 0,
+            num_burned: 0,
         };
 
         let res = create_badge(deps.as_mut(), &badge);
-        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.messages, vec![SubMsg::new(utils::cache_metadata_msg(2, &badge))]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/create_badge"),
+                attr("id", "2"),
+                attr("manager", "jake"),
+                attr("rule", "by_keys"),
+                attr("fee", "[]"),
+            ]
+        );
+
+        let cfg = query::config(deps.as_ref()).unwrap();
+        assert_eq!(cfg.badge_count, 2);
+
+        let b = query::badge(deps.as_ref(), 2).unwrap();
+        assert_eq!(b, (2, badge).into());
+    }
+}
+
+#[test]
+fn restricting_creators() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+
+    // non-developer cannot restrict badge creation or manage the creator allowlist
+    {
+        let err = execute::set_creators_restricted(deps.as_mut(), Addr::unchecked("jake"), true)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+
+        let err = execute::add_creator(deps.as_mut(), Addr::unchecked("jake"), "jake")
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+
+        let err = execute::remove_creator(deps.as_mut(), Addr::unchecked("jake"), "jake")
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer restricts badge creation
+    {
+        let res = execute::set_creators_restricted(deps.as_mut(), Addr::unchecked("larry"), true)
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_creators_restricted"),
+                attr("restricted", "true"),
+            ],
+        );
+    }
+
+    // accounts not on the creator allowlist cannot create badges
+    {
+        let err = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            badge.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotCreator);
+    }
+
+    // developer adds creators to the allowlist
+    {
+        let res = execute::add_creator(deps.as_mut(), Addr::unchecked("larry"), "creator").unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/add_creator"),
+                attr("creator", "creator"),
+            ],
+        );
+
+        execute::add_creator(deps.as_mut(), Addr::unchecked("larry"), "jake").unwrap();
+
+        let res = query::creators(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.creators, vec!["creator".to_string(), "jake".to_string()]);
+
+        let res = query::creators(deps.as_ref(), Some("creator".to_string()), None).unwrap();
+        assert_eq!(res.creators, vec!["jake".to_string()]);
+    }
+
+    // accounts on the creator allowlist can create badges
+    {
+        create_badge(deps.as_mut(), &badge);
+    }
+
+    // developer removes a creator from the allowlist
+    {
+        let res = execute::remove_creator(deps.as_mut(), Addr::unchecked("larry"), "creator")
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/remove_creator"),
+                attr("creator", "creator"),
+            ],
+        );
+
+        let err = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            badge.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotCreator);
+    }
+
+    // anyone can create badges once the restriction is lifted
+    {
+        execute::set_creators_restricted(deps.as_mut(), Addr::unchecked("larry"), false).unwrap();
+        create_badge(deps.as_mut(), &badge);
+    }
+}
+
+#[test]
+fn creating_badges_in_batch() {
+    let mut deps = setup_test();
+
+    // cannot create an empty batch
+    {
+        let err = execute::create_badges(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            vec![],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::EmptyBatch);
+    }
+
+    // if any badge in the batch is invalid, none of them is created
+    {
+        let mut invalid_badge = mock_badge();
+        invalid_badge.max_supply = Some(0);
+
+        let err = execute::create_badges(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            vec![mock_badge(), invalid_badge],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+
+    // create a batch of badges
+    {
+        let first_badge = mock_badge();
+        let mut second_badge = mock_badge();
+        second_badge.rule = MintRule::ByKeys;
+
+        let res = execute::create_badges(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            vec![first_badge.clone(), second_badge.clone()],
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(utils::cache_metadata_msg(1, &first_badge)),
+                SubMsg::new(utils::cache_metadata_msg(2, &second_badge)),
+            ],
+        );
+        assert_eq!(res.data, Some(to_binary(&vec![1u64, 2]).unwrap()));
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/create_badges"),
+                attr("ids", "1,2"),
+                attr("fee", "[]"),
+            ],
+        );
+
+        let cfg = query::config(deps.as_ref()).unwrap();
+        assert_eq!(cfg.badge_count, 2);
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b, (1, first_badge).into());
+
+        let b = query::badge(deps.as_ref(), 2).unwrap();
+        assert_eq!(b, (2, second_badge).into());
+    }
+}
+
+#[test]
+fn editing_badge() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    let new_metadata = Metadata {
+        name: Some("edited-badge".to_string()),
+        ..Default::default()
+    };
+
+    // non-manager cannot edit
+    {
+        let err = execute::edit_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jake", &[]),
+            1,
+            Metadata::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can edit
+    {
+        let res = execute::edit_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(badge.manager.as_str(), &[]),
+            1,
+            new_metadata.clone(),
+        )
+        .unwrap();
+        let edited = Badge {
+            metadata: new_metadata.clone(),
+            ..badge.clone()
+        };
+        assert_eq!(res.messages, vec![SubMsg::new(utils::cache_metadata_msg(1, &edited))]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/edit_badge"),
+                attr("id", "1"),
+                attr("revision", "1"),
+                attr("fee", "[]"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.metadata, new_metadata);
+
+        // the edit is recorded in the metadata history, along with the hash of the old metadata
+        let res = query::metadata_history(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(
+            res.revisions,
+            vec![MetadataRevisionResponse {
+                revision: 1,
+                editor: "larry".to_string(),
+                height: mock_env().block.height,
+                previous_hash: badge_hub::helpers::hash_metadata(&badge.metadata).unwrap(),
+            }],
+        );
+    }
+
+    // non-manager cannot freeze the metadata
+    {
+        let err = execute::freeze_metadata(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager freezes the metadata
+    {
+        let res = execute::freeze_metadata(deps.as_mut(), mock_info("larry", &[]), 1).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/freeze_metadata"),
+                attr("id", "1"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(b.metadata_frozen);
+    }
+
+    // cannot edit once the metadata is frozen
+    {
+        let err = execute::edit_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            badge.metadata,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MetadataFrozen);
+    }
+}
+
+#[test]
+fn validating_metadata() {
+    let mut deps = setup_test();
+
+    fn create(deps: DepsMut, metadata: Metadata) -> Result<Response, ContractError> {
+        execute::create_badge(
+            deps,
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            Badge {
+                metadata,
+                ..mock_badge()
+            },
+        )
+    }
+
+    // must have a name or an image
+    {
+        let err = create(deps.as_mut(), Metadata::default()).unwrap_err();
+        assert_eq!(err, ContractError::EmptyMetadata);
+
+        let metadata = Metadata {
+            image: Some("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()),
+            ..Default::default()
+        };
+        create(deps.as_mut(), metadata).unwrap();
+    }
+
+    // urls must use a supported scheme
+    {
+        let metadata = Metadata {
+            external_url: Some("javascript:alert(1)".to_string()),
+            ..mock_badge().metadata
+        };
+        let err = create(deps.as_mut(), metadata).unwrap_err();
+        assert_eq!(err, ContractError::invalid_url("external_url", "javascript:alert(1)"));
+    }
+
+    // ipfs urls must contain a well-formed cid, v0 or v1, optionally followed by a path
+    {
+        for url in [
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/badge.png",
+            "ipfs://zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7",
+        ] {
+            let metadata = Metadata {
+                image: Some(url.to_string()),
+                ..mock_badge().metadata
+            };
+            create(deps.as_mut(), metadata).unwrap();
+        }
+
+        for url in [
+            "ipfs://hash",
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd",
+        ] {
+            let metadata = Metadata {
+                image: Some(url.to_string()),
+                ..mock_badge().metadata
+            };
+            let err = create(deps.as_mut(), metadata).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidCid {
+                    field: "image".to_string(),
+                    url: url.to_string(),
+                },
+            );
+        }
+    }
+
+    // background color must be a hex color without the leading #
+    {
+        let metadata = Metadata {
+            background_color: Some("#ffffff".to_string()),
+            ..mock_badge().metadata
+        };
+        let err = create(deps.as_mut(), metadata).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidBackgroundColor {
+                color: "#ffffff".to_string(),
+            },
+        );
+    }
+
+    // traits with a display type must have a value that can be interpreted accordingly
+    {
+        let metadata = Metadata {
+            attributes: Some(vec![
+                Trait {
+                    display_type: Some("number".to_string()),
+                    trait_type: "level".to_string(),
+                    value: "5".to_string(),
+                },
+                Trait {
+                    display_type: Some("boost_number".to_string()),
+                    trait_type: "speed".to_string(),
+                    value: "-12.5".to_string(),
+                },
+                Trait {
+                    display_type: Some("date".to_string()),
+                    trait_type: "awarded".to_string(),
+                    value: "1672531200".to_string(),
+                },
+            ]),
+            ..mock_badge().metadata
+        };
+        create(deps.as_mut(), metadata).unwrap();
+
+        let metadata = Metadata {
+            attributes: Some(vec![Trait {
+                display_type: Some("boost_percentage".to_string()),
+                trait_type: "stamina".to_string(),
+                value: "lots".to_string(),
+            }]),
+            ..mock_badge().metadata
+        };
+        let err = create(deps.as_mut(), metadata).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NonNumericTrait {
+                trait_type: "stamina".to_string(),
+                display_type: "boost_percentage".to_string(),
+                value: "lots".to_string(),
+            },
+        );
+
+        for value in ["", "-", "1.", ".5", "1e3", "NaN"] {
+            let metadata = Metadata {
+                attributes: Some(vec![Trait {
+                    display_type: Some("number".to_string()),
+                    trait_type: "level".to_string(),
+                    value: value.to_string(),
+                }]),
+                ..mock_badge().metadata
+            };
+            let err = create(deps.as_mut(), metadata).unwrap_err();
+            assert!(matches!(err, ContractError::NonNumericTrait { .. }), "{}", value);
+        }
+
+        let metadata = Metadata {
+            attributes: Some(vec![Trait {
+                display_type: Some("rarity".to_string()),
+                trait_type: "tier".to_string(),
+                value: "SSR".to_string(),
+            }]),
+            ..mock_badge().metadata
+        };
+        let err = create(deps.as_mut(), metadata).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnknownDisplayType {
+                display_type: "rarity".to_string(),
+            },
+        );
+    }
+
+    // fields must not be excessively long
+    {
+        let metadata = Metadata {
+            name: Some("a".repeat(257)),
+            ..Default::default()
+        };
+        let err = create(deps.as_mut(), metadata).unwrap_err();
+        assert_eq!(err, ContractError::metadata_too_long("name", 256));
+    }
+
+    // edits are validated the same way
+    {
+        let err = execute::edit_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Metadata::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::EmptyMetadata);
+    }
+}
+
+#[test]
+fn setting_serial_traits() {
+    let mut deps = setup_test();
+
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            current_supply: 3,
+            ..mock_badge()
+        },
+    );
+
+    let traits = vec![Trait {
+        display_type: None,
+        trait_type: "placement".to_string(),
+        value: "1st".to_string(),
+    }];
+
+    // only the manager can set traits
+    {
+        let err = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            2,
+            traits.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // the instance must have been minted
+    {
+        let err = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            4,
+            traits.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_minted(1, 4));
+    }
+
+    // the traits are forwarded to the nft contract
+    {
+        let res = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            2,
+            traits.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+                    msg: ExtensionMsg::SetSerialTraits {
+                        id: 1,
+                        serial: 2,
+                        traits,
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_serial_traits"),
+                attr("id", "1"),
+                attr("serial", "2"),
+                attr("fee", "[]"),
+            ],
+        );
+    }
+
+    // cannot set traits once the metadata is frozen
+    {
+        badge_map()
+            .update(deps.as_mut().storage, 1, |badge| {
+                let mut badge = badge.unwrap();
+                badge.metadata_frozen = true;
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        let err = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            2,
+            vec![],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MetadataFrozen);
+    }
+}
+
+#[test]
+fn setting_svg_template() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let template = r#"<svg xmlns="http://www.w3.org/2000/svg"><text>#{serial}</text></svg>"#;
+
+    // only the manager can set the template
+    {
+        let err = execute::set_svg_template(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            Some(template.to_string()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // the template must be an svg document
+    {
+        let err = execute::set_svg_template(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            Some("<html></html>".to_string()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSvgTemplate {
+                max: 16384,
+            },
+        );
+    }
+
+    // the template is saved and forwarded to the nft contract
+    {
+        let res = execute::set_svg_template(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            Some(template.to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+                    msg: ExtensionMsg::SetSvgTemplate {
+                        id: 1,
+                        template: Some(template.to_string()),
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "badges/hub/set_svg_template"), attr("id", "1"), attr("fee", "[]")],
+        );
+
+        let res = query::svg_template(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.template, Some(template.to_string()));
+    }
+
+    // the template can be removed
+    {
+        execute::set_svg_template(deps.as_mut(), mock_info("larry", &[]), 1, None).unwrap();
+
+        let res = query::svg_template(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.template, None);
+    }
+}
+
+#[test]
+fn setting_media() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let media = vec![
+        MediaAsset {
+            uri: "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            purpose: MediaPurpose::Video,
+        },
+        MediaAsset {
+            uri: "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/poster.png".to_string(),
+            mime_type: "image/png".to_string(),
+            purpose: MediaPurpose::Image,
+        },
+    ];
+
+    // only the manager can set media
+    {
+        let err = execute::set_media(deps.as_mut(), mock_info("jake", &[]), 1, media.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // urls must use a supported scheme
+    {
+        let bad_media = vec![MediaAsset {
+            uri: "http://video".to_string(),
+            ..media[0].clone()
+        }];
+        let err =
+            execute::set_media(deps.as_mut(), mock_info("larry", &[]), 1, bad_media).unwrap_err();
+        assert_eq!(err, ContractError::invalid_url("media uri", "http://video"));
+    }
+
+    // mime types must match the assets' purposes
+    {
+        let bad_media = vec![MediaAsset {
+            mime_type: "image/png".to_string(),
+            ..media[0].clone()
+        }];
+        let err =
+            execute::set_media(deps.as_mut(), mock_info("larry", &[]), 1, bad_media).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidMimeType {
+                mime_type: "image/png".to_string(),
+                purpose: "video".to_string(),
+            },
+        );
+    }
+
+    // there must not be too many assets
+    {
+        let bad_media = vec![media[0].clone(); 9];
+        let err =
+            execute::set_media(deps.as_mut(), mock_info("larry", &[]), 1, bad_media).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TooManyMediaAssets {
+                max: 8,
+            },
+        );
+    }
+
+    // the assets are saved and forwarded to the nft contract
+    {
+        let res =
+            execute::set_media(deps.as_mut(), mock_info("larry", &[]), 1, media.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+                    msg: ExtensionMsg::SetMedia {
+                        id: 1,
+                        media: media.clone(),
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "badges/hub/set_media"), attr("id", "1"), attr("fee", "[]")],
+        );
+
+        let res = query::media(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.media, media);
+    }
+
+    // an empty list removes the assets
+    {
+        execute::set_media(deps.as_mut(), mock_info("larry", &[]), 1, vec![]).unwrap();
+
+        let res = query::media(deps.as_ref(), 1).unwrap();
+        assert!(res.media.is_empty());
+    }
+}
+
+#[test]
+fn setting_commemorative_token() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            manager: Addr::unchecked("jake"),
+            ..mock_badge()
+        },
+    );
+
+    let token = CommemorativeToken {
+        subdenom: "points".to_string(),
+        amount: Uint128::new(100),
+    };
+
+    // only the manager can set the token
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jake", &[]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // the amount must be non-zero
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(CommemorativeToken {
+                amount: Uint128::zero(),
+                ..token.clone()
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroCommemorativeAmount);
+    }
+
+    // the subdenom must be accepted by the token factory
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(CommemorativeToken {
+                subdenom: "loyalty points".to_string(),
+                ..token.clone()
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSubdenom {
+                subdenom: "loyalty points".to_string(),
+            },
+        );
+    }
+
+    // only the developer can set the denom creation fee
+    {
+        let fee = Some(coin(10000000, "ustars"));
+        let err = execute::set_denom_creation_fee(deps.as_mut(), Addr::unchecked("jake"), fee)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+
+        let fee = Some(coin(10000000, "ustars"));
+        execute::set_denom_creation_fee(deps.as_mut(), Addr::unchecked("larry"), fee).unwrap();
+    }
+
+    // creating a denom requires the denom creation fee
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[coin(5000000, "ustars")]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::incorrect_payment("10000000ustars", "5000000ustars"));
+    }
+
+    // the first use of a subdenom creates the denom
+    {
+        let res = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[coin(10000000, "ustars")]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(token_factory::create_denom_msg(
+                &Addr::unchecked(MOCK_CONTRACT_ADDR),
+                "points",
+            ))],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_commemorative_token"),
+                attr("id", "1"),
+                attr("denom", format!("factory/{}/points", MOCK_CONTRACT_ADDR)),
+            ],
+        );
+
+        let res = query::commemorative_token(deps.as_ref(), mock_env(), 1).unwrap();
+        assert_eq!(
+            res,
+            CommemorativeTokenResponse {
+                id: 1,
+                denom: Some(format!("factory/{}/points", MOCK_CONTRACT_ADDR)),
+                amount: Some(Uint128::new(100)),
+            },
+        );
+    }
+
+    // the same manager can reuse the subdenom without creating it again, or paying the fee
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[coin(10000000, "ustars")]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+
+        let res = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    // other managers cannot use it
+    {
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jake", &[]),
+            2,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SubdenomTaken {
+                subdenom: "points".to_string(),
+            },
+        );
+    }
+
+    // the token can be removed
+    {
+        execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            None,
+        )
+        .unwrap();
+
+        let res = query::commemorative_token(deps.as_ref(), mock_env(), 1).unwrap();
+        assert_eq!(res.denom, None);
+    }
+
+    // the token can't be changed once the metadata is frozen
+    {
+        let mut badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        badge.metadata_frozen = true;
+        badge_map().save(deps.as_mut().storage, 1, &badge).unwrap();
+
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(token.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MetadataFrozen);
+    }
+
+    // nor once the badge is archived
+    {
+        let mut badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        badge.metadata_frozen = false;
+        badge.archived = true;
+        badge_map().save(deps.as_mut().storage, 1, &badge).unwrap();
+
+        let err = execute::set_commemorative_token(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Some(token),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Archived);
+    }
+}
+
+#[test]
+fn setting_localized_metadata() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let translation = Metadata {
+        name: Some("Insignia de prueba".to_string()),
+        ..Default::default()
+    };
+
+    // only the manager can set translations
+    {
+        let err = execute::set_localized_metadata(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            "es".to_string(),
+            Some(translation.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // the locale must be well-formed
+    {
+        let err = execute::set_localized_metadata(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            "Spanish".to_string(),
+            Some(translation.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidLocale {
+                locale: "Spanish".to_string(),
+            },
+        );
+    }
+
+    // the translation is saved and forwarded to the nft contract
+    {
+        let res = execute::set_localized_metadata(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            "es".to_string(),
+            Some(translation.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&badges::nft::ExecuteMsg::Extension {
+                    msg: ExtensionMsg::SetLocalizedMetadata {
+                        id: 1,
+                        locale: "es".to_string(),
+                        metadata: Some(translation),
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_localized_metadata"),
+                attr("id", "1"),
+                attr("locale", "es"),
+                attr("fee", "[]"),
+            ],
+        );
+
+        let res = query::locales(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.locales, vec!["es".to_string()]);
+    }
+
+    // the badge can be queried in the locale, its language, or an untranslated locale
+    {
+        let res = query::localized_badge(deps.as_ref(), 1, Some("es-MX".to_string())).unwrap();
+        assert_eq!(res.metadata.name, Some("Insignia de prueba".to_string()));
+
+        let res = query::localized_badge(deps.as_ref(), 1, Some("fr".to_string())).unwrap();
+        assert_eq!(res, query::badge(deps.as_ref(), 1).unwrap());
+    }
+
+    // the translation can be removed
+    {
+        execute::set_localized_metadata(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            "es".to_string(),
+            None,
+        )
+        .unwrap();
+
+        let res = query::locales(deps.as_ref(), 1).unwrap();
+        assert!(res.locales.is_empty());
+    }
+}
+
+#[test]
+fn querying_badges_by_id() {
+    let mut deps = setup_test();
+
+    for _ in 0..3 {
+        create_badge(deps.as_mut(), &mock_badge());
+    }
+
+    // badges are returned in the order given
+    let res = query::badges_by_id(deps.as_ref(), vec![3, 1]).unwrap();
+    assert_eq!(res.badges.iter().map(|b| b.id).collect::<Vec<_>>(), vec![3, 1]);
+    assert_eq!(res.badges[0], query::badge(deps.as_ref(), 3).unwrap());
+
+    // fails if any of the badges does not exist
+    assert!(query::badges_by_id(deps.as_ref(), vec![2, 4]).is_err());
+
+    // fails if more badges are requested than the maximum page size
+    let ids = vec![1; query::MAX_LIMIT as usize + 1];
+    assert!(query::badges_by_id(deps.as_ref(), ids).is_err());
+}
+
+#[test]
+fn querying_badges_by_manager() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    let jakes_badge = Badge {
+        manager: Addr::unchecked("jake"),
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+    create_badge(deps.as_mut(), &jakes_badge);
+    create_badge(deps.as_mut(), &badge);
+
+    fn query_ids(
+        deps: Deps,
+        manager: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<u64> {
+        query::badges_by_manager(deps, manager.to_string(), start_after, limit)
+            .unwrap()
+            .badges
+            .into_iter()
+            .map(|b| b.id)
+            .collect()
+    }
+
+    // badges are listed per manager, in ascending order of ids
+    {
+        assert_eq!(query_ids(deps.as_ref(), "larry", None, None), vec![1, 3]);
+        assert_eq!(query_ids(deps.as_ref(), "jake", None, None), vec![2]);
+        assert_eq!(query_ids(deps.as_ref(), "pumpkin", None, None), Vec::<u64>::new());
+    }
+
+    // paginate
+    {
+        assert_eq!(query_ids(deps.as_ref(), "larry", None, Some(1)), vec![1]);
+        assert_eq!(query_ids(deps.as_ref(), "larry", Some(1), None), vec![3]);
+    }
+
+    // the index follows the manager role when it is transferred
+    {
+        execute::transfer_manager(deps.as_mut(), mock_info("larry", &[]), 1, "jake".to_string())
+            .unwrap();
+        assert_eq!(query_ids(deps.as_ref(), "larry", None, None), vec![1, 3]);
+
+        execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap();
+        assert_eq!(query_ids(deps.as_ref(), "larry", None, None), vec![3]);
+        assert_eq!(query_ids(deps.as_ref(), "jake", None, None), vec![1, 2]);
+    }
+}
+
+#[test]
+fn querying_badges_by_rule() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    let minter_badge = Badge {
+        rule: MintRule::by_minter("larry"),
+        ..mock_badge()
+    };
+    let hidden_badge = Badge {
+        hidden: true,
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+    create_badge(deps.as_mut(), &minter_badge);
+    create_badge(deps.as_mut(), &hidden_badge);
+    create_badge(deps.as_mut(), &badge);
+
+    fn query_ids(
+        deps: Deps,
+        rule: &str,
+        start_after: Option<u64>,
+        include_hidden: Option<bool>,
+    ) -> Vec<u64> {
+        query::badges_by_rule(deps, rule.to_string(), start_after, None, include_hidden)
+            .unwrap()
+            .badges
+            .into_iter()
+            .map(|b| b.id)
+            .collect()
+    }
+
+    // badges are listed per rule type, skipping hidden ones unless requested
+    {
+        assert_eq!(query_ids(deps.as_ref(), "by_keys", None, None), vec![1, 4]);
+        assert_eq!(query_ids(deps.as_ref(), "by_keys", None, Some(true)), vec![1, 3, 4]);
+        assert_eq!(query_ids(deps.as_ref(), "by_minter", None, None), vec![2]);
+        assert_eq!(query_ids(deps.as_ref(), "open", None, None), Vec::<u64>::new());
+    }
+
+    // paginate
+    {
+        assert_eq!(query_ids(deps.as_ref(), "by_keys", Some(1), None), vec![4]);
+    }
+
+    // the index follows the badge when its rule is updated
+    {
+        execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1, MintRule::Open)
+            .unwrap();
+        assert_eq!(query_ids(deps.as_ref(), "by_keys", None, None), vec![4]);
+        assert_eq!(query_ids(deps.as_ref(), "open", None, None), vec![1]);
+    }
+}
+
+#[test]
+fn querying_badges_by_expiry() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            expiry: None,
+            ..mock_badge()
+        },
+    );
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            expiry: Some(11000),
+            ..mock_badge()
+        },
+    );
+    create_badge(deps.as_mut(), &badge);
+
+    fn query_ids(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> Vec<u64> {
+        query::badges_by_expiry(deps, start_after, limit)
+            .unwrap()
+            .badges
+            .into_iter()
+            .map(|b| b.id)
+            .collect()
+    }
+
+    // soonest expiry first, badges with the same expiry by id, and badges that never expire last
+    assert_eq!(query_ids(deps.as_ref(), None, None), vec![3, 1, 4, 2]);
+
+    // paginate
+    assert_eq!(query_ids(deps.as_ref(), None, Some(2)), vec![3, 1]);
+    assert_eq!(query_ids(deps.as_ref(), Some(1), None), vec![4, 2]);
+}
+
+#[test]
+fn transferring_manager() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    // non-manager cannot propose a transfer
+    {
+        let err = execute::transfer_manager(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can propose a transfer
+    {
+        let res = execute::transfer_manager(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            "jake".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/transfer_manager"),
+                attr("id", "1"),
+                attr("new_manager", "jake"),
+            ],
+        );
+
+        let res = query::pending_manager(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.pending_manager, Some("jake".to_string()));
+
+        // the current manager should keep its powers until the transfer is accepted
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.manager, "larry");
+    }
+
+    // accounts other than the proposed one cannot accept
+    {
+        let err = execute::accept_manager(deps.as_mut(), mock_info("pumpkin", &[]), 1)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotPendingManager);
+    }
+
+    // the proposed account can accept
+    {
+        let res = execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/accept_manager"),
+                attr("id", "1"),
+                attr("manager", "jake"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.manager, "jake");
+
+        let res = query::pending_manager(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.pending_manager, None);
+    }
+
+    // the previous manager can no longer edit the badge
+    {
+        let err = execute::edit_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            Metadata::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // a transfer cannot be accepted twice
+    {
+        let err = execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotPendingManager);
+    }
+}
+
+#[test]
+fn archiving_badge() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+    create_badge(deps.as_mut(), &badge);
+
+    // non-manager cannot archive
+    {
+        let err = execute::archive_badge(deps.as_mut(), mock_info("jake", &[]), 1, true)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager archives and hides the badge
+    {
+        let res = execute::archive_badge(deps.as_mut(), mock_info("larry", &[]), 1, true).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/archive_badge"),
+                attr("id", "1"),
+                attr("hidden", "true"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(b.archived);
+        assert!(b.hidden);
+    }
+
+    // cannot archive the same badge twice
+    {
+        let err = execute::archive_badge(deps.as_mut(), mock_info("larry", &[]), 1, false)
+            .unwrap_err();
+        assert_eq!(err, ContractError::Archived);
+    }
+
+    // hidden badges are skipped in the default listing
+    {
+        let res = query::badges(deps.as_ref(), None, None, None).unwrap();
+        assert_eq!(res.badges.iter().map(|b| b.id).collect::<Vec<_>>(), vec![2]);
+
+        let res = query::badges(deps.as_ref(), None, None, Some(true)).unwrap();
+        assert_eq!(res.badges.iter().map(|b| b.id).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    // archived badges can no longer be prepared for minting
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Archived);
+    }
+}
+
+#[test]
+fn updating_mint_params() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+    badge_map()
+        .update(deps.as_mut().storage, 1, |b| -> StdResult<_> {
+            let mut b = b.unwrap();
+            b.current_supply = 20;
+            Ok(b)
+        })
+        .unwrap();
+
+    // non-manager cannot update
+    {
+        let err = execute::update_mint_params(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+            Some(20000),
+            Some(200),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot set a deadline that has already passed
+    {
+        let err = execute::update_mint_params(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            Some(9999),
+            Some(200),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // cannot set a max supply below the number of instances already minted
+    {
+        let err = execute::update_mint_params(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            Some(20000),
+            Some(19),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+
+    // manager extends the deadline and lowers the max supply
+    {
+        let res = execute::update_mint_params(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            Some(20000),
+            Some(20),
+        )
+        .unwrap();
+        let updated = Badge {
+            expiry: Some(20000),
+            max_supply: Some(20),
+            ..badge.clone()
+        };
+        assert_eq!(res.messages, vec![SubMsg::new(utils::cache_metadata_msg(1, &updated))]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/update_mint_params"),
+                attr("id", "1"),
+                attr("expiry", "20000"),
+                attr("max_supply", "20"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.expiry, Some(20000));
+        assert_eq!(b.max_supply, Some(20));
+    }
+
+    // manager removes both limits
+    {
+        let res = execute::update_mint_params(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("expiry", "undefined"));
+        assert_eq!(res.attributes[3], attr("max_supply", "undefined"));
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.expiry, None);
+        assert_eq!(b.max_supply, None);
+    }
+}
+
+#[test]
+fn querying_supply() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        start_time: Some(10000),
+        current_supply: 40,
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+
+    // mintable between the start time and the expiry
+    {
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(10000), 1).unwrap();
+        assert_eq!(
+            res,
+            SupplyResponse {
+                id: 1,
+                max_supply: Some(100),
+                current_supply: 40,
+                remaining: Some(60),
+                mintable: true,
+            },
+        );
+    }
+
+    // not mintable before the start time or after the expiry
+    {
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(9999), 1).unwrap();
+        assert!(!res.mintable);
+
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(12346), 1).unwrap();
+        assert!(!res.mintable);
+    }
+
+    // not mintable while the badge or the hub is paused
+    {
+        let paused_badge = Badge {
+            paused: true,
+            ..badge.clone()
+        };
+        badge_map().save(deps.as_mut().storage, 1, &paused_badge).unwrap();
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(10000), 1).unwrap();
+        assert!(!res.mintable);
+
+        badge_map().save(deps.as_mut().storage, 1, &badge).unwrap();
+        HUB_PAUSED.save(deps.as_mut().storage, &true).unwrap();
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(10000), 1).unwrap();
+        assert!(!res.mintable);
+
+        HUB_PAUSED.save(deps.as_mut().storage, &false).unwrap();
+    }
+
+    // not mintable once sold out
+    {
+        let sold_out_badge = Badge {
+            current_supply: 100,
+            ..badge.clone()
+        };
+        badge_map().save(deps.as_mut().storage, 1, &sold_out_badge).unwrap();
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(10000), 1).unwrap();
+        assert_eq!(res.remaining, Some(0));
+        assert!(!res.mintable);
+    }
+
+    // no remaining count without a max supply
+    {
+        let unlimited_badge = Badge {
+            max_supply: None,
+            ..badge
+        };
+        badge_map().save(deps.as_mut().storage, 1, &unlimited_badge).unwrap();
+        let res = query::supply(deps.as_ref(), utils::mock_env_at_timestamp(10000), 1).unwrap();
+        assert_eq!(res.remaining, None);
+        assert!(res.mintable);
+    }
+}
+
+#[test]
+fn querying_active_badges() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            start_time: Some(20000),
+            expiry: None,
+            ..mock_badge()
+        },
+    );
+    create_badge(deps.as_mut(), &badge);
+    create_badge(deps.as_mut(), &badge);
+
+    // badge 3 is sold out, and badge 4 is paused
+    badge_map()
+        .save(
+            deps.as_mut().storage,
+            3,
+            &Badge {
+                current_supply: 100,
+                ..badge.clone()
+            },
+        )
+        .unwrap();
+    badge_map()
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                paused: true,
+                ..badge.clone()
+            },
+        )
+        .unwrap();
+
+    fn query_ids(deps: Deps, timestamp: u64, start_after: Option<u64>) -> Vec<u64> {
+        query::active_badges(deps, utils::mock_env_at_timestamp(timestamp), start_after, None)
+            .unwrap()
+            .badges
+            .into_iter()
+            .map(|b| b.id)
+            .collect()
+    }
+
+    // badge 2 has not started yet
+    assert_eq!(query_ids(deps.as_ref(), 10000, None), vec![1]);
+
+    // once badge 2 has started, badge 1 has expired
+    assert_eq!(query_ids(deps.as_ref(), 20000, None), vec![2]);
+    assert_eq!(query_ids(deps.as_ref(), 20000, Some(2)), Vec::<u64>::new());
+
+    // the number of badges looked at per query is capped, and the response tells where to resume
+    {
+        let last_id = 4 + query::MAX_BADGES_SCANNED as u64 + 1;
+        for id in 5..last_id {
+            badge_map().save(deps.as_mut().storage, id, &badge).unwrap();
+        }
+        let unlimited_badge = Badge {
+            expiry: None,
+            ..badge.clone()
+        };
+        badge_map().save(deps.as_mut().storage, last_id, &unlimited_badge).unwrap();
+        BADGE_COUNT.save(deps.as_mut().storage, &last_id).unwrap();
+
+        let env = utils::mock_env_at_timestamp(20000);
+        let res = query::active_badges(deps.as_ref(), env.clone(), Some(2), None).unwrap();
+        assert!(res.badges.is_empty());
+        assert_eq!(res.next_start_after, Some(2 + query::MAX_BADGES_SCANNED as u64));
+
+        let res = query::active_badges(deps.as_ref(), env, res.next_start_after, None).unwrap();
+        assert_eq!(res.badges.into_iter().map(|b| b.id).collect::<Vec<_>>(), vec![last_id]);
+        assert_eq!(res.next_start_after, None);
+    }
+
+    // nothing is active while the hub is paused
+    HUB_PAUSED.save(deps.as_mut().storage, &true).unwrap();
+    assert_eq!(query_ids(deps.as_ref(), 20000, None), Vec::<u64>::new());
+}
+
+#[test]
+fn querying_stats() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+    create_badge(
+        deps.as_mut(),
+        &Badge {
+            rule: MintRule::by_minter("larry"),
+            ..mock_badge()
+        },
+    );
+
+    execute::mint_by_minter(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        2,
+        utils::btreeset(&["jake", "pumpkin"]),
+        Addr::unchecked("larry"),
+    )
+    .unwrap();
+
+    // instances minted under the old rule are still counted towards it after the rule is updated
+    execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 2, MintRule::Open).unwrap();
+
+    let res = query::stats(deps.as_ref(), utils::mock_env_at_timestamp(10000), None, None).unwrap();
+    assert_eq!(
+        res,
+        StatsResponse {
+            badge_count: 2,
+            minted_count: 2,
+            rules: vec![
+                RuleStatsResponse {
+                    rule: "by_keys".to_string(),
+                    badge_count: 1,
+                    minted_count: 0,
+                },
+                RuleStatsResponse {
+                    rule: "by_minter".to_string(),
+                    badge_count: 0,
+                    minted_count: 2,
+                },
+                RuleStatsResponse {
+                    rule: "open".to_string(),
+                    badge_count: 1,
+                    minted_count: 0,
+                },
+            ],
+            supplies: vec![
+                SupplyResponse {
+                    id: 1,
+                    max_supply: Some(100),
+                    current_supply: 0,
+                    remaining: Some(100),
+                    mintable: true,
+                },
+                SupplyResponse {
+                    id: 2,
+                    max_supply: Some(100),
+                    current_supply: 2,
+                    remaining: Some(98),
+                    mintable: true,
+                },
+            ],
+        },
+    );
+
+    // the supplies are paginated by badge id
+    let res =
+        query::stats(deps.as_ref(), utils::mock_env_at_timestamp(10000), Some(1), None).unwrap();
+    assert_eq!(res.supplies.iter().map(|supply| supply.id).collect::<Vec<_>>(), vec![2]);
+
+    // instances minted during a phase are counted towards the phase's rule, not the badge's own
+    let mut badge = badge_map().load(deps.as_ref().storage, 2).unwrap();
+    badge.phases = vec![Phase {
+        rule: MintRule::by_minter("larry"),
+        start_time: 10000,
+        end_time: None,
+        max_per_owner: None,
+    }];
+    badge_map().save(deps.as_mut().storage, 2, &badge).unwrap();
+
+    execute::mint_by_minter(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        2,
+        utils::btreeset(&["larry"]),
+        Addr::unchecked("larry"),
+    )
+    .unwrap();
+
+    let res = query::stats(deps.as_ref(), utils::mock_env_at_timestamp(10000), None, None).unwrap();
+    assert_eq!(res.minted_count, 3);
+    assert_eq!(
+        res.rules.iter().map(|rule| (rule.rule.as_str(), rule.minted_count)).collect::<Vec<_>>(),
+        vec![("by_keys", 0), ("by_minter", 3), ("open", 0)],
+    );
+}
+
+#[test]
+fn updating_mint_rule() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        rule: MintRule::by_minter("larry"),
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+    OWNERS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
+
+    // non-manager cannot update
+    {
+        let err = execute::update_mint_rule(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            MintRule::by_key(KEY_1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot update to an invalid rule
+    {
+        let err = execute::update_mint_rule(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            MintRule::by_key(INVALID_KEY),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+
+    // manager can update
+    {
+        let res = execute::update_mint_rule(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            MintRule::by_key(KEY_1),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/update_mint_rule"),
+                attr("id", "1"),
+                attr("rule", format!("by_key:{}", KEY_1)),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.rule, MintRule::by_key(KEY_1));
+
+        // existing claim records should be kept
+        let res = query::owner(deps.as_ref(), 1, "jake");
+        assert!(res.claimed);
+    }
+
+    // non-manager cannot freeze
+    {
+        let err = execute::freeze_mint_rule(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can freeze
+    {
+        let res = execute::freeze_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "badges/hub/freeze_mint_rule"), attr("id", "1")],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(b.rule_frozen);
+    }
+
+    // once frozen, the rule can no longer be updated or have its key rotated
+    {
+        let err = execute::update_mint_rule(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            MintRule::ByKeys,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RuleFrozen);
+
+        let err = execute::rotate_key(deps.as_mut(), mock_info("larry", &[]), 1, KEY_2.to_string())
+            .unwrap_err();
+        assert_eq!(err, ContractError::RuleFrozen);
+    }
+}
+
+#[test]
+fn updating_mint_rule_with_pending_state() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        rule: MintRule::by_raffle(10000, 1),
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+    REGISTRANTS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
+
+    // cannot switch away from a raffle that has registrants but hasn't been drawn
+    {
+        let err =
+            execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1, MintRule::ByKeys)
+                .unwrap_err();
+        assert_eq!(err, ContractError::raffle_undrawn(1));
+    }
+
+    // can change the raffle's parameters
+    execute::update_mint_rule(
+        deps.as_mut(),
+        mock_info("larry", &[]),
+        1,
+        MintRule::by_raffle(20000, 1),
+    )
+    .unwrap();
+
+    // can switch once the raffle is drawn
+    RAFFLES_DRAWN.insert(deps.as_mut().storage, 1).unwrap();
+    execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1, MintRule::ByKeys)
+        .unwrap();
+
+    KEYS.insert(deps.as_mut().storage, (1, KEY_1)).unwrap();
+
+    // cannot switch to a rule that no longer uses the unused keys
+    {
+        let err = execute::update_mint_rule(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            MintRule::ByVoucher,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::keys_outstanding(1));
+    }
+
+    // can switch to a rule that still uses them
+    execute::update_mint_rule(
+        deps.as_mut(),
+        mock_info("larry", &[]),
+        1,
+        MintRule::any_of([MintRule::ByKeys, MintRule::ByVoucher]),
+    )
+    .unwrap();
+
+    KEYS.remove(deps.as_mut().storage, (1, KEY_1)).unwrap();
+    VOUCHERS.insert(deps.as_mut().storage, (1, "hash")).unwrap();
+
+    // cannot switch to a rule that no longer uses the unused vouchers
+    {
+        let err =
+            execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1, MintRule::ByKeys)
+                .unwrap_err();
+        assert_eq!(err, ContractError::vouchers_outstanding(1));
+    }
+}
+
+#[test]
+fn updating_phases_with_pending_state() {
+    let mut deps = setup_test();
+
+    let phase = |rule: MintRule| Phase {
+        rule,
+        start_time: 10000,
+        end_time: None,
+        max_per_owner: None,
+    };
+
+    let badge = Badge {
+        rule: MintRule::Open,
+        phases: vec![phase(MintRule::by_raffle(10000, 1))],
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+    REGISTRANTS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
+
+    // cannot remove a raffle phase that has registrants but hasn't been drawn
+    {
+        let err = execute::update_phases(deps.as_mut(), mock_info("larry", &[]), 1, vec![])
+            .unwrap_err();
+        assert_eq!(err, ContractError::raffle_undrawn(1));
+    }
+
+    // can change the raffle's parameters
+    execute::update_phases(
+        deps.as_mut(),
+        mock_info("larry", &[]),
+        1,
+        vec![phase(MintRule::by_raffle(20000, 1))],
+    )
+    .unwrap();
+
+    // can remove the phase once the raffle is drawn
+    RAFFLES_DRAWN.insert(deps.as_mut().storage, 1).unwrap();
+    execute::update_phases(
+        deps.as_mut(),
+        mock_info("larry", &[]),
+        1,
+        vec![phase(MintRule::ByKeys)],
+    )
+    .unwrap();
+
+    KEYS.insert(deps.as_mut().storage, (1, KEY_1)).unwrap();
+
+    // cannot replace the phase with one that no longer uses the unused keys
+    {
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![phase(MintRule::ByVoucher)],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::keys_outstanding(1));
+    }
+
+    // can replace it with one that still uses them
+    execute::update_phases(
+        deps.as_mut(),
+        mock_info("larry", &[]),
+        1,
+        vec![phase(MintRule::any_of([MintRule::ByKeys, MintRule::ByVoucher]))],
+    )
+    .unwrap();
+
+    KEYS.remove(deps.as_mut().storage, (1, KEY_1)).unwrap();
+    VOUCHERS.insert(deps.as_mut().storage, (1, "hash")).unwrap();
+
+    // cannot remove the phase while its vouchers are unused
+    {
+        let err = execute::update_phases(deps.as_mut(), mock_info("larry", &[]), 1, vec![])
+            .unwrap_err();
+        assert_eq!(err, ContractError::vouchers_outstanding(1));
+    }
+
+    // unless the badge's own rule still uses them
+    execute::update_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1, MintRule::ByVoucher)
+        .unwrap();
+    execute::update_phases(deps.as_mut(), mock_info("larry", &[]), 1, vec![]).unwrap();
+}
+
+#[test]
+fn updating_phases() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let allowlist_phase = Phase {
+        rule: MintRule::ByAllowlist,
+        start_time: 10000,
+        end_time: Some(10999),
+        max_per_owner: None,
+    };
+    let public_phase = Phase {
+        rule: MintRule::Open,
+        start_time: 11000,
+        end_time: None,
+        max_per_owner: Some(1),
+    };
+
+    // non-manager cannot update
+    {
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            vec![allowlist_phase.clone(), public_phase.clone()],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // phases must be in chronological order
+    {
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![public_phase.clone(), allowlist_phase.clone()],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPhases);
+    }
+
+    // phases must not overlap
+    {
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![
+                allowlist_phase.clone(),
+                Phase {
+                    start_time: 10999,
+                    ..public_phase.clone()
+                },
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPhases);
+    }
+
+    // the rule of each phase must be valid
+    {
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![Phase {
+                rule: MintRule::by_key(INVALID_KEY),
+                ..allowlist_phase.clone()
+            }],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+
+    // manager can update
+    {
+        let res = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![allowlist_phase.clone(), public_phase.clone()],
+        )
+        .unwrap();
         assert_eq!(
             res.attributes,
             vec![
-                attr("action", "badges/hub/create_badge"),
-                attr("id", "2"),
-                attr("fee", "[]"),
-            ]
+                attr("action", "badges/hub/update_phases"),
+                attr("id", "1"),
+                attr("phases", "2"),
+            ],
         );
 
-        let cfg = query::config(deps.as_ref()).unwrap();
-        assert_eq!(cfg.badge_count, 2);
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.phases, vec![allowlist_phase.clone(), public_phase]);
+    }
 
-        let b = query::badge(deps.as_ref(), 2).unwrap();
-        assert_eq!(b, (2, badge).into());
+    // once the rule is frozen, the phases can no longer be updated
+    {
+        execute::freeze_mint_rule(deps.as_mut(), mock_info("larry", &[]), 1).unwrap();
+
+        let err = execute::update_phases(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            vec![allowlist_phase],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RuleFrozen);
     }
 }
 
 #[test]
-fn editing_badge() {
+fn rotating_key() {
     let mut deps = setup_test();
 
-    let badge = mock_badge();
+    let badge = Badge {
+        rule: MintRule::by_key(KEY_1),
+        ..mock_badge()
+    };
     create_badge(deps.as_mut(), &badge);
+    OWNERS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
 
-    // non-manager cannot edit
+    // non-manager cannot rotate
     {
-        let err = execute::edit_badge(
-            deps.as_mut(),
-            mock_info("jake", &[]),
-            1,
-            Metadata::default(),
-        )
-        .unwrap_err();
+        let err = execute::rotate_key(deps.as_mut(), mock_info("jake", &[]), 1, KEY_2.to_string())
+            .unwrap_err();
         assert_eq!(err, ContractError::NotManager);
     }
 
-    // manager can edit
+    // cannot rotate to an invalid key
     {
-        let res = execute::edit_badge(
-            deps.as_mut(),
-            mock_info(badge.manager.as_str(), &[]),
-            1,
-            Metadata::default(),
-        )
-        .unwrap();
-        assert_eq!(res.messages, vec![]);
+        let err =
+            execute::rotate_key(deps.as_mut(), mock_info("larry", &[]), 1, INVALID_KEY.to_string())
+                .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+
+    // manager can rotate
+    {
+        let res = execute::rotate_key(deps.as_mut(), mock_info("larry", &[]), 1, KEY_2.to_string())
+            .unwrap();
         assert_eq!(
             res.attributes,
-            vec![
-                attr("action", "badges/hub/edit_badge"),
-                attr("id", "1"),
-                attr("fee", "[]"),
-            ],
+            vec![attr("action", "badges/hub/rotate_key"), attr("id", "1")],
         );
 
         let b = query::badge(deps.as_ref(), 1).unwrap();
-        assert_eq!(b.metadata, Metadata::default());
+        assert_eq!(b.rule, MintRule::by_key(KEY_2));
+
+        // existing claim records should be kept
+        let res = query::owner(deps.as_ref(), 1, "jake");
+        assert!(res.claimed);
+    }
+
+    // cannot rotate a badge that doesn't use the "by key" rule
+    {
+        create_badge(deps.as_mut(), &mock_badge());
+
+        let err = execute::rotate_key(deps.as_mut(), mock_info("larry", &[]), 2, KEY_1.to_string())
+            .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_key", &MintRule::ByKeys));
     }
 }
 
@@ -306,6 +2500,321 @@ fn adding_keys() {
     }
 }
 
+#[test]
+fn adding_vouchers() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        rule: MintRule::ByVoucher,
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+
+    let hash_1 = hex::encode(badge_hub::helpers::hash("apple"));
+    let hash_2 = hex::encode(badge_hub::helpers::hash("banana"));
+
+    // non-manager cannot add vouchers
+    {
+        let err = execute::add_vouchers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+            utils::btreeset(&[&hash_1]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot add something that isn't a SHA-256 hash
+    {
+        let err = execute::add_vouchers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&["apple"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVoucherHash);
+    }
+
+    // manager can add vouchers
+    {
+        let res = execute::add_vouchers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[&hash_1, &hash_2]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/add_vouchers"),
+                attr("id", "1"),
+                attr("fee", "[]"),
+                attr("vouchers_added", "2"),
+            ],
+        );
+
+        let res = query::voucher(deps.as_ref(), 1, &hash_2);
+        assert!(res.available);
+    }
+
+    // cannot add the same voucher twice
+    {
+        let err = execute::add_vouchers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[&hash_1]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::voucher_exists(1, &hash_1));
+    }
+}
+
+#[test]
+fn delegating_minters() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let badge = Badge {
+        rule: MintRule::ByMinter("larry".to_string()),
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+
+    let expiration = Expiration::AtTime(Timestamp::from_seconds(12345));
+
+    // cannot delegate if the badge doesn't use the "by minter" or "by minters" rule
+    {
+        let err = execute::grant_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            "jake".to_string(),
+            expiration,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_minter", &mock_badge().rule));
+    }
+
+    // non-manager cannot delegate
+    {
+        let err = execute::grant_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            2,
+            "jake".to_string(),
+            expiration,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot delegate with an expiration that has already passed
+    {
+        let err = execute::grant_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(20000),
+            mock_info("larry", &[]),
+            2,
+            "jake".to_string(),
+            expiration,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DelegationExpired);
+    }
+
+    // manager can delegate
+    {
+        let res = execute::grant_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "jake".to_string(),
+            expiration,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/grant_minter"),
+                attr("id", "2"),
+                attr("minter", "jake"),
+                attr("expiration", "expiration time: 12345.000000000"),
+            ],
+        );
+
+        let res = query::delegated_minter(deps.as_ref(), 2, "jake").unwrap();
+        assert_eq!(res.expiration, Some(expiration));
+    }
+
+    // non-manager cannot revoke
+    {
+        let err =
+            execute::revoke_minter(deps.as_mut(), mock_info("jake", &[]), 2, "jake".to_string())
+                .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can revoke early
+    {
+        let res =
+            execute::revoke_minter(deps.as_mut(), mock_info("larry", &[]), 2, "jake".to_string())
+                .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/revoke_minter"),
+                attr("id", "2"),
+                attr("minter", "jake"),
+            ],
+        );
+
+        let res = query::delegated_minter(deps.as_ref(), 2, "jake").unwrap();
+        assert_eq!(res.expiration, None);
+    }
+}
+
+#[test]
+fn managing_allowlist() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let badge = Badge {
+        rule: MintRule::ByAllowlist,
+        ..mock_badge()
+    };
+    create_badge(deps.as_mut(), &badge);
+
+    // cannot add to the allowlist if the badge doesn't use the "by allowlist" rule
+    {
+        let err = execute::add_to_allowlist(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_allowlist", &mock_badge().rule));
+    }
+
+    // non-manager cannot add to the allowlist
+    {
+        let err = execute::add_to_allowlist(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            2,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can add to the allowlist
+    {
+        let res = execute::add_to_allowlist(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&["jake", "pumpkin"]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/add_to_allowlist"),
+                attr("id", "2"),
+                attr("users_added", "2"),
+            ],
+        );
+
+        let res = query::allowlist(deps.as_ref(), 2, None, None).unwrap();
+        assert_eq!(res.users, vec!["jake".to_string(), "pumpkin".to_string()]);
+    }
+
+    // non-manager cannot remove from the allowlist
+    {
+        let err = execute::remove_from_allowlist(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            2,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager can remove from the allowlist
+    {
+        let res = execute::remove_from_allowlist(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/remove_from_allowlist"),
+                attr("id", "2"),
+                attr("users_removed", "1"),
+            ],
+        );
+
+        let res = query::allowlist(deps.as_ref(), 2, None, None).unwrap();
+        assert_eq!(res.users, vec!["pumpkin".to_string()]);
+    }
+}
+
+#[test]
+fn rejecting_invalid_revenue_split() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        rule: MintRule::ByPayment(Price::native(10000, "ustars")),
+        revenue_split: vec![RevenueShare::new("artist", 6000), RevenueShare::new("collab", 4001)],
+        ..mock_badge()
+    };
+    let err = execute::create_badge(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        mock_info("creator", &[]),
+        badge,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRevenueSplit);
+}
+
+#[test]
+fn rejecting_invalid_royalty() {
+    let mut deps = setup_test();
+
+    let badge = Badge {
+        royalty: Some(RevenueShare::new("artist", 10001)),
+        ..mock_badge()
+    };
+    let err = execute::create_badge(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        mock_info("creator", &[]),
+        badge,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoyalty);
+}
+
 #[test]
 fn rejecting_invalid_keys() {
     let mut deps = setup_test();
@@ -319,11 +2828,25 @@ fn rejecting_invalid_keys() {
             Badge {
                 manager: Addr::unchecked("larry"),
                 metadata: Metadata::default(),
+                token_uri: None,
                 transferrable: false,
+                revocable: false,
                 rule: MintRule::ByKey(INVALID_KEY.into()),
+                rule_frozen: false,
+                metadata_frozen: false,
+                paused: false,
+                archived: false,
+                hidden: false,
+                phases: vec![],
+                revenue_split: vec![],
+                royalty: None,
+                start_time: None,
                 expiry: None,
                 max_supply: None,
+                max_per_owner: None,
+                rate_limit: None,
                 current_supply: 0,
+                num_burned: 0,
             },
         )
         .unwrap_err();
@@ -339,12 +2862,26 @@ fn rejecting_invalid_keys() {
             mock_info("larry", &[]),
             Badge {
                 manager: Addr::unchecked("larry"),
-                metadata: Metadata::default(),
+                metadata: mock_badge().metadata,
+                token_uri: None,
                 transferrable: false,
+                revocable: false,
                 rule: MintRule::ByKeys,
+                rule_frozen: false,
+                metadata_frozen: false,
+                paused: false,
+                archived: false,
+                hidden: false,
+                phases: vec![],
+                revenue_split: vec![],
+                royalty: None,
+                start_time: None,
                 expiry: None,
                 max_supply: None,
+                max_per_owner: None,
+                rate_limit: None,
                 current_supply: 0,
+                num_burned: 0,
             },
         )
         .unwrap();
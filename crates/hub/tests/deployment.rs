@@ -1,11 +1,19 @@
-use cosmwasm_std::testing::{mock_dependencies};
-use cosmwasm_std::{attr, Addr, Decimal};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, to_binary, Addr, Binary, ContractResult, Decimal, Empty, StdError, SubMsg,
+    SystemResult, WasmMsg, WasmQuery,
+};
 
-use badges::FeeRate;
+use badges::hub::{MigrateMsg, SudoMsg};
+use badges::{FeeRate, MintRule, TokenIdFormat};
 
+use badge_hub::contract::{self, CONTRACT_NAME, CONTRACT_VERSION};
 use badge_hub::error::ContractError;
-use badge_hub::state::{BADGE_COUNT, NFT, DEVELOPER};
-use badge_hub::execute;
+use badge_hub::state::{
+    badge_map, BADGE_COUNT, CREATION_FEE, DENOM_CREATION_FEE, DEVELOPER, FEE_COLLECTOR, HUB_PAUSED,
+    MINTED_COUNT, NFT, PENDING_DEVELOPER, RULE_STATS, TOKEN_ID_FORMAT,
+};
+use badge_hub::{execute, query};
 
 #[test]
 fn instantiating() {
@@ -18,13 +26,27 @@ fn instantiating() {
             metadata: Decimal::from_ratio(10u128, 1u128),
             key: Decimal::from_ratio(2u128, 1u128),
         },
+        None,
+        Addr::unchecked("larry"),
+        TokenIdFormat::ZeroPadded {
+            width: 4,
+        },
     )
     .unwrap();
     assert_eq!(res.messages, vec![]);
     assert_eq!(res.attributes, vec![attr("action", "badges/hub/init")]);
 
+    let token_id_format = TOKEN_ID_FORMAT.load(deps.as_ref().storage).unwrap();
+    assert_eq!(token_id_format.build(69, 420).unwrap(), "0069-0420");
+
     let badge_count = BADGE_COUNT.load(deps.as_ref().storage).unwrap();
     assert_eq!(badge_count, 0);
+
+    let fee_collector = FEE_COLLECTOR.load(deps.as_ref().storage).unwrap();
+    assert_eq!(fee_collector, Addr::unchecked("larry"));
+
+    let creation_fee = CREATION_FEE.may_load(deps.as_ref().storage).unwrap();
+    assert_eq!(creation_fee, None);
 }
 
 #[test]
@@ -64,3 +86,408 @@ fn setting_nft() {
         assert_eq!(err, ContractError::DoubleInit);
     }
 }
+
+#[test]
+fn transferring_ownership() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+
+    // non-developer cannot propose a transfer
+    {
+        let err = execute::transfer_ownership(deps.as_mut(), Addr::unchecked("jake"), "jake")
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer proposes a transfer
+    {
+        let res = execute::transfer_ownership(deps.as_mut(), Addr::unchecked("larry"), "jake")
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/transfer_ownership"),
+                attr("new_developer", "jake"),
+            ],
+        );
+
+        // the developer doesn't change until the transfer is accepted
+        let developer = DEVELOPER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(developer, Addr::unchecked("larry"));
+    }
+
+    // only the proposed account can accept
+    {
+        let err = execute::accept_ownership(deps.as_mut(), Addr::unchecked("pumpkin")).unwrap_err();
+        assert_eq!(err, ContractError::NotPendingDeveloper);
+    }
+
+    // the proposed account accepts
+    {
+        let res = execute::accept_ownership(deps.as_mut(), Addr::unchecked("jake")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/accept_ownership"),
+                attr("developer", "jake"),
+            ],
+        );
+
+        let developer = DEVELOPER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(developer, Addr::unchecked("jake"));
+
+        let pending = PENDING_DEVELOPER.may_load(deps.as_ref().storage).unwrap();
+        assert!(pending.is_none());
+    }
+
+    // the previous developer no longer has privileges
+    {
+        let err = execute::set_nft(deps.as_mut(), Addr::unchecked("larry"), "nft").unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+}
+
+#[test]
+fn migrating() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+
+    // a badge as stored by v1.2 of the contract
+    deps.as_mut().storage.set(
+        &badge_map().key(1),
+        br#"{"manager":"larry","metadata":{},"transferrable":true,"rule":"by_keys","expiry":12345,"max_supply":100,"current_supply":88}"#,
+    );
+
+    // cannot migrate from an unknown version
+    {
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.9.0").unwrap();
+
+        let err = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(err, ContractError::incorrect_contract_version("1.0.0 or 1.2.0", "0.9.0"));
+    }
+
+    // migrate from v1.2
+    {
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.2.0").unwrap();
+
+        let res = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/migrate"),
+                attr("from_version", "1.2.0"),
+                attr("to_version", CONTRACT_VERSION),
+            ],
+        );
+
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+
+        // the badge should have been converted to the new storage layout, with the new settings
+        // at their defaults
+        let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.manager, Addr::unchecked("larry"));
+        assert_eq!(badge.rule, MintRule::ByKeys);
+        assert_eq!(badge.expiry, Some(12345));
+        assert_eq!(badge.max_supply, Some(100));
+        assert_eq!(badge.current_supply, 88);
+        assert!(badge.phases.is_empty());
+        assert!(!badge.archived);
+
+        // the badge should have been indexed by its manager
+        let res = query::badges_by_manager(deps.as_ref(), "larry".to_string(), None, None).unwrap();
+        assert_eq!(res.badges.iter().map(|b| b.id).collect::<Vec<_>>(), vec![1]);
+
+        // ...and by its mint rule, with its supply counted towards the stats
+        let res = query::badges_by_rule(deps.as_ref(), "by_keys".to_string(), None, None, None)
+            .unwrap();
+        assert_eq!(res.badges.iter().map(|b| b.id).collect::<Vec<_>>(), vec![1]);
+
+        assert_eq!(MINTED_COUNT.load(deps.as_ref().storage).unwrap(), 88);
+        assert_eq!(RULE_STATS.load(deps.as_ref().storage, "by_keys").unwrap(), (1, 88));
+
+        let fee_collector = FEE_COLLECTOR.load(deps.as_ref().storage).unwrap();
+        assert_eq!(fee_collector, Addr::unchecked("larry"));
+    }
+}
+
+#[test]
+fn migrating_from_v1_0() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+
+    // badges as stored by v1.0 of the contract, including badge 17 with its incorrect claim key
+    deps.as_mut().storage.set(
+        &badge_map().key(1),
+        br#"{"manager":"larry","metadata":{},"transferrable":true,"rule":"by_keys","expiry":null,"max_supply":null,"current_supply":5}"#,
+    );
+    deps.as_mut().storage.set(
+        &badge_map().key(17),
+        br#"{"manager":"jake","metadata":{},"transferrable":false,"rule":{"by_key":"0000"},"expiry":null,"max_supply":null,"current_supply":3}"#,
+    );
+
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.0.0").unwrap();
+
+    let res = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "badges/hub/migrate"),
+            attr("from_version", "1.0.0"),
+            attr("to_version", CONTRACT_VERSION),
+        ],
+    );
+
+    // badge 17 should have its claim key corrected, and both badges should have been converted
+    // to the new storage layout
+    let badge = badge_map().load(deps.as_ref().storage, 17).unwrap();
+    assert_eq!(badge.manager, Addr::unchecked("jake"));
+    assert_eq!(
+        badge.rule,
+        MintRule::ByKey(
+            "036986114808be5b9f9009754014bdf5ae210cc17c93f4e1d010164be74b8653f4".to_string()
+        ),
+    );
+    assert_eq!(badge.current_supply, 3);
+
+    let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+    assert_eq!(badge.rule, MintRule::ByKeys);
+
+    assert_eq!(MINTED_COUNT.load(deps.as_ref().storage).unwrap(), 8);
+    assert_eq!(RULE_STATS.load(deps.as_ref().storage, "by_key").unwrap(), (1, 3));
+}
+
+#[test]
+fn migrating_from_v1_0_without_badge_17() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.0.0").unwrap();
+
+    let err = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(err, StdError::not_found("badge 17").into());
+}
+
+#[test]
+fn migrating_nft() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+    NFT.save(deps.as_mut().storage, &Addr::unchecked("nft")).unwrap();
+
+    let msg = to_binary(&Empty {}).unwrap();
+
+    // non-developer cannot migrate the nft contract
+    {
+        let err = execute::migrate_nft(deps.as_mut(), Addr::unchecked("jake"), 69, msg.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer migrates the nft contract
+    {
+        let res = execute::migrate_nft(deps.as_mut(), Addr::unchecked("larry"), 69, msg.clone())
+            .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Migrate {
+                contract_addr: "nft".to_string(),
+                new_code_id: 69,
+                msg,
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/migrate_nft"),
+                attr("nft", "nft"),
+                attr("new_code_id", "69"),
+            ],
+        );
+    }
+}
+
+#[test]
+fn managing_nft_admin() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+    NFT.save(deps.as_mut().storage, &Addr::unchecked("nft")).unwrap();
+
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::ContractInfo {
+            contract_addr,
+        } if contract_addr == "nft" => {
+            let res = format!(
+                r#"{{"code_id":1,"creator":"larry","admin":"{}","pinned":false}}"#,
+                MOCK_CONTRACT_ADDR,
+            );
+            SystemResult::Ok(ContractResult::Ok(Binary::from(res.as_bytes())))
+        },
+        _ => panic!("[mock]: unsupported wasm query: {:?}", query),
+    });
+
+    // query the nft contract's linkage
+    {
+        let res = query::nft_contract(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(res.nft, "nft");
+        assert_eq!(res.admin, Some(MOCK_CONTRACT_ADDR.to_string()));
+        assert!(res.hub_is_admin);
+    }
+
+    // non-developer cannot update or clear the nft contract's admin
+    {
+        let err = execute::update_nft_admin(deps.as_mut(), Addr::unchecked("jake"), "jake")
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+
+        let err = execute::clear_nft_admin(deps.as_mut(), Addr::unchecked("jake")).unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer updates the nft contract's admin
+    {
+        let res = execute::update_nft_admin(deps.as_mut(), Addr::unchecked("larry"), "dao")
+            .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::UpdateAdmin {
+                contract_addr: "nft".to_string(),
+                admin: "dao".to_string(),
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/update_nft_admin"),
+                attr("nft", "nft"),
+                attr("admin", "dao"),
+            ],
+        );
+    }
+
+    // developer clears the nft contract's admin
+    {
+        let res = execute::clear_nft_admin(deps.as_mut(), Addr::unchecked("larry")).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::ClearAdmin {
+                contract_addr: "nft".to_string(),
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/clear_nft_admin"),
+                attr("nft", "nft"),
+            ],
+        );
+    }
+}
+
+#[test]
+fn overriding_by_governance() {
+    let mut deps = mock_dependencies();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+    PENDING_DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("jake")).unwrap();
+
+    // governance pauses the hub
+    {
+        let res = contract::sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetHubPaused {
+                paused: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_hub_paused"),
+                attr("paused", "true"),
+            ],
+        );
+
+        let paused = HUB_PAUSED.load(deps.as_ref().storage).unwrap();
+        assert!(paused);
+    }
+
+    // governance overrides the config
+    {
+        let res = contract::sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::UpdateConfig {
+                creation_fee: Some(coin(12345, "ustars")),
+                fee_collector: "community_pool".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/update_config"),
+                attr("creation_fee", "12345ustars"),
+                attr("fee_collector", "community_pool"),
+            ],
+        );
+
+        let creation_fee = CREATION_FEE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(creation_fee, coin(12345, "ustars"));
+
+        let fee_collector = FEE_COLLECTOR.load(deps.as_ref().storage).unwrap();
+        assert_eq!(fee_collector, Addr::unchecked("community_pool"));
+    }
+
+    // governance overrides the denom creation fee
+    {
+        let res = contract::sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetDenomCreationFee {
+                fee: Some(coin(10000000, "ustars")),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_denom_creation_fee"),
+                attr("fee", "10000000ustars"),
+            ],
+        );
+
+        let fee = DENOM_CREATION_FEE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(fee, coin(10000000, "ustars"));
+    }
+
+    // governance replaces the developer, cancelling the pending transfer
+    {
+        let res = contract::sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetDeveloper {
+                developer: "pumpkin".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/override_developer"),
+                attr("developer", "pumpkin"),
+            ],
+        );
+
+        let developer = DEVELOPER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(developer, Addr::unchecked("pumpkin"));
+
+        let pending_developer = PENDING_DEVELOPER.may_load(deps.as_ref().storage).unwrap();
+        assert_eq!(pending_developer, None);
+    }
+}
@@ -1,8 +1,11 @@
 use std::collections::BTreeSet;
 
-use cosmwasm_std::testing::{mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
 use cosmwasm_std::{
-    coins, to_binary, Addr, BankMsg, Decimal, DepsMut, Empty, Event, OwnedDeps, SubMsg, Uint128,
+    attr, coin, coins, to_binary, Addr, BankMsg, Decimal, DepsMut, Empty, Event, OwnedDeps, SubMsg,
+    Uint128, WasmMsg,
 };
 use cw_utils::PaymentError;
 use k256::ecdsa::VerifyingKey;
@@ -11,9 +14,11 @@ use sg_metadata::{Metadata, Trait};
 use sg_std::{create_fund_fairburn_pool_msg, Response, NATIVE_DENOM};
 
 use badge_hub::error::ContractError;
+use badge_hub::fee::charge_creation_fee;
+use badge_hub::helpers::{serial_traits_msg, split_payment, Payee};
 use badge_hub::{execute, query};
 use badge_hub::state::*;
-use badges::{Badge, MintRule, FeeRate};
+use badges::{Badge, MintRule, FeeDestination, FeeRate, FeeRouting, Price};
 
 mod utils;
 
@@ -36,24 +41,25 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
     deps
 }
 
-fn assert_correct_sg1_output(res: &Response, fee_amount: u128) {
+/// Assert that the fee is split by sg1, followed by any other messages expected of the action
+fn assert_correct_sg1_output(res: &Response, fee_amount: u128, other_msgs: &[WasmMsg]) {
     let dev_amount = fee_amount * 10 / 100;
     let burn_amount = fee_amount * 40 / 100;
     let dist_amount = fee_amount - dev_amount - burn_amount;
 
-    assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(BankMsg::Send {
-                to_address: "larry".to_string(),
-                amount: coins(dev_amount, NATIVE_DENOM),
-            }),
-            SubMsg::new(BankMsg::Burn {
-                amount: coins(burn_amount, NATIVE_DENOM),
-            }),
-            SubMsg::new(create_fund_fairburn_pool_msg(coins(dist_amount, NATIVE_DENOM)))
-        ]
-    );
+    let mut expected_msgs = vec![
+        SubMsg::new(BankMsg::Send {
+            to_address: "larry".to_string(),
+            amount: coins(dev_amount, NATIVE_DENOM),
+        }),
+        SubMsg::new(BankMsg::Burn {
+            amount: coins(burn_amount, NATIVE_DENOM),
+        }),
+        SubMsg::new(create_fund_fairburn_pool_msg(coins(dist_amount, NATIVE_DENOM))),
+    ];
+    expected_msgs.extend(other_msgs.iter().cloned().map(SubMsg::new));
+
+    assert_eq!(res.messages, expected_msgs);
     assert_eq!(
         res.events,
         vec![Event::new("fair-burn")
@@ -70,12 +76,29 @@ fn badge_creation_fee() {
 
     let mock_badge = Badge {
         manager: Addr::unchecked("manager"),
-        metadata: Metadata::default(),
+        metadata: Metadata {
+            name: Some("whiterun".to_string()),
+            ..Default::default()
+        },
+        token_uri: None,
         transferrable: false,
+        revocable: false,
         rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry: None,
         max_supply: None,
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 0,
+        num_burned: 0,
     };
 
     let mut create = |amount: u128, denom: &str| -> Result<Response, ContractError> {
@@ -116,7 +139,144 @@ fn badge_creation_fee() {
     // try create with correct amount and denom, should succeed
     {
         let res = create(fee_amount, NATIVE_DENOM).unwrap();
-        assert_correct_sg1_output(&res, fee_amount);
+        assert_correct_sg1_output(&res, fee_amount, &[utils::cache_metadata_msg(1, &mock_badge)]);
+    }
+}
+
+#[test]
+fn badge_flat_creation_fee() {
+    let mut deps = setup_test();
+
+    let mock_badge = Badge {
+        manager: Addr::unchecked("manager"),
+        metadata: Metadata {
+            name: Some("whiterun".to_string()),
+            ..Default::default()
+        },
+        token_uri: None,
+        transferrable: false,
+        revocable: false,
+        rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
+        expiry: None,
+        max_supply: None,
+        max_per_owner: None,
+        rate_limit: None,
+        current_supply: 0,
+        num_burned: 0,
+    };
+
+    let bytes = to_binary(&mock_badge).unwrap();
+    let fee_amount = (Uint128::from(bytes.len() as u128) * mock_fee_rate().metadata).u128();
+
+    // non-developer cannot update the config
+    {
+        let err = execute::update_config(
+            deps.as_mut(),
+            Addr::unchecked("jake"),
+            Some(coin(12345, NATIVE_DENOM)),
+            "collector",
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer sets a creation fee
+    {
+        let res = execute::update_config(
+            deps.as_mut(),
+            Addr::unchecked("larry"),
+            Some(coin(12345, NATIVE_DENOM)),
+            "collector",
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/update_config"),
+                attr("creation_fee", format!("12345{}", NATIVE_DENOM)),
+                attr("fee_collector", "collector"),
+            ],
+        );
+    }
+
+    // try create paying only the metadata fee, should fail
+    {
+        let err = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &coins(fee_amount, NATIVE_DENOM)),
+            mock_badge.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::incorrect_payment(
+                coin(12345, NATIVE_DENOM),
+                coin(fee_amount, NATIVE_DENOM),
+            ),
+        );
+    }
+
+    // try create paying both the creation fee and the metadata fee, should succeed
+    {
+        let res = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &coins(fee_amount + 12345, NATIVE_DENOM)),
+            mock_badge.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 5);
+        assert_eq!(
+            res.messages[3],
+            SubMsg::new(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: coins(12345, NATIVE_DENOM),
+            }),
+        );
+    }
+
+    // when creating in a batch, the creation fee is charged for each badge
+    {
+        let res = execute::create_badges(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &coins(fee_amount * 2 + 12345 * 2, NATIVE_DENOM)),
+            vec![mock_badge.clone(), mock_badge.clone()],
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 6);
+        assert_eq!(
+            res.messages[3],
+            SubMsg::new(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: coins(12345 * 2, NATIVE_DENOM),
+            }),
+        );
+    }
+
+    // developer removes the creation fee
+    {
+        execute::update_config(deps.as_mut(), Addr::unchecked("larry"), None, "collector")
+            .unwrap();
+
+        let res = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &coins(fee_amount, NATIVE_DENOM)),
+            mock_badge.clone(),
+        )
+        .unwrap();
+        assert_correct_sg1_output(&res, fee_amount, &[utils::cache_metadata_msg(4, &mock_badge)]);
     }
 }
 
@@ -161,19 +321,34 @@ fn badge_editing_fee() {
     let mock_badge = Badge {
         manager: Addr::unchecked("manager"),
         metadata: old_metadata.clone(),
+        token_uri: None,
         transferrable: false,
+        revocable: false,
         rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry: None,
         max_supply: None,
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 0,
+        num_burned: 0,
     };
 
-    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+    badge_map().save(deps.as_mut().storage, 1, &mock_badge).unwrap();
 
     // can't use closure here due to borrowing
     fn edit(deps: DepsMut, metadata: &Metadata, amount: u128) -> Result<Response, ContractError> {
         execute::edit_badge(
             deps,
+            mock_env(),
             mock_info("manager", &coins(amount, NATIVE_DENOM)),
             1,
             metadata.clone(),
@@ -182,17 +357,24 @@ fn badge_editing_fee() {
 
     // if data size is smaller, no fee should be charged
     {
-        let metadata = Metadata::default();
+        let metadata = Metadata {
+            name: Some("skyrim".to_string()),
+            ..Default::default()
+        };
 
         let res = edit(deps.as_mut(), &metadata, 0).unwrap();
-        assert_eq!(res.messages, vec![]);
+        let edited = Badge {
+            metadata: metadata.clone(),
+            ..mock_badge.clone()
+        };
+        assert_eq!(res.messages, vec![SubMsg::new(utils::cache_metadata_msg(1, &edited))]);
 
-        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
         assert_eq!(badge.metadata, metadata);
     }
 
     // reset badge
-    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+    badge_map().save(deps.as_mut().storage, 1, &mock_badge).unwrap();
 
     // calculate the expected fee amount
     let old_bytes = to_binary(&old_metadata).unwrap().len() as u128;
@@ -210,7 +392,98 @@ fn badge_editing_fee() {
     // send sufficient fee, should succeed
     {
         let res = edit(deps.as_mut(), &new_metadata, fee_amount).unwrap();
-        assert_correct_sg1_output(&res, fee_amount);
+        let edited = Badge {
+            metadata: new_metadata.clone(),
+            ..mock_badge.clone()
+        };
+        assert_correct_sg1_output(&res, fee_amount, &[utils::cache_metadata_msg(1, &edited)]);
+    }
+}
+
+#[test]
+fn serial_traits_fee() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .save(
+            deps.as_mut().storage,
+            1,
+            &Badge {
+                manager: Addr::unchecked("manager"),
+                metadata: Metadata::default(),
+                token_uri: None,
+                transferrable: false,
+                revocable: false,
+                rule: MintRule::ByKeys,
+                rule_frozen: false,
+                metadata_frozen: false,
+                paused: false,
+                archived: false,
+                hidden: false,
+                phases: vec![],
+                revenue_split: vec![],
+                royalty: None,
+                start_time: None,
+                expiry: None,
+                max_supply: None,
+                max_per_owner: None,
+                rate_limit: None,
+                current_supply: 1,
+                num_burned: 0,
+            },
+        )
+        .unwrap();
+
+    let traits = vec![Trait {
+        display_type: None,
+        trait_type: "placement".to_string(),
+        value: "1st".to_string(),
+    }];
+
+    // the hub doesn't keep the traits, so the fee is charged on their full size
+    let bytes = to_binary(&traits).unwrap().len() as u128;
+    let fee_amount = (Uint128::new(bytes) * mock_fee_rate().metadata).u128();
+
+    // not sending sufficient fee, should fail
+    {
+        let insufficient_amount = fee_amount * 9 / 10;
+
+        let err = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("manager", &coins(insufficient_amount, NATIVE_DENOM)),
+            1,
+            1,
+            traits.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, FeeError::InsufficientFee(fee_amount, insufficient_amount).into());
+    }
+
+    // send sufficient fee, should succeed
+    {
+        let res = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("manager", &coins(fee_amount, NATIVE_DENOM)),
+            1,
+            1,
+            traits.clone(),
+        )
+        .unwrap();
+        let msg = serial_traits_msg(&Addr::unchecked("nft"), 1, 1, traits).unwrap();
+        assert_correct_sg1_output(&res, fee_amount, &[msg]);
+    }
+
+    // removing the traits is free
+    {
+        let err = execute::set_serial_traits(
+            deps.as_mut(),
+            mock_info("manager", &coins(1, NATIVE_DENOM)),
+            1,
+            1,
+            vec![],
+        )
+        .unwrap_err();
+        assert_eq!(err, PaymentError::NonPayable {}.into());
     }
 }
 
@@ -220,15 +493,32 @@ fn key_adding_fee() {
 
     let mock_badge = Badge {
         manager: Addr::unchecked("manager"),
-        metadata: Metadata::default(),
+        metadata: Metadata {
+            name: Some("whiterun".to_string()),
+            ..Default::default()
+        },
+        token_uri: None,
         transferrable: false,
+        revocable: false,
         rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry: None,
         max_supply: None,
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 0,
+        num_burned: 0,
     };
 
-    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+    badge_map().save(deps.as_mut().storage, 1, &mock_badge).unwrap();
 
     let mock_keys = (1..20)
         .map(|_| {
@@ -271,9 +561,171 @@ fn key_adding_fee() {
     // sending sufficient fee
     {
         let res = add(deps.as_mut(), &mock_keys_set, fee_amount).unwrap();
-        assert_correct_sg1_output(&res, fee_amount);
+        assert_correct_sg1_output(&res, fee_amount, &[]);
 
         let res = query::key(deps.as_ref(), 1, &mock_keys[7]);
         assert!(res.whitelisted);
     }
 }
+
+#[test]
+fn setting_treasury_fee() {
+    let mut deps = setup_test();
+
+    // cannot take more than the whole
+    {
+        let err = execute::set_treasury_fee(deps.as_mut(), 10001).unwrap_err();
+        assert_eq!(err, ContractError::InvalidTreasuryFee);
+    }
+
+    // properly set the treasury fee
+    {
+        execute::set_treasury_fee(deps.as_mut(), 500).unwrap();
+
+        let fee = TREASURY_FEE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(fee, 500);
+    }
+}
+
+#[test]
+fn routing_fees() {
+    let mut deps = setup_test();
+
+    let burn_half = FeeRouting {
+        destination: FeeDestination::Burn,
+        bps: 5000,
+    };
+
+    // non-developer cannot route fees
+    {
+        let err = execute::set_fee_routing(
+            deps.as_mut(),
+            Addr::unchecked("jake"),
+            Some(burn_half.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // cannot route more than the whole
+    {
+        let err = execute::set_fee_routing(
+            deps.as_mut(),
+            Addr::unchecked("larry"),
+            Some(FeeRouting {
+                bps: 10001,
+                ..burn_half.clone()
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidFeeRouting);
+    }
+
+    // developer routes half of the fees to be burned
+    {
+        let res =
+            execute::set_fee_routing(deps.as_mut(), Addr::unchecked("larry"), Some(burn_half))
+                .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_fee_routing"),
+                attr("destination", "burn"),
+                attr("bps", "5000"),
+            ],
+        );
+    }
+
+    // half of the creation fee is burned, and the rest goes to the fee collector
+    {
+        let (_, msgs) = charge_creation_fee(
+            deps.as_ref().storage,
+            &mock_info("creator", &coins(10000, NATIVE_DENOM)),
+            1,
+        )
+        .unwrap();
+        assert!(msgs.is_empty());
+
+        CREATION_FEE.save(deps.as_mut().storage, &coin(10000, NATIVE_DENOM)).unwrap();
+        FEE_COLLECTOR.save(deps.as_mut().storage, &Addr::unchecked("collector")).unwrap();
+
+        let (_, msgs) = charge_creation_fee(
+            deps.as_ref().storage,
+            &mock_info("creator", &coins(10000, NATIVE_DENOM)),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            msgs,
+            vec![
+                BankMsg::Burn {
+                    amount: coins(5000, NATIVE_DENOM),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: "collector".to_string(),
+                    amount: coins(5000, NATIVE_DENOM),
+                }
+                .into(),
+            ],
+        );
+    }
+
+    // governance routes the treasury's entire cut of paid mints to the community pool
+    {
+        execute::override_fee_routing(
+            deps.as_mut(),
+            Some(FeeRouting {
+                destination: FeeDestination::CommunityPool,
+                bps: 10000,
+            }),
+        )
+        .unwrap();
+        TREASURY_FEE.save(deps.as_mut().storage, &500).unwrap();
+
+        let badge = Badge {
+            manager: Addr::unchecked("manager"),
+            metadata: Metadata::default(),
+            token_uri: None,
+            transferrable: true,
+            revocable: false,
+            rule: MintRule::ByPayment(Price::native(10000, NATIVE_DENOM)),
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
+            expiry: None,
+            max_supply: None,
+            max_per_owner: None,
+            rate_limit: None,
+            current_supply: 0,
+            num_burned: 0,
+        };
+
+        let payouts =
+            split_payment(deps.as_ref().storage, &badge, Uint128::new(10000), true).unwrap();
+        assert_eq!(
+            payouts,
+            vec![
+                (Payee::Routed(FeeDestination::CommunityPool), Uint128::new(500)),
+                (Payee::Account("manager".to_string()), Uint128::new(9500)),
+            ],
+        );
+
+        // payments in CW20 tokens can't be donated to the community pool
+        let payouts =
+            split_payment(deps.as_ref().storage, &badge, Uint128::new(10000), false).unwrap();
+        assert_eq!(
+            payouts,
+            vec![
+                (Payee::Account("larry".to_string()), Uint128::new(500)),
+                (Payee::Account("manager".to_string()), Uint128::new(9500)),
+            ],
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Gas benchmarks for minting, run against the compiled Hub contract in the metered VM.
+//!
+//! The benchmarks need the wasm binary and link the VM, so they sit behind the `gas-bench` feature
+//! and are ignored by default. To run them:
+//!
+//! ```bash
+//! cargo build --release --lib --target wasm32-unknown-unknown -p badge-hub
+//! cargo test -p badge-hub --features gas-bench --test gas -- --ignored --nocapture
+//! ```
+//!
+//! The binary is read from `BADGE_HUB_WASM` if set, or otherwise from the workspace's target dir.
+//!
+//! The gas used by each case is compared against a baseline, read from `GAS_BASELINE` if set, or
+//! otherwise from `tests/gas_baseline.json`. The run fails if any case uses more than
+//! `TOLERANCE_BPS` more gas than its baseline. Run with `UPDATE_GAS_BASELINE=1` to record the
+//! current numbers as the new baseline, e.g. after an intended change. CI records the baseline from
+//! the main branch this way before benchmarking the pushed commit, and skips the comparison if the
+//! main branch has no benchmarks to record one with.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use cosmwasm_std::{from_slice, to_vec, ContractResult, Decimal, MessageInfo};
+use cosmwasm_vm::testing::{
+    execute, instantiate, mock_env, mock_info, mock_instance_with_options, MockApi,
+    MockInstanceOptions, MockQuerier, MockStorage,
+};
+use cosmwasm_vm::Instance;
+use sg_metadata::{Metadata, Trait};
+use sg_std::Response;
+
+use badges::hub::{BadgeSpec, ExecuteMsg, InstantiateMsg};
+use badges::{FeeRate, MintRule};
+
+type HubInstance = Instance<MockApi, MockStorage, MockQuerier>;
+
+/// Large enough that none of the cases runs out of gas
+const GAS_LIMIT: u64 = u64::MAX / 2;
+
+/// How much more gas than the baseline a case may use before it's reported as a regression, in
+/// basis points
+const TOLERANCE_BPS: u64 = 500;
+
+const OWNER_COUNTS: [usize; 4] = [1, 10, 100, 1000];
+
+fn wasm_path() -> PathBuf {
+    match std::env::var("BADGE_HUB_WASM") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/wasm32-unknown-unknown/release/badge_hub.wasm"),
+    }
+}
+
+fn baseline_path() -> PathBuf {
+    match std::env::var("GAS_BASELINE") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/gas_baseline.json"),
+    }
+}
+
+fn setup_instance(wasm: &[u8]) -> HubInstance {
+    let mut instance = mock_instance_with_options(
+        wasm,
+        MockInstanceOptions {
+            available_capabilities: ["iterator", "staking", "stargate", "stargaze"]
+                .into_iter()
+                .map(String::from)
+                .collect::<HashSet<_>>(),
+            gas_limit: GAS_LIMIT,
+            ..Default::default()
+        },
+    );
+
+    let res: ContractResult<Response> = instantiate(
+        &mut instance,
+        mock_env(),
+        mock_info("larry", &[]),
+        InstantiateMsg {
+            fee_rate: FeeRate {
+                metadata: Decimal::zero(),
+                key: Decimal::zero(),
+            },
+            creation_fee: None,
+            fee_collector: None,
+            token_id_format: None,
+        },
+    );
+    res.unwrap();
+
+    run(
+        &mut instance,
+        mock_info("larry", &[]),
+        ExecuteMsg::SetNft {
+            nft: "nft".to_string(),
+        },
+    );
+
+    instance
+}
+
+/// Execute a message and return the gas it used, panicking if it fails
+fn run(instance: &mut HubInstance, info: MessageInfo, msg: ExecuteMsg) -> u64 {
+    let gas_before = instance.get_gas_left();
+    let res: ContractResult<Response> = execute(instance, mock_env(), info, msg);
+    res.unwrap();
+    gas_before - instance.get_gas_left()
+}
+
+fn small_metadata() -> Metadata {
+    Metadata {
+        name: Some("Test Badge".to_string()),
+        image: Some("ipfs://hash".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Metadata close to the size limits the Hub enforces
+fn large_metadata() -> Metadata {
+    Metadata {
+        name: Some("n".repeat(256)),
+        description: Some("d".repeat(8192)),
+        image: Some(format!("ipfs://{}", "h".repeat(500))),
+        external_url: Some(format!("https://{}", "u".repeat(500))),
+        attributes: Some(
+            (0..64)
+                .map(|idx| Trait {
+                    display_type: None,
+                    trait_type: format!("{:0>256}", idx),
+                    value: "v".repeat(256),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+/// Create a badge mintable by "larry" with the given metadata, returning the gas used. On a fresh
+/// instance, the badge's id is 1.
+fn create_badge(instance: &mut HubInstance, metadata: Metadata) -> u64 {
+    let msg = BadgeSpec::new("larry", MintRule::by_minter("larry")).metadata(metadata).into();
+    run(instance, mock_info("larry", &[]), msg)
+}
+
+fn mint_by_minter(instance: &mut HubInstance, num_owners: usize) -> u64 {
+    let owners = (0..num_owners).map(|idx| format!("user{:04}", idx));
+    run(instance, mock_info("larry", &[]), ExecuteMsg::mint_by_minter(1, owners))
+}
+
+fn measure(wasm: &[u8]) -> BTreeMap<String, u64> {
+    let mut report = BTreeMap::new();
+
+    for (label, metadata) in [("small", small_metadata()), ("large", large_metadata())] {
+        let mut instance = setup_instance(wasm);
+        let gas = create_badge(&mut instance, metadata.clone());
+        report.insert(format!("create_badge/{}_metadata", label), gas);
+
+        // each case starts from a fresh instance, so that none benefits from a previous one having
+        // already written to the same storage slots
+        for num_owners in OWNER_COUNTS {
+            let mut instance = setup_instance(wasm);
+            create_badge(&mut instance, metadata.clone());
+            let gas = mint_by_minter(&mut instance, num_owners);
+            report.insert(format!("mint_by_minter/{}_metadata/{}_owners", label, num_owners), gas);
+        }
+    }
+
+    report
+}
+
+#[test]
+#[ignore = "requires the compiled wasm binary; see the module docs"]
+fn minting_gas() {
+    let wasm = std::fs::read(wasm_path())
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", wasm_path().display(), err));
+    let report = measure(&wasm);
+
+    let update = std::env::var("UPDATE_GAS_BASELINE").is_ok();
+
+    // without a baseline there's nothing to compare against, so only proceed if one is being
+    // recorded, rather than passing silently
+    let baseline: BTreeMap<String, u64> = match std::fs::read(baseline_path()) {
+        Ok(bytes) => from_slice(&bytes).unwrap(),
+        Err(err) if update => {
+            println!("no baseline at {}: {}", baseline_path().display(), err);
+            BTreeMap::new()
+        },
+        Err(err) => panic!(
+            "failed to read {}: {}; run with `UPDATE_GAS_BASELINE=1` to record one",
+            baseline_path().display(),
+            err,
+        ),
+    };
+
+    let mut regressions = vec![];
+    println!("{:<48} {:>16} {:>16} {:>8}", "case", "gas", "baseline", "change");
+    for (case, gas) in &report {
+        match baseline.get(case) {
+            Some(base) => {
+                let change_bps = (*gas as i128 - *base as i128) * 10000 / (*base as i128).max(1);
+                println!(
+                    "{:<48} {:>16} {:>16} {:>7.2}%",
+                    case,
+                    gas,
+                    base,
+                    change_bps as f64 / 100.0,
+                );
+                if change_bps > TOLERANCE_BPS as i128 {
+                    regressions.push(format!("{}: {} -> {}", case, base, gas));
+                }
+            },
+            None => println!("{:<48} {:>16} {:>16} {:>8}", case, gas, "-", "-"),
+        }
+    }
+
+    if update {
+        std::fs::write(baseline_path(), to_vec(&report).unwrap()).unwrap();
+        println!("baseline written to {}", baseline_path().display());
+        return;
+    }
+
+    assert!(regressions.is_empty(), "gas regressions:\n{}", regressions.join("\n"));
+}
@@ -1,12 +1,17 @@
-use cosmwasm_std::testing::mock_dependencies;
-use cosmwasm_std::Addr;
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Addr, Timestamp};
+use cw_utils::Expiration;
+use ed25519_zebra::VerificationKey;
 use k256::ecdsa::VerifyingKey;
 use sg_metadata::Metadata;
 
 use badge_hub::error::ContractError;
 use badge_hub::helpers::*;
-use badge_hub::state::{KEYS, OWNERS};
-use badges::{Badge, MintRule};
+use badge_hub::query;
+use badge_hub::state::{DELEGATED_MINTERS, KEYS, MINTERS, MINT_WINDOWS, OWNERS, TOKEN_ID_FORMAT};
+use badges::hub::{SignMode, TokenSerialResponse};
+use badges::signing::Claim;
+use badges::{Badge, MintRule, Price, RateLimit, TokenIdFormat, Window};
 
 mod utils;
 
@@ -14,11 +19,25 @@ fn mock_badge(rule: Option<MintRule>, expiry: Option<u64>, max_supply: Option<u6
     Badge {
         manager: Addr::unchecked("larry"),
         metadata: Metadata::default(),
+        token_uri: None,
         transferrable: true,
+        revocable: false,
         rule: rule.unwrap_or(MintRule::ByKeys),
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry,
         max_supply,
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 99,
+        num_burned: 0,
     }
 }
 
@@ -80,6 +99,35 @@ fn asserting_availability_max_supply() {
     assert_eq!(assert_unavailable(&badge, &env.block), Ok(()));
 }
 
+/// Badge has been archived by its manager
+#[test]
+fn asserting_availability_archived() {
+    let mut badge = mock_badge(None, None, None);
+    badge.archived = true;
+
+    // an archived badge is unavailable regardless of its deadline or max supply
+    let env = utils::mock_env_at_timestamp(10000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::Archived));
+    assert_eq!(assert_unavailable(&badge, &env.block), Ok(()));
+}
+
+#[test]
+fn asserting_started() {
+    let mut badge = mock_badge(None, None, None);
+
+    // badge has no start time, can be minted any time
+    let env = utils::mock_env_at_timestamp(10000);
+    assert_eq!(assert_started(&badge, &env.block), Ok(()));
+
+    // badge has a start time which has not been reached
+    badge.start_time = Some(10001);
+    assert_eq!(assert_started(&badge, &env.block), Err(ContractError::NotStarted));
+
+    // start time is reached
+    let env = utils::mock_env_at_timestamp(10001);
+    assert_eq!(assert_started(&badge, &env.block), Ok(()));
+}
+
 #[test]
 fn asserting_eligible() {
     let mut deps = mock_dependencies();
@@ -104,18 +152,27 @@ fn asserting_eligible() {
 
 #[test]
 fn asserting_user_can_mint() {
+    let deps = mock_dependencies();
+
+    let block = mock_env().block;
     let minter = Addr::unchecked("larry");
     let badge = mock_badge(Some(MintRule::ByMinter(minter.to_string())), None, None);
 
     // minter can mint
     {
-        assert_eq!(assert_can_mint_by_minter(&badge, &minter), Ok(()));
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, 1, &badge, &minter),
+            Ok(()),
+        );
     }
 
     // non-minter cannot mint
     {
         let non_minter = Addr::unchecked("jake");
-        assert_eq!(assert_can_mint_by_minter(&badge, &non_minter), Err(ContractError::NotMinter));
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, 1, &badge, &non_minter),
+            Err(ContractError::NotMinter),
+        );
     }
 }
 
@@ -132,33 +189,367 @@ fn asserting_can_mint_by_key() {
     let badge = mock_badge(Some(rule), None, None);
 
     let owner = "larry";
-    let msg = message(id, owner);
+    let env = mock_env();
+    let msg = message(&env, id, owner, 0, None);
     let signature = utils::sign(&privkey, &msg);
 
+    let sender = Addr::unchecked("jake");
+    let check = |signature: &str| {
+        let signature = ClaimSignature::new(signature);
+        assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature)
+    };
+
     // use the correct privkey, msg, and an unused salts
     {
-        assert_eq!(assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature), Ok(()));
+        assert_eq!(check(&signature), Ok(()));
+    }
+
+    // sign a message meant for a different hub contract
+    {
+        let mut false_env = mock_env();
+        false_env.contract.address = Addr::unchecked("another_hub");
+        let signature = utils::sign(&privkey, &message(&false_env, id, owner, 0, None));
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
+    }
+
+    // sign a message meant for a different chain
+    {
+        let mut false_env = mock_env();
+        false_env.block.chain_id = "another-chain".to_string();
+        let signature = utils::sign(&privkey, &message(&false_env, id, owner, 0, None));
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
     }
 
     // use the correct privkey but sign the wrong message
     {
-        let false_msg = message(id, "jake");
+        let false_msg = message(&env, id, "jake", 0, None);
         let signature = utils::sign(&privkey, &false_msg);
-        assert_eq!(
-            assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature),
-            Err(ContractError::InvalidSignature),
-        );
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
     }
 
     // sign the correct msg but with the wrong privkey
     {
         let false_privkey = utils::random_privkey();
         let signature = utils::sign(&false_privkey, &msg);
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
+    }
+}
+
+#[test]
+fn asserting_signature_deadline() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::mock_privkey();
+    let pubkey = VerifyingKey::from(&privkey);
+    let pubkey_str = hex::encode(pubkey.to_bytes());
+
+    let id = 1;
+    let badge = mock_badge(Some(MintRule::ByKey(pubkey_str)), None, None);
+    let owner = "larry";
+
+    // mock_env is at height 12345 and time 1571797419
+    let env = mock_env();
+    let sender = Addr::unchecked("jake");
+    let check = |deadline: Option<&Expiration>, signature: &str| {
+        let signature = ClaimSignature {
+            signature: signature.to_string(),
+            deadline: deadline.cloned(),
+            sign_mode: SignMode::Raw,
+            signer: None,
+        };
+        assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature)
+    };
+
+    // deadline by height, not yet reached
+    {
+        let deadline = Expiration::AtHeight(12346);
+        let signature = utils::sign(&privkey, &message(&env, id, owner, 0, Some(&deadline)));
+        assert_eq!(check(Some(&deadline), &signature), Ok(()));
+    }
+
+    // deadline by time, not yet reached
+    {
+        let deadline = Expiration::AtTime(Timestamp::from_seconds(1571797420));
+        let signature = utils::sign(&privkey, &message(&env, id, owner, 0, Some(&deadline)));
+        assert_eq!(check(Some(&deadline), &signature), Ok(()));
+    }
+
+    // deadline already reached
+    {
+        let deadline = Expiration::AtHeight(12345);
+        let signature = utils::sign(&privkey, &message(&env, id, owner, 0, Some(&deadline)));
+        assert_eq!(check(Some(&deadline), &signature), Err(ContractError::SignatureExpired));
+    }
+
+    // the submitter attempts to extend the deadline
+    {
+        let deadline = Expiration::AtHeight(12346);
+        let signature = utils::sign(&privkey, &message(&env, id, owner, 0, Some(&deadline)));
+        let extended = Expiration::AtHeight(99999);
+        assert_eq!(check(Some(&extended), &signature), Err(ContractError::InvalidSignature));
+    }
+
+    // the submitter attempts to remove the deadline
+    {
+        let deadline = Expiration::AtHeight(12346);
+        let signature = utils::sign(&privkey, &message(&env, id, owner, 0, Some(&deadline)));
+        assert_eq!(check(None, &signature), Err(ContractError::InvalidSignature));
+    }
+}
+
+#[test]
+fn asserting_adr36_signature() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::mock_privkey();
+    let pubkey = VerifyingKey::from(&privkey);
+    let pubkey_str = hex::encode(pubkey.to_bytes());
+
+    let id = 1;
+    let badge = mock_badge(Some(MintRule::ByKey(pubkey_str)), None, None);
+    let owner = "larry";
+
+    let env = mock_env();
+    let msg = message(&env, id, owner, 0, None);
+    let sender = Addr::unchecked("larry");
+
+    // the sign doc should match the one produced by wallets' `signArbitrary`
+    {
+        let sign_doc = sign_doc("hello".to_string(), &SignMode::Adr36, &sender);
+        assert_eq!(
+            sign_doc,
+            concat!(
+                r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","#,
+                r#""msgs":[{"type":"sign/MsgSignData","#,
+                r#""value":{"data":"aGVsbG8=","signer":"larry"}}],"#,
+                r#""sequence":"0"}"#,
+            ),
+        );
+    }
+
+    let signature = ClaimSignature {
+        signature: utils::sign(&privkey, &sign_doc(msg.clone(), &SignMode::Adr36, &sender)),
+        deadline: None,
+        sign_mode: SignMode::Adr36,
+        signer: None,
+    };
+
+    // submitted by the signer
+    {
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature),
+            Ok(()),
+        );
+    }
+
+    // submitted by someone other than the signer
+    {
+        let false_sender = Addr::unchecked("jake");
+        assert_eq!(
+            assert_can_mint_by_key(
+                deps.as_ref(),
+                &env,
+                &false_sender,
+                id,
+                &badge,
+                owner,
+                &signature,
+            ),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // relayed by someone other than the signer, who is specified explicitly
+    {
+        let relayer = Addr::unchecked("jake");
+        let signature = ClaimSignature {
+            signer: Some(sender.clone()),
+            ..signature.clone()
+        };
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref(), &env, &relayer, id, &badge, owner, &signature),
+            Ok(()),
+        );
+    }
+
+    // relayed, but the specified signer is not the one who signed
+    {
+        let relayer = Addr::unchecked("jake");
+        let signature = ClaimSignature {
+            signer: Some(relayer.clone()),
+            ..signature.clone()
+        };
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref(), &env, &relayer, id, &badge, owner, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // submitted in the wrong sign mode
+    {
+        let signature = ClaimSignature {
+            sign_mode: SignMode::Raw,
+            ..signature.clone()
+        };
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+}
+
+#[test]
+fn asserting_ed25519_signature() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::random_ed25519_privkey();
+    let pubkey_bytes: [u8; 32] = VerificationKey::from(&privkey).into();
+    let pubkey_str = hex::encode(pubkey_bytes);
+
+    let rule = MintRule::ByKey(pubkey_str);
+    assert_eq!(assert_valid_mint_rule(deps.as_ref().api, &rule), Ok(()));
+
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+    let owner = "larry";
+
+    let env = mock_env();
+    let msg = message(&env, id, owner, 0, None);
+    let sender = Addr::unchecked("jake");
+    let check = |signature: &str| {
+        let signature = ClaimSignature::new(signature);
+        assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature)
+    };
+
+    // sign the correct message with the ed25519 privkey
+    {
+        let signature = utils::sign_ed25519(&privkey, &msg);
+        assert_eq!(check(&signature), Ok(()));
+    }
+
+    // sign the wrong message
+    {
+        let signature = utils::sign_ed25519(&privkey, &message(&env, id, "jake", 0, None));
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
+    }
+
+    // sign the correct message with a different ed25519 privkey
+    {
+        let signature = utils::sign_ed25519(&utils::random_ed25519_privkey(), &msg);
+        assert_eq!(check(&signature), Err(ContractError::InvalidSignature));
+    }
+}
+
+#[test]
+fn asserting_eth_personal_sign_signature() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::mock_privkey();
+    let pubkey_str = hex::encode(VerifyingKey::from(&privkey).to_bytes());
+    let address_str = utils::eth_address(&privkey);
+
+    let rule = MintRule::ByKey(address_str);
+    assert_eq!(assert_valid_mint_rule(deps.as_ref().api, &rule), Ok(()));
+
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+    let owner = "larry";
+
+    let env = mock_env();
+    let msg = message(&env, id, owner, 0, None);
+    let sender = Addr::unchecked("jake");
+    let sign_doc = sign_doc(msg.clone(), &SignMode::EthPersonalSign, &sender);
+    let check = |badge: &Badge, signature: String, sign_mode: SignMode| {
+        let signature = ClaimSignature {
+            sign_mode,
+            ..ClaimSignature::new(signature)
+        };
+        assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, badge, owner, &signature)
+    };
+
+    // the message is prefixed the way `personal_sign` does
+    {
+        assert_eq!(sign_doc, format!("\x19Ethereum Signed Message:\n{}{}", msg.len(), msg));
+    }
+
+    // sign the correct message, checked against the ethereum address
+    {
+        let signature = utils::sign_eth(&privkey, &sign_doc);
+        assert_eq!(check(&badge, signature, SignMode::EthPersonalSign), Ok(()));
+    }
+
+    // the recovery id can also be given as 27 or 28, as ethereum wallets do
+    {
+        let mut sig_bytes = hex::decode(utils::sign_eth(&privkey, &sign_doc)).unwrap();
+        sig_bytes[64] += 27;
+        let signature = hex::encode(sig_bytes);
+        assert_eq!(check(&badge, signature, SignMode::EthPersonalSign), Ok(()));
+    }
+
+    // the same signature, checked against the pubkey
+    {
+        let badge = mock_badge(Some(MintRule::ByKey(pubkey_str)), None, None);
+        let signature = utils::sign_eth(&privkey, &sign_doc);
+        assert_eq!(check(&badge, signature, SignMode::EthPersonalSign), Ok(()));
+    }
+
+    // sign the correct message with a different privkey
+    {
+        let signature = utils::sign_eth(&utils::random_privkey(), &sign_doc);
         assert_eq!(
-            assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature),
+            check(&badge, signature, SignMode::EthPersonalSign),
             Err(ContractError::InvalidSignature),
         );
     }
+
+    // an ethereum address can't be checked against a signature in another sign mode
+    {
+        let signature = utils::sign(&privkey, &msg);
+        assert_eq!(check(&badge, signature, SignMode::Raw), Err(ContractError::InvalidSignature));
+    }
+}
+
+#[test]
+fn signing_claims_with_package_helper() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::mock_privkey();
+    let pubkey_str = hex::encode(VerifyingKey::from(&privkey).to_bytes());
+
+    let id = 1;
+    let owner = "larry";
+    let env = mock_env();
+    let sender = Addr::unchecked("jake");
+    let claim = Claim {
+        hub: env.contract.address.to_string(),
+        chain_id: env.block.chain_id.clone(),
+        id,
+        owner: owner.to_string(),
+        nonce: 0,
+        deadline: Some(Expiration::AtHeight(env.block.height + 100)),
+    };
+
+    // the claim commits to the same message the hub expects
+    assert_eq!(claim.message(), message(&env, id, owner, 0, claim.deadline.as_ref()));
+
+    // signatures produced by the helper are accepted by the hub, in every sign mode
+    for (sign_mode, key) in [
+        (SignMode::Raw, pubkey_str.clone()),
+        (SignMode::Adr36, pubkey_str.clone()),
+        (SignMode::EthPersonalSign, utils::eth_address(&privkey)),
+    ] {
+        let badge = mock_badge(Some(MintRule::ByKey(key)), None, None);
+        let signature = ClaimSignature {
+            signature: claim.sign(&privkey, &sign_mode, sender.as_str()),
+            deadline: claim.deadline,
+            sign_mode,
+            signer: None,
+        };
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref(), &env, &sender, id, &badge, owner, &signature),
+            Ok(()),
+        );
+    }
 }
 
 #[test]
@@ -175,15 +566,26 @@ fn asserting_can_mint_by_keys() {
     let badge = mock_badge(Some(rule), None, None);
 
     let owner = "larry";
-    let msg = message(id, owner);
+    let env = mock_env();
+    let msg = message(&env, id, owner, 0, None);
     let signature = utils::sign(&privkey, &msg);
+    let sender = Addr::unchecked("jake");
 
     KEYS.insert(deps.as_mut().storage, (id, &pubkey_str)).unwrap();
 
     // use a whitelisted key and sign the correct message
     {
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &env,
+                &sender,
+                id,
+                &badge,
+                owner,
+                &pubkey_str,
+                &ClaimSignature::new(&signature),
+            ),
             Ok(()),
         );
     }
@@ -193,7 +595,16 @@ fn asserting_can_mint_by_keys() {
         let false_msg = "ngmi";
         let signature = utils::sign(&privkey, false_msg);
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &env,
+                &sender,
+                id,
+                &badge,
+                owner,
+                &pubkey_str,
+                &ClaimSignature::new(&signature),
+            ),
             Err(ContractError::InvalidSignature),
         );
     }
@@ -205,8 +616,241 @@ fn asserting_can_mint_by_keys() {
         let false_pubkey_str = hex::encode(false_pubkey.to_bytes());
         let signature = utils::sign(&false_privkey, &msg);
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &false_pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &env,
+                &sender,
+                id,
+                &badge,
+                owner,
+                &false_pubkey_str,
+                &ClaimSignature::new(&signature),
+            ),
             Err(ContractError::key_does_not_exist(id)),
         );
     }
 }
+
+#[test]
+fn asserting_user_can_mint_by_minters() {
+    let mut deps = mock_dependencies();
+
+    let block = mock_env().block;
+    let id = 1;
+    let badge = mock_badge(Some(MintRule::ByMinters), None, None);
+
+    MINTERS.insert(deps.as_mut().storage, (id, "larry")).unwrap();
+    MINTERS.insert(deps.as_mut().storage, (id, "jake")).unwrap();
+
+    // minters can mint
+    {
+        let minter = Addr::unchecked("larry");
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, id, &badge, &minter),
+            Ok(()),
+        );
+
+        let minter = Addr::unchecked("jake");
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, id, &badge, &minter),
+            Ok(()),
+        );
+    }
+
+    // non-minter cannot mint
+    {
+        let non_minter = Addr::unchecked("pumpkin");
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, id, &badge, &non_minter),
+            Err(ContractError::NotMinter),
+        );
+    }
+}
+
+#[test]
+fn asserting_delegated_minter_can_mint() {
+    let mut deps = mock_dependencies();
+
+    let id = 1;
+    let badge = mock_badge(Some(MintRule::ByMinter("larry".to_string())), None, None);
+
+    let staff = Addr::unchecked("jake");
+    let expiration = Expiration::AtTime(Timestamp::from_seconds(12345));
+    DELEGATED_MINTERS.save(deps.as_mut().storage, (id, staff.as_str()), &expiration).unwrap();
+
+    // delegated minter can mint before the delegation expires
+    {
+        let block = utils::mock_env_at_timestamp(10000).block;
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, id, &badge, &staff),
+            Ok(()),
+        );
+    }
+
+    // delegated minter cannot mint after the delegation expires
+    {
+        let block = utils::mock_env_at_timestamp(12345).block;
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, id, &badge, &staff),
+            Err(ContractError::NotMinter),
+        );
+    }
+
+    // a delegation does not carry over to other badges
+    {
+        let block = utils::mock_env_at_timestamp(10000).block;
+        assert_eq!(
+            assert_can_mint_by_minter(deps.as_ref().storage, &block, 2, &badge, &staff),
+            Err(ContractError::NotMinter),
+        );
+    }
+}
+
+#[test]
+fn incrementing_window_count() {
+    let mut deps = mock_dependencies();
+
+    let id = 1;
+    let mut badge = mock_badge(None, None, None);
+    badge.rate_limit = Some(RateLimit {
+        max_mints: 3,
+        window: Window::Seconds(100),
+    });
+
+    // mint 2 within the window 100
+    let env = utils::mock_env_at_timestamp(10000);
+    assert_eq!(increment_window_count(deps.as_mut().storage, &env.block, id, &badge, 2), Ok(()));
+
+    // minting 2 more within the same window should fail
+    let env = utils::mock_env_at_timestamp(10099);
+    assert_eq!(
+        increment_window_count(deps.as_mut().storage, &env.block, id, &badge, 2),
+        Err(ContractError::RateLimited),
+    );
+
+    // but minting 1 more should succeed
+    assert_eq!(increment_window_count(deps.as_mut().storage, &env.block, id, &badge, 1), Ok(()));
+    assert_eq!(MINT_WINDOWS.load(deps.as_ref().storage, id).unwrap(), (100, 3));
+
+    // once the next window starts, the counter should reset
+    let env = utils::mock_env_at_timestamp(10100);
+    assert_eq!(increment_window_count(deps.as_mut().storage, &env.block, id, &badge, 3), Ok(()));
+    assert_eq!(MINT_WINDOWS.load(deps.as_ref().storage, id).unwrap(), (101, 3));
+}
+
+#[test]
+fn assigning_counter_token_ids() {
+    let mut deps = mock_dependencies();
+    let block = mock_env().block;
+
+    TOKEN_ID_FORMAT.save(deps.as_mut().storage, &TokenIdFormat::Counter).unwrap();
+
+    let mut badge_1 = mock_badge(Some(MintRule::Open), None, None);
+    let mut badge_2 = mock_badge(Some(MintRule::Open), None, None);
+    let owners = ["jake".to_string(), "pumpkin".to_string()];
+
+    // token ids count the instances minted across all badges, in the order they are minted
+    record_mint(deps.as_mut().storage, &block, 1, &mut badge_1, &owners).unwrap();
+    record_mint(deps.as_mut().storage, &block, 2, &mut badge_2, &owners[..1]).unwrap();
+    record_mint(deps.as_mut().storage, &block, 1, &mut badge_1, &owners[1..]).unwrap();
+
+    let store = deps.as_ref().storage;
+    assert_eq!(token_id(store, 1, 100).unwrap(), "1");
+    assert_eq!(token_id(store, 1, 101).unwrap(), "2");
+    assert_eq!(token_id(store, 2, 100).unwrap(), "3");
+    assert_eq!(token_id(store, 1, 102).unwrap(), "4");
+
+    // serials that haven't been minted have no token id
+    token_id(store, 2, 101).unwrap_err();
+
+    // the badge id and serial of a token can be looked up
+    assert_eq!(parse_token_id(store, "3").unwrap(), (2, 100));
+    assert_eq!(
+        query::token_serial(deps.as_ref(), "4".to_string()).unwrap(),
+        TokenSerialResponse {
+            token_id: "4".to_string(),
+            id: 1,
+            serial: 102,
+        },
+    );
+
+    // the number must be written without leading zeros, and must have been assigned
+    parse_token_id(store, "04").unwrap_err();
+    parse_token_id(store, "5").unwrap_err();
+}
+
+#[test]
+fn drawing_winners() {
+    let registrants: Vec<String> =
+        ["doge", "jake", "larry", "pumpkin"].iter().map(|s| s.to_string()).collect();
+    let seed = hash("seed");
+
+    // winners are distinct registrants
+    let winners = draw_winners(&seed, registrants.clone(), 3);
+    assert_eq!(winners.len(), 3);
+    for winner in &winners {
+        assert!(registrants.contains(winner));
+        assert_eq!(winners.iter().filter(|w| *w == winner).count(), 1);
+    }
+
+    // the same seed always gives the same winners
+    assert_eq!(draw_winners(&seed, registrants.clone(), 3), winners);
+
+    // if there are fewer registrants than winners, everyone wins
+    let mut winners = draw_winners(&seed, registrants.clone(), 10);
+    winners.sort();
+    assert_eq!(winners, registrants);
+}
+
+#[test]
+fn asserting_valid_raffle_rule() {
+    let deps = mock_dependencies();
+
+    let rule = MintRule::by_raffle(10000, 3);
+    assert_eq!(assert_valid_mint_rule(deps.as_ref().api, &rule), Ok(()));
+
+    let rule = MintRule::by_raffle(10000, 0);
+    assert_eq!(
+        assert_valid_mint_rule(deps.as_ref().api, &rule),
+        Err(ContractError::ZeroRaffleWinners),
+    );
+
+    let rule = MintRule::by_raffle(10000, MAX_RAFFLE_WINNERS + 1);
+    assert_eq!(
+        assert_valid_mint_rule(deps.as_ref().api, &rule),
+        Err(ContractError::TooManyRaffleWinners {
+            max: MAX_RAFFLE_WINNERS,
+        }),
+    );
+
+    let rule = MintRule::any_of([MintRule::Open, MintRule::by_raffle(10000, 3)]);
+    assert_eq!(
+        assert_valid_mint_rule(deps.as_ref().api, &rule),
+        Err(ContractError::NestedRaffle),
+    );
+}
+
+#[test]
+fn asserting_valid_composite_payments() {
+    let deps = mock_dependencies();
+
+    let rule = MintRule::all_of([
+        MintRule::by_payment(Price::native(100, "ustars")),
+        MintRule::ByAllowlist,
+    ]);
+    assert_eq!(assert_valid_mint_rule(deps.as_ref().api, &rule), Ok(()));
+
+    // each payment sub-rule would be checked against the same funds, so a single payment could
+    // satisfy all of them, while each would pay out
+    let rule = MintRule::all_of([
+        MintRule::by_payment(Price::native(100, "ustars")),
+        MintRule::any_of([
+            MintRule::ByAllowlist,
+            MintRule::by_payment(Price::native(200, "ustars")),
+        ]),
+    ]);
+    assert_eq!(
+        assert_valid_mint_rule(deps.as_ref().api, &rule),
+        Err(ContractError::MultiplePayments),
+    );
+}
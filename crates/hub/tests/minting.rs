@@ -1,14 +1,34 @@
-use cosmwasm_std::testing::{mock_dependencies, MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{attr, to_binary, Addr, Empty, OwnedDeps, StdResult, Storage, SubMsg, WasmMsg};
+use std::collections::BTreeSet;
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, Addr, BankMsg, Decimal, Empty, FullDelegation,
+    OwnedDeps, StdResult, Storage, SubMsg, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::Cw20ExecuteMsg;
+use cw4::{Cw4QueryMsg, MemberResponse};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse};
+use cw_utils::PaymentError;
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use cw721_base::MintMsg;
 use sg_metadata::Metadata;
 
 use badge_hub::error::ContractError;
-use badge_hub::helpers::{message, token_id};
+use badge_hub::helpers::{
+    draw_winners, hash, message, raffle_seed, ClaimSignature, MAX_RAFFLE_REGISTRANTS,
+};
 use badge_hub::state::*;
-use badge_hub::{execute, query};
-use badges::{Badge, MintRule};
+use badge_hub::{execute, query, token_factory};
+use badges::hub::{
+    CompositeProof, MintReceipt, OraclePriceResponse, OracleQueryMsg, ResolveResponse,
+    ResolverQueryMsg,
+};
+use badges::{
+    Badge, CommemorativeToken, MintRule, Phase, Price, RateLimit, RevenueShare, TokenIdFormat,
+    Window,
+};
 
 mod utils;
 
@@ -21,7 +41,7 @@ fn mock_keys() -> (SigningKey, VerifyingKey, String) {
 }
 
 fn set_badge_supply(store: &mut dyn Storage, id: u64, current_supply: u64) {
-    BADGES
+    badge_map()
         .update(store, id, |badge| {
             let mut badge = badge.unwrap();
             badge.current_supply = current_supply;
@@ -30,6 +50,10 @@ fn set_badge_supply(store: &mut dyn Storage, id: u64, current_supply: u64) {
         .unwrap();
 }
 
+fn btreeset_u64(slice: &[u64]) -> BTreeSet<u64> {
+    slice.iter().cloned().collect()
+}
+
 fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
     let mut deps = mock_dependencies();
 
@@ -38,16 +62,30 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
     let default_badge = Badge {
         manager: Addr::unchecked("larry"),
         metadata: Metadata::default(),
+        token_uri: None,
         transferrable: true,
+        revocable: false,
         rule: MintRule::ByKeys,
+        rule_frozen: false,
+        metadata_frozen: false,
+        paused: false,
+        archived: false,
+        hidden: false,
+        phases: vec![],
+        revenue_split: vec![],
+        royalty: None,
+        start_time: None,
         expiry: Some(12345),
         max_supply: Some(100),
+        max_per_owner: None,
+        rate_limit: None,
         current_supply: 98,
+        num_burned: 0,
     };
 
     let (_, _, pubkey_str) = mock_keys();
 
-    BADGES
+    badge_map()
         .save(
             deps.as_mut().storage,
             1,
@@ -58,7 +96,7 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
         )
         .unwrap();
 
-    BADGES
+    badge_map()
         .save(
             deps.as_mut().storage,
             2,
@@ -69,12 +107,23 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
         )
         .unwrap();
 
-    BADGES
+    badge_map()
         .save(
             deps.as_mut().storage,
             3,
             &Badge {
                 rule: MintRule::ByKeys,
+                ..default_badge.clone()
+            },
+        )
+        .unwrap();
+
+    badge_map()
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                rule: MintRule::ByPayment(Price::cw20("token", 12345)),
                 ..default_badge
             },
         )
@@ -152,7 +201,7 @@ fn minting_by_minter() {
                     SubMsg::new(WasmMsg::Execute {
                         contract_addr: "nft".to_string(),
                         msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                            token_id: token_id(1, serial),
+                            token_id: TokenIdFormat::Pipe.build(1, serial).unwrap(),
                             owner: owner.to_string(),
                             token_uri: None,
                             extension: None,
@@ -179,6 +228,7 @@ fn minting_by_minter() {
             vec![
                 attr("action", "badges/hub/mint_by_minter"),
                 attr("id", "1"),
+                attr("serials", "99-100"),
                 attr("amount", "2"),
             ],
         );
@@ -186,282 +236,654 @@ fn minting_by_minter() {
 }
 
 #[test]
-fn minting_by_key() {
+fn minting_by_minter_with_names() {
     let mut deps = setup_test();
 
-    let privkey = utils::mock_privkey();
-    let msg = message(2, "larry");
-    let signature = utils::sign(&privkey, &msg);
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg,
+        } if contract_addr == "resolver" => {
+            let address = match from_binary(msg).unwrap() {
+                ResolverQueryMsg::Resolve {
+                    name,
+                } => match name.as_str() {
+                    "jake.stars" => Some("jake".to_string()),
+                    "pumpkin.stars" => Some("pumpkin".to_string()),
+                    _ => None,
+                },
+            };
+            Ok(to_binary(&ResolveResponse {
+                address,
+            })
+            .into())
+            .into()
+        },
+        _ => panic!("unexpected query: {:?}", query),
+    });
 
-    // wrong mint rule
+    // cannot mint to names before a resolver is set
     {
-        let err = execute::mint_by_key(
+        let err = execute::mint_by_minter(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            3,
-            "larry".to_string(),
-            signature.clone(),
+            1,
+            utils::btreeset(&["jake.stars"]),
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::wrong_mint_rule("by_key", &MintRule::ByKeys));
+        assert_eq!(
+            err,
+            ContractError::NoResolver {
+                name: "jake.stars".to_string(),
+            },
+        );
     }
 
-    // attempt to mint with correct privkey but false message
+    // non-developer cannot set the resolver
     {
-        let false_msg = message(2, "jake");
-        let signature = utils::sign(&privkey, &false_msg);
+        DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("dev")).unwrap();
 
-        let err = execute::mint_by_key(
+        let err = execute::set_resolver(
             deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
-            2,
-            "larry".to_string(),
-            signature,
+            Addr::unchecked("jake"),
+            Some("resolver".to_string()),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::InvalidSignature);
+        assert_eq!(err, ContractError::NotDeveloper);
     }
 
-    // attempt to mint with false key wtith correct message
+    // developer sets the resolver
     {
-        let false_privkey = utils::random_privkey();
-        let signature = utils::sign(&false_privkey, &msg);
+        let res = execute::set_resolver(
+            deps.as_mut(),
+            Addr::unchecked("dev"),
+            Some("resolver".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "badges/hub/set_resolver"), attr("resolver", "resolver")],
+        );
 
-        let err = execute::mint_by_key(
+        let resolver_addr = RESOLVER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(resolver_addr, Addr::unchecked("resolver"));
+    }
+
+    // cannot mint to a name that isn't registered
+    {
+        let err = execute::mint_by_minter(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            2,
-            "larry".to_string(),
-            signature,
+            1,
+            utils::btreeset(&["jake.stars", "doge.stars"]),
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::InvalidSignature);
+        assert_eq!(
+            err,
+            ContractError::NameNotResolved {
+                name: "doge.stars".to_string(),
+            },
+        );
     }
 
-    // properly mint
+    // names are minted to the addresses they resolve to, and an address given both directly and
+    // by name is only minted to once
     {
-        let res = execute::mint_by_key(
+        let res = execute::mint_by_minter(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            2,
-            "larry".to_string(),
-            signature.clone(),
+            1,
+            utils::btreeset(&["jake.stars", "pumpkin", "pumpkin.stars"]),
+            Addr::unchecked("larry"),
         )
         .unwrap();
         assert_eq!(
             res.messages,
-            vec![SubMsg::new(WasmMsg::Execute {
-                contract_addr: "nft".to_string(),
-                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                    token_id: "2|99".to_string(),
-                    owner: "larry".to_string(),
-                    token_uri: None,
-                    extension: None,
-                }))
-                .unwrap(),
-                funds: vec![],
-            })],
+            ["jake", "pumpkin"]
+                .iter()
+                .enumerate()
+                .map(|(idx, owner)| {
+                    SubMsg::new(WasmMsg::Execute {
+                        contract_addr: "nft".to_string(),
+                        msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                            token_id: TokenIdFormat::Pipe.build(1, 99 + idx as u64).unwrap(),
+                            owner: owner.to_string(),
+                            token_uri: None,
+                            extension: None,
+                        }))
+                        .unwrap(),
+                        funds: vec![],
+                    })
+                })
+                .collect::<Vec<_>>(),
         );
         assert_eq!(
             res.attributes,
             vec![
-                attr("action", "badges/hub/mint_by_key"),
-                attr("id", "2"),
-                attr("serial", "99"),
-                attr("recipient", "larry"),
+                attr("action", "badges/hub/mint_by_minter"),
+                attr("id", "1"),
+                attr("serials", "99-100"),
+                attr("amount", "2"),
             ],
         );
+    }
+}
 
-        // current supply should have been updated
-        let badge = BADGES.load(deps.as_ref().storage, 2).unwrap();
-        assert_eq!(badge.current_supply, 99);
+#[test]
+fn minting_commemorative_tokens() {
+    let mut deps = setup_test();
 
-        // larry should be marked as already received
-        let res = query::owner(deps.as_ref(), 2, "larry");
-        assert!(res.claimed);
-    }
+    COMMEMORATIVE_TOKENS
+        .save(
+            deps.as_mut().storage,
+            1,
+            &CommemorativeToken {
+                subdenom: "points".to_string(),
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap();
 
-    // attempt to mint to the same user
+    let res = execute::mint_by_minter(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        1,
+        utils::btreeset(&["jake"]),
+        Addr::unchecked("larry"),
+    )
+    .unwrap();
+
+    // the tokens are minted to each recipient after the badge itself
+    let hub_addr = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let denom = token_factory::denom(&hub_addr, "points");
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[1],
+        SubMsg::new(token_factory::mint_msg(&hub_addr, &denom, Uint128::new(100), "jake")),
+    );
+}
+
+#[test]
+fn minting_in_batch() {
+    let mut deps = setup_test();
+
+    // make larry the minter of badge 3 as well
+    badge_map()
+        .update(deps.as_mut().storage, 3, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_minter("larry");
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // cannot mint an empty batch
     {
-        let err = execute::mint_by_key(
+        let err = execute::mint_batch(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            2,
-            "larry".to_string(),
-            signature.clone(),
+            vec![],
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::already_claimed(2, "larry"));
+        assert_eq!(err, ContractError::EmptyBatch);
     }
 
-    // attempt to mint after expiry
+    // the whole batch fails if the sender is not the minter of any one of the badges
     {
-        let err = execute::mint_by_key(
+        let (_, _, pubkey_str) = mock_keys();
+
+        let err = execute::mint_batch(
             deps.as_mut(),
-            utils::mock_env_at_timestamp(99999),
-            2,
-            "larry".to_string(),
-            signature.clone(),
+            utils::mock_env_at_timestamp(10000),
+            vec![(2, utils::btreeset(&["jake"])), (1, utils::btreeset(&["jake"]))],
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::Expired);
+        assert_eq!(err, ContractError::wrong_mint_rule("by_minter", &MintRule::ByKey(pubkey_str)));
     }
 
-    // attempt to mint after max supply is reached
+    // minter mints multiple badges at once
     {
-        set_badge_supply(deps.as_mut().storage, 2, 100);
-
-        let err = execute::mint_by_key(
+        let res = execute::mint_batch(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            2,
-            "larry".to_string(),
-            signature,
+            vec![(1, utils::btreeset(&["jake", "pumpkin"])), (3, utils::btreeset(&["jake"]))],
+            Addr::unchecked("larry"),
         )
-        .unwrap_err();
-        assert_eq!(err, ContractError::SoldOut);
+        .unwrap();
+
+        let expected_msgs = [(1, 99, "jake"), (1, 100, "pumpkin"), (3, 99, "jake")]
+            .into_iter()
+            .map(|(id, serial, owner)| {
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "nft".to_string(),
+                    msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                        token_id: TokenIdFormat::Pipe.build(id, serial).unwrap(),
+                        owner: owner.to_string(),
+                        token_uri: None,
+                        extension: None,
+                    }))
+                    .unwrap(),
+                    funds: vec![],
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res.messages, expected_msgs);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_batch"),
+                attr("id", "1"),
+                attr("serials", "99-100"),
+                attr("amount", "2"),
+                attr("id", "3"),
+                attr("serials", "99-99"),
+                attr("amount", "1"),
+            ],
+        );
+        assert_eq!(
+            from_binary::<Vec<MintReceipt>>(&res.data.unwrap()).unwrap(),
+            vec![
+                MintReceipt {
+                    id: 1,
+                    start_serial: 99,
+                    amount: 2,
+                    token_ids: vec!["1|99".to_string(), "1|100".to_string()],
+                    recipients: vec!["jake".to_string(), "pumpkin".to_string()],
+                },
+                MintReceipt {
+                    id: 3,
+                    start_serial: 99,
+                    amount: 1,
+                    token_ids: vec!["3|99".to_string()],
+                    recipients: vec!["jake".to_string()],
+                },
+            ],
+        );
+
+        let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 100);
+
+        let badge = badge_map().load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(badge.current_supply, 99);
     }
 }
 
 #[test]
-fn minting_by_keys() {
+fn minting_by_queue() {
     let mut deps = setup_test();
+    set_badge_supply(deps.as_mut().storage, 1, 90);
 
-    let (privkey, _, pubkey_str) = mock_keys();
-    let msg = message(3, "larry");
-    let signature = utils::sign(&privkey, &msg);
+    let expected_msgs = |first_serial: u64, owners: &[&str]| {
+        owners
+            .iter()
+            .enumerate()
+            .map(|(idx, owner)| {
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "nft".to_string(),
+                    msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                        token_id: TokenIdFormat::Pipe.build(1, first_serial + idx as u64).unwrap(),
+                        owner: owner.to_string(),
+                        token_uri: None,
+                        extension: None,
+                    }))
+                    .unwrap(),
+                    funds: vec![],
+                })
+            })
+            .collect::<Vec<_>>()
+    };
 
-    // wrong mint rule
+    // non-minter cannot queue
     {
-        let err = execute::mint_by_key(
+        let err = execute::queue_mint(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
             1,
-            "larry".to_string(),
-            signature.clone(),
-        )
-        .unwrap_err();
-        assert_eq!(
-            err,
-            ContractError::wrong_mint_rule("by_key", &MintRule::by_minter("larry")),
-        );
-    }
-
-    // attempt to mint with a whitelisted privkey but with wrong message
-    {
-        let false_msg = message(3, "jake");
-        let signature = utils::sign(&privkey, &false_msg);
-
-        let err = execute::mint_by_keys(
-            deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
-            3,
-            "larry".to_string(),
-            pubkey_str.clone(),
-            signature,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("jake"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::InvalidSignature);
+        assert_eq!(err, ContractError::NotMinter);
     }
 
-    // attempt to mint with the correct message but a non-whitelisted privkey
+    // cannot queue past max supply
     {
-        let false_privkey = utils::random_privkey();
-        let false_pubkey = VerifyingKey::from(&false_privkey);
-        let false_pubkey_str = hex::encode(false_pubkey.to_bytes());
-        let signature = utils::sign(&false_privkey, &msg);
-
-        let err = execute::mint_by_keys(
+        let err = execute::queue_mint(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            3,
-            "larry".to_string(),
-            false_pubkey_str,
-            signature,
+            1,
+            (0..11).map(|idx| format!("user{}", idx)).collect(),
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::key_does_not_exist(3));
+        assert_eq!(err, ContractError::SoldOut);
     }
 
-    // properly mint
+    // minter queues a mint; the supply is reserved but nothing is minted yet
     {
-        let res = execute::mint_by_keys(
+        let res = execute::queue_mint(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            3,
-            "larry".to_string(),
-            pubkey_str.clone(),
-            signature.clone(),
+            1,
+            utils::btreeset(&["alice", "bob", "charlie", "dave", "eve"]),
+            Addr::unchecked("larry"),
         )
         .unwrap();
-        assert_eq!(
-            res.messages,
-            vec![SubMsg::new(WasmMsg::Execute {
-                contract_addr: "nft".to_string(),
-                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                    token_id: "3|99".to_string(),
-                    owner: "larry".to_string(),
-                    token_uri: None,
-                    extension: None,
-                }))
-                .unwrap(),
-                funds: vec![],
-            })],
-        );
+        assert!(res.messages.is_empty());
         assert_eq!(
             res.attributes,
             vec![
-                attr("action", "badges/hub/mint_by_keys"),
-                attr("id", "3"),
-                attr("serial", "99"),
-                attr("recipient", "larry"),
+                attr("action", "badges/hub/queue_mint"),
+                attr("id", "1"),
+                attr("serials", "91-95"),
+                attr("amount", "5"),
             ],
         );
 
-        // current supply should have been updated
-        let badge = BADGES.load(deps.as_ref().storage, 3).unwrap();
-        assert_eq!(badge.current_supply, 99);
-
-        // larry should be marked as already received
-        let res = query::owner(deps.as_ref(), 3, "larry");
-        assert!(res.claimed);
+        let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 95);
 
-        // the pubkey should be removed from the whitelist
-        let res = query::key(deps.as_ref(), 3, &pubkey_str);
-        assert!(!res.whitelisted);
+        let res = query::pending_mint(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.next_serial, Some(91));
+        assert_eq!(res.remaining, 5);
     }
 
-    // attempt to mint to using the same privkey again
+    // cannot queue another mint while one is in progress
     {
-        let msg = message(3, "jake");
-        let signature = utils::sign(&privkey, &msg);
-
-        let err = execute::mint_by_keys(
+        let err = execute::queue_mint(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            3,
-            "jake".to_string(),
-            pubkey_str.clone(),
-            signature,
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::key_does_not_exist(3));
-    }
+        assert_eq!(
+            err,
+            ContractError::MintPending {
+                id: 1,
+            },
+        );
+    }
 
-    // attempt to mint to the same user again
+    // other mints in the meantime don't take the queued serials
     {
-        KEYS.insert(deps.as_mut().storage, (3, "larry")).unwrap();
+        let res = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+        assert_eq!(res.messages, expected_msgs(96, &["jake"]));
+    }
 
-        let err = execute::mint_by_keys(
+    // anyone can resume the mint, one chunk at a time
+    {
+        let res =
+            execute::resume_mint(deps.as_mut(), utils::mock_env_at_timestamp(10000), 1, Some(2))
+                .unwrap();
+        assert_eq!(res.messages, expected_msgs(91, &["alice", "bob"]));
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/resume_mint"),
+                attr("id", "1"),
+                attr("serials", "91-92"),
+                attr("amount", "2"),
+                attr("remaining", "3"),
+            ],
+        );
+
+        let receipt: MintReceipt = from_binary(res.data.as_ref().unwrap()).unwrap();
+        assert_eq!(receipt.start_serial, 91);
+        assert_eq!(receipt.recipients, vec!["alice".to_string(), "bob".to_string()]);
+
+        let res = query::pending_mint(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.next_serial, Some(93));
+        assert_eq!(res.remaining, 3);
+    }
+
+    // the last chunk clears the queue
+    {
+        let res = execute::resume_mint(deps.as_mut(), utils::mock_env_at_timestamp(10000), 1, None)
+            .unwrap();
+        assert_eq!(res.messages, expected_msgs(93, &["charlie", "dave", "eve"]));
+        assert_eq!(res.attributes[2], attr("serials", "93-95"));
+        assert_eq!(res.attributes[4], attr("remaining", "0"));
+
+        let res = query::pending_mint(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.next_serial, None);
+        assert_eq!(res.remaining, 0);
+    }
+
+    // nothing left to resume
+    {
+        let err = execute::resume_mint(deps.as_mut(), utils::mock_env_at_timestamp(10000), 1, None)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NoPendingMint {
+                id: 1,
+            },
+        );
+    }
+}
+
+#[test]
+fn minting_by_contract() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_contract("quest_engine");
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::hook_mint(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
+            mock_info("quest_engine", &[]),
             3,
-        "larry".to_string(),
-            pubkey_str,
-            signature.clone(),
+            "jake".to_string(),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::already_claimed(3, "larry"));
+        assert_eq!(err, ContractError::wrong_mint_rule("by_contract", &MintRule::ByKeys));
+    }
+
+    // only the designated contract can invoke the hook
+    {
+        let err = execute::hook_mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotHookContract);
+    }
+
+    // the designated contract properly mints
+    {
+        let res = execute::hook_mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("quest_engine", &[]),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/hook_mint"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+                attr("hook", "quest_engine"),
+            ],
+        );
+        assert_eq!(
+            from_binary::<MintReceipt>(&res.data.unwrap()).unwrap(),
+            MintReceipt {
+                id: 4,
+                start_serial: 99,
+                amount: 1,
+                token_ids: vec!["4|99".to_string()],
+                recipients: vec!["jake".to_string()],
+            },
+        );
+    }
+}
+
+#[test]
+fn minting_by_key() {
+    let mut deps = setup_test();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let privkey = utils::mock_privkey();
+    let msg = message(&env, 2, "larry", 0, None);
+    let signature = utils::sign(&privkey, &msg);
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_key", &MintRule::ByKeys));
+    }
+
+    // attempt to mint with correct privkey but false message
+    {
+        let false_msg = message(&env, 2, "jake", 0, None);
+        let signature = utils::sign(&privkey, &false_msg);
+
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // attempt to mint with false key wtith correct message
+    {
+        let false_privkey = utils::random_privkey();
+        let signature = utils::sign(&false_privkey, &msg);
+
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "2|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_key"),
+                attr("id", "2"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = badge_map().load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 2, "larry");
+        assert!(res.claimed);
+
+        // claim status can also be queried for several users at once
+        let users = vec!["jake".to_string(), "larry".to_string()];
+        let res = query::owner_batch(deps.as_ref(), 2, users).unwrap();
+        assert_eq!(
+            res.owners.iter().map(|owner| (owner.user.as_str(), owner.claimed)).collect::<Vec<_>>(),
+            vec![("jake", false), ("larry", true)],
+        );
+
+        // but not for more users than the maximum page size
+        let users = vec!["larry".to_string(); query::MAX_LIMIT as usize + 1];
+        assert!(query::owner_batch(deps.as_ref(), 2, users).is_err());
+    }
+
+    // attempt to mint to the same user
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(2, "larry"));
     }
 
     // attempt to mint after expiry
@@ -469,9 +891,10 @@ fn minting_by_keys() {
         let err = execute::mint_by_key(
             deps.as_mut(),
             utils::mock_env_at_timestamp(99999),
-            3,
+            mock_info("larry", &[]),
+            2,
             "larry".to_string(),
-            signature.clone(),
+            ClaimSignature::new(signature.clone()),
         )
         .unwrap_err();
         assert_eq!(err, ContractError::Expired);
@@ -479,16 +902,2127 @@ fn minting_by_keys() {
 
     // attempt to mint after max supply is reached
     {
-        set_badge_supply(deps.as_mut().storage, 3, 100);
+        set_badge_supply(deps.as_mut().storage, 2, 100);
 
         let err = execute::mint_by_key(
             deps.as_mut(),
             utils::mock_env_at_timestamp(10000),
-            3,
+            mock_info("larry", &[]),
+            2,
             "larry".to_string(),
-            signature,
+            ClaimSignature::new(signature),
         )
         .unwrap_err();
         assert_eq!(err, ContractError::SoldOut);
     }
 }
+
+#[test]
+fn minting_by_keys() {
+    let mut deps = setup_test();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let (privkey, _, pubkey_str) = mock_keys();
+    let msg = message(&env, 3, "larry", 0, None);
+    let signature = utils::sign(&privkey, &msg);
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            "larry".to_string(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::wrong_mint_rule("by_key", &MintRule::by_minter("larry")),
+        );
+    }
+
+    // attempt to mint with a whitelisted privkey but with wrong message
+    {
+        let false_msg = message(&env, 3, "jake", 0, None);
+        let signature = utils::sign(&privkey, &false_msg);
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            pubkey_str.clone(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // attempt to mint with the correct message but a non-whitelisted privkey
+    {
+        let false_privkey = utils::random_privkey();
+        let false_pubkey = VerifyingKey::from(&false_privkey);
+        let false_pubkey_str = hex::encode(false_pubkey.to_bytes());
+        let signature = utils::sign(&false_privkey, &msg);
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            false_pubkey_str,
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::key_does_not_exist(3));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            pubkey_str.clone(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "3|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_keys"),
+                attr("id", "3"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = badge_map().load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 3, "larry");
+        assert!(res.claimed);
+
+        // the pubkey should be removed from the whitelist
+        let res = query::key(deps.as_ref(), 3, &pubkey_str);
+        assert!(!res.whitelisted);
+    }
+
+    // attempt to mint to using the same privkey again
+    {
+        let msg = message(&env, 3, "jake", 0, None);
+        let signature = utils::sign(&privkey, &msg);
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "jake".to_string(),
+            pubkey_str.clone(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::key_does_not_exist(3));
+    }
+
+    // attempt to mint to the same user again
+    {
+        KEYS.insert(deps.as_mut().storage, (3, "larry")).unwrap();
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            pubkey_str,
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(3, "larry"));
+    }
+
+    // attempt to mint after expiry
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            ClaimSignature::new(signature.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // attempt to mint after max supply is reached
+    {
+        set_badge_supply(deps.as_mut().storage, 3, 100);
+
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "larry".to_string(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_by_voucher() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByVoucher;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let voucher_hash = hex::encode(hash("apple"));
+    VOUCHERS.insert(deps.as_mut().storage, (4, &voucher_hash)).unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            "apple".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_voucher", &MintRule::ByKeys));
+    }
+
+    // attempt to mint with a code that hasn't been uploaded
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            "banana".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::voucher_does_not_exist(4));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_voucher(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            "apple".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_voucher"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // the voucher should have been consumed
+        let res = query::voucher(deps.as_ref(), 4, &voucher_hash);
+        assert!(!res.available);
+    }
+
+    // attempt to reuse the same code
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            "apple".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::voucher_does_not_exist(4));
+    }
+}
+
+#[test]
+fn minting_by_payment() {
+    let mut deps = setup_test();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::wrong_mint_rule("by_payment", &MintRule::by_minter("larry")),
+        );
+    }
+
+    // cannot attach native coins when the price is in a cw20 token
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(12345, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+    }
+
+    // properly mint, pulling the cw20 tokens from the sender to the manager
+    {
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "pumpkin".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "token".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: "jake".to_string(),
+                        recipient: "larry".to_string(),
+                        amount: Uint128::new(12345),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "nft".to_string(),
+                    msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                        token_id: "4|99".to_string(),
+                        owner: "pumpkin".to_string(),
+                        token_uri: None,
+                        extension: None,
+                    }))
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_payment"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "pumpkin"),
+            ],
+        );
+
+        let badge = badge_map().load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+    }
+
+    // properly mint with native coins
+    {
+        badge_map()
+            .update(deps.as_mut().storage, 4, |badge| {
+                let mut badge = badge.unwrap();
+                badge.rule = MintRule::ByPayment(Price::native(420, "ustars"));
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(69, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::incorrect_payment("420ustars", "69ustars"));
+
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(420, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: "larry".to_string(),
+                amount: coins(420, "ustars"),
+            }),
+        );
+    }
+
+    // attempt to mint after max supply is reached
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(420, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_by_payment_with_revenue_split() {
+    let mut deps = setup_test();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("dev")).unwrap();
+    TREASURY_FEE.save(deps.as_mut().storage, &500).unwrap();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByPayment(Price::native(10000, "ustars"));
+            badge.revenue_split =
+                vec![RevenueShare::new("artist", 2000), RevenueShare::new("collab", 1)];
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // the treasury takes 5%, the artist takes 20% of the rest, and the manager receives the
+    // remainder. the collaborator's share rounds down to zero, so it's left out
+    let res = execute::mint_by_payment(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        mock_info("jake", &coins(10000, "ustars")),
+        4,
+        "jake".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[..3],
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "dev".to_string(),
+                amount: coins(500, "ustars"),
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "artist".to_string(),
+                amount: coins(1900, "ustars"),
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "larry".to_string(),
+                amount: coins(7600, "ustars"),
+            }),
+        ],
+    );
+    assert_eq!(res.messages.len(), 4);
+}
+
+#[test]
+fn minting_by_payment_in_usd() {
+    let mut deps = setup_test();
+
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg,
+        } if contract_addr == "oracle" => {
+            let price = match from_binary(msg).unwrap() {
+                OracleQueryMsg::Price {
+                    denom,
+                } if denom == "ustars" => Decimal::percent(3),
+                _ => Decimal::zero(),
+            };
+            Ok(to_binary(&OraclePriceResponse {
+                price,
+            })
+            .into())
+            .into()
+        },
+        _ => panic!("unexpected query: {:?}", query),
+    });
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByPayment(Price::usd(Decimal::percent(1000), "ustars"));
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // cannot mint before an oracle is set
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(400, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoOracle);
+    }
+
+    // non-developer cannot set the oracle
+    {
+        DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("dev")).unwrap();
+
+        let err = execute::set_oracle(
+            deps.as_mut(),
+            Addr::unchecked("jake"),
+            Some("oracle".to_string()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer sets the oracle
+    {
+        let res =
+            execute::set_oracle(deps.as_mut(), Addr::unchecked("dev"), Some("oracle".to_string()))
+                .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "badges/hub/set_oracle"), attr("oracle", "oracle")],
+        );
+
+        let oracle_addr = ORACLE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(oracle_addr, Addr::unchecked("oracle"));
+    }
+
+    // $10 at $0.03 per ustars is 333.33... ustars, rounded up to 334
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(333, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::incorrect_payment("334ustars", "333ustars"));
+    }
+
+    // properly mint, refunding the excess to the sender
+    {
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(400, "ustars")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[..2],
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "larry".to_string(),
+                    amount: coins(334, "ustars"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "jake".to_string(),
+                    amount: coins(66, "ustars"),
+                }),
+            ],
+        );
+        assert_eq!(res.messages.len(), 3);
+    }
+
+    // the oracle must quote a non-zero price for the denom
+    {
+        badge_map()
+            .update(deps.as_mut().storage, 4, |badge| {
+                let mut badge = badge.unwrap();
+                badge.rule = MintRule::ByPayment(Price::usd(Decimal::percent(1000), "uatom"));
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(400, "uatom")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidOraclePrice {
+                denom: "uatom".to_string(),
+            },
+        );
+    }
+}
+
+#[test]
+fn minting_by_nft() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_nft("collection");
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // in the mock collection, token "1" is held by jake, while all other tokens are held by pumpkin
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg,
+        } if contract_addr == "collection" => {
+            let owner = match from_binary(msg).unwrap() {
+                Cw721QueryMsg::OwnerOf {
+                    token_id,
+                    ..
+                } if token_id == "1" => "jake",
+                _ => "pumpkin",
+            };
+            let res = OwnerOfResponse {
+                owner: owner.to_string(),
+                approvals: vec![],
+            };
+            Ok(to_binary(&res).into()).into()
+        },
+        _ => panic!("[mock]: unsupported wasm query: {:?}", query),
+    });
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_nft(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "jake".to_string(),
+            "1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_nft", &MintRule::ByKeys));
+    }
+
+    // attempt to mint using a token not held by the owner
+    {
+        let err = execute::mint_by_nft(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            "2".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_nft_owner("2"));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_nft(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            "1".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_nft"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+                attr("token_id", "1"),
+            ],
+        );
+
+        // the token should be marked as used
+        let res = query::nft(deps.as_ref(), 4, "1");
+        assert!(res.used);
+    }
+
+    // attempt to mint using the same token again
+    {
+        let err = execute::mint_by_nft(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            "1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::nft_used(4, "1"));
+    }
+}
+
+#[test]
+fn minting_by_deposit() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 3, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_deposit("collection", true);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_deposit("collection", false);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            Addr::unchecked("collection"),
+            Addr::unchecked("jake"),
+            "1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::wrong_mint_rule("by_deposit", &MintRule::by_minter("larry")),
+        );
+    }
+
+    // tokens of other collections are not accepted
+    {
+        let err = execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("other_collection"),
+            Addr::unchecked("jake"),
+            "1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDepositCollection);
+    }
+
+    // the deposited token is held in escrow
+    {
+        let res = execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("collection"),
+            Addr::unchecked("jake"),
+            "1".to_string(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_deposit"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+                attr("token_id", "1"),
+                attr("burned", "false"),
+            ],
+        );
+
+        let res = query::deposit(deps.as_ref(), "collection".to_string(), "1".to_string()).unwrap();
+        assert_eq!(res.id, Some(4));
+        assert_eq!(res.depositor, Some("jake".to_string()));
+    }
+
+    // the deposited token is burned
+    {
+        let res = execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("collection"),
+            Addr::unchecked("pumpkin"),
+            "2".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "collection".to_string(),
+                msg: to_binary(&Cw721ExecuteMsg::Burn {
+                    token_id: "2".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        );
+
+        let res = query::deposit(deps.as_ref(), "collection".to_string(), "2".to_string()).unwrap();
+        assert_eq!(res.id, None);
+    }
+
+    // upgrading by burning one of the hub's own badges
+    badge_map()
+        .update(deps.as_mut().storage, 3, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_deposit("nft", true);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // the burn of the deposited badge is recorded
+    {
+        let res = execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("nft"),
+            Addr::unchecked("jake"),
+            "1|98".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&Cw721ExecuteMsg::Burn {
+                    token_id: "1|98".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        );
+
+        let source = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(source.current_supply, 98);
+        assert_eq!(source.num_burned, 1);
+
+        let target = badge_map().load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(target.current_supply, 100);
+        assert_eq!(target.num_burned, 0);
+    }
+
+    // a badge can be upgraded by burning another instance of itself
+    {
+        badge_map()
+            .update(deps.as_mut().storage, 3, |badge| {
+                let mut badge = badge.unwrap();
+                badge.max_supply = None;
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        execute::mint_by_deposit(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("nft"),
+            Addr::unchecked("pumpkin"),
+            "3|1".to_string(),
+        )
+        .unwrap();
+
+        let badge = badge_map().load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(badge.current_supply, 101);
+        assert_eq!(badge.num_burned, 1);
+    }
+}
+
+#[test]
+fn minting_by_delegation() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_delegation("validator", 10000);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let delegation = |delegator: &str, amount: u128| FullDelegation {
+        delegator: Addr::unchecked(delegator),
+        validator: "validator".to_string(),
+        amount: coin(amount, "ustars"),
+        can_redelegate: coin(amount, "ustars"),
+        accumulated_rewards: vec![],
+    };
+    deps.querier.update_staking(
+        "ustars",
+        &[],
+        &[delegation("jake", 12345), delegation("pumpkin", 6969)],
+    );
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_delegation(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_delegation", &MintRule::ByKeys));
+    }
+
+    // user has delegated less than the minimum amount
+    {
+        let err = execute::mint_by_delegation(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientDelegation {
+                expected: Uint128::new(10000),
+                found: Uint128::new(6969),
+            },
+        );
+    }
+
+    // user has not delegated at all
+    {
+        let err = execute::mint_by_delegation(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("doge"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientDelegation {
+                expected: Uint128::new(10000),
+                found: Uint128::zero(),
+            },
+        );
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_delegation(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_delegation"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        let res = query::owner(deps.as_ref(), 4, "jake");
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_delegation(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+}
+
+#[test]
+fn minting_by_group() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_group("group", Some(5));
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // in the mock group, jake has weight 10 and pumpkin has weight 1; no one else is a member
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg,
+        } if contract_addr == "group" => {
+            let weight = match from_binary(msg).unwrap() {
+                Cw4QueryMsg::Member {
+                    addr,
+                    ..
+                } => match addr.as_str() {
+                    "jake" => Some(10),
+                    "pumpkin" => Some(1),
+                    _ => None,
+                },
+                _ => panic!("[mock]: unsupported group query"),
+            };
+            Ok(to_binary(&MemberResponse {
+                weight,
+            })
+            .into())
+            .into()
+        },
+        _ => panic!("[mock]: unsupported wasm query: {:?}", query),
+    });
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_group(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_group", &MintRule::ByKeys));
+    }
+
+    // user's weight is less than the minimum
+    {
+        let err = execute::mint_by_group(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientGroupWeight {
+                expected: 5,
+                found: 1,
+            },
+        );
+    }
+
+    // user is not a member at all
+    {
+        let err = execute::mint_by_group(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("doge"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotGroupMember);
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_group(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_group"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        let res = query::owner(deps.as_ref(), 4, "jake");
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_group(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+}
+
+#[test]
+fn minting_by_composite() {
+    let mut deps = setup_test();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let (privkey, _, pubkey_str) = mock_keys();
+    let signature = utils::sign(&privkey, &message(&env, 4, "larry", 0, None));
+
+    // badge 4 can be minted either by presenting a signature, or by jake acting as the minter
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::any_of([
+                MintRule::by_key(&pubkey_str),
+                MintRule::by_minter("jake"),
+            ]);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            3,
+            "larry".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("all_of|any_of", &MintRule::ByKeys));
+    }
+
+    // neither of the sub-rules is satisfied
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("pumpkin", &[]),
+            4,
+            "larry".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotMinter);
+    }
+
+    // the first sub-rule is satisfied
+    {
+        let res = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("pumpkin", &[]),
+            4,
+            "larry".to_string(),
+            CompositeProof {
+                signature: Some(signature.clone()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_composite"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+    }
+
+    // the second sub-rule is satisfied
+    {
+        let res = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "pumpkin".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    // now, the badge requires both a signature AND a payment
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::all_of([
+                MintRule::by_key(&pubkey_str),
+                MintRule::by_payment(Price::native(420, "ustars")),
+            ]);
+            badge.current_supply = 0;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let signature = utils::sign(&privkey, &message(&env, 4, "jake", 0, None));
+
+    // signature is provided but payment is not
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "jake".to_string(),
+            CompositeProof {
+                signature: Some(signature.clone()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+    }
+
+    // payment is provided but signature is not
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(420, "ustars")),
+            4,
+            "jake".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::missing_proof("signature"));
+    }
+
+    // both are provided
+    {
+        let res = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(420, "ustars")),
+            4,
+            "jake".to_string(),
+            CompositeProof {
+                signature: Some(signature),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: "larry".to_string(),
+                amount: coins(420, "ustars"),
+            }),
+        );
+        assert_eq!(res.messages.len(), 2);
+    }
+
+    // now, the badge can be minted either by jake acting as the minter, or by payment
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::any_of([
+                MintRule::by_minter("jake"),
+                MintRule::by_payment(Price::native(420, "ustars")),
+            ]);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // funds sent along with a branch that doesn't require payment are rejected
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(420, "ustars")),
+            4,
+            "ash".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+    }
+
+    // now, the badge is open to the allowlist
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::any_of([MintRule::ByAllowlist]);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+    ALLOWLIST.insert(deps.as_mut().storage, (4, "doge")).unwrap();
+
+    // others cannot use up an allowlisted account's eligibility by minting to it
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("pumpkin", &[]),
+            4,
+            "doge".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SenderNotOwner);
+        assert!(!OWNERS.contains(deps.as_ref().storage, (4, "doge")));
+    }
+
+    // a composite rule without any payment sub-rule doesn't accept funds
+    {
+        let err = execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("doge", &coins(420, "ustars")),
+            4,
+            "doge".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+    }
+
+    // the allowlisted account can mint to itself
+    {
+        execute::mint_by_composite(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("doge", &[]),
+            4,
+            "doge".to_string(),
+            CompositeProof::default(),
+        )
+        .unwrap();
+        assert!(OWNERS.contains(deps.as_ref().storage, (4, "doge")));
+    }
+}
+
+#[test]
+fn claiming() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::Open;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("open", &MintRule::ByKeys));
+    }
+
+    // properly claim
+    {
+        let res = execute::claim(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/claim"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        let res = query::owner(deps.as_ref(), 4, "jake");
+        assert!(res.claimed);
+    }
+
+    // attempt to claim again
+    {
+        let err = execute::claim(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+}
+
+#[test]
+fn minting_by_allowlist() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByAllowlist;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    ALLOWLIST.insert(deps.as_mut().storage, (4, "jake")).unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_allowlist", &MintRule::ByKeys));
+    }
+
+    // user is not on the allowlist
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_allowlisted(4, "pumpkin"));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_allowlist"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        let res = query::owner(deps.as_ref(), 4, "jake");
+        assert!(res.claimed);
+    }
+
+    // attempt to mint again
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+}
+
+#[test]
+fn minting_by_raffle() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::by_raffle(10000, 2);
+            // the rate limit is lower than the number of winners, but doesn't apply to draws
+            badge.rate_limit = Some(RateLimit {
+                max_mints: 1,
+                window: Window::Seconds(100),
+            });
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::register_for_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9999),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_raffle", &MintRule::ByKeys));
+    }
+
+    // properly register
+    for user in ["jake", "pumpkin", "doge"] {
+        let res = execute::register_for_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9999),
+            4,
+            Addr::unchecked(user),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/register_for_raffle"),
+                attr("id", "4"),
+                attr("user", user),
+            ],
+        );
+    }
+
+    let registrants = vec!["doge".to_string(), "jake".to_string(), "pumpkin".to_string()];
+    let res = query::registrants(deps.as_ref(), 4, None, None).unwrap();
+    assert_eq!(res.registrants, registrants);
+
+    // attempt to register again
+    {
+        let err = execute::register_for_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9999),
+            4,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_registered(4, "jake"));
+    }
+
+    assert_eq!(REGISTRANT_COUNTS.load(deps.as_ref().storage, 4).unwrap(), 3);
+
+    // cannot register once the raffle is full
+    {
+        REGISTRANT_COUNTS.save(deps.as_mut().storage, 4, &MAX_RAFFLE_REGISTRANTS).unwrap();
+
+        let err = execute::register_for_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9999),
+            4,
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RaffleFull {
+                max: MAX_RAFFLE_REGISTRANTS,
+            },
+        );
+
+        REGISTRANT_COUNTS.save(deps.as_mut().storage, 4, &3).unwrap();
+    }
+
+    // non-manager cannot draw before registration ends
+    {
+        let err = execute::draw_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RaffleNotClosed);
+    }
+
+    // cannot register after registration ends
+    {
+        let err = execute::register_for_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10001),
+            4,
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RaffleClosed);
+    }
+
+    // anyone can draw once registration ends
+    {
+        let env = utils::mock_env_at_timestamp(10001);
+        let winners = draw_winners(&raffle_seed(&env, 4), registrants, 2);
+
+        let res = execute::draw_raffle(deps.as_mut(), env, mock_info("jake", &[]), 4).unwrap();
+        assert_eq!(
+            res.messages,
+            winners
+                .iter()
+                .enumerate()
+                .map(|(idx, winner)| {
+                    SubMsg::new(WasmMsg::Execute {
+                        contract_addr: "nft".to_string(),
+                        msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(
+                            MintMsg::<Option<Empty>> {
+                                token_id: format!("4|{}", 99 + idx),
+                                owner: winner.clone(),
+                                token_uri: None,
+                                extension: None,
+                            },
+                        ))
+                        .unwrap(),
+                        funds: vec![],
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/draw_raffle"),
+                attr("id", "4"),
+                attr("serials", "99-100"),
+                attr("amount", "2"),
+            ],
+        );
+
+        let badge = badge_map().load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 100);
+
+        let mut expected = winners.clone();
+        expected.sort();
+        let res = query::winners(deps.as_ref(), 4, None, None).unwrap();
+        assert_eq!(res.winners, expected);
+
+        for winner in &winners {
+            assert!(query::owner(deps.as_ref(), 4, winner).claimed);
+        }
+    }
+
+    // attempt to draw again
+    {
+        let err = execute::draw_raffle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10001),
+            mock_info("larry", &[]),
+            4,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::raffle_drawn(4));
+    }
+}
+
+#[test]
+fn minting_with_per_owner_limit() {
+    let mut deps = setup_test();
+
+    badge_map()
+        .update(deps.as_mut().storage, 1, |badge| {
+            let mut badge = badge.unwrap();
+            badge.max_per_owner = Some(1);
+            badge.max_supply = None;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // mint one to jake, should succeed
+    execute::mint_by_minter(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        1,
+        utils::btreeset(&["jake"]),
+        Addr::unchecked("larry"),
+    )
+    .unwrap();
+
+    // mint another one to jake, should fail
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake", "pumpkin"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::max_per_owner_reached(1, "jake"));
+    }
+
+    // mint to other users is not affected
+    execute::mint_by_minter(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        1,
+        utils::btreeset(&["pumpkin"]),
+        Addr::unchecked("larry"),
+    )
+    .unwrap();
+
+    let count = MINT_COUNTS.load(deps.as_ref().storage, (1, "pumpkin")).unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn minting_in_phases() {
+    let mut deps = setup_test();
+
+    // a minter phase limited to one per account, then an allowlist phase, then the badge's own
+    // "open" rule applies
+    badge_map()
+        .update(deps.as_mut().storage, 4, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::Open;
+            badge.phases = vec![
+                Phase {
+                    rule: MintRule::by_minter("larry"),
+                    start_time: 9000,
+                    end_time: Some(9499),
+                    max_per_owner: Some(1),
+                },
+                Phase {
+                    rule: MintRule::ByAllowlist,
+                    start_time: 9500,
+                    end_time: Some(9999),
+                    max_per_owner: None,
+                },
+            ];
+            badge.max_supply = None;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    ALLOWLIST.insert(deps.as_mut().storage, (4, "pumpkin")).unwrap();
+
+    // during the minter phase, only the minter rule applies
+    {
+        let res = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9000),
+            4,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("amount", "1"));
+
+        let err = execute::claim(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9499),
+            4,
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("open", &MintRule::by_minter("larry")));
+    }
+
+    // during the allowlist phase, only the allowlist rule applies
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9500),
+            4,
+            utils::btreeset(&["doge"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_minter", &MintRule::ByAllowlist));
+
+        let res = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9500),
+            4,
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("serial", "100"));
+    }
+
+    // once all phases have ended, the badge's own rule applies
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("doge"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_allowlist", &MintRule::Open));
+
+        let res = execute::claim(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            Addr::unchecked("doge"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("serial", "101"));
+    }
+
+    // serial numbers are continuous across phases
+    let badge = badge_map().load(deps.as_ref().storage, 4).unwrap();
+    assert_eq!(badge.current_supply, 101);
+
+    // the minter phase's per-account limit has been reached for jake
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(9499),
+            4,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::phase_max_per_owner_reached(4, 0, "jake"));
+    }
+}
+
+#[test]
+fn minting_after_bumping_nonce() {
+    let mut deps = setup_test();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let privkey = utils::mock_privkey();
+    let signature = utils::sign(&privkey, &message(&env, 2, "larry", 0, None));
+
+    // non-manager attempts to bump the nonce
+    {
+        let err = execute::bump_nonce(deps.as_mut(), mock_info("jake", &[]), 2).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager bumps the nonce
+    {
+        let res = execute::bump_nonce(deps.as_mut(), mock_info("larry", &[]), 2).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/bump_nonce"),
+                attr("id", "2"),
+                attr("nonce", "1"),
+            ],
+        );
+
+        let res = query::nonce(deps.as_ref(), 2).unwrap();
+        assert_eq!(res.nonce, 1);
+    }
+
+    // attempt to mint with a signature committing to the old nonce
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // mint with a signature committing to the new nonce
+    {
+        let signature = utils::sign(&privkey, &message(&env, 2, "larry", 1, None));
+        execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            "larry".to_string(),
+            ClaimSignature::new(signature),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn minting_while_paused() {
+    let mut deps = setup_test();
+
+    // non-manager cannot pause
+    {
+        let err = execute::set_badge_paused(deps.as_mut(), mock_info("jake", &[]), 1, true)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager pauses
+    {
+        let res =
+            execute::set_badge_paused(deps.as_mut(), mock_info("larry", &[]), 1, true).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_badge_paused"),
+                attr("id", "1"),
+                attr("paused", "true"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(b.paused);
+    }
+
+    // cannot mint while paused
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused);
+    }
+
+    // manager unpauses
+    {
+        execute::set_badge_paused(deps.as_mut(), mock_info("larry", &[]), 1, false).unwrap();
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(!b.paused);
+    }
+
+    // can mint again once unpaused
+    {
+        execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn minting_while_hub_paused() {
+    let mut deps = setup_test();
+
+    DEVELOPER.save(deps.as_mut().storage, &Addr::unchecked("larry")).unwrap();
+
+    // non-developer cannot pause the hub
+    {
+        let err = execute::set_hub_paused(deps.as_mut(), Addr::unchecked("jake"), true)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotDeveloper);
+    }
+
+    // developer pauses the hub
+    {
+        let res = execute::set_hub_paused(deps.as_mut(), Addr::unchecked("larry"), true).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_hub_paused"),
+                attr("paused", "true"),
+            ],
+        );
+    }
+
+    // cannot create badges while the hub is paused
+    {
+        let badge = badge_map().load(deps.as_ref().storage, 1).unwrap();
+        let err = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            badge,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::HubPaused);
+    }
+
+    // cannot mint while the hub is paused
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::HubPaused);
+    }
+
+    // can mint again once the hub is unpaused
+    {
+        execute::set_hub_paused(deps.as_mut(), Addr::unchecked("larry"), false).unwrap();
+
+        execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn revoking() {
+    let mut deps = setup_test();
+
+    // badge 1 is not revocable
+    {
+        let err = execute::revoke(deps.as_mut(), mock_info("larry", &[]), 1, btreeset_u64(&[98]))
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotRevocable);
+    }
+
+    badge_map()
+        .update(deps.as_mut().storage, 1, |badge| {
+            let mut badge = badge.unwrap();
+            badge.revocable = true;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // non-manager cannot revoke
+    {
+        let err = execute::revoke(deps.as_mut(), mock_info("jake", &[]), 1, btreeset_u64(&[98]))
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot revoke instances that haven't been minted
+    {
+        let err = execute::revoke(deps.as_mut(), mock_info("larry", &[]), 1, btreeset_u64(&[99]))
+            .unwrap_err();
+        assert_eq!(err, ContractError::not_minted(1, 99));
+    }
+
+    // manager revokes
+    {
+        let res =
+            execute::revoke(deps.as_mut(), mock_info("larry", &[]), 1, btreeset_u64(&[97, 98]))
+                .unwrap();
+        assert_eq!(
+            res.messages,
+            [97, 98]
+                .iter()
+                .map(|serial| {
+                    SubMsg::new(WasmMsg::Execute {
+                        contract_addr: "nft".to_string(),
+                        msg: to_binary(&sg721::ExecuteMsg::<Option<Empty>, Empty>::Burn {
+                            token_id: TokenIdFormat::Pipe.build(1, *serial).unwrap(),
+                        })
+                        .unwrap(),
+                        funds: vec![],
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/revoke"),
+                attr("id", "1"),
+                attr("amount", "2"),
+            ],
+        );
+
+        let res = query::revocations(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.serials, vec![97, 98]);
+
+        // revoked instances count as burned
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.num_burned, 2);
+    }
+
+    // cannot revoke the same instance twice
+    {
+        let err = execute::revoke(deps.as_mut(), mock_info("larry", &[]), 1, btreeset_u64(&[98]))
+            .unwrap_err();
+        assert_eq!(err, ContractError::already_revoked(1, 98));
+    }
+}
+
+#[test]
+fn recording_burns() {
+    let mut deps = setup_test();
+
+    // accounts other than the nft contract cannot record burns
+    {
+        let err = execute::record_burn(deps.as_mut(), mock_info("jake", &[]), 1, 98).unwrap_err();
+        assert_eq!(err, ContractError::NotNft);
+    }
+
+    // the nft contract records a burn
+    {
+        let res = execute::record_burn(deps.as_mut(), mock_info("nft", &[]), 1, 98).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/record_burn"),
+                attr("id", "1"),
+                attr("serial", "98"),
+            ],
+        );
+
+        // the burn doesn't make room for new instances to be minted
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.num_burned, 1);
+        assert_eq!(b.current_supply, 98);
+    }
+}
@@ -12,17 +12,31 @@ mod utils;
 fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
     let mut deps = mock_dependencies();
 
-    BADGES.save(
+    badge_map().save(
         deps.as_mut().storage,
         1,
         &Badge {
             manager: Addr::unchecked("larry"),
             metadata: Metadata::default(),
+            token_uri: None,
             transferrable: true,
+            revocable: false,
             rule: MintRule::ByKeys,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
             expiry: Some(12345),
             max_supply: Some(100),
+            max_per_owner: None,
+            rate_limit: None,
             current_supply: 2,
+            num_burned: 0,
         },
     )
     .unwrap();
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_std::testing::{mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{from_binary, Addr, Empty, OwnedDeps};
+use k256::ecdsa::VerifyingKey;
+use proptest::prelude::*;
+use sg_metadata::Metadata;
+use sg_std::Response;
+
+use badge_hub::error::ContractError;
+use badge_hub::execute;
+use badge_hub::helpers::{message, ClaimSignature};
+use badge_hub::state::{badge_map, NFT};
+use badges::hub::MintReceipt;
+use badges::{Badge, MintRule, TokenIdFormat};
+
+mod utils;
+
+const NUM_USERS: usize = 8;
+
+/// A call to the hub that mints badges
+#[derive(Clone, Debug)]
+enum Op {
+    /// The minter mints badge 1 or 2 to a set of users
+    ByMinter {
+        id: u64,
+        users: Vec<usize>,
+    },
+    /// A user claims badge 3 with a signature from the minting key
+    ByKey {
+        user: usize,
+    },
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (1u64..=2, prop::collection::vec(0..NUM_USERS, 1..5)).prop_map(|(id, users)| {
+            Op::ByMinter {
+                id,
+                users,
+            }
+        }),
+        (0..NUM_USERS).prop_map(|user| Op::ByKey {
+            user,
+        }),
+    ]
+}
+
+fn user(idx: usize) -> String {
+    format!("user{}", idx)
+}
+
+fn setup_test(
+    max_supplies: &[Option<u64>; 3],
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
+    let mut deps = mock_dependencies();
+
+    NFT.save(deps.as_mut().storage, &Addr::unchecked("nft")).unwrap();
+
+    let pubkey = VerifyingKey::from(&utils::mock_privkey());
+    let rules = [
+        MintRule::by_minter("larry"),
+        MintRule::by_minter("larry"),
+        MintRule::by_key(hex::encode(pubkey.to_bytes())),
+    ];
+
+    for (idx, (rule, max_supply)) in rules.into_iter().zip(max_supplies).enumerate() {
+        let badge = Badge {
+            manager: Addr::unchecked("larry"),
+            metadata: Metadata::default(),
+            token_uri: None,
+            transferrable: true,
+            revocable: false,
+            rule,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
+            expiry: None,
+            max_supply: *max_supply,
+            max_per_owner: None,
+            rate_limit: None,
+            current_supply: 0,
+            num_burned: 0,
+        };
+        badge_map().save(deps.as_mut().storage, idx as u64 + 1, &badge).unwrap();
+    }
+
+    deps
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// However mints of different badges by different rules are interleaved, each badge's serials
+    /// are allocated consecutively from 1 without gaps or duplicates, and its supply never exceeds
+    /// the max supply. Failed mints leave the supply untouched.
+    #[test]
+    fn allocating_serials(
+        max_supplies in [
+            prop::option::of(1u64..20),
+            prop::option::of(1u64..20),
+            prop::option::of(1u64..NUM_USERS as u64),
+        ],
+        ops in prop::collection::vec(op_strategy(), 1..30),
+    ) {
+        let mut deps = setup_test(&max_supplies);
+        let env = utils::mock_env_at_timestamp(10000);
+
+        // signatures don't depend on the badge's supply, so they can be produced upfront
+        let privkey = utils::mock_privkey();
+        let signatures = (0..NUM_USERS)
+            .map(|idx| utils::sign(&privkey, &message(&env, 3, user(idx), 0, None)))
+            .collect::<Vec<_>>();
+
+        let mut serials: BTreeMap<u64, BTreeSet<u64>> = BTreeMap::new();
+        let mut claimed: BTreeSet<usize> = BTreeSet::new();
+
+        for op in ops {
+            let (id, amount, result) = match &op {
+                Op::ByMinter {
+                    id,
+                    users,
+                } => {
+                    let owners = users.iter().map(|idx| user(*idx)).collect::<BTreeSet<_>>();
+                    let amount = owners.len() as u64;
+                    let result = execute::mint_by_minter(
+                        deps.as_mut(),
+                        env.clone(),
+                        *id,
+                        owners,
+                        Addr::unchecked("larry"),
+                    );
+                    (*id, amount, result)
+                },
+                Op::ByKey {
+                    user: idx,
+                } => {
+                    let result = execute::mint_by_key(
+                        deps.as_mut(),
+                        env.clone(),
+                        mock_info("relayer", &[]),
+                        3,
+                        user(*idx),
+                        ClaimSignature::new(signatures[*idx].clone()),
+                    );
+                    (3, 1, result)
+                },
+            };
+
+            let minted = serials.entry(id).or_default();
+            let supply = minted.len() as u64;
+            let max_supply = max_supplies[id as usize - 1];
+            let sold_out = max_supply.is_some_and(|max| supply + amount > max);
+            let key_user = match &op {
+                Op::ByKey {
+                    user,
+                } => Some(*user),
+                Op::ByMinter {
+                    ..
+                } => None,
+            };
+            let already_claimed = key_user.is_some_and(|idx| claimed.contains(&idx));
+
+            match result {
+                Ok(res) => {
+                    prop_assert!(!sold_out && !already_claimed);
+                    let receipt = receipt(&res);
+                    prop_assert_eq!(receipt.id, id);
+                    prop_assert_eq!(receipt.start_serial, supply + 1);
+                    prop_assert_eq!(receipt.amount, amount);
+                    for (idx, token_id) in receipt.token_ids.iter().enumerate() {
+                        let serial = receipt.start_serial + idx as u64;
+                        prop_assert_eq!(token_id, &TokenIdFormat::Pipe.build(id, serial).unwrap());
+                        prop_assert!(minted.insert(serial), "serial {} allocated twice", serial);
+                    }
+                    if let Some(idx) = key_user {
+                        claimed.insert(idx);
+                    }
+                },
+                Err(err) => {
+                    let expected = if sold_out {
+                        ContractError::SoldOut
+                    } else {
+                        prop_assert!(already_claimed, "unexpected error: {}", err);
+                        ContractError::already_claimed(3, user(key_user.unwrap()))
+                    };
+                    prop_assert_eq!(err, expected);
+                },
+            }
+
+            let badge = badge_map().load(deps.as_ref().storage, id).unwrap();
+            prop_assert_eq!(badge.current_supply, minted.len() as u64);
+        }
+
+        for (id, minted) in serials {
+            let badge = badge_map().load(deps.as_ref().storage, id).unwrap();
+            prop_assert_eq!(minted, (1..=badge.current_supply).collect::<BTreeSet<_>>());
+            if let Some(max_supply) = badge.max_supply {
+                prop_assert!(badge.current_supply <= max_supply);
+            }
+        }
+    }
+}
+
+fn receipt(res: &Response) -> MintReceipt {
+    from_binary(res.data.as_ref().unwrap()).unwrap()
+}
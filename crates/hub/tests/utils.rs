@@ -3,9 +3,16 @@
 use std::collections::BTreeSet;
 
 use cosmwasm_std::testing::mock_env;
-use cosmwasm_std::{Env, Timestamp};
-use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use cosmwasm_std::{to_binary, Env, Timestamp, WasmMsg};
+use ed25519_zebra::SigningKey as Ed25519SigningKey;
+use k256::ecdsa::signature::{DigestSigner, Signer};
+use k256::ecdsa::{recoverable, Signature, SigningKey, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
 use rand::rngs::OsRng;
+use sha3::{Digest, Keccak256};
+
+use badges::nft::{ExecuteMsg, ExtensionMsg, MetadataCache};
+use badges::Badge;
 
 pub const MOCK_PRIVKEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
 
@@ -27,6 +34,31 @@ pub fn sign(privkey: &SigningKey, msg: &str) -> String {
     hex::encode(sig_bytes)
 }
 
+/// Sign the Keccak-256 hash of a message using the provided privkey, as Ethereum wallets do, and
+/// encode the 65-byte recoverable signature in hex
+pub fn sign_eth(privkey: &SigningKey, msg: &str) -> String {
+    let sig: recoverable::Signature = privkey.sign_digest(Keccak256::new_with_prefix(msg));
+    hex::encode(sig.as_ref())
+}
+
+/// The hex-encoded Ethereum address of the provided privkey
+pub fn eth_address(privkey: &SigningKey) -> String {
+    let pubkey = VerifyingKey::from(privkey).to_encoded_point(false);
+    let hash = Keccak256::digest(&pubkey.as_bytes()[1..]);
+    hex::encode(&hash[12..])
+}
+
+/// Generate a random ed25519 private key
+pub fn random_ed25519_privkey() -> Ed25519SigningKey {
+    Ed25519SigningKey::new(OsRng)
+}
+
+/// Sign a message using the provided ed25519 privkey, and encode the signature in hex
+pub fn sign_ed25519(privkey: &Ed25519SigningKey, msg: &str) -> String {
+    let sig_bytes: [u8; 64] = privkey.sign(msg.as_bytes()).into();
+    hex::encode(sig_bytes)
+}
+
 /// Cast a slice of strings into a btreeset
 pub fn btreeset(slice: &[&str]) -> BTreeSet<String> {
     slice.iter().map(|s| s.to_string()).collect()
@@ -38,3 +70,22 @@ pub fn mock_env_at_timestamp(timestamp: u64) -> Env {
     env.block.time = Timestamp::from_seconds(timestamp);
     env
 }
+
+/// The message the hub sends to the NFT contract to cache the badge's metadata
+pub fn cache_metadata_msg(id: u64, badge: &Badge) -> WasmMsg {
+    WasmMsg::Execute {
+        contract_addr: "nft".to_string(),
+        msg: to_binary(&ExecuteMsg::Extension {
+            msg: ExtensionMsg::CacheMetadata {
+                id,
+                cache: MetadataCache {
+                    metadata: badge.metadata.clone(),
+                    token_uri: badge.token_uri.clone(),
+                    max_supply: badge.max_supply,
+                },
+            },
+        })
+        .unwrap(),
+        funds: vec![],
+    }
+}
@@ -2,11 +2,14 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
+use cosmwasm_std::Empty;
 
 use badges::nft::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, CollectionInfoResponse,
-    ContractInfoResponse, ExecuteMsg, InstantiateMsg, MinterResponse, NftInfoResponse,
-    NumTokensResponse, OperatorsResponse, OwnerOfResponse, QueryMsg, TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, CheckRoyaltiesResponse,
+    CollectionInfoResponse, ContractInfoResponse, ExecuteMsg, Ics721Ack, InstantiateMsg,
+    MinterResponse, NftInfoResponse, NonFungibleTokenPacketData, NumTokensResponse,
+    OperatorsResponse, OwnerOfResponse, PortfolioResponse, QueryMsg, RoyaltiesInfoResponse,
+    SnapshotHoldersResponse, SnapshotsResponse, TokensByBadgeResponse, TokensResponse,
 };
 
 fn main() {
@@ -27,6 +30,21 @@ fn main() {
     export_schema(&schema_for!(TokensResponse), &out_dir);
     export_schema(&schema_for!(MinterResponse), &out_dir);
     export_schema(&schema_for!(CollectionInfoResponse), &out_dir);
+    export_schema(&schema_for!(RoyaltiesInfoResponse), &out_dir);
+    export_schema(&schema_for!(CheckRoyaltiesResponse), &out_dir);
+    export_schema(&schema_for!(TokensByBadgeResponse), &out_dir);
+    export_schema(&schema_for!(PortfolioResponse), &out_dir);
+    export_schema(&schema_for!(SnapshotsResponse), &out_dir);
+    export_schema(&schema_for!(SnapshotHoldersResponse), &out_dir);
+    export_schema(&schema_for!(NonFungibleTokenPacketData), &out_dir);
+    export_schema(&schema_for!(Ics721Ack), &out_dir);
+
+    // the contract migrates without any parameters
+    export_schema_with_title(
+        &schema_for!(Empty),
+        &out_dir,
+        "MigrateMsg",
+    );
 
     // types with generics need to be renamed
     export_schema_with_title(
@@ -1,19 +1,51 @@
-use std::any::type_name;
-use std::str::FromStr;
+use std::collections::BTreeMap;
 
-use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, StdError, StdResult, Storage};
+use cosmwasm_std::{
+    from_slice, to_binary, to_vec, Addr, ContractInfoResponse, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, QueryRequest, StdError, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
+};
 use cw721::Cw721Query;
+use cw_storage_plus::Bound;
 use sg_metadata::{Metadata, Trait};
 use sg_std::Response;
 
-use badges::hub::BadgeResponse;
-use badges::nft::{AllNftInfoResponse, Extension, InstantiateMsg, NftInfoResponse};
+use badges::hub::{BadgeResponse, ConfigResponse, TokenSerialResponse};
+use badges::nft::{
+    AllNftInfoResponse, BadgeTokenResponse, ExecuteMsg, Extension, HoldingResponse,
+    InstantiateMsg, MetadataCache, NftInfoResponse, PortfolioResponse, RoyaltiesInfoResponse,
+    Snapshot, SnapshotHolderResponse, SnapshotHoldersResponse, SnapshotResponse,
+    SnapshotsResponse, TokensByBadgeResponse,
+};
+use badges::locale::{locale_fallbacks, localize};
+use badges::{MediaAsset, MediaPurpose, TokenIdFormat, BPS_DENOMINATOR};
 
-use crate::state::API_URL;
+use crate::state::{
+    API_URL, HOLDINGS, HOLDINGS_BACKFILL, IBC_ESCROW, LOCALIZED_METADATA, MEDIA, METADATA_CACHE,
+    SERIAL_TRAITS, SNAPSHOTS, SNAPSHOT_CURSORS, SNAPSHOT_HOLDERS, SVG_TEMPLATES, TOKEN_IDS,
+    TOKEN_ID_FORMAT, TOKEN_SERIALS,
+};
 
 pub const CONTRACT_NAME: &str = "crates.io:badge-hub";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+/// The maximum number of serials `tokens_by_badge` looks at in a single query, whether or not
+/// they have been burned
+pub const MAX_SERIALS_SCANNED: u64 = 300;
+
+/// The number of serials walked by `take_snapshot`, and by `resume_snapshot` if no limit is given
+pub const DEFAULT_SNAPSHOT_CHUNK: u32 = 200;
+/// The maximum number of serials `resume_snapshot` walks in a single call
+pub const MAX_SNAPSHOT_CHUNK: u32 = 1000;
+
+/// The number of tokens `backfill_holdings` indexes if no limit is given
+pub const DEFAULT_BACKFILL_CHUNK: u32 = 100;
+/// The maximum number of tokens `backfill_holdings` indexes in a single call; it can't exceed the
+/// page size of cw721's `all_tokens` query, which it reads the tokens through
+pub const MAX_BACKFILL_CHUNK: u32 = 100;
+
 #[derive(Default)]
 pub struct NftContract<'a> {
     pub parent: sg721_base::Sg721Contract<'a, Extension>,
@@ -31,6 +63,11 @@ impl<'a> NftContract<'a> {
 
         API_URL.save(deps.storage, &msg.api_url)?;
 
+        // token ids are built by the Hub, so they must be parsed in the Hub's format
+        let config: ConfigResponse =
+            deps.querier.query_wasm_smart(&msg.hub, &badges::hub::QueryMsg::Config {})?;
+        TOKEN_ID_FORMAT.save(deps.storage, &config.token_id_format)?;
+
         self.parent.instantiate(
             deps,
             env,
@@ -46,7 +83,7 @@ impl<'a> NftContract<'a> {
 
     /// Assert that the badge is transferrable
     pub fn assert_transferrable(&self, deps: Deps, token_id: impl ToString) -> StdResult<()> {
-        let (id, _) = parse_token_id(&token_id.to_string())?;
+        let (id, _) = parse_token_id(deps.storage, &token_id.to_string())?;
         let badge = self.query_badge(deps, id)?;
         if badge.transferrable {
             Ok(())
@@ -55,14 +92,516 @@ impl<'a> NftContract<'a> {
         }
     }
 
+    /// Burn a token owned by, or approved to, the sender, and report the burn to the Hub so that it
+    /// can keep count of the badge's burned instances
+    pub fn burn(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let (id, serial) = parse_token_id(deps.storage, &token_id)?;
+        let minter = self.parent.parent.minter(deps.as_ref())?;
+        let token = self.parent.parent.tokens.load(deps.storage, &token_id)?;
+
+        let res = self.parent.execute(deps.branch(), env, info, sg721::ExecuteMsg::Burn {
+            token_id,
+        })?;
+
+        HOLDINGS.remove(deps.storage, (&token.owner, id, serial));
+
+        Ok(res.add_message(WasmMsg::Execute {
+            contract_addr: minter.minter,
+            msg: to_binary(&badges::hub::ExecuteMsg::RecordBurn {
+                id,
+                serial,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    /// Burn a token on behalf of the Hub, regardless of who owns it, so that the managers of
+    /// revocable badges can revoke them. Other accounts can only burn the tokens they own or have
+    /// been approved for, as in vanilla cw721.
+    pub fn burn_by_hub(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response, sg721_base::ContractError> {
+        // the token must exist
+        let token = self.parent.parent.tokens.load(deps.storage, &token_id)?;
+        let (id, serial) = parse_token_id(deps.storage, &token_id)?;
+
+        self.parent.parent.tokens.remove(deps.storage, &token_id)?;
+        self.parent.parent.decrement_tokens(deps.storage)?;
+        HOLDINGS.remove(deps.storage, (&token.owner, id, serial));
+        // a token revoked while on another chain can no longer be refunded or come back
+        IBC_ESCROW.remove(deps.storage, &token_id);
+
+        Ok(Response::new()
+            .add_attribute("action", "burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    /// Replace the minter, so that the Hub can be replaced without redeploying the NFT contract and
+    /// orphaning existing tokens. Only the contract's admin can do this.
+    pub fn update_minter(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        minter: String,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let contract_info: ContractInfoResponse =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                contract_addr: env.contract.address.to_string(),
+            }))?;
+        if contract_info.admin.as_deref() != Some(info.sender.as_str()) {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        let minter_addr = deps.api.addr_validate(&minter)?;
+
+        // existing token ids must still parse, so the new Hub must build them in the same format
+        let config: ConfigResponse =
+            deps.querier.query_wasm_smart(&minter_addr, &badges::hub::QueryMsg::Config {})?;
+        if config.token_id_format != TOKEN_ID_FORMAT.may_load(deps.storage)?.unwrap_or_default() {
+            return Err(StdError::generic_err(
+                "the new minter's token id format differs from the collection's",
+            )
+            .into());
+        }
+
+        self.parent.parent.minter.save(deps.storage, &minter_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_minter")
+            .add_attribute("sender", info.sender)
+            .add_attribute("minter", minter_addr))
+    }
+
+    /// Cache a badge's metadata pushed by the Hub. Only the Hub can do this.
+    pub fn cache_metadata(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+        cache: MetadataCache,
+    ) -> Result<Response, sg721_base::ContractError> {
+        if !self.is_hub(deps.as_ref(), &info.sender)? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        METADATA_CACHE.save(deps.storage, id, &cache)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "cache_metadata")
+            .add_attribute("id", id.to_string()))
+    }
+
+    /// Set or remove the SVG template of a badge's on-chain image. Only the Hub can do this.
+    pub fn set_svg_template(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+        template: Option<String>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        if !self.is_hub(deps.as_ref(), &info.sender)? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        match &template {
+            Some(template) => SVG_TEMPLATES.save(deps.storage, id, template)?,
+            None => SVG_TEMPLATES.remove(deps.storage, id),
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_svg_template")
+            .add_attribute("id", id.to_string()))
+    }
+
+    /// Replace the media assets attached to a badge. Only the Hub can do this.
+    pub fn set_media(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+        media: Vec<MediaAsset>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        if !self.is_hub(deps.as_ref(), &info.sender)? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        if media.is_empty() {
+            MEDIA.remove(deps.storage, id);
+        } else {
+            MEDIA.save(deps.storage, id, &media)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_media")
+            .add_attribute("id", id.to_string()))
+    }
+
+    /// Set or remove a translation of a badge's metadata. Only the Hub can do this.
+    pub fn set_localized_metadata(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+        locale: String,
+        metadata: Option<Metadata>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        if !self.is_hub(deps.as_ref(), &info.sender)? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        match &metadata {
+            Some(metadata) => LOCALIZED_METADATA.save(deps.storage, (id, &locale), metadata)?,
+            None => LOCALIZED_METADATA.remove(deps.storage, (id, &locale)),
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_localized_metadata")
+            .add_attribute("id", id.to_string())
+            .add_attribute("locale", locale))
+    }
+
+    /// Attach traits to a single instance of a badge. Only the Hub can do this.
+    pub fn set_serial_traits(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+        serial: u64,
+        traits: Vec<Trait>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        if !self.is_hub(deps.as_ref(), &info.sender)? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        if traits.is_empty() {
+            SERIAL_TRAITS.remove(deps.storage, (id, serial));
+        } else {
+            SERIAL_TRAITS.save(deps.storage, (id, serial), &traits)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_serial_traits")
+            .add_attribute("id", id.to_string())
+            .add_attribute("serial", serial.to_string()))
+    }
+
+    /// Record the current holders of a badge. Only the badge's manager or the Hub's developer can
+    /// do this.
+    ///
+    /// Only the first chunk of serials is walked here, so that badges with large supplies don't
+    /// run out of gas; the rest are walked with `resume_snapshot`. Serials minted after the
+    /// snapshot is started are left out. A token that changes hands while the snapshot is in
+    /// progress is recorded under whoever holds it when its serial is walked.
+    pub fn take_snapshot(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: u64,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let badge = self.query_badge(deps.as_ref(), id)?;
+        if info.sender != badge.manager && info.sender != self.query_developer(deps.as_ref())? {
+            return Err(sg721_base::ContractError::Unauthorized {});
+        }
+
+        let last_snapshot_id = SNAPSHOTS
+            .prefix(id)
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?;
+
+        if let Some(last_snapshot_id) = last_snapshot_id {
+            if SNAPSHOT_CURSORS.has(deps.storage, (id, last_snapshot_id)) {
+                return Err(StdError::generic_err(format!(
+                    "snapshot {} of badge {} is still in progress",
+                    last_snapshot_id, id,
+                ))
+                .into());
+            }
+        }
+
+        let snapshot_id = last_snapshot_id.map_or(1, |last| last + 1);
+
+        SNAPSHOTS.save(
+            deps.storage,
+            (id, snapshot_id),
+            &Snapshot {
+                height: env.block.height,
+                num_holders: 0,
+                num_tokens: 0,
+            },
+        )?;
+        SNAPSHOT_CURSORS.save(deps.storage, (id, snapshot_id), &(1, badge.current_supply))?;
+
+        let remaining = self.walk_snapshot(deps.storage, id, snapshot_id, DEFAULT_SNAPSHOT_CHUNK)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "take_snapshot")
+            .add_attribute("id", id.to_string())
+            .add_attribute("snapshot", snapshot_id.to_string())
+            .add_attribute("height", env.block.height.to_string())
+            .add_attribute("remaining", remaining.to_string()))
+    }
+
+    /// Walk the next chunk of serials of a badge's snapshot in progress
+    pub fn resume_snapshot(
+        &self,
+        deps: DepsMut,
+        id: u64,
+        limit: Option<u32>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let snapshot_id = SNAPSHOT_CURSORS
+            .prefix(id)
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .ok_or_else(|| {
+                StdError::generic_err(format!("badge {} has no snapshot in progress", id))
+            })?;
+
+        let limit = limit.unwrap_or(DEFAULT_SNAPSHOT_CHUNK).clamp(1, MAX_SNAPSHOT_CHUNK);
+        let remaining = self.walk_snapshot(deps.storage, id, snapshot_id, limit)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "resume_snapshot")
+            .add_attribute("id", id.to_string())
+            .add_attribute("snapshot", snapshot_id.to_string())
+            .add_attribute("remaining", remaining.to_string()))
+    }
+
+    /// Record the holders of up to `limit` serials of a snapshot in progress, advancing its cursor.
+    /// Returns the number of serials left to be walked.
+    fn walk_snapshot(
+        &self,
+        store: &mut dyn Storage,
+        id: u64,
+        snapshot_id: u64,
+        limit: u32,
+    ) -> StdResult<u64> {
+        let (next_serial, last_serial) = SNAPSHOT_CURSORS.load(store, (id, snapshot_id))?;
+        let end_serial = last_serial.min(next_serial + limit as u64 - 1);
+
+        let mut serials_by_owner: BTreeMap<Addr, Vec<u64>> = BTreeMap::new();
+        let mut snapshot = SNAPSHOTS.load(store, (id, snapshot_id))?;
+        for serial in next_serial..=end_serial {
+            let token_id = match build_token_id(store, id, serial)? {
+                Some(token_id) => token_id,
+                None => continue,
+            };
+            if let Some(token) = self.parent.parent.tokens.may_load(store, &token_id)? {
+                serials_by_owner.entry(token.owner).or_default().push(serial);
+                snapshot.num_tokens += 1;
+            }
+        }
+
+        // owners may already have serials recorded from earlier chunks
+        for (owner, serials) in serials_by_owner {
+            let key = (id, snapshot_id, &owner);
+            let mut recorded = match SNAPSHOT_HOLDERS.may_load(store, key)? {
+                Some(recorded) => recorded,
+                None => {
+                    snapshot.num_holders += 1;
+                    vec![]
+                },
+            };
+            recorded.extend(serials);
+            SNAPSHOT_HOLDERS.save(store, key, &recorded)?;
+        }
+
+        SNAPSHOTS.save(store, (id, snapshot_id), &snapshot)?;
+
+        if end_serial >= last_serial {
+            SNAPSHOT_CURSORS.remove(store, (id, snapshot_id));
+        } else {
+            SNAPSHOT_CURSORS.save(store, (id, snapshot_id), &(end_serial + 1, last_serial))?;
+        }
+
+        Ok(last_serial.saturating_sub(end_serial))
+    }
+
+    /// Index the holdings of the next chunk of tokens minted before the holdings index existed,
+    /// in ascending order of token ids. Tokens that change hands or are burned during the
+    /// backfill are kept in the index as usual, so each token is indexed under its owner at the
+    /// time it's walked.
+    pub fn backfill_holdings(
+        &self,
+        deps: DepsMut,
+        limit: Option<u32>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let cursor = HOLDINGS_BACKFILL
+            .may_load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("holdings are not being backfilled"))?;
+
+        let limit = limit.unwrap_or(DEFAULT_BACKFILL_CHUNK).clamp(1, MAX_BACKFILL_CHUNK);
+
+        // the token map is keyed by the parent's own storage-plus version, so page through it with
+        // the parent's query rather than ranging over it directly
+        let tokens = self.parent.parent.all_tokens(deps.as_ref(), cursor, Some(limit))?.tokens;
+
+        for token_id in &tokens {
+            let owner = self.parent.parent.tokens.load(deps.storage, token_id)?.owner;
+            let (id, serial) = parse_token_id(deps.storage, token_id)?;
+            HOLDINGS.save(deps.storage, (&owner, id, serial), &Empty {})?;
+        }
+
+        // a short page means the end of the token map has been reached
+        let complete = tokens.len() < limit as usize;
+        if complete {
+            HOLDINGS_BACKFILL.remove(deps.storage);
+        } else {
+            let last = tokens.last().cloned();
+            HOLDINGS_BACKFILL.save(deps.storage, &last)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "backfill_holdings")
+            .add_attribute("indexed", tokens.len().to_string())
+            .add_attribute("complete", complete.to_string()))
+    }
+
+    /// Forward a message to the parent SG-721 contract.
+    ///
+    /// The parent doesn't know our extension messages; every other variant serializes identically
+    /// regardless of the extension type, so we convert via a JSON round trip.
+    ///
+    /// Mints and transfers change the owner of a token, so the holdings index is updated after
+    /// them.
+    pub fn execute_parent(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, sg721_base::ContractError> {
+        let token_id = match &msg {
+            ExecuteMsg::Mint(mint_msg) => Some(mint_msg.token_id.clone()),
+            ExecuteMsg::TransferNft {
+                token_id,
+                ..
+            }
+            | ExecuteMsg::SendNft {
+                token_id,
+                ..
+            } => Some(token_id.clone()),
+            _ => None,
+        };
+        let prev_owner = match &token_id {
+            Some(token_id) => {
+                self.parent.parent.tokens.may_load(deps.storage, token_id)?.map(|token| token.owner)
+            },
+            None => None,
+        };
+
+        let is_mint = matches!(msg, ExecuteMsg::Mint(_));
+        let msg: sg721::ExecuteMsg<Extension, Empty> = from_slice(&to_vec(&msg)?)?;
+        let res = self.parent.execute(deps.branch(), env, info, msg)?;
+
+        if let (true, Some(token_id)) = (is_mint, &token_id) {
+            self.record_token_serial(deps.branch(), token_id)?;
+        }
+
+        if let Some(token_id) = &token_id {
+            self.update_holding(deps.storage, token_id, prev_owner.as_ref())?;
+        }
+
+        Ok(res)
+    }
+
+    /// In the counter token id format, look up the badge id and serial of a newly minted token from
+    /// the Hub, which recorded them before minting it
+    pub fn record_token_serial(&self, deps: DepsMut, token_id: &str) -> StdResult<()> {
+        if TOKEN_ID_FORMAT.may_load(deps.storage)?.unwrap_or_default() != TokenIdFormat::Counter {
+            return Ok(());
+        }
+
+        let minter = self.parent.parent.minter(deps.as_ref())?;
+        let res: TokenSerialResponse = deps.querier.query_wasm_smart(
+            minter.minter,
+            &badges::hub::QueryMsg::TokenSerial {
+                token_id: token_id.to_string(),
+            },
+        )?;
+        TOKEN_SERIALS.save(deps.storage, token_id, &(res.id, res.serial))?;
+        TOKEN_IDS.save(deps.storage, (res.id, res.serial), &token_id.to_string())
+    }
+
+    /// Move a token's entry in the holdings index from its previous owner, if any, to its current
+    /// owner, unless the token no longer exists
+    pub fn update_holding(
+        &self,
+        store: &mut dyn Storage,
+        token_id: &str,
+        prev_owner: Option<&Addr>,
+    ) -> StdResult<()> {
+        let (id, serial) = parse_token_id(store, token_id)?;
+        if let Some(prev_owner) = prev_owner {
+            HOLDINGS.remove(store, (prev_owner, id, serial));
+        }
+        if let Some(token) = self.parent.parent.tokens.may_load(store, token_id)? {
+            HOLDINGS.save(store, (&token.owner, id, serial), &Empty {})?;
+        }
+        Ok(())
+    }
+
+    /// Whether the account is the Hub, i.e. the minter of this collection
+    pub fn is_hub(&self, deps: Deps, account: &Addr) -> StdResult<bool> {
+        let minter = self.parent.parent.minter(deps)?;
+        Ok(minter.minter == *account)
+    }
+
     /// Overrides vanilla cw721's `nft_info` method
-    pub fn nft_info(&self, deps: Deps, token_id: impl ToString) -> StdResult<NftInfoResponse> {
-        let (id, serial) = parse_token_id(&token_id.to_string())?;
+    ///
+    /// If the badge has a token URI template, the rendered URI is returned without any on-chain
+    /// metadata. Otherwise, the badge's metadata is returned in the `extension` field, in the same
+    /// shape as cw721-metadata-onchain, so that generic marketplaces and explorers can render it.
+    /// Media assets fill in the image and animation url if the metadata lacks them. If the badge
+    /// has an SVG template, it is rendered into the `image_data` field. If a locale is given, the
+    /// metadata is translated into it, falling back to the locale's language alone and then to the
+    /// untranslated metadata.
+    pub fn nft_info(
+        &self,
+        deps: Deps,
+        token_id: impl ToString,
+        locale: Option<&str>,
+    ) -> StdResult<NftInfoResponse> {
+        let (id, serial) = parse_token_id(deps.storage, &token_id.to_string())?;
+        let badge = self.load_metadata(deps, id)?;
+        if let Some(template) = badge.token_uri {
+            return Ok(NftInfoResponse {
+                token_uri: Some(render_token_uri(&template, id, serial)),
+                extension: Metadata::default(),
+            });
+        }
         let uri = uri(deps.storage, id, serial)?;
-        let badge = self.query_badge(deps, id)?;
+        let serial_traits = SERIAL_TRAITS.may_load(deps.storage, (id, serial))?.unwrap_or_default();
+        let metadata = match locale {
+            Some(locale) => self.translate(deps, id, locale, badge.metadata)?,
+            None => badge.metadata,
+        };
+        let media = MEDIA.may_load(deps.storage, id)?.unwrap_or_default();
+        let mut metadata = apply_media(override_traits(metadata, serial_traits), &media);
+        if let Some(template) = SVG_TEMPLATES.may_load(deps.storage, id)? {
+            metadata.image_data = Some(template);
+        }
+        let metadata = self.fill_placeholders(deps, id, serial, metadata)?;
         Ok(NftInfoResponse {
             token_uri: Some(uri),
-            extension: prepend_traits(badge.metadata, id, serial),
+            extension: append_serial(
+                prepend_traits(metadata, id, serial),
+                serial,
+                badge.max_supply,
+            ),
         })
     }
 
@@ -73,6 +612,7 @@ impl<'a> NftContract<'a> {
         env: Env,
         token_id: impl ToString,
         include_expired: Option<bool>,
+        locale: Option<&str>,
     ) -> StdResult<AllNftInfoResponse> {
         let access = self.parent.parent.owner_of(
             deps,
@@ -80,13 +620,284 @@ impl<'a> NftContract<'a> {
             token_id.to_string(),
             include_expired.unwrap_or(false),
         )?;
-        let info = self.nft_info(deps, token_id)?;
+        let info = self.nft_info(deps, token_id, locale)?;
         Ok(AllNftInfoResponse {
             access,
             info,
         })
     }
 
+    /// Implements CW-2981's `royalty_info` method. The royalty is configured per badge at the Hub.
+    pub fn royalty_info(
+        &self,
+        deps: Deps,
+        token_id: impl ToString,
+        sale_price: Uint128,
+    ) -> StdResult<RoyaltiesInfoResponse> {
+        let (id, _) = parse_token_id(deps.storage, &token_id.to_string())?;
+        let badge = self.query_badge(deps, id)?;
+        Ok(match badge.royalty {
+            Some(royalty) => RoyaltiesInfoResponse {
+                address: royalty.recipient,
+                royalty_amount: sale_price.multiply_ratio(royalty.bps, BPS_DENOMINATOR),
+            },
+            None => RoyaltiesInfoResponse {
+                address: String::new(),
+                royalty_amount: Uint128::zero(),
+            },
+        })
+    }
+
+    /// Enumerate the existing tokens of a badge, in ascending order of serial numbers.
+    ///
+    /// Tokens are keyed by their string ids, which don't sort by serial number. Instead, we walk
+    /// the serial numbers up to the badge's current supply, skipping the ones that have been
+    /// burned. At most `MAX_SERIALS_SCANNED` serials are walked per query, so that a long run of
+    /// burned tokens can't make it run out of gas; the response tells where to resume from.
+    pub fn tokens_by_badge(
+        &self,
+        deps: Deps,
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensByBadgeResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let badge = self.query_badge(deps, id)?;
+
+        let mut serial = start_after.unwrap_or(0);
+        let end = badge.current_supply.min(serial.saturating_add(MAX_SERIALS_SCANNED));
+
+        let mut tokens = vec![];
+        while tokens.len() < limit && serial < end {
+            serial += 1;
+            let token_id = match build_token_id(deps.storage, id, serial)? {
+                Some(token_id) => token_id,
+                None => continue,
+            };
+            if let Some(token) = self.parent.parent.tokens.may_load(deps.storage, &token_id)? {
+                tokens.push(BadgeTokenResponse {
+                    token_id,
+                    serial,
+                    owner: token.owner.into(),
+                });
+            }
+        }
+
+        Ok(TokensByBadgeResponse {
+            tokens,
+            next_start_after: (serial < badge.current_supply).then_some(serial),
+        })
+    }
+
+    /// The tokens held by an account, grouped by badge, in ascending order of badge ids.
+    ///
+    /// The holdings index is sorted by badge id and then by serial, so the account's tokens are
+    /// grouped as they are read, stopping as soon as the page is full.
+    pub fn portfolio(
+        &self,
+        deps: Deps,
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<PortfolioResponse> {
+        // until all existing tokens are indexed, the results would be incomplete
+        if HOLDINGS_BACKFILL.may_load(deps.storage)?.is_some() {
+            return Err(StdError::generic_err("holdings are still being backfilled"));
+        }
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let start = start_after.map(|id| Bound::exclusive((id, u64::MAX)));
+
+        let mut serials_by_badge: Vec<(u64, Vec<u64>)> = vec![];
+        let index = HOLDINGS.sub_prefix(&owner_addr);
+        for key in index.keys(deps.storage, start, None, Order::Ascending) {
+            let (id, serial) = key?;
+            if let Some((last_id, serials)) = serials_by_badge.last_mut() {
+                if *last_id == id {
+                    serials.push(serial);
+                    continue;
+                }
+            }
+            if serials_by_badge.len() == limit {
+                break;
+            }
+            serials_by_badge.push((id, vec![serial]));
+        }
+
+        let holdings = serials_by_badge
+            .into_iter()
+            .map(|(id, serials)| {
+                let badge = self.load_metadata(deps, id)?;
+                Ok(HoldingResponse {
+                    id,
+                    serials,
+                    name: badge.metadata.name,
+                    image: badge.metadata.image,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(PortfolioResponse {
+            owner,
+            holdings,
+        })
+    }
+
+    pub fn snapshots(
+        &self,
+        deps: Deps,
+        id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<SnapshotsResponse> {
+        let start = start_after.map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let snapshots = SNAPSHOTS
+            .prefix(id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (snapshot, data) = item?;
+                Ok(SnapshotResponse {
+                    snapshot,
+                    height: data.height,
+                    num_holders: data.num_holders,
+                    num_tokens: data.num_tokens,
+                    complete: !SNAPSHOT_CURSORS.has(deps.storage, (id, snapshot)),
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(SnapshotsResponse {
+            id,
+            snapshots,
+        })
+    }
+
+    pub fn snapshot_holders(
+        &self,
+        deps: Deps,
+        id: u64,
+        snapshot: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<SnapshotHoldersResponse> {
+        if SNAPSHOT_CURSORS.has(deps.storage, (id, snapshot)) {
+            return Err(StdError::generic_err(format!(
+                "snapshot {} of badge {} is still in progress",
+                snapshot, id,
+            )));
+        }
+
+        let start = start_after.map(|owner| Bound::ExclusiveRaw(owner.into_bytes()));
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let holders = SNAPSHOT_HOLDERS
+            .prefix((id, snapshot))
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (owner, serials) = item?;
+                Ok(SnapshotHolderResponse {
+                    owner: owner.into(),
+                    serials,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(SnapshotHoldersResponse {
+            id,
+            snapshot,
+            holders,
+        })
+    }
+
+    /// Apply the first translation of a badge's metadata found for the locale or its fallbacks
+    fn translate(
+        &self,
+        deps: Deps,
+        id: u64,
+        locale: &str,
+        metadata: Metadata,
+    ) -> StdResult<Metadata> {
+        for locale in locale_fallbacks(locale) {
+            if let Some(translation) = LOCALIZED_METADATA.may_load(deps.storage, (id, locale))? {
+                return Ok(localize(metadata, translation));
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Substitute the `{serial}`, `{total_supply}` and `{owner}` placeholders in the metadata's
+    /// strings and trait values, so that a single badge can carry personalized metadata.
+    ///
+    /// The Hub is only queried for the badge's current supply, and the token's owner only looked
+    /// up, if the corresponding placeholder is actually used. If the token does not exist (e.g. it
+    /// has been burned), `{owner}` is left as is.
+    fn fill_placeholders(
+        &self,
+        deps: Deps,
+        id: u64,
+        serial: u64,
+        mut metadata: Metadata,
+    ) -> StdResult<Metadata> {
+        let mut fields: Vec<&mut String> = [
+            &mut metadata.name,
+            &mut metadata.description,
+            &mut metadata.image,
+            &mut metadata.image_data,
+            &mut metadata.external_url,
+            &mut metadata.animation_url,
+            &mut metadata.youtube_url,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        fields.extend(metadata.attributes.iter_mut().flatten().map(|attr| &mut attr.value));
+
+        let uses = |placeholder: &str| fields.iter().any(|field| field.contains(placeholder));
+
+        let mut values = vec![("{serial}", serial.to_string())];
+        if uses("{total_supply}") {
+            let badge = self.query_badge(deps, id)?;
+            values.push(("{total_supply}", badge.current_supply.to_string()));
+        }
+        if uses("{owner}") {
+            if let Some(token_id) = build_token_id(deps.storage, id, serial)? {
+                if let Some(token) = self.parent.parent.tokens.may_load(deps.storage, &token_id)? {
+                    values.push(("{owner}", token.owner.into()));
+                }
+            }
+        }
+
+        for field in fields {
+            for (placeholder, value) in &values {
+                if field.contains(placeholder) {
+                    *field = field.replace(placeholder, value);
+                }
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// The badge's metadata, from the cache if the Hub has pushed it, otherwise from the Hub.
+    fn load_metadata(&self, deps: Deps, id: u64) -> StdResult<MetadataCache> {
+        if let Some(cache) = METADATA_CACHE.may_load(deps.storage, id)? {
+            return Ok(cache);
+        }
+
+        let badge = self.query_badge(deps, id)?;
+        Ok(MetadataCache {
+            metadata: badge.metadata,
+            token_uri: badge.token_uri,
+            max_supply: badge.max_supply,
+        })
+    }
+
     /// To save storage space, we save the badge's metadata at the Hub contract, instead of saving
     /// a separate copy in each token's extension. This function queries the Hub contract for the
     /// metadata of a given token id.
@@ -96,9 +907,18 @@ impl<'a> NftContract<'a> {
             &minter.minter,
             &badges::hub::QueryMsg::Badge {
                 id,
+                locale: None,
             },
         )
     }
+
+    /// The Hub's developer, who can take snapshots of any badge
+    fn query_developer(&self, deps: Deps) -> StdResult<Addr> {
+        let minter = self.parent.parent.minter(deps)?;
+        let config: ConfigResponse =
+            deps.querier.query_wasm_smart(&minter.minter, &badges::hub::QueryMsg::Config {})?;
+        deps.api.addr_validate(&config.developer)
+    }
 }
 
 /// URL of an API serving the metadata of the NFT.
@@ -111,22 +931,82 @@ pub fn uri(store: &dyn Storage, id: u64, serial: u64) -> StdResult<String> {
     Ok(format!("{}?id={}&serial={}", api_url, id, serial))
 }
 
-/// Split a token id into badge id and serial number.
-/// The token id must be in the format `{u64}|{u64}`, where the 1st number is id and 2nd is serial.
-pub fn parse_token_id(token_id: &str) -> StdResult<(u64, u64)> {
-    let split = token_id.split('|').collect::<Vec<&str>>();
-    if split.len() != 2 {
-        return Err(StdError::generic_err(format!(
-            "invalid token id `{}`: must be in the format {{serial}}|{{id}}",
-            token_id
-        )));
+/// The token's serial number, and the badge's max supply if any, are appended to its name, e.g.
+/// `Test Badge #3/100`, so that tokens of the same badge can be told apart in wallets.
+pub fn append_serial(mut metadata: Metadata, serial: u64, max_supply: Option<u64>) -> Metadata {
+    if let Some(name) = metadata.name {
+        metadata.name = Some(match max_supply {
+            Some(max_supply) => format!("{} #{}/{}", name, serial, max_supply),
+            None => format!("{} #{}", name, serial),
+        });
     }
+    metadata
+}
+
+/// Render a badge's token URI template for a given token, replacing the `{id}` and `{serial}`
+/// placeholders.
+pub fn render_token_uri(template: &str, id: u64, serial: u64) -> String {
+    template.replace("{id}", &id.to_string()).replace("{serial}", &serial.to_string())
+}
 
-    let id = u64::from_str(split[0]).map_err(|err| StdError::parse_err(type_name::<u64>(), err))?;
-    let serial =
-        u64::from_str(split[1]).map_err(|err| StdError::parse_err(type_name::<u64>(), err))?;
+/// Build the token id of a badge id and serial number, according to the token id format the
+/// contract was instantiated with. In the counter format, returns None if no token has been minted
+/// with the serial.
+pub fn build_token_id(store: &dyn Storage, id: u64, serial: u64) -> StdResult<Option<String>> {
+    match TOKEN_ID_FORMAT.may_load(store)?.unwrap_or_default() {
+        TokenIdFormat::Counter => TOKEN_IDS.may_load(store, (id, serial)),
+        format => format.build(id, serial).map(Some),
+    }
+}
 
-    Ok((id, serial))
+/// Split a token id into badge id and serial number, according to the token id format the contract
+/// was instantiated with.
+pub fn parse_token_id(store: &dyn Storage, token_id: &str) -> StdResult<(u64, u64)> {
+    match TOKEN_ID_FORMAT.may_load(store)?.unwrap_or_default() {
+        TokenIdFormat::Counter => TOKEN_SERIALS
+            .may_load(store, token_id)?
+            .ok_or_else(|| StdError::generic_err(format!("token {} not found", token_id))),
+        format => format.parse(token_id),
+    }
+}
+
+/// Merge traits attached to a single instance into the badge's shared traits. A shared trait of the
+/// same type as an instance's trait is replaced by it; the other instance traits are appended.
+pub fn override_traits(mut metadata: Metadata, overrides: Vec<Trait>) -> Metadata {
+    if overrides.is_empty() {
+        return metadata;
+    }
+
+    let mut traits = metadata.attributes.unwrap_or_default();
+    for attr in overrides {
+        match traits.iter_mut().find(|t| t.trait_type == attr.trait_type) {
+            Some(existing) => *existing = attr,
+            None => traits.push(attr),
+        }
+    }
+
+    metadata.attributes = Some(traits);
+    metadata
+}
+
+/// Fill in the image and animation url of metadata that lacks them from the badge's media assets,
+/// so that clients unaware of the assets can still render the badge. The animation url is taken
+/// from the first video, 3D model or audio asset, in this order of preference.
+pub fn apply_media(mut metadata: Metadata, media: &[MediaAsset]) -> Metadata {
+    let find = |purpose: MediaPurpose| {
+        media.iter().find(|asset| asset.purpose == purpose).map(|asset| asset.uri.clone())
+    };
+
+    if metadata.image.is_none() {
+        metadata.image = find(MediaPurpose::Image);
+    }
+    if metadata.animation_url.is_none() {
+        metadata.animation_url = find(MediaPurpose::Video)
+            .or_else(|| find(MediaPurpose::Model))
+            .or_else(|| find(MediaPurpose::Audio));
+    }
+
+    metadata
 }
 
 /// The badge's id and serial are prepended to it's list of traits.
@@ -144,7 +1024,7 @@ pub fn prepend_traits(mut metadata: Metadata, id: u64, serial: u64) -> Metadata
         },
     ];
 
-    traits.extend(metadata.attributes.unwrap_or_default().into_iter());
+    traits.extend(metadata.attributes.unwrap_or_default());
 
     metadata.attributes = Some(traits);
     metadata
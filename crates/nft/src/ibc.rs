@@ -0,0 +1,276 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, DepsMut, Env, IbcBasicResponse, IbcChannel,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg,
+    IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, IbcTimeout, MessageInfo, StdError, StdResult, Storage,
+};
+use sg_std::Response;
+
+use badges::nft::{Ics721Ack, NonFungibleTokenPacketData};
+
+use crate::contract::NftContract;
+use crate::state::{IBC_CHANNELS, IBC_ESCROW};
+
+/// The channel version of ICS-721
+pub const IBC_VERSION: &str = "ics721-1";
+
+/// How long relayers have to deliver a transfer, if the sender doesn't specify
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 600;
+
+impl<'a> NftContract<'a> {
+    /// Move a token into escrow and send it to another chain over an ICS-721 channel.
+    ///
+    /// The packet carries the token's URI and on-chain metadata, as rendered from the badge at the
+    /// Hub, so that the receiving chain can display the token without querying this chain.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ibc_transfer(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        channel_id: String,
+        receiver: String,
+        timeout_seconds: Option<u64>,
+    ) -> Result<Response, sg721_base::ContractError> {
+        self.assert_transferrable(deps.as_ref(), &token_id)?;
+
+        if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+            return Err(StdError::generic_err(format!("unknown ics721 channel `{}`", channel_id))
+                .into());
+        }
+
+        let nft_info = self.nft_info(deps.as_ref(), &token_id, None)?;
+
+        let owner = self.parent.parent.tokens.load(deps.storage, &token_id)?.owner;
+
+        // this checks that the sender is the token's owner or an approved spender
+        self.parent.parent._transfer_nft(
+            deps.branch(),
+            &env,
+            &info,
+            env.contract.address.as_str(),
+            &token_id,
+        )?;
+        self.update_holding(deps.storage, &token_id, Some(&owner))?;
+        // the sender may be a spender or operator, so remember the owner to refund the token to
+        IBC_ESCROW.save(deps.storage, &token_id, &(channel_id.clone(), owner))?;
+
+        let data = NonFungibleTokenPacketData {
+            class_id: env.contract.address.to_string(),
+            class_uri: None,
+            class_data: None,
+            token_ids: vec![token_id.clone()],
+            token_uris: Some(vec![nft_info.token_uri.unwrap_or_default()]),
+            token_data: Some(vec![to_binary(&nft_info.extension)?]),
+            sender: info.sender.to_string(),
+            receiver: receiver.clone(),
+            memo: None,
+        };
+        let timeout_seconds = timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+
+        Ok(Response::new()
+            .add_message(IbcMsg::SendPacket {
+                channel_id: channel_id.clone(),
+                data: to_binary(&data)?,
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(timeout_seconds)),
+            })
+            .add_attribute("action", "ibc_transfer")
+            .add_attribute("token_id", token_id)
+            .add_attribute("channel_id", channel_id)
+            .add_attribute("receiver", receiver))
+    }
+
+    /// Only accept unordered channels speaking ICS-721, as ordered channels would be closed by a
+    /// single timed out packet.
+    pub fn ibc_channel_open(&self, msg: IbcChannelOpenMsg) -> StdResult<IbcChannelOpenResponse> {
+        assert_valid_channel(msg.channel(), msg.counterparty_version())?;
+        Ok(None)
+    }
+
+    pub fn ibc_channel_connect(
+        &self,
+        deps: DepsMut,
+        msg: IbcChannelConnectMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        let channel = msg.channel();
+        assert_valid_channel(channel, msg.counterparty_version())?;
+
+        IBC_CHANNELS.save(
+            deps.storage,
+            &channel.endpoint.channel_id,
+            &channel.counterparty_endpoint,
+        )?;
+
+        Ok(IbcBasicResponse::new()
+            .add_attribute("action", "ibc_channel_connect")
+            .add_attribute("channel_id", &channel.endpoint.channel_id))
+    }
+
+    /// Closing a channel would strand the tokens escrowed for it, so we refuse to initiate it. If
+    /// the counterparty closes it anyway, the channel can no longer be used to send tokens.
+    pub fn ibc_channel_close(
+        &self,
+        deps: DepsMut,
+        msg: IbcChannelCloseMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        match msg {
+            IbcChannelCloseMsg::CloseInit {
+                ..
+            } => Err(StdError::generic_err("ics721 channels cannot be closed")),
+            IbcChannelCloseMsg::CloseConfirm {
+                channel,
+            } => {
+                IBC_CHANNELS.remove(deps.storage, &channel.endpoint.channel_id);
+                Ok(IbcBasicResponse::new()
+                    .add_attribute("action", "ibc_channel_close")
+                    .add_attribute("channel_id", channel.endpoint.channel_id))
+            },
+        }
+    }
+
+    /// Only tokens that originate from this collection can be received, i.e. ones coming back
+    /// from the chain they were sent to. Failures are reported to the sending chain as an error
+    /// acknowledgement, so that it can refund the tokens.
+    pub fn ibc_packet_receive(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: IbcPacketReceiveMsg,
+    ) -> StdResult<IbcReceiveResponse> {
+        match self.receive_returning_tokens(deps, &env, &msg.packet) {
+            Ok(receiver) => Ok(IbcReceiveResponse::new()
+                .set_ack(to_binary(&Ics721Ack::Result(Binary::from(vec![1])))?)
+                .add_attribute("action", "ibc_packet_receive")
+                .add_attribute("receiver", receiver)),
+            Err(err) => Ok(IbcReceiveResponse::new()
+                .set_ack(to_binary(&Ics721Ack::Error(err.to_string()))?)
+                .add_attribute("action", "ibc_packet_receive")
+                .add_attribute("error", err.to_string())),
+        }
+    }
+
+    /// If the receiving chain failed to process the transfer, the tokens are returned to the
+    /// accounts that owned them before they were escrowed
+    pub fn ibc_packet_ack(
+        &self,
+        deps: DepsMut,
+        msg: IbcPacketAckMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        match from_binary(&msg.acknowledgement.data)? {
+            Ics721Ack::Result(_) => Ok(IbcBasicResponse::new()
+                .add_attribute("action", "ibc_packet_ack")
+                .add_attribute("success", "true")),
+            Ics721Ack::Error(err) => {
+                self.refund(deps.storage, &msg.original_packet)?;
+                Ok(IbcBasicResponse::new()
+                    .add_attribute("action", "ibc_packet_ack")
+                    .add_attribute("success", "false")
+                    .add_attribute("error", err))
+            },
+        }
+    }
+
+    pub fn ibc_packet_timeout(
+        &self,
+        deps: DepsMut,
+        msg: IbcPacketTimeoutMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        self.refund(deps.storage, &msg.packet)?;
+        Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_timeout"))
+    }
+
+    /// Release tokens that come back through the channel they were sent out from. Everything is
+    /// validated before any token is released, so that an error acknowledgement leaves no partial
+    /// changes behind.
+    fn receive_returning_tokens(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        packet: &IbcPacket,
+    ) -> StdResult<Addr> {
+        let data: NonFungibleTokenPacketData = from_binary(&packet.data)?;
+
+        // the chain we sent the tokens to has prefixed the class id with its end of the channel
+        let class_id = format!(
+            "{}/{}/{}",
+            packet.src.port_id, packet.src.channel_id, env.contract.address,
+        );
+        if data.class_id != class_id {
+            return Err(StdError::generic_err(format!(
+                "class `{}` does not originate from this collection",
+                data.class_id,
+            )));
+        }
+
+        let receiver = deps.api.addr_validate(&data.receiver)?;
+
+        for token_id in &data.token_ids {
+            let escrow = IBC_ESCROW.may_load(deps.storage, token_id)?;
+            if escrow.map(|(channel_id, _)| channel_id).as_ref() != Some(&packet.dest.channel_id) {
+                return Err(StdError::generic_err(format!(
+                    "token `{}` was not sent through channel `{}`",
+                    token_id, packet.dest.channel_id,
+                )));
+            }
+        }
+
+        for token_id in &data.token_ids {
+            self.release(deps.storage, token_id, &receiver)?;
+        }
+
+        Ok(receiver)
+    }
+
+    /// Return tokens to their owners. Tokens no longer in escrow, e.g. because the Hub revoked
+    /// them in the meantime, are skipped, so that the packet can still be settled.
+    fn refund(&self, store: &mut dyn Storage, packet: &IbcPacket) -> StdResult<()> {
+        let data: NonFungibleTokenPacketData = from_binary(&packet.data)?;
+
+        for token_id in &data.token_ids {
+            if let Some((_, owner)) = IBC_ESCROW.may_load(store, token_id)? {
+                self.release(store, token_id, &owner)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take a token out of escrow and hand it to the recipient
+    fn release(&self, store: &mut dyn Storage, token_id: &str, recipient: &Addr) -> StdResult<()> {
+        IBC_ESCROW.remove(store, token_id);
+
+        let escrow = self.parent.parent.tokens.may_load(store, token_id)?.map(|token| token.owner);
+
+        self.parent.parent.tokens.update(store, token_id, |token| match token {
+            Some(mut token) => {
+                token.owner = recipient.clone();
+                token.approvals = vec![];
+                Ok(token)
+            },
+            None => Err(StdError::generic_err(format!("token `{}` does not exist", token_id))),
+        })?;
+
+        self.update_holding(store, token_id, escrow.as_ref())
+    }
+}
+
+fn assert_valid_channel(
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> StdResult<()> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(StdError::generic_err("ics721 channels must be unordered"));
+    }
+
+    for version in [Some(channel.version.as_str()), counterparty_version].into_iter().flatten() {
+        if version != IBC_VERSION {
+            return Err(StdError::generic_err(format!(
+                "invalid channel version `{}`: expected `{}`",
+                version, IBC_VERSION,
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,22 @@
 pub mod contract;
+pub mod ibc;
 pub mod state;
 pub mod upgrades;
 
 #[cfg(not(feature = "library"))]
 pub mod entry {
     use cosmwasm_std::{
-        entry_point, to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, StdResult,
+        entry_point, to_binary, Binary, Deps, DepsMut, Empty, Env, IbcBasicResponse,
+        IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse,
+        IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
+        MessageInfo, StdResult,
     };
     use sg721_base::ContractError;
     use sg_std::Response;
 
-    use badges::nft::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use badges::nft::{
+        CheckRoyaltiesResponse, Cw2981QueryMsg, ExecuteMsg, ExtensionMsg, InstantiateMsg, QueryMsg,
+    };
 
     use crate::{contract::*, upgrades};
 
@@ -33,7 +39,126 @@ pub mod entry {
     ) -> Result<Response, ContractError> {
         let tract = NftContract::default();
         // Transfers and approvals are only allowed if the badge is transferrable
+        // The Hub can burn any token, so that revocable badges can be revoked. Burns by owners are
+        // reported back to the Hub. The admin can point the collection to a new Hub, the Hub can
+        // cache badge metadata, media assets, translations, SVG templates and traits of single
+        // instances, and a badge's manager can snapshot its holders
         match &msg {
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::UpdateMinter {
+                        minter,
+                    },
+            } => {
+                return tract.update_minter(deps, env, info, minter.clone());
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::CacheMetadata {
+                        id,
+                        cache,
+                    },
+            } => {
+                return tract.cache_metadata(deps, info, *id, cache.clone());
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::SetSvgTemplate {
+                        id,
+                        template,
+                    },
+            } => {
+                return tract.set_svg_template(deps, info, *id, template.clone());
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::IbcTransfer {
+                        token_id,
+                        channel_id,
+                        receiver,
+                        timeout_seconds,
+                    },
+            } => {
+                return tract.ibc_transfer(
+                    deps,
+                    env,
+                    info,
+                    token_id.clone(),
+                    channel_id.clone(),
+                    receiver.clone(),
+                    *timeout_seconds,
+                );
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::SetMedia {
+                        id,
+                        media,
+                    },
+            } => {
+                return tract.set_media(deps, info, *id, media.clone());
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::SetLocalizedMetadata {
+                        id,
+                        locale,
+                        metadata,
+                    },
+            } => {
+                return tract.set_localized_metadata(
+                    deps,
+                    info,
+                    *id,
+                    locale.clone(),
+                    metadata.clone(),
+                );
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::SetSerialTraits {
+                        id,
+                        serial,
+                        traits,
+                    },
+            } => {
+                return tract.set_serial_traits(deps, info, *id, *serial, traits.clone());
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::TakeSnapshot {
+                        id,
+                    },
+            } => {
+                return tract.take_snapshot(deps, env, info, *id);
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::ResumeSnapshot {
+                        id,
+                        limit,
+                    },
+            } => {
+                return tract.resume_snapshot(deps, *id, *limit);
+            },
+            ExecuteMsg::Extension {
+                msg:
+                    ExtensionMsg::BackfillHoldings {
+                        limit,
+                    },
+            } => {
+                return tract.backfill_holdings(deps, *limit);
+            },
+            ExecuteMsg::Burn {
+                token_id,
+            } if tract.is_hub(deps.as_ref(), &info.sender)? => {
+                return tract.burn_by_hub(deps, info, token_id.clone());
+            },
+            ExecuteMsg::Burn {
+                token_id,
+            } => {
+                return tract.burn(deps, env, info, token_id.clone());
+            },
             ExecuteMsg::TransferNft {
                 token_id,
                 ..
@@ -48,28 +173,124 @@ pub mod entry {
             } => tract.assert_transferrable(deps.as_ref(), token_id)?,
             _ => (),
         }
-        tract.parent.execute(deps, env, info, msg)
+        tract.execute_parent(deps, env, info, msg)
     }
 
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         let tract = NftContract::default();
-        // We implement two custom query methods: `nft_info` and `all_nft_info`.
-        // For all other queries, simply dispatch them to the parent.
+        // We implement two custom query methods: `nft_info` and `all_nft_info`, as well as the
+        // CW-2981 royalty extension, `tokens_by_badge`, `portfolio` and the snapshot queries. For
+        // all other queries, simply dispatch them to the parent.
         match msg {
             QueryMsg::NftInfo {
                 token_id,
-            } => to_binary(&tract.nft_info(deps, token_id)?),
+                locale,
+            } => to_binary(&tract.nft_info(deps, token_id, locale.as_deref())?),
             QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
-            } => to_binary(&tract.all_nft_info(deps, env, token_id, include_expired)?),
-            _ => tract.parent.query(deps, env, msg),
+                locale,
+            } => to_binary(&tract.all_nft_info(
+                deps,
+                env,
+                token_id,
+                include_expired,
+                locale.as_deref(),
+            )?),
+            QueryMsg::Extension {
+                msg:
+                    Cw2981QueryMsg::RoyaltyInfo {
+                        token_id,
+                        sale_price,
+                    },
+            } => to_binary(&tract.royalty_info(deps, token_id, sale_price)?),
+            QueryMsg::Extension {
+                msg: Cw2981QueryMsg::CheckRoyalties {},
+            } => to_binary(&CheckRoyaltiesResponse {
+                royalty_payments: true,
+            }),
+            QueryMsg::TokensByBadge {
+                id,
+                start_after,
+                limit,
+            } => to_binary(&tract.tokens_by_badge(deps, id, start_after, limit)?),
+            QueryMsg::Portfolio {
+                owner,
+                start_after,
+                limit,
+            } => to_binary(&tract.portfolio(deps, owner, start_after, limit)?),
+            QueryMsg::Snapshots {
+                id,
+                start_after,
+                limit,
+            } => to_binary(&tract.snapshots(deps, id, start_after, limit)?),
+            QueryMsg::SnapshotHolders {
+                id,
+                snapshot,
+                start_after,
+                limit,
+            } => to_binary(&tract.snapshot_holders(deps, id, snapshot, start_after, limit)?),
+            _ => tract.parent.query(deps, env, msg.try_into()?),
         }
     }
 
+    #[entry_point]
+    pub fn ibc_channel_open(
+        _deps: DepsMut,
+        _env: Env,
+        msg: IbcChannelOpenMsg,
+    ) -> StdResult<IbcChannelOpenResponse> {
+        NftContract::default().ibc_channel_open(msg)
+    }
+
+    #[entry_point]
+    pub fn ibc_channel_connect(
+        deps: DepsMut,
+        _env: Env,
+        msg: IbcChannelConnectMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        NftContract::default().ibc_channel_connect(deps, msg)
+    }
+
+    #[entry_point]
+    pub fn ibc_channel_close(
+        deps: DepsMut,
+        _env: Env,
+        msg: IbcChannelCloseMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        NftContract::default().ibc_channel_close(deps, msg)
+    }
+
+    #[entry_point]
+    pub fn ibc_packet_receive(
+        deps: DepsMut,
+        env: Env,
+        msg: IbcPacketReceiveMsg,
+    ) -> StdResult<IbcReceiveResponse> {
+        NftContract::default().ibc_packet_receive(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn ibc_packet_ack(
+        deps: DepsMut,
+        _env: Env,
+        msg: IbcPacketAckMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        NftContract::default().ibc_packet_ack(deps, msg)
+    }
+
+    #[entry_point]
+    pub fn ibc_packet_timeout(
+        deps: DepsMut,
+        _env: Env,
+        msg: IbcPacketTimeoutMsg,
+    ) -> StdResult<IbcBasicResponse> {
+        NftContract::default().ibc_packet_timeout(deps, msg)
+    }
+
     #[entry_point]
     pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
-        upgrades::v1_2::migrate(deps)
+        upgrades::v1_3::migrate(deps)
     }
 }
@@ -1,3 +1,66 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Empty, IbcEndpoint};
+use cw_storage_plus::{Item, Map};
+use sg_metadata::{Metadata, Trait};
+
+use badges::nft::{MetadataCache, Snapshot};
+use badges::{MediaAsset, TokenIdFormat};
 
 pub const API_URL: Item<String> = Item::new("api_url");
+
+/// How token ids are derived from badge ids and serials. Defaults to `{id}|{serial}` if not set.
+pub const TOKEN_ID_FORMAT: Item<TokenIdFormat> = Item::new("token_id_format");
+
+/// In the counter token id format, the badge id and serial of each token, looked up from the Hub
+/// when the token is minted, indexed by token id
+pub const TOKEN_SERIALS: Map<&str, (u64, u64)> = Map::new("token_serials");
+
+/// In the counter token id format, the id of each token, indexed by {badge_id, serial}
+pub const TOKEN_IDS: Map<(u64, u64), String> = Map::new("token_ids");
+
+/// Metadata of each badge, pushed by the Hub, indexed by badge id. Badges created before caching
+/// was introduced have no entry here; their metadata is queried from the Hub instead.
+pub const METADATA_CACHE: Map<u64, MetadataCache> = Map::new("metadata_cache");
+
+/// SVG templates of badges whose images are rendered on-chain, pushed by the Hub, indexed by badge
+/// ids
+pub const SVG_TEMPLATES: Map<u64, String> = Map::new("svg_templates");
+
+/// Media assets attached to badges, pushed by the Hub, indexed by badge ids
+pub const MEDIA: Map<u64, Vec<MediaAsset>> = Map::new("media");
+
+/// Translations of badges' metadata, pushed by the Hub, indexed by {badge_id, locale}
+pub const LOCALIZED_METADATA: Map<(u64, &str), Metadata> = Map::new("localized_metadata");
+
+/// Traits attached to individual instances of badges, pushed by the Hub, indexed by {badge_id,
+/// serial}. Only instances with such traits have an entry here.
+pub const SERIAL_TRAITS: Map<(u64, u64), Vec<Trait>> = Map::new("serial_traits");
+
+/// Snapshots of badges' holders, indexed by {badge_id, snapshot_id}. Snapshot ids of each badge
+/// are numbered from 1.
+pub const SNAPSHOTS: Map<(u64, u64), Snapshot> = Map::new("snapshots");
+
+/// Serials held by each account at the time of a snapshot, indexed by {badge_id, snapshot_id,
+/// owner}
+pub const SNAPSHOT_HOLDERS: Map<(u64, u64, &Addr), Vec<u64>> = Map::new("snapshot_holders");
+
+/// For snapshots still being taken, the next serial to be walked and the last serial to be walked,
+/// indexed by {badge_id, snapshot_id}. Removed once the snapshot is complete.
+pub const SNAPSHOT_CURSORS: Map<(u64, u64), (u64, u64)> = Map::new("snapshot_cursors");
+
+/// Serials of the badges held by each account, indexed by {owner, badge_id, serial}. Kept in sync
+/// with the token map on every mint, transfer and burn, so that an account's holdings can be
+/// enumerated in order of badge id without loading all of its tokens.
+pub const HOLDINGS: Map<(&Addr, u64, u64), Empty> = Map::new("holdings");
+
+/// While the holdings of tokens minted before the index was introduced are being backfilled, the
+/// id of the last token indexed, or None if none has been yet. Removed once the backfill is done.
+pub const HOLDINGS_BACKFILL: Item<Option<String>> = Item::new("holdings_backfill");
+
+/// ICS-721 channels that have completed the handshake, indexed by channel id, along with the
+/// counterparty's endpoint
+pub const IBC_CHANNELS: Map<&str, IbcEndpoint> = Map::new("ibc_channels");
+
+/// Tokens held in escrow while they are on another chain, indexed by token id, along with the
+/// channel they were sent through and the account that owned them. They can only come back through
+/// the same channel, and are refunded to that owner if the transfer fails.
+pub const IBC_ESCROW: Map<&str, (String, Addr)> = Map::new("ibc_escrow");
@@ -1 +1,2 @@
 pub mod v1_2;
+pub mod v1_3;
@@ -0,0 +1,30 @@
+use cosmwasm_std::{DepsMut, StdResult};
+use sg_std::Response;
+
+use crate::contract::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::state::HOLDINGS_BACKFILL;
+
+pub fn migrate(deps: DepsMut) -> StdResult<Response> {
+    // contracts deployed before v1.2 didn't record their version
+    let from_version = cw2::get_contract_version(deps.storage).ok().map(|info| info.version);
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // the holdings index was introduced in v1.3, so only tokens minted by earlier versions are
+    // missing from it. they are indexed in chunks with `BackfillHoldings`, as walking the whole
+    // token map in one transaction could run out of gas. in the meantime, the index is already
+    // updated along with every mint, transfer and burn
+    if from_version.is_none_or(|version| predates_holdings(&version)) {
+        HOLDINGS_BACKFILL.save(deps.storage, &None)?;
+    }
+
+    Ok(Response::new())
+}
+
+/// Whether the version is below 1.3.0, i.e. predates the holdings index
+fn predates_holdings(version: &str) -> bool {
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) < (1, 3)
+}
@@ -1,14 +1,13 @@
 #![allow(dead_code)]
 
-use std::collections::HashMap;
-
-use cosmwasm_std::testing::MockQuerier;
+use cosmwasm_std::testing::{MockQuerier, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Addr, ContractInfoResponse, Empty, Querier, QuerierResult,
     QueryRequest, SystemError, WasmQuery,
 };
 
-use badges::{hub, Badge};
+use badges::hub;
+use badges::testing::HubQuerier;
 
 pub struct CustomQuerier {
     pub base: MockQuerier<Empty>,
@@ -54,51 +53,6 @@ impl Querier for CustomQuerier {
     }
 }
 
-pub struct HubQuerier {
-    contract_addr: Addr,
-    badges: HashMap<u64, Badge>,
-}
-
-impl Default for HubQuerier {
-    fn default() -> Self {
-        HubQuerier {
-            contract_addr: Addr::unchecked("hub"),
-            badges: HashMap::default(),
-        }
-    }
-}
-
-impl HubQuerier {
-    pub fn set_badge(&mut self, id: u64, badge: Badge) {
-        self.badges.insert(id, badge);
-    }
-
-    pub fn handle_query(&self, contract_addr: &Addr, msg: hub::QueryMsg) -> QuerierResult {
-        if contract_addr != &self.contract_addr {
-            panic!(
-                "[mock]: made a badge hub query but addresses is incorrect: expected {}, found {}",
-                self.contract_addr, contract_addr
-            );
-        }
-
-        match msg {
-            hub::QueryMsg::Badge {
-                id,
-            } => {
-                let badge = self
-                    .badges
-                    .get(&id)
-                    .cloned()
-                    .unwrap_or_else(|| panic!("[mock]: cannot find badge with id {}", id));
-                let res = hub::BadgeResponse::from((id, badge));
-                Ok(to_binary(&res).into()).into()
-            },
-
-            _ => panic!("[mock]: unsupported hub query: {:?}", msg),
-        }
-    }
-}
-
 /// sg721 requires that the deployer must be a contract:
 /// https://github.com/public-awesome/launchpad/blob/v0.21.1/contracts/sg721-base/src/contract.rs#L39-L47
 ///
@@ -111,6 +65,13 @@ fn wasm_querier_handler(query: &WasmQuery) -> QuerierResult {
         } if contract_addr == "badge_hub" => {
             Ok(to_binary(&ContractInfoResponse::new(69420, "larry")).into()).into()
         },
+        WasmQuery::ContractInfo {
+            contract_addr,
+        } if contract_addr == MOCK_CONTRACT_ADDR => {
+            let mut info = ContractInfoResponse::new(69421, "larry");
+            info.admin = Some("larry".to_string());
+            Ok(to_binary(&info).into()).into()
+        },
         _ => panic!("[mock]: unimplemented wasm query: {query:?}"),
     }
 }
@@ -2,16 +2,39 @@ use std::any::type_name;
 use std::marker::PhantomData;
 
 use badge_nft::entry;
-use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
-use cosmwasm_std::{Addr, Empty, OwnedDeps, StdError};
-use cw721::{AllNftInfoResponse, Cw721Query};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{
+    mock_env, mock_ibc_channel_connect_ack, mock_ibc_channel_open_init, mock_ibc_packet_ack,
+    mock_ibc_packet_recv, mock_ibc_packet_timeout, mock_info, MockApi, MockStorage,
+    MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Binary, Deps, DepsMut, Empty, IbcAcknowledgement, IbcMsg,
+    IbcOrder, IbcTimeout, Order, OwnedDeps, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw721::{AllNftInfoResponse, Approval, Cw721Query, Cw721ReceiveMsg, Expiration};
 use cw721_base::MintMsg;
 use sg721::CollectionInfo;
+use sg721_base::ContractError;
 use sg_metadata::{Metadata, Trait};
+use sg_std::Response;
 
-use badge_nft::contract::{parse_token_id, prepend_traits, NftContract};
-use badges::nft::{ExecuteMsg, Extension, InstantiateMsg};
-use badges::{Badge, MintRule};
+use badge_nft::contract::{
+    append_serial, build_token_id, parse_token_id, prepend_traits, render_token_uri, NftContract,
+    CONTRACT_NAME, MAX_SERIALS_SCANNED,
+};
+use badge_nft::ibc::{DEFAULT_TIMEOUT_SECONDS, IBC_VERSION};
+use badge_nft::state::{
+    HOLDINGS, HOLDINGS_BACKFILL, IBC_CHANNELS, IBC_ESCROW, METADATA_CACHE, TOKEN_ID_FORMAT,
+};
+use badges::nft::{
+    ApprovalResponse, ApprovalsResponse, CheckRoyaltiesResponse, ContractInfoResponse,
+    Cw2981QueryMsg, ExecuteMsg, Extension, ExtensionMsg, Ics721Ack, InstantiateMsg, MetadataCache,
+    NonFungibleTokenPacketData, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+    PortfolioResponse, QueryMsg, RoyaltiesInfoResponse, SnapshotHoldersResponse, SnapshotResponse,
+    SnapshotsResponse, TokensByBadgeResponse, TokensResponse,
+};
+use badges::{Badge, MediaAsset, MediaPurpose, MintRule, RevenueShare, TokenIdFormat};
 
 mod mock_querier;
 
@@ -29,6 +52,22 @@ fn mock_metadata() -> Metadata {
     }
 }
 
+fn mock_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        hub: "hub".to_string(),
+        api_url: "https://badges-api.larry.engineer/metadata".to_string(),
+        collection_info: CollectionInfo {
+            creator: "larry".to_string(),
+            description: "this is a test".to_string(),
+            image: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            external_link: Some("https://larry.engineer/".to_string()),
+            explicit_content: None,
+            start_trading_time: None,
+            royalty_info: None,
+        },
+    }
+}
+
 fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier, Empty> {
     let mut deps = OwnedDeps {
         storage: MockStorage::default(),
@@ -42,11 +81,25 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier,
         Badge {
             manager: Addr::unchecked("larry"),
             metadata: mock_metadata(),
+            token_uri: None,
             transferrable: true,
+            revocable: false,
             rule: MintRule::ByKeys,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: None,
+            start_time: None,
             expiry: None,
             max_supply: None,
+            max_per_owner: None,
+            rate_limit: None,
             current_supply: 420,
+            num_burned: 0,
         },
     );
 
@@ -55,11 +108,25 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier,
         Badge {
             manager: Addr::unchecked("jake"),
             metadata: mock_metadata(),
+            token_uri: Some("ipfs://hash/{id}/{serial}.json".to_string()),
             transferrable: false,
+            revocable: false,
             rule: MintRule::ByKeys,
+            rule_frozen: false,
+            metadata_frozen: false,
+            paused: false,
+            archived: false,
+            hidden: false,
+            phases: vec![],
+            revenue_split: vec![],
+            royalty: Some(RevenueShare::new("jake", 500)),
+            start_time: None,
             expiry: None,
             max_supply: None,
+            max_per_owner: None,
+            rate_limit: None,
             current_supply: 88888,
+            num_burned: 0,
         },
     );
 
@@ -71,82 +138,170 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier,
     // to pass the test, we use a custom wasm query handler that returns "badge_hub"
     // as a valid contract, and make sure to use "badge_hub" here as the sender.
     contract
-        .instantiate(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("badge_hub", &[]),
-            InstantiateMsg {
-                hub: "hub".to_string(),
-                api_url: "https://badges-api.larry.engineer/metadata".to_string(),
-                collection_info: CollectionInfo {
-                    creator: "larry".to_string(),
-                    description: "this is a test".to_string(),
-                    image: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
-                    external_link: Some("https://larry.engineer/".to_string()),
-                    explicit_content: None,
-                    start_trading_time: None,
-                    royalty_info: None,
-                },
-            },
-        )
+        .instantiate(deps.as_mut(), mock_env(), mock_info("badge_hub", &[]), mock_instantiate_msg())
         .unwrap();
 
-    contract
-        .parent
-        .mint(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("hub", &[]),
-            MintMsg::<Extension> {
-                token_id: "69|420".to_string(),
-                owner: "jake".to_string(),
-                token_uri: None,
-                extension: None,
-            },
-        )
-        .unwrap();
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: "69|420".to_string(),
+            owner: "jake".to_string(),
+            token_uri: None,
+            extension: None,
+        }),
+    )
+    .unwrap();
 
-    contract
-        .parent
-        .mint(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("hub", &[]),
-            MintMsg::<Extension> {
-                token_id: "420|69".to_string(),
-                owner: "pumpkin".to_string(),
-                token_uri: None,
-                extension: None,
-            },
-        )
-        .unwrap();
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: "420|69".to_string(),
+            owner: "pumpkin".to_string(),
+            token_uri: None,
+            extension: None,
+        }),
+    )
+    .unwrap();
 
     deps
 }
 
 #[test]
 fn parsing_token_id() {
+    let format = TokenIdFormat::Pipe;
     assert_eq!(
-        parse_token_id("").unwrap_err(),
-        StdError::generic_err("invalid token id ``: must be in the format {serial}|{id}"),
+        format.parse("").unwrap_err(),
+        StdError::generic_err("invalid token id ``: must be in the format {id}|{serial}"),
     );
     assert_eq!(
-        parse_token_id("ngmi").unwrap_err(),
-        StdError::generic_err("invalid token id `ngmi`: must be in the format {serial}|{id}"),
+        format.parse("ngmi").unwrap_err(),
+        StdError::generic_err("invalid token id `ngmi`: must be in the format {id}|{serial}"),
     );
     assert_eq!(
-        parse_token_id("1|2|3").unwrap_err(),
-        StdError::generic_err("invalid token id `1|2|3`: must be in the format {serial}|{id}"),
+        format.parse("1|2|3").unwrap_err(),
+        StdError::generic_err("invalid token id `1|2|3`: must be in the format {id}|{serial}"),
     );
     assert_eq!(
-        parse_token_id("69|").unwrap_err(),
+        format.parse("69|").unwrap_err(),
         StdError::parse_err(type_name::<u64>(), "cannot parse integer from empty string"),
     );
     assert_eq!(
-        parse_token_id("69|hfsp").unwrap_err(),
+        format.parse("69|hfsp").unwrap_err(),
         StdError::parse_err(type_name::<u64>(), "invalid digit found in string"),
     );
-    assert_eq!(parse_token_id("69|420").unwrap(), (69, 420));
+    assert_eq!(format.parse("69|420").unwrap(), (69, 420));
+
+    let format = TokenIdFormat::Dash;
+    assert_eq!(format.build(69, 420).unwrap(), "69-420");
+    assert_eq!(format.parse("69-420").unwrap(), (69, 420));
+    assert_eq!(
+        format.parse("69|420").unwrap_err(),
+        StdError::generic_err("invalid token id `69|420`: must be in the format {id}-{serial}"),
+    );
+
+    let format = TokenIdFormat::ZeroPadded {
+        width: 6,
+    };
+    assert_eq!(format.build(69, 420).unwrap(), "000069-000420");
+    assert_eq!(format.parse("000069-000420").unwrap(), (69, 420));
+    // ids without the exact padding are rejected, so that each token has only one valid id
+    format.parse("69-000420").unwrap_err();
+    format.parse("0000069-000420").unwrap_err();
+
+    // the same goes for the unpadded formats
+    TokenIdFormat::Pipe.parse("69|0420").unwrap_err();
+    TokenIdFormat::Pipe.parse("+69|420").unwrap_err();
+    TokenIdFormat::Dash.parse("069-420").unwrap_err();
+
+    // counter ids don't contain the badge id and serial, so they must be looked up
+    let format = TokenIdFormat::Counter;
+    format.build(69, 420).unwrap_err();
+    format.parse("1234").unwrap_err();
+    assert_eq!(TokenIdFormat::build_counter(1234), "1234");
+    assert_eq!(TokenIdFormat::parse_counter("1234").unwrap(), 1234);
+    assert_eq!(
+        TokenIdFormat::parse_counter("01234").unwrap_err(),
+        StdError::generic_err("invalid token id `01234`: must be in the format {count}"),
+    );
+}
+
+#[test]
+fn parsing_token_id_in_configured_format() {
+    // the format is copied from the hub when the contract is instantiated
+    {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: mock_querier::CustomQuerier::default(),
+            custom_query_type: PhantomData,
+        };
+        deps.querier.hub.token_id_format = TokenIdFormat::Dash;
+
+        let msg = mock_instantiate_msg();
+        NftContract::default()
+            .instantiate(deps.as_mut(), mock_env(), mock_info("badge_hub", &[]), msg)
+            .unwrap();
+        assert_eq!(TOKEN_ID_FORMAT.load(deps.as_ref().storage).unwrap(), TokenIdFormat::Dash);
+    }
+
+    let mut deps = setup_test();
+
+    // the hub in the default setup uses the default format, i.e. pipes
+    assert_eq!(parse_token_id(deps.as_ref().storage, "69|420").unwrap(), (69, 420));
+
+    TOKEN_ID_FORMAT.save(deps.as_mut().storage, &TokenIdFormat::Dash).unwrap();
+    assert_eq!(parse_token_id(deps.as_ref().storage, "69-420").unwrap(), (69, 420));
+    parse_token_id(deps.as_ref().storage, "69|420").unwrap_err();
+    assert_eq!(build_token_id(deps.as_ref().storage, 69, 420).unwrap().unwrap(), "69-420");
+
+    // tokens of a badge are looked up by ids in the configured format
+    NftContract::default()
+        .parent
+        .mint(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            MintMsg::<Extension> {
+                token_id: "69-1".to_string(),
+                owner: "jake".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+        )
+        .unwrap();
+    let res = NftContract::default().tokens_by_badge(deps.as_ref(), 69, None, Some(1)).unwrap();
+    assert_eq!(res.tokens[0].token_id, "69-1");
+
+    // in the counter format, the badge id and serial of a token are looked up from the hub when
+    // it's minted
+    TOKEN_ID_FORMAT.save(deps.as_mut().storage, &TokenIdFormat::Counter).unwrap();
+    deps.querier.hub.set_token_serial("7", 69, 2);
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: "7".to_string(),
+            owner: "pumpkin".to_string(),
+            token_uri: None,
+            extension: None,
+        }),
+    )
+    .unwrap();
+    assert_eq!(parse_token_id(deps.as_ref().storage, "7").unwrap(), (69, 2));
+    assert_eq!(build_token_id(deps.as_ref().storage, 69, 2).unwrap(), Some("7".to_string()));
+
+    // serials that haven't been minted have no token id
+    assert_eq!(build_token_id(deps.as_ref().storage, 69, 3).unwrap(), None);
+    parse_token_id(deps.as_ref().storage, "8").unwrap_err();
+
+    let res = NftContract::default().tokens_by_badge(deps.as_ref(), 69, Some(1), None).unwrap();
+    assert_eq!(res.tokens[0].token_id, "7");
+    assert_eq!(res.tokens[0].owner, "pumpkin");
 }
 
 #[test]
@@ -174,6 +329,25 @@ fn prepending_traits() {
     );
 }
 
+#[test]
+fn appending_serial() {
+    let metadata = append_serial(mock_metadata(), 3, Some(100));
+    assert_eq!(metadata.name, Some("Test Badge #3/100".to_string()));
+
+    let metadata = append_serial(mock_metadata(), 3, None);
+    assert_eq!(metadata.name, Some("Test Badge #3".to_string()));
+
+    let metadata = append_serial(Metadata::default(), 3, Some(100));
+    assert_eq!(metadata.name, None);
+}
+
+#[test]
+fn rendering_token_uri() {
+    assert_eq!(render_token_uri("ipfs://hash/{serial}.json", 69, 420), "ipfs://hash/420.json");
+    assert_eq!(render_token_uri("ipfs://hash/{id}/{serial}", 69, 420), "ipfs://hash/69/420");
+    assert_eq!(render_token_uri("ipfs://hash", 69, 420), "ipfs://hash");
+}
+
 #[test]
 fn instantiating() {
     let deps = setup_test();
@@ -238,25 +412,1639 @@ fn rejecting_transfers() {
 }
 
 #[test]
-fn querying_nft_info() {
-    let deps = setup_test();
+fn sending() {
+    let mut deps = setup_test();
     let contract = NftContract::default();
 
-    let info = contract.nft_info(deps.as_ref(), "69|420").unwrap();
-    assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
-    assert_eq!(info.extension, prepend_traits(mock_metadata(), 69, 420));
+    // send a transferrable token to a contract, which is notified via the receive hook
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ExecuteMsg::SendNft {
+            contract: "market".to_string(),
+            token_id: "69|420".to_string(),
+            msg: to_binary("list").unwrap(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "market".to_string(),
+            msg: Cw721ReceiveMsg {
+                sender: "jake".to_string(),
+                token_id: "69|420".to_string(),
+                msg: to_binary("list").unwrap(),
+            }
+            .into_binary()
+            .unwrap(),
+            funds: vec![],
+        })],
+    );
+    let owner = contract
+        .parent
+        .parent
+        .owner_of(deps.as_ref(), mock_env(), "69|420".to_string(), false)
+        .unwrap();
+    assert_eq!(owner.owner, "market");
+
+    // attempt to send a untransferrable token, should fail
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::SendNft {
+            contract: "market".to_string(),
+            token_id: "420|69".to_string(),
+            msg: to_binary("list").unwrap(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: badge 420 is not transferrable");
 }
 
 #[test]
-fn querying_all_nft_info() {
-    let deps = setup_test();
+fn approving() {
+    let mut deps = setup_test();
     let contract = NftContract::default();
 
-    let AllNftInfoResponse {
-        access,
-        info,
-    } = contract.all_nft_info(deps.as_ref(), mock_env(), "69|420".to_string(), None).unwrap();
-    assert_eq!(access.owner, "jake");
-    assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
-    assert_eq!(info.extension, prepend_traits(mock_metadata(), 69, 420));
+    // approve a spender for a transferrable token, e.g. a marketplace escrow
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "69|420".to_string(),
+            expires: None,
+        },
+    )
+    .unwrap();
+
+    let res: ApprovalResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Approval {
+                token_id: "69|420".to_string(),
+                spender: "market".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.approval.spender, "market");
+
+    let res: ApprovalsResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Approvals {
+                token_id: "69|420".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.approvals.len(), 1);
+
+    // the approved spender can move the token on the owner's behalf
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("market", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: "pumpkin".to_string(),
+            token_id: "69|420".to_string(),
+        },
+    )
+    .unwrap();
+    let owner = contract
+        .parent
+        .parent
+        .owner_of(deps.as_ref(), mock_env(), "69|420".to_string(), false)
+        .unwrap();
+    assert_eq!(owner.owner, "pumpkin");
+
+    // approving a spender for an untransferrable token should fail
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "420|69".to_string(),
+            expires: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: badge 420 is not transferrable");
+
+    // operators can be approved and revoked for all of an owner's tokens
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::ApproveAll {
+            operator: "market".to_string(),
+            expires: None,
+        },
+    )
+    .unwrap();
+
+    fn query_operators(deps: Deps) -> Vec<Approval> {
+        let res: OperatorsResponse = from_binary(
+            &entry::query(
+                deps,
+                mock_env(),
+                QueryMsg::AllOperators {
+                    owner: "pumpkin".to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.operators
+    }
+
+    let operators = query_operators(deps.as_ref());
+    assert_eq!(operators.len(), 1);
+    assert_eq!(operators[0].spender, "market");
+
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::RevokeAll {
+            operator: "market".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(query_operators(deps.as_ref()).is_empty());
+}
+
+#[test]
+fn listing_operators() {
+    let mut deps = setup_test();
+
+    let env = mock_env();
+    for (operator, expires) in [
+        ("market", None),
+        ("expired", Some(Expiration::AtHeight(env.block.height + 1))),
+        ("auction", None),
+    ] {
+        entry::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("pumpkin", &[]),
+            ExecuteMsg::ApproveAll {
+                operator: operator.to_string(),
+                expires,
+            },
+        )
+        .unwrap();
+    }
+
+    fn query_operators(
+        deps: Deps,
+        include_expired: Option<bool>,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> Vec<String> {
+        let mut env = mock_env();
+        env.block.height += 10;
+
+        let res: OperatorsResponse = from_binary(
+            &entry::query(deps, env, QueryMsg::AllOperators {
+                owner: "pumpkin".to_string(),
+                include_expired,
+                start_after: start_after.map(String::from),
+                limit,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        res.operators.into_iter().map(|approval| approval.spender).collect()
+    }
+
+    // expired grants are skipped by default
+    let operators = query_operators(deps.as_ref(), None, None, None);
+    assert_eq!(operators, vec!["auction".to_string(), "market".to_string()]);
+
+    // unless explicitly requested
+    let operators = query_operators(deps.as_ref(), Some(true), None, None);
+    assert_eq!(operators, vec!["auction".to_string(), "expired".to_string(), "market".to_string()]);
+
+    // paginate
+    let operators = query_operators(deps.as_ref(), None, None, Some(1));
+    assert_eq!(operators, vec!["auction".to_string()]);
+
+    let operators = query_operators(deps.as_ref(), None, Some("auction"), Some(1));
+    assert_eq!(operators, vec!["market".to_string()]);
+}
+
+#[test]
+fn burning_by_hub() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    // accounts other than the owner or the hub cannot burn the token
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::Burn {
+            token_id: "69|420".to_string(),
+        },
+    )
+    .unwrap_err();
+
+    // the hub can burn the token even though it doesn't own it
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Burn {
+            token_id: "69|420".to_string(),
+        },
+    )
+    .unwrap();
+    let err = contract
+        .parent
+        .parent
+        .owner_of(deps.as_ref(), mock_env(), "69|420".to_string(), false)
+        .unwrap_err();
+    assert!(matches!(err, StdError::NotFound { .. }));
+
+    let num_tokens = contract.parent.parent.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(num_tokens.count, 1);
+
+    // the owner can still burn their own token, and the burn is reported to the hub
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::Burn {
+            token_id: "420|69".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "hub".to_string(),
+            msg: to_binary(&badges::hub::ExecuteMsg::RecordBurn {
+                id: 420,
+                serial: 69,
+            })
+            .unwrap(),
+            funds: vec![],
+        })],
+    );
+}
+
+#[test]
+fn updating_minter() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    fn update_minter_msg() -> ExecuteMsg {
+        ExecuteMsg::Extension {
+            msg: ExtensionMsg::UpdateMinter {
+                minter: "new_hub".to_string(),
+            },
+        }
+    }
+
+    fn mint_msg(token_id: &str) -> ExecuteMsg {
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: "jake".to_string(),
+            token_uri: None,
+            extension: None,
+        })
+    }
+
+    // only the contract's admin can update the minter; not even the current minter can
+    for sender in ["hub", "jake"] {
+        let err =
+            entry::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), update_minter_msg())
+                .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+    }
+
+    // the new hub must build token ids in the same format as the old one
+    deps.querier.hub.contract_addr = Addr::unchecked("new_hub");
+    deps.querier.hub.token_id_format = TokenIdFormat::Dash;
+    let err =
+        entry::execute(deps.as_mut(), mock_env(), mock_info("larry", &[]), update_minter_msg())
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: the new minter's token id format differs from the collection's",
+    );
+
+    deps.querier.hub.token_id_format = TokenIdFormat::Pipe;
+    let res =
+        entry::execute(deps.as_mut(), mock_env(), mock_info("larry", &[]), update_minter_msg())
+            .unwrap();
+    assert_eq!(res.attributes[2], attr("minter", "new_hub"));
+
+    let minter = contract.parent.parent.minter(deps.as_ref()).unwrap();
+    assert_eq!(minter.minter, "new_hub");
+
+    // the old hub can no longer mint, while the new one can
+    entry::execute(deps.as_mut(), mock_env(), mock_info("hub", &[]), mint_msg("69|1")).unwrap_err();
+    entry::execute(deps.as_mut(), mock_env(), mock_info("new_hub", &[]), mint_msg("69|1"))
+        .unwrap();
+
+    let num_tokens = contract.parent.parent.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(num_tokens.count, 3);
+}
+
+#[test]
+fn caching_metadata() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    let cached_metadata = Metadata {
+        name: Some("Cached Badge".to_string()),
+        ..Default::default()
+    };
+    let msg = ExecuteMsg::Extension {
+        msg: ExtensionMsg::CacheMetadata {
+            id: 69,
+            cache: MetadataCache {
+                metadata: cached_metadata.clone(),
+                token_uri: None,
+                max_supply: Some(1000),
+            },
+        },
+    };
+
+    // only the hub can cache metadata
+    {
+        let err = entry::execute(deps.as_mut(), mock_env(), mock_info("jake", &[]), msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+
+        let cache = METADATA_CACHE.may_load(deps.as_ref().storage, 69).unwrap();
+        assert_eq!(cache, None);
+    }
+
+    // the hub caches metadata
+    {
+        let res = entry::execute(deps.as_mut(), mock_env(), mock_info("hub", &[]), msg).unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "cache_metadata"), attr("id", "69")]);
+    }
+
+    // token info is served from the cache instead of the metadata stored at the hub
+    {
+        let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+        assert_eq!(
+            info.extension,
+            append_serial(prepend_traits(cached_metadata, 69, 420), 420, Some(1000)),
+        );
+    }
+
+    // badges without a cache entry still fall back to the hub
+    {
+        let info = contract.nft_info(deps.as_ref(), "420|69", None).unwrap();
+        assert_eq!(info.token_uri.unwrap(), "ipfs://hash/420/69.json");
+    }
+}
+
+#[test]
+fn setting_serial_traits() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    fn set_traits(traits: Vec<Trait>) -> ExecuteMsg {
+        ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetSerialTraits {
+                id: 69,
+                serial: 420,
+                traits,
+            },
+        }
+    }
+
+    let traits = vec![
+        Trait {
+            display_type: None,
+            trait_type: "rarity".to_string(),
+            value: "UR".to_string(),
+        },
+        Trait {
+            display_type: None,
+            trait_type: "placement".to_string(),
+            value: "1st".to_string(),
+        },
+    ];
+
+    // only the hub can set traits
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            set_traits(traits.clone()),
+        )
+        .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+    }
+
+    // the instance's traits replace shared traits of the same type, and the others are appended
+    {
+        let res = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            set_traits(traits.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "set_serial_traits"), attr("id", "69"), attr("serial", "420")],
+        );
+
+        let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+        let attributes = info.extension.attributes.unwrap();
+        assert_eq!(attributes[2..], traits[..]);
+
+        // other instances of the badge are not affected
+        let info = contract.nft_info(deps.as_ref(), "69|1", None).unwrap();
+        assert_eq!(info.extension.attributes.unwrap()[2].value, "SSR");
+    }
+
+    // an empty list removes the instance's traits
+    {
+        entry::execute(deps.as_mut(), mock_env(), mock_info("hub", &[]), set_traits(vec![]))
+            .unwrap();
+
+        let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+        assert_eq!(
+            info.extension,
+            append_serial(prepend_traits(mock_metadata(), 69, 420), 420, None),
+        );
+    }
+}
+
+#[test]
+fn filling_placeholders() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    let metadata = Metadata {
+        name: Some("Finisher".to_string()),
+        description: Some("Finisher {serial} of {total_supply}, awarded to {owner}".to_string()),
+        image: Some("ipfs://hash/{serial}.png".to_string()),
+        attributes: Some(vec![Trait {
+            display_type: None,
+            trait_type: "holder".to_string(),
+            value: "{owner}".to_string(),
+        }]),
+        ..Default::default()
+    };
+    METADATA_CACHE
+        .save(
+            deps.as_mut().storage,
+            69,
+            &MetadataCache {
+                metadata,
+                token_uri: None,
+                max_supply: None,
+            },
+        )
+        .unwrap();
+
+    let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+    assert_eq!(
+        info.extension.description,
+        Some("Finisher 420 of 420, awarded to jake".to_string()),
+    );
+    assert_eq!(info.extension.image, Some("ipfs://hash/420.png".to_string()));
+    assert_eq!(info.extension.attributes.unwrap()[2].value, "jake");
+
+    // the owner placeholder is left as is for tokens that don't exist
+    let info = contract.nft_info(deps.as_ref(), "69|1", None).unwrap();
+    assert_eq!(
+        info.extension.description,
+        Some("Finisher 1 of 420, awarded to {owner}".to_string()),
+    );
+}
+
+#[test]
+fn rendering_svg_templates() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    let template = "<svg><text>{serial}/{total_supply}</text></svg>";
+
+    // only the hub can set templates
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::SetSvgTemplate {
+                    id: 69,
+                    template: Some(template.to_string()),
+                },
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+    }
+
+    // the template is rendered into the image data, with placeholders filled in
+    {
+        let res = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::SetSvgTemplate {
+                    id: 69,
+                    template: Some(template.to_string()),
+                },
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "set_svg_template"), attr("id", "69")]);
+
+        let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+        assert_eq!(
+            info.extension.image_data,
+            Some("<svg><text>420/420</text></svg>".to_string()),
+        );
+    }
+
+    // removing the template falls back to the badge's own metadata
+    {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::SetSvgTemplate {
+                    id: 69,
+                    template: None,
+                },
+            },
+        )
+        .unwrap();
+
+        let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+        assert_eq!(info.extension.image_data, mock_metadata().image_data);
+    }
+}
+
+#[test]
+fn applying_media() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    let media = vec![
+        MediaAsset {
+            uri: "ipfs://poster".to_string(),
+            mime_type: "image/png".to_string(),
+            purpose: MediaPurpose::Image,
+        },
+        MediaAsset {
+            uri: "ipfs://model".to_string(),
+            mime_type: "model/gltf-binary".to_string(),
+            purpose: MediaPurpose::Model,
+        },
+        MediaAsset {
+            uri: "ipfs://video".to_string(),
+            mime_type: "video/mp4".to_string(),
+            purpose: MediaPurpose::Video,
+        },
+    ];
+
+    // only the hub can set media
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::SetMedia {
+                    id: 69,
+                    media: media.clone(),
+                },
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+    }
+
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetMedia {
+                id: 69,
+                media,
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "set_media"), attr("id", "69")]);
+
+    // the badge's own image is kept, while the missing animation url is taken from the video
+    let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+    assert_eq!(info.extension.image, mock_metadata().image);
+    assert_eq!(info.extension.animation_url, Some("ipfs://video".to_string()));
+}
+
+#[test]
+fn localizing_metadata() {
+    let mut deps = setup_test();
+    let contract = NftContract::default();
+
+    fn set_translation(locale: &str, metadata: Option<Metadata>) -> ExecuteMsg {
+        ExecuteMsg::Extension {
+            msg: ExtensionMsg::SetLocalizedMetadata {
+                id: 69,
+                locale: locale.to_string(),
+                metadata,
+            },
+        }
+    }
+
+    let translation = Metadata {
+        name: Some("Insignia de prueba".to_string()),
+        ..Default::default()
+    };
+
+    // only the hub can set translations
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            set_translation("es", Some(translation.clone())),
+        )
+        .unwrap_err();
+        assert!(matches!(err, sg721_base::ContractError::Unauthorized {}));
+    }
+
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        set_translation("es", Some(translation)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "set_localized_metadata"), attr("id", "69"), attr("locale", "es")],
+    );
+
+    // translated fields replace the badge's own, and the others are kept
+    {
+        let info = contract.nft_info(deps.as_ref(), "69|420", Some("es")).unwrap();
+        let expected = Metadata {
+            name: Some("Insignia de prueba".to_string()),
+            ..mock_metadata()
+        };
+        assert_eq!(info.extension, append_serial(prepend_traits(expected, 69, 420), 420, None));
+    }
+
+    // a regional locale falls back to its language
+    {
+        let info = contract.nft_info(deps.as_ref(), "69|420", Some("es-AR")).unwrap();
+        assert_eq!(info.extension.name, Some("Insignia de prueba #420".to_string()));
+    }
+
+    // a locale without a translation falls back to the untranslated metadata
+    {
+        let info = contract.nft_info(deps.as_ref(), "69|420", Some("fr")).unwrap();
+        assert_eq!(
+            info.extension,
+            append_serial(prepend_traits(mock_metadata(), 69, 420), 420, None),
+        );
+    }
+
+    // the translation can be removed
+    {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            set_translation("es", None),
+        )
+        .unwrap();
+
+        let info = contract.nft_info(deps.as_ref(), "69|420", Some("es")).unwrap();
+        assert_eq!(info.extension.name, Some("Test Badge #420".to_string()));
+    }
+}
+
+#[test]
+fn querying_nft_info() {
+    let deps = setup_test();
+    let contract = NftContract::default();
+
+    let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+    assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
+    assert_eq!(info.extension, append_serial(prepend_traits(mock_metadata(), 69, 420), 420, None));
+}
+
+#[test]
+fn querying_nft_info_with_token_uri() {
+    let deps = setup_test();
+    let contract = NftContract::default();
+
+    // badge 420 uses an off-chain token uri, so no on-chain metadata is returned
+    let info = contract.nft_info(deps.as_ref(), "420|69", None).unwrap();
+    assert_eq!(info.token_uri.unwrap(), "ipfs://hash/420/69.json");
+    assert_eq!(info.extension, Metadata::default());
+}
+
+#[test]
+fn querying_nft_info_as_onchain_metadata() {
+    let deps = setup_test();
+
+    // the metadata extension as defined by cw721-metadata-onchain. cw_serde denies unknown fields,
+    // so deserialization fails if our response has any field that generic clients don't expect
+    #[cw_serde]
+    struct OnchainTrait {
+        display_type: Option<String>,
+        trait_type: String,
+        value: String,
+    }
+
+    #[cw_serde]
+    struct OnchainMetadata {
+        image: Option<String>,
+        image_data: Option<String>,
+        external_url: Option<String>,
+        description: Option<String>,
+        name: Option<String>,
+        attributes: Option<Vec<OnchainTrait>>,
+        background_color: Option<String>,
+        animation_url: Option<String>,
+        youtube_url: Option<String>,
+    }
+
+    let res: cw721::NftInfoResponse<OnchainMetadata> = from_binary(
+        &entry::query(deps.as_ref(), mock_env(), QueryMsg::NftInfo {
+            token_id: "69|420".to_string(),
+            locale: None,
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.extension.name, Some("Test Badge #420".to_string()));
+    assert_eq!(res.extension.image, Some("ipfs://hash".to_string()));
+    assert_eq!(
+        res.extension.attributes.unwrap().last(),
+        Some(&OnchainTrait {
+            display_type: None,
+            trait_type: "rarity".to_string(),
+            value: "SSR".to_string(),
+        }),
+    );
+}
+
+#[test]
+fn querying_all_nft_info() {
+    let deps = setup_test();
+    let contract = NftContract::default();
+
+    let AllNftInfoResponse {
+        access,
+        info,
+    } = contract
+        .all_nft_info(deps.as_ref(), mock_env(), "69|420".to_string(), None, None)
+        .unwrap();
+    assert_eq!(access.owner, "jake");
+    assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
+    assert_eq!(info.extension, append_serial(prepend_traits(mock_metadata(), 69, 420), 420, None));
+}
+
+#[test]
+fn querying_standard_cw721() {
+    let deps = setup_test();
+
+    // apart from `nft_info` and `all_nft_info`, standard cw721 queries are served by the parent
+    let res: OwnerOfResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: "69|420".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.owner, "jake");
+
+    let res: NumTokensResponse =
+        from_binary(&entry::query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap())
+            .unwrap();
+    assert_eq!(res.count, 2);
+
+    let res: TokensResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Tokens {
+                owner: "pumpkin".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.tokens, vec!["420|69".to_string()]);
+
+    let res: TokensResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.tokens, vec!["420|69".to_string(), "69|420".to_string()]);
+
+    let res: ContractInfoResponse =
+        from_binary(&entry::query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap())
+            .unwrap();
+    assert_eq!(res.name, "Badges");
+    assert_eq!(res.symbol, "B");
+}
+
+#[test]
+fn querying_royalties() {
+    let deps = setup_test();
+
+    fn query_royalty_info(deps: Deps, token_id: &str) -> RoyaltiesInfoResponse {
+        from_binary(
+            &entry::query(
+                deps,
+                mock_env(),
+                QueryMsg::Extension {
+                    msg: Cw2981QueryMsg::RoyaltyInfo {
+                        token_id: token_id.to_string(),
+                        sale_price: Uint128::new(12345),
+                    },
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    let res: CheckRoyaltiesResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Extension {
+                msg: Cw2981QueryMsg::CheckRoyalties {},
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.royalty_payments);
+
+    // badge 420 has a 5% royalty
+    let res = query_royalty_info(deps.as_ref(), "420|69");
+    assert_eq!(
+        res,
+        RoyaltiesInfoResponse {
+            address: "jake".to_string(),
+            royalty_amount: Uint128::new(617),
+        },
+    );
+
+    // badge 69 has no royalty
+    let res = query_royalty_info(deps.as_ref(), "69|420");
+    assert_eq!(
+        res,
+        RoyaltiesInfoResponse {
+            address: "".to_string(),
+            royalty_amount: Uint128::zero(),
+        },
+    );
+}
+
+#[test]
+fn querying_tokens_by_badge() {
+    let mut deps = setup_test();
+
+    // mint a few more tokens of badge 69, then burn one of them
+    for (serial, owner) in [(1, "jake"), (2, "pumpkin"), (3, "larry")] {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: format!("69|{}", serial),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+            }),
+        )
+        .unwrap();
+    }
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Burn {
+            token_id: "69|2".to_string(),
+        },
+    )
+    .unwrap();
+
+    fn query_tokens(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> (Vec<String>, Option<u64>) {
+        let res: TokensByBadgeResponse = from_binary(
+            &entry::query(
+                deps,
+                mock_env(),
+                QueryMsg::TokensByBadge {
+                    id: 69,
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let tokens = res
+            .tokens
+            .into_iter()
+            .map(|token| format!("{}:{}", token.token_id, token.owner))
+            .collect();
+        (tokens, res.next_start_after)
+    }
+
+    // tokens are returned in order of serial numbers, skipping the burned one
+    let (tokens, next) = query_tokens(deps.as_ref(), None, Some(2));
+    assert_eq!(tokens, vec!["69|1:jake".to_string(), "69|3:larry".to_string()]);
+    assert_eq!(next, Some(3));
+
+    // the number of serials looked at per query is capped, so the gap between serials 3 and 420
+    // takes more than one query to skip
+    let (tokens, next) = query_tokens(deps.as_ref(), Some(3), None);
+    assert!(tokens.is_empty());
+    assert_eq!(next, Some(3 + MAX_SERIALS_SCANNED));
+
+    let (tokens, next) = query_tokens(deps.as_ref(), next, None);
+    assert_eq!(tokens, vec!["69|420:jake".to_string()]);
+    assert_eq!(next, None);
+}
+
+#[test]
+fn querying_portfolio() {
+    let mut deps = setup_test();
+
+    // give jake a few more tokens across both badges. the serials are picked so that the token
+    // ids' string order ("420|12" < "420|5") differs from the serials' numeric order
+    for token_id in ["69|1", "420|5", "420|12"] {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: token_id.to_string(),
+                owner: "jake".to_string(),
+                token_uri: None,
+                extension: None,
+            }),
+        )
+        .unwrap();
+    }
+
+    fn query_portfolio(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> PortfolioResponse {
+        from_binary(
+            &entry::query(
+                deps,
+                mock_env(),
+                QueryMsg::Portfolio {
+                    owner: "jake".to_string(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    // holdings are grouped by badge, with serials in ascending numeric order
+    let res = query_portfolio(deps.as_ref(), None, None);
+    assert_eq!(res.owner, "jake");
+    assert_eq!(
+        res.holdings.iter().map(|h| (h.id, h.serials.clone())).collect::<Vec<_>>(),
+        vec![(69, vec![1, 420]), (420, vec![5, 12])],
+    );
+    assert_eq!(res.holdings[0].name, Some("Test Badge".to_string()));
+    assert_eq!(res.holdings[0].image, Some("ipfs://hash".to_string()));
+
+    // paginate by badge id
+    let res = query_portfolio(deps.as_ref(), None, Some(1));
+    assert_eq!(res.holdings.iter().map(|h| h.id).collect::<Vec<_>>(), vec![69]);
+
+    let res = query_portfolio(deps.as_ref(), Some(69), None);
+    assert_eq!(res.holdings.iter().map(|h| h.id).collect::<Vec<_>>(), vec![420]);
+
+    // the holdings follow the tokens as they are transferred and burned
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: "pumpkin".to_string(),
+            token_id: "69|1".to_string(),
+        },
+    )
+    .unwrap();
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ExecuteMsg::Burn {
+            token_id: "420|5".to_string(),
+        },
+    )
+    .unwrap();
+
+    let res = query_portfolio(deps.as_ref(), None, None);
+    assert_eq!(
+        res.holdings.iter().map(|h| (h.id, h.serials.clone())).collect::<Vec<_>>(),
+        vec![(69, vec![420]), (420, vec![12])],
+    );
+
+    let holdings = HOLDINGS
+        .sub_prefix(&Addr::unchecked("pumpkin"))
+        .keys(deps.as_ref().storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(holdings, vec![(69, 1), (420, 69)]);
+}
+
+#[test]
+fn backfilling_holdings() {
+    let mut deps = setup_test();
+
+    // migrating from a version that already has the holdings index doesn't start a backfill
+    entry::migrate(deps.as_mut(), mock_env(), Empty {}).unwrap();
+    assert_eq!(HOLDINGS_BACKFILL.may_load(deps.as_ref().storage).unwrap(), None);
+
+    // emulate a contract that was deployed before the holdings index existed
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.2.0").unwrap();
+    let keys = HOLDINGS
+        .keys(deps.as_ref().storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    for (owner, id, serial) in keys {
+        HOLDINGS.remove(deps.as_mut().storage, (&owner, id, serial));
+    }
+
+    entry::migrate(deps.as_mut(), mock_env(), Empty {}).unwrap();
+
+    fn query_portfolio(deps: Deps, owner: &str) -> StdResult<Binary> {
+        entry::query(
+            deps,
+            mock_env(),
+            QueryMsg::Portfolio {
+                owner: owner.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    fn backfill(deps: DepsMut, limit: Option<u32>) -> Result<Response, ContractError> {
+        entry::execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::BackfillHoldings {
+                    limit,
+                },
+            },
+        )
+    }
+
+    // the portfolio is unavailable until the backfill is done
+    let err = query_portfolio(deps.as_ref(), "jake").unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: holdings are still being backfilled");
+
+    let res = backfill(deps.as_mut(), Some(1)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "backfill_holdings"),
+            attr("indexed", "1"),
+            attr("complete", "false"),
+        ],
+    );
+    assert!(query_portfolio(deps.as_ref(), "jake").is_err());
+
+    let res = backfill(deps.as_mut(), None).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "backfill_holdings"),
+            attr("indexed", "1"),
+            attr("complete", "true"),
+        ],
+    );
+
+    let res: PortfolioResponse =
+        from_binary(&query_portfolio(deps.as_ref(), "jake").unwrap()).unwrap();
+    assert_eq!(
+        res.holdings.iter().map(|h| (h.id, h.serials.clone())).collect::<Vec<_>>(),
+        vec![(69, vec![420])],
+    );
+
+    let res: PortfolioResponse =
+        from_binary(&query_portfolio(deps.as_ref(), "pumpkin").unwrap()).unwrap();
+    assert_eq!(
+        res.holdings.iter().map(|h| (h.id, h.serials.clone())).collect::<Vec<_>>(),
+        vec![(420, vec![69])],
+    );
+
+    // there's nothing left to backfill
+    let err = backfill(deps.as_mut(), None).unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: holdings are not being backfilled");
+}
+
+#[test]
+fn taking_snapshots() {
+    let mut deps = setup_test();
+
+    for (serial, owner) in [(1, "pumpkin"), (2, "jake")] {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hub", &[]),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: format!("69|{}", serial),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+            }),
+        )
+        .unwrap();
+    }
+
+    fn take_snapshot(
+        deps: DepsMut,
+        sender: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let mut env = mock_env();
+        env.block.height = height;
+        entry::execute(
+            deps,
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::TakeSnapshot {
+                    id: 69,
+                },
+            },
+        )
+    }
+
+    // only the badge's manager or the hub's developer can take snapshots
+    let err = take_snapshot(deps.as_mut(), "pumpkin", 100).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let res = take_snapshot(deps.as_mut(), "larry", 100).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "take_snapshot"),
+            attr("id", "69"),
+            attr("snapshot", "1"),
+            attr("height", "100"),
+            attr("remaining", "220"),
+        ],
+    );
+
+    // badge 69 has a supply of 420, more than fits in one chunk, so the snapshot is in progress
+    // until the remaining serials are walked
+    let err = take_snapshot(deps.as_mut(), "larry", 100).unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: snapshot 1 of badge 69 is still in progress");
+
+    let err = entry::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SnapshotHolders {
+            id: 69,
+            snapshot: 1,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: snapshot 1 of badge 69 is still in progress");
+
+    fn resume_snapshot(deps: DepsMut, limit: Option<u32>) -> Result<Response, ContractError> {
+        entry::execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Extension {
+                msg: ExtensionMsg::ResumeSnapshot {
+                    id: 69,
+                    limit,
+                },
+            },
+        )
+    }
+
+    let res = resume_snapshot(deps.as_mut(), Some(100)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "resume_snapshot"),
+            attr("id", "69"),
+            attr("snapshot", "1"),
+            attr("remaining", "120"),
+        ],
+    );
+
+    resume_snapshot(deps.as_mut(), None).unwrap();
+
+    let err = resume_snapshot(deps.as_mut(), None).unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: badge 69 has no snapshot in progress");
+
+    // jake transfers his first token to pumpkin; the first snapshot should not be affected
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: "pumpkin".to_string(),
+            token_id: "69|2".to_string(),
+        },
+    )
+    .unwrap();
+
+    take_snapshot(deps.as_mut(), "developer", 200).unwrap();
+
+    let res: SnapshotsResponse = from_binary(
+        &entry::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Snapshots {
+                id: 69,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SnapshotsResponse {
+            id: 69,
+            snapshots: vec![
+                SnapshotResponse {
+                    snapshot: 1,
+                    height: 100,
+                    num_holders: 2,
+                    num_tokens: 3,
+                    complete: true,
+                },
+                SnapshotResponse {
+                    snapshot: 2,
+                    height: 200,
+                    num_holders: 1,
+                    num_tokens: 2,
+                    complete: false,
+                },
+            ],
+        },
+    );
+
+    // jake's serial 420 is only recorded in the second snapshot once it's finished
+    resume_snapshot(deps.as_mut(), Some(1000)).unwrap();
+
+    fn query_holders(
+        deps: Deps,
+        snapshot: u64,
+        start_after: Option<String>,
+    ) -> Vec<(String, Vec<u64>)> {
+        let res: SnapshotHoldersResponse = from_binary(
+            &entry::query(
+                deps,
+                mock_env(),
+                QueryMsg::SnapshotHolders {
+                    id: 69,
+                    snapshot,
+                    start_after,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.holders.into_iter().map(|holder| (holder.owner, holder.serials)).collect()
+    }
+
+    assert_eq!(
+        query_holders(deps.as_ref(), 1, None),
+        vec![("jake".to_string(), vec![2, 420]), ("pumpkin".to_string(), vec![1])],
+    );
+    assert_eq!(
+        query_holders(deps.as_ref(), 2, None),
+        vec![("jake".to_string(), vec![420]), ("pumpkin".to_string(), vec![1, 2])],
+    );
+    assert_eq!(
+        query_holders(deps.as_ref(), 2, Some("jake".to_string())),
+        vec![("pumpkin".to_string(), vec![1, 2])],
+    );
+}
+
+#[test]
+fn ibc_channel_handshake() {
+    let mut deps = setup_test();
+
+    // channels must be unordered and speak ics721
+    {
+        let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        assert!(entry::ibc_channel_open(deps.as_mut(), mock_env(), msg).is_err());
+
+        let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, "ics20-1");
+        assert!(entry::ibc_channel_open(deps.as_mut(), mock_env(), msg).is_err());
+    }
+
+    let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, IBC_VERSION);
+    entry::ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let msg = mock_ibc_channel_connect_ack("channel-0", IbcOrder::Unordered, IBC_VERSION);
+    entry::ibc_channel_connect(deps.as_mut(), mock_env(), msg).unwrap();
+    assert!(IBC_CHANNELS.has(deps.as_ref().storage, "channel-0"));
+}
+
+fn setup_ibc_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier, Empty> {
+    let mut deps = setup_test();
+
+    let msg = mock_ibc_channel_connect_ack("channel-0", IbcOrder::Unordered, IBC_VERSION);
+    entry::ibc_channel_connect(deps.as_mut(), mock_env(), msg).unwrap();
+
+    deps
+}
+
+fn ibc_transfer(token_id: &str, channel_id: &str) -> ExecuteMsg {
+    ExecuteMsg::Extension {
+        msg: ExtensionMsg::IbcTransfer {
+            token_id: token_id.to_string(),
+            channel_id: channel_id.to_string(),
+            receiver: "osmo1jake".to_string(),
+            timeout_seconds: None,
+        },
+    }
+}
+
+fn mock_packet_data(class_id: &str) -> NonFungibleTokenPacketData {
+    let contract = NftContract::default();
+    let deps = setup_test();
+    let info = contract.nft_info(deps.as_ref(), "69|420", None).unwrap();
+
+    NonFungibleTokenPacketData {
+        class_id: class_id.to_string(),
+        class_uri: None,
+        class_data: None,
+        token_ids: vec!["69|420".to_string()],
+        token_uris: Some(vec![info.token_uri.unwrap()]),
+        token_data: Some(vec![to_binary(&info.extension).unwrap()]),
+        sender: "jake".to_string(),
+        receiver: "osmo1jake".to_string(),
+        memo: None,
+    }
+}
+
+fn query_owner(deps: Deps, token_id: &str) -> String {
+    let contract = NftContract::default();
+    contract.parent.parent.owner_of(deps, mock_env(), token_id.to_string(), false).unwrap().owner
+}
+
+#[test]
+fn sending_over_ibc() {
+    let mut deps = setup_ibc_test();
+
+    // the channel must have completed the handshake
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jake", &[]),
+            ibc_transfer("69|420", "channel-1"),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: unknown ics721 channel `channel-1`");
+    }
+
+    // only the owner or an approved spender can send the token
+    {
+        let res = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pumpkin", &[]),
+            ibc_transfer("69|420", "channel-0"),
+        );
+        assert!(res.is_err());
+    }
+
+    // non-transferrable badges can't be sent
+    {
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pumpkin", &[]),
+            ibc_transfer("420|69", "channel-0"),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: badge 420 is not transferrable");
+    }
+
+    // the token is escrowed, and a packet is sent with its metadata
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ibc_transfer("69|420", "channel-0"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(IbcMsg::SendPacket {
+            channel_id: "channel-0".to_string(),
+            data: to_binary(&mock_packet_data(MOCK_CONTRACT_ADDR)).unwrap(),
+            timeout: IbcTimeout::with_timestamp(
+                mock_env().block.time.plus_seconds(DEFAULT_TIMEOUT_SECONDS),
+            ),
+        })],
+    );
+    assert_eq!(query_owner(deps.as_ref(), "69|420"), MOCK_CONTRACT_ADDR);
+    assert_eq!(
+        IBC_ESCROW.load(deps.as_ref().storage, "69|420").unwrap(),
+        ("channel-0".to_string(), Addr::unchecked("jake")),
+    );
+}
+
+#[test]
+fn receiving_over_ibc() {
+    let mut deps = setup_ibc_test();
+
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake", &[]),
+        ibc_transfer("69|420", "channel-0"),
+    )
+    .unwrap();
+
+    // tokens that don't originate from this collection are refused
+    {
+        let data = NonFungibleTokenPacketData {
+            receiver: "pumpkin".to_string(),
+            ..mock_packet_data("their-port/channel-1234/stars1othercollection")
+        };
+        let msg = mock_ibc_packet_recv("channel-0", &data).unwrap();
+        let res = entry::ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: Ics721Ack = from_binary(&res.acknowledgement).unwrap();
+        assert!(matches!(ack, Ics721Ack::Error(_)));
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), MOCK_CONTRACT_ADDR);
+    }
+
+    // tokens coming back must do so through the channel they were sent through
+    {
+        let data = NonFungibleTokenPacketData {
+            receiver: "pumpkin".to_string(),
+            ..mock_packet_data(&format!("their-port/channel-1234/{}", MOCK_CONTRACT_ADDR))
+        };
+        let msg = mock_ibc_packet_recv("channel-1", &data).unwrap();
+        let res = entry::ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: Ics721Ack = from_binary(&res.acknowledgement).unwrap();
+        assert!(matches!(ack, Ics721Ack::Error(_)));
+    }
+
+    // the token is released from escrow to the receiver
+    {
+        let data = NonFungibleTokenPacketData {
+            receiver: "pumpkin".to_string(),
+            ..mock_packet_data(&format!("their-port/channel-1234/{}", MOCK_CONTRACT_ADDR))
+        };
+        let msg = mock_ibc_packet_recv("channel-0", &data).unwrap();
+        let res = entry::ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: Ics721Ack = from_binary(&res.acknowledgement).unwrap();
+        assert!(matches!(ack, Ics721Ack::Result(_)));
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), "pumpkin");
+        assert!(!IBC_ESCROW.has(deps.as_ref().storage, "69|420"));
+    }
+}
+
+#[test]
+fn refunding_failed_ibc_transfers() {
+    let mut deps = setup_ibc_test();
+
+    fn send(deps: DepsMut) {
+        let msg = ibc_transfer("69|420", "channel-0");
+        entry::execute(deps, mock_env(), mock_info("jake", &[]), msg).unwrap();
+    }
+
+    let data = mock_packet_data(MOCK_CONTRACT_ADDR);
+
+    // a successful acknowledgement keeps the token in escrow
+    {
+        send(deps.as_mut());
+        let ack = IbcAcknowledgement::new(to_binary(&Ics721Ack::Result(vec![1].into())).unwrap());
+        let msg = mock_ibc_packet_ack("channel-0", &data, ack).unwrap();
+        entry::ibc_packet_ack(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), MOCK_CONTRACT_ADDR);
+    }
+
+    // an error acknowledgement returns the token to the sender
+    {
+        let ack = IbcAcknowledgement::new(to_binary(&Ics721Ack::Error("oops".into())).unwrap());
+        let msg = mock_ibc_packet_ack("channel-0", &data, ack).unwrap();
+        entry::ibc_packet_ack(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), "jake");
+    }
+
+    // so does a timeout
+    {
+        send(deps.as_mut());
+        let msg = mock_ibc_packet_timeout("channel-0", &data).unwrap();
+        entry::ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), "jake");
+        assert!(!IBC_ESCROW.has(deps.as_ref().storage, "69|420"));
+    }
+
+    // a token sent by an approved spender is refunded to its owner, not to the spender
+    {
+        let msg = ExecuteMsg::Approve {
+            spender: "pumpkin".to_string(),
+            token_id: "69|420".to_string(),
+            expires: None,
+        };
+        entry::execute(deps.as_mut(), mock_env(), mock_info("jake", &[]), msg).unwrap();
+
+        let msg = ibc_transfer("69|420", "channel-0");
+        entry::execute(deps.as_mut(), mock_env(), mock_info("pumpkin", &[]), msg).unwrap();
+
+        let data = NonFungibleTokenPacketData {
+            sender: "pumpkin".to_string(),
+            ..data.clone()
+        };
+        let msg = mock_ibc_packet_timeout("channel-0", &data).unwrap();
+        entry::ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(query_owner(deps.as_ref(), "69|420"), "jake");
+    }
+
+    // a token revoked by the hub while in escrow is skipped, so the packet can still be settled
+    {
+        send(deps.as_mut());
+        let msg = ExecuteMsg::Burn {
+            token_id: "69|420".to_string(),
+        };
+        entry::execute(deps.as_mut(), mock_env(), mock_info("hub", &[]), msg).unwrap();
+        assert!(!IBC_ESCROW.has(deps.as_ref().storage, "69|420"));
+
+        let msg = mock_ibc_packet_timeout("channel-0", &data).unwrap();
+        entry::ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+        let contract = NftContract::default();
+        let res =
+            contract.parent.parent.owner_of(deps.as_ref(), mock_env(), "69|420".into(), false);
+        assert!(res.is_err());
+    }
 }
@@ -96,6 +96,7 @@ const args = yargs(hideBin(process.argv))
     rule: {
       by_minter: senderAddr,
     },
+    ruleFrozen: false,
     expiry: args["expiry"],
     maxSupply: args["max-supply"],
   };
@@ -88,6 +88,7 @@ const args = yargs(hideBin(process.argv))
     rule: {
       by_key: args["pubkey"],
     },
+    ruleFrozen: false,
     expiry: args["expiry"],
     maxSupply: args["max-supply"],
   };
@@ -94,6 +94,7 @@ const args = yargs(hideBin(process.argv))
     metadata,
     transferrable: args["transferrable"],
     rule: "by_keys",
+    ruleFrozen: false,
     expiry: args["expiry"],
     maxSupply: args["max-supply"],
   };
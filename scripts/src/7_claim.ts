@@ -69,7 +69,13 @@ const args = yargs(hideBin(process.argv))
   const privKey = Buffer.from(args["privkey"], "hex");
   const pubKey = Buffer.from(secp256k1.publicKeyCreate(privKey, true));
 
-  const message = `claim badge ${args["id"]} for user ${args["owner"]}`;
+  const chainId = await client.getChainId();
+  const { nonce } = await client.queryContractSmart(args["hub-addr"], {
+    nonce: { id: args["id"] },
+  });
+  const message =
+    `claim badge ${args["id"]} for user ${args["owner"]} ` +
+    `at hub ${args["hub-addr"]} on chain ${chainId} with nonce ${nonce}`;
   const msgBytes = Buffer.from(message, "utf8");
   const msgHashBytes = sha256(msgBytes);
   const { signature } = secp256k1.ecdsaSign(msgHashBytes, privKey);
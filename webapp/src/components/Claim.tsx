@@ -363,7 +363,13 @@ export default function Claim() {
 
   async function getMintMsg() {
     const privKey = Buffer.from(privkeyStr, "hex");
-    const msg = `claim badge ${idStr} for user ${owner}`;
+    const { hub, chainId } = store.networkConfig!;
+    const { nonce } = await store.wasmClient!.queryContractSmart(hub, {
+      nonce: { id: Number(idStr) },
+    });
+    const msg =
+      `claim badge ${idStr} for user ${owner} at hub ${hub} on chain ${chainId} ` +
+      `with nonce ${nonce}`;
     const msgBytes = Buffer.from(msg, "utf8");
     const msgHashBytes = sha256(msgBytes);
     const { signature } = secp256k1.ecdsaSign(msgHashBytes, privKey);